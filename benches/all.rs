@@ -2,13 +2,22 @@
 
 mod parse;
 mod arb;
+mod opportunity_clone;
+mod scanner_scaling;
+mod wire;
 
 use criterion::criterion_main;
 
 use arb::arb_benches;
+use opportunity_clone::opportunity_clone_benches;
 use parse::parse_benches;
+use scanner_scaling::scanner_scaling_benches;
+use wire::wire_benches;
 
 criterion_main!(
     arb_benches,
+    opportunity_clone_benches,
     parse_benches,
+    scanner_scaling_benches,
+    wire_benches,
 );
@@ -3,6 +3,8 @@
 // cargo bench --bench arb -- --save-baseline current
 // critcmp current
 
+use std::time::Instant;
+
 use criterion::{
     criterion_group,
     criterion_main,
@@ -27,6 +29,8 @@ fn mock_updates(symbols: &[String], count: usize) -> Vec<TopOfBookUpdate> {
             symbol: symbol.clone(),
             bid_price: 1.0 + (i as f64 % 100.0) * 0.0001,
             ask_price: 1.0 + (i as f64 % 100.0) * 0.00015,
+            recv_ts: Instant::now(),
+            event_time_ms: None,
         })
     }
     updates.shuffle(&mut thread_rng());
@@ -2,6 +2,9 @@
 
 // cargo bench --bench arb_thru -- --save-baseline current
 
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
 use criterion::{
     criterion_group,
     criterion_main,
@@ -27,6 +30,8 @@ fn mock_updates(symbols: &[String], count: usize) -> Vec<TopOfBookUpdate> {
             symbol: symbol.clone(),
             bid_price: 1.0 + (i as f64 % 100.0) * 0.0001,
             ask_price: 1.0 + (i as f64 % 100.0) * 0.00015,
+            recv_ts: Instant::now(),
+            event_time_ms: None,
         });
     }
     updates.shuffle(&mut thread_rng());
@@ -34,19 +39,156 @@ fn mock_updates(symbols: &[String], count: usize) -> Vec<TopOfBookUpdate> {
 }
 
 
+/// Number of linear sub-buckets per power-of-two range (must be a power of two).
+const SUB_BUCKETS: u64 = 16;
+const SUB_BUCKET_BITS: u32 = SUB_BUCKETS.trailing_zeros();
+
+/// Fixed-size log-linear latency histogram (nanosecond resolution, ~6% relative error).
+///
+/// Criterion reports mean/median over whole iterations, which hides per-update tail latency.
+/// This records individual `process_update` calls in constant memory so p50/p99/max can be
+/// printed next to Criterion's estimates.
+struct LatencyHistogram {
+    counts: Vec<u64>,
+    total: u64,
+    max_ns: u64,
+}
+
+impl LatencyHistogram {
+    fn new() -> Self {
+        let buckets = (64 - SUB_BUCKET_BITS as usize + 1) * SUB_BUCKETS as usize;
+        Self { counts: vec![0; buckets], total: 0, max_ns: 0 }
+    }
+
+    fn bucket_index(ns: u64) -> usize {
+        if ns < SUB_BUCKETS {
+            return ns as usize;
+        }
+        let exp = 63 - ns.leading_zeros();
+        let shift = exp - SUB_BUCKET_BITS;
+        let mantissa = (ns >> shift) - SUB_BUCKETS;
+        ((shift as u64 + 1) * SUB_BUCKETS + mantissa) as usize
+    }
+
+    /// Upper bound (inclusive) of the values that land in bucket `index`.
+    fn bucket_upper_bound(index: usize) -> u64 {
+        let index = index as u64;
+        if index < SUB_BUCKETS {
+            return index;
+        }
+        let shift = index / SUB_BUCKETS - 1;
+        let mantissa = index % SUB_BUCKETS + SUB_BUCKETS;
+        ((((mantissa + 1) as u128) << shift) - 1).min(u64::MAX as u128) as u64
+    }
+
+    fn record(&mut self, elapsed: Duration) {
+        let ns = elapsed.as_nanos().min(u64::MAX as u128) as u64;
+        self.counts[Self::bucket_index(ns)] += 1;
+        self.total += 1;
+        self.max_ns = self.max_ns.max(ns);
+    }
+
+    fn percentile(&self, p: f64) -> u64 {
+        if self.total == 0 {
+            return 0;
+        }
+        let rank = ((p / 100.0) * self.total as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Self::bucket_upper_bound(index).min(self.max_ns);
+            }
+        }
+        self.max_ns
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+        self.total += other.total;
+        self.max_ns = self.max_ns.max(other.max_ns);
+    }
+
+    fn report(&self, label: &str) {
+        println!(
+            "{label:<40} samples={:<10} p50={:>8}ns p90={:>8}ns p99={:>8}ns p99.9={:>8}ns max={:>8}ns",
+            self.total,
+            self.percentile(50.0),
+            self.percentile(90.0),
+            self.percentile(99.0),
+            self.percentile(99.9),
+            self.max_ns,
+        );
+    }
+}
+
+
+/// Samples Criterion takes per bench; both groups set it so `SampledHistograms` knows how many
+/// `iter_custom` calls were measured.
+const SAMPLE_SIZE: usize = 100;
+
+/// One `LatencyHistogram` per `iter_custom` call, keeping only the last `SAMPLE_SIZE`.
+///
+/// Criterion's warm-up calls `iter_custom` too, and its measured samples are always the final
+/// `SAMPLE_SIZE` calls, so the merged report covers exactly the timed iterations behind
+/// Criterion's estimate and none of the warm-up.
+struct SampledHistograms {
+    batches: VecDeque<LatencyHistogram>,
+}
+
+impl SampledHistograms {
+    fn new() -> Self {
+        Self { batches: VecDeque::with_capacity(SAMPLE_SIZE) }
+    }
+
+    /// A fresh histogram for the `iter_custom` call about to run.
+    fn next_batch(&mut self) -> &mut LatencyHistogram {
+        if self.batches.len() == SAMPLE_SIZE {
+            self.batches.pop_front();
+        }
+        self.batches.push_back(LatencyHistogram::new());
+        self.batches.back_mut().unwrap()
+    }
+
+    /// Prints the merged histogram; nothing if the bench was filtered out.
+    fn report(&self, label: &str) {
+        let mut merged = LatencyHistogram::new();
+        for batch in &self.batches {
+            merged.merge(batch);
+        }
+        if merged.total > 0 {
+            merged.report(label);
+        }
+    }
+}
+
+
 fn bench_scanner_throughput<B: ArbEvaluator>(
     group: &mut BenchmarkGroup<'_, criterion::measurement::WallTime>,
     name: &str,
     updates: &[TopOfBookUpdate],
     scanner: B,
 ) {
+    let mut samples = SampledHistograms::new();
     group.bench_function(name, |b| {
-        b.iter(|| {
-            for u in black_box(updates) {
-                let _ = scanner.process_update(u);
+        b.iter_custom(|iters| {
+            let histogram = samples.next_batch();
+            let mut total = Duration::ZERO;
+            for _ in 0..iters {
+                for u in black_box(updates) {
+                    let start = Instant::now();
+                    let _ = black_box(scanner.process_update(u));
+                    let elapsed = start.elapsed();
+                    histogram.record(elapsed);
+                    total += elapsed;
+                }
             }
+            total
         });
     });
+    samples.report(&format!("arb_throughput/{name}"));
 }
 
 
@@ -65,6 +207,7 @@ fn bench_arb_scanner_throughput(c: &mut Criterion) {
 
     let mut group = c.benchmark_group("arb_throughput");
     group.throughput(Throughput::Elements(n_updates as u64));
+    group.sample_size(SAMPLE_SIZE);
     
     bench_scanner_throughput(&mut group, "naive", &updates, naive);
     bench_scanner_throughput(&mut group, "edge", &updates, edge);
@@ -74,5 +217,59 @@ fn bench_arb_scanner_throughput(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(arb_thru_benches, bench_arb_scanner_throughput);
-criterion_main!(arb_thru_benches);
\ No newline at end of file
+
+fn bench_scanner_single_update<B: ArbEvaluator>(
+    group: &mut BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    name: &str,
+    updates: &[TopOfBookUpdate],
+    scanner: B,
+) {
+    let mut samples = SampledHistograms::new();
+    let mut cursor = 0;
+    group.bench_function(name, |b| {
+        b.iter_custom(|iters| {
+            let histogram = samples.next_batch();
+            let mut total = Duration::ZERO;
+            for _ in 0..iters {
+                let u = &updates[cursor % updates.len()];
+                cursor += 1;
+                let start = Instant::now();
+                let _ = black_box(scanner.process_update(black_box(u)));
+                let elapsed = start.elapsed();
+                histogram.record(elapsed);
+                total += elapsed;
+            }
+            total
+        });
+    });
+    samples.report(&format!("arb_latency/{name}"));
+}
+
+
+fn bench_arb_scanner_latency(c: &mut Criterion) {
+    let path_count = 50;
+    let n_updates = 10_000;
+
+    let (paths, symbols) = sample_paths("USDT", path_count).expect("Failed to sample paths");
+    let updates = mock_updates(&symbols, n_updates);
+
+    // Arb scanners
+    let naive = NaivePrecompiledScanner::new(paths.clone());
+    let edge = HashMapEdgeScanner::new(paths.clone());
+    let rayon_best = RayonBestMatchScanner::new(paths.clone());
+    let rayon_first = RayonFirstMatchScanner::new(paths.clone());
+
+    let mut group = c.benchmark_group(format!("arb_latency/single_update/paths={path_count}"));
+    group.throughput(Throughput::Elements(1));
+    group.sample_size(SAMPLE_SIZE);
+
+    bench_scanner_single_update(&mut group, "naive", &updates, naive);
+    bench_scanner_single_update(&mut group, "edge", &updates, edge);
+    bench_scanner_single_update(&mut group, "rayon_best", &updates, rayon_best);
+    bench_scanner_single_update(&mut group, "rayon_first", &updates, rayon_first);
+
+    group.finish();
+}
+
+criterion_group!(arb_thru_benches, bench_arb_scanner_throughput, bench_arb_scanner_latency);
+criterion_main!(arb_thru_benches);
@@ -0,0 +1,165 @@
+// benches/opportunity_clone.rs
+
+// cargo bench --bench opportunity_clone -- --save-baseline current
+// cargo bench --bench opportunity_clone -- opportunity_report
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use criterion::{
+    criterion_group,
+    criterion_main,
+    BenchmarkGroup,
+    BenchmarkId,
+    Criterion,
+    Throughput,
+    black_box,
+};
+
+use tri_arb::arb::config::OnUpdateReturn;
+use tri_arb::arb::{ArbEvaluator, ArbMode, ArbOpportunity, ScannerBuilder};
+use tri_arb::parse::TopOfBookUpdate;
+use tri_arb::price_path::{PathLeg, PricingPath, Side, SymbolInfo};
+
+
+fn leg(symbol: &str, base: &str, quote: &str, side: Side) -> PathLeg {
+    PathLeg {
+        symbol: Arc::new(SymbolInfo {
+            symbol: symbol.to_string(),
+            base_asset: base.to_string(),
+            quote_asset: quote.to_string(),
+            status: "TRADING".into(),
+            tick_size: None,
+        }),
+        side,
+    }
+}
+
+/// USDT → ETH → BTC → USDT, returning ≈1.1663 at `QUOTES`.
+fn profitable_path() -> PricingPath {
+    PricingPath {
+        leg1: leg("ETHUSDT", "ETH", "USDT", Side::Ask),
+        leg2: leg("ETHBTC", "ETH", "BTC", Side::Bid),
+        leg3: leg("BTCUSDT", "BTC", "USDT", Side::Bid),
+    }
+}
+
+const QUOTES: [(&str, f64, f64); 3] = [
+    ("ETHUSDT", 3000.0, 3001.0),
+    ("ETHBTC", 0.07, 0.0701),
+    ("BTCUSDT", 50000.0, 50010.0),
+];
+
+/// Cycles the path's three quotes; once warmed, every update completes the profitable cycle.
+fn cycling_updates(count: usize) -> Vec<TopOfBookUpdate> {
+    (0..count)
+        .map(|i| {
+            let (symbol, bid_price, ask_price) = QUOTES[i % QUOTES.len()];
+            TopOfBookUpdate {
+                symbol: symbol.to_string(),
+                bid_price,
+                ask_price,
+                recv_ts: Instant::now(),
+                event_time_ms: None,
+            }
+        })
+        .collect()
+}
+
+const MODES: [(&str, ArbMode, OnUpdateReturn); 7] = [
+    ("naive", ArbMode::Naive, OnUpdateReturn::First),
+    ("edge", ArbMode::EdgeMap, OnUpdateReturn::First),
+    ("interned", ArbMode::Interned, OnUpdateReturn::First),
+    ("rayon_first", ArbMode::RayonScan, OnUpdateReturn::First),
+    ("rayon_best", ArbMode::RayonScan, OnUpdateReturn::Best),
+    ("simd", ArbMode::SimdBatch, OnUpdateReturn::First),
+    ("adaptive", ArbMode::Adaptive, OnUpdateReturn::First),
+];
+
+
+fn bench_scanner(
+    group: &mut BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    id: BenchmarkId,
+    updates: &[TopOfBookUpdate],
+    scanner: &dyn ArbEvaluator,
+) {
+    group.bench_with_input(id, updates, |b, updates| {
+        b.iter(|| {
+            for u in black_box(updates) {
+                let _ = black_box(scanner.process_update(u));
+            }
+        });
+    });
+}
+
+
+/// Every update reports (`hit`), against the same pricing work with `min_return` set out of
+/// reach (`miss`). The gap between the two is the cost of building and returning an
+/// `ArbOpportunity`, which the random-price benches never pay because their updates rarely
+/// profit. Opportunities share the scanner's `Arc<PricingPath>`, so no path is deep-cloned.
+pub fn bench_opportunity_found(c: &mut Criterion) {
+    let n_updates = 3_000;
+    let updates = cycling_updates(n_updates);
+
+    let mut group = c.benchmark_group("opportunity_found");
+    group.throughput(Throughput::Elements(n_updates as u64));
+
+    for (name, mode, on_update_return) in MODES {
+        for (outcome, min_return) in [("hit", 1.0), ("miss", 10.0)] {
+            let scanner = ScannerBuilder::new(vec![profitable_path()])
+                .with_min_return(min_return)
+                .with_on_update_return(on_update_return.clone())
+                .build(mode);
+            for u in &updates[..QUOTES.len()] {
+                scanner.process_update(u);
+            }
+            assert_eq!(scanner.process_update(&updates[0]).is_some(), outcome == "hit", "{name} {outcome}");
+
+            bench_scanner(&mut group, BenchmarkId::new(outcome, name), &updates, scanner.as_ref());
+        }
+    }
+
+    group.finish();
+}
+
+
+/// The `Some` branch's path handoff in isolation: a deep `PricingPath` clone (six `String`s
+/// across three `SymbolInfo`s, plus statuses) versus bumping the refcount of an `Arc<PricingPath>`,
+/// which the scanners already index paths by.
+pub fn bench_opportunity_path_return(c: &mut Criterion) {
+    let path = profitable_path();
+    let shared = Arc::new(profitable_path());
+
+    let mut group = c.benchmark_group("opportunity_path_return");
+    group.bench_function("clone_path", |b| b.iter(|| black_box(&path).clone()));
+    group.bench_function("arc_path", |b| b.iter(|| Arc::clone(black_box(&shared))));
+    group.finish();
+}
+
+
+/// Building a whole `ArbOpportunity` on the detection path: from the scanner's shared path
+/// (`shared`, what every scanner does) versus from a fresh deep copy of it (`deep_clone`, the
+/// allocations each report used to pay before opportunities carried `Arc<PricingPath>`).
+pub fn bench_opportunity_report(c: &mut Criterion) {
+    let shared = Arc::new(profitable_path());
+    let quotes = cycling_updates(QUOTES.len());
+    let quotes = [&quotes[0], &quotes[1], &quotes[2]];
+    let home_asset: Arc<str> = Arc::from("USDT");
+    let report = |path: Arc<PricingPath>| {
+        ArbOpportunity::new(path, Arc::clone(&home_asset), 1.0, 1.1663, [1.0, 1.0, 1.1663], 1.0, quotes)
+    };
+
+    let mut group = c.benchmark_group("opportunity_report");
+    group.bench_function("shared", |b| b.iter(|| report(Arc::clone(black_box(&shared)))));
+    group.bench_function("deep_clone", |b| b.iter(|| report(Arc::new(black_box(&shared).as_ref().clone()))));
+    group.finish();
+}
+
+
+criterion_group!(
+    opportunity_clone_benches,
+    bench_opportunity_found,
+    bench_opportunity_path_return,
+    bench_opportunity_report,
+);
+criterion_main!(opportunity_clone_benches);
@@ -11,8 +11,8 @@ const SAMPLE_MSG: &str = r#"{"e":"bookTicker","u":123456,"s":"BTCUSDT","b":"3000
 pub fn bench_single_parse(c: &mut Criterion) {
     let input = Bytes::from_static(SAMPLE_MSG.as_bytes());
 
-    let serde_parser = SerdeJsonParser;
-    let manual_parser = ManualScanParser;
+    let serde_parser = SerdeJsonParser::new();
+    let manual_parser = ManualScanParser::new();
 
     c.bench_function("parse/serde_json/single", |b| {
         b.iter(|| {
@@ -36,8 +36,8 @@ pub fn bench_batch_parse(c: &mut Criterion) {
         .map(|_| single_msg.clone())
         .collect();
 
-    let serde_parser = SerdeJsonParser;
-    let manual_parser = ManualScanParser;
+    let serde_parser = SerdeJsonParser::new();
+    let manual_parser = ManualScanParser::new();
 
     c.bench_function(&format!("parse/serde_json/batch_parse_{}", batch_size), |b| {
         b.iter(|| {
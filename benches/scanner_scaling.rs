@@ -0,0 +1,170 @@
+// benches/scanner_scaling.rs
+
+// cargo bench --bench scanner_scaling -- --save-baseline current
+// cargo bench --bench scanner_scaling -- hot_symbol_batch
+// cargo bench --bench scanner_scaling -- adaptive_crossover
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use criterion::{
+    criterion_group,
+    criterion_main,
+    BenchmarkGroup,
+    BenchmarkId,
+    Criterion,
+    Throughput,
+    black_box,
+};
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+use tri_arb::arb::{
+    AdaptiveScanner,
+    ArbEvaluator,
+    HashMapEdgeScanner,
+    InternedEdgeScanner,
+    RayonBestMatchScanner,
+    RayonFirstMatchScanner,
+    SimdBatchScanner,
+};
+use tri_arb::devtools::path_sampler::sample_paths;
+use tri_arb::parse::TopOfBookUpdate;
+use tri_arb::price_path::{PathLeg, PricingPath, Side, SymbolInfo};
+
+
+fn mock_updates(symbols: &[String], count: usize) -> Vec<TopOfBookUpdate> {
+    let mut updates = Vec::with_capacity(count);
+    for i in 0..count {
+        let symbol = &symbols[i % symbols.len()];
+        updates.push(TopOfBookUpdate {
+            symbol: symbol.clone(),
+            bid_price: 1.0 + (i as f64 % 100.0) * 0.0001,
+            ask_price: 1.0 + (i as f64 % 100.0) * 0.00015,
+            recv_ts: Instant::now(),
+            event_time_ms: None,
+        });
+    }
+    updates.shuffle(&mut thread_rng());
+    updates
+}
+
+
+fn bench_scanner<B: ArbEvaluator>(
+    group: &mut BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    name: &str,
+    path_count: usize,
+    updates: &[TopOfBookUpdate],
+    scanner: B,
+) {
+    group.bench_with_input(BenchmarkId::new(name, path_count), updates, |b, updates| {
+        b.iter(|| {
+            for u in black_box(updates) {
+                let _ = scanner.process_update(u);
+            }
+        });
+    });
+}
+
+
+/// Sweeps universe size and compares the string-keyed edge scanner against the
+/// interned-id scanner on identical update streams, reported as elements/sec.
+pub fn bench_scanner_scaling(c: &mut Criterion) {
+    let n_updates = 50_000;
+
+    let mut group = c.benchmark_group("scanner_scaling");
+    group.throughput(Throughput::Elements(n_updates as u64));
+
+    for path_count in [5, 50, 500, 5000] {
+        let (paths, symbols) = sample_paths("USDT", path_count).expect("path sampling failed");
+        if paths.len() < path_count {
+            println!("⚠️ Fixture only yields {} paths (requested {path_count})", paths.len());
+        }
+        let updates = mock_updates(&symbols, n_updates);
+
+        bench_scanner(&mut group, "edge_string", path_count, &updates, HashMapEdgeScanner::new(paths.clone()));
+        bench_scanner(&mut group, "edge_interned", path_count, &updates, InternedEdgeScanner::new(paths));
+    }
+
+    group.finish();
+}
+
+
+fn leg(symbol: &str, base: &str, quote: &str, side: Side) -> PathLeg {
+    PathLeg {
+        symbol: Arc::new(SymbolInfo {
+            symbol: symbol.to_string(),
+            base_asset: base.to_string(),
+            quote_asset: quote.to_string(),
+            status: "TRADING".into(),
+            tick_size: None,
+        }),
+        side,
+    }
+}
+
+/// `count` triangles USDT → BTC → X{i} → USDT, all sharing the BTCUSDT leg.
+fn hot_symbol_paths(count: usize) -> Vec<PricingPath> {
+    (0..count)
+        .map(|i| {
+            let alt = format!("X{i}");
+            PricingPath {
+                leg1: leg("BTCUSDT", "BTC", "USDT", Side::Ask),
+                leg2: leg(&format!("{alt}BTC"), &alt, "BTC", Side::Ask),
+                leg3: leg(&format!("{alt}USDT"), &alt, "USDT", Side::Bid),
+            }
+        })
+        .collect()
+}
+
+/// Feeds every leg once, then returns the scanner so the bench only measures hot-symbol updates.
+fn warmed<B: ArbEvaluator>(scanner: B, paths: &[PricingPath]) -> B {
+    for path in paths {
+        for symbol in path.symbols() {
+            scanner.process_update(&TopOfBookUpdate { symbol, bid_price: 1.0, ask_price: 1.0001, recv_ts: Instant::now(), event_time_ms: None });
+        }
+    }
+    scanner
+}
+
+
+/// 500 paths hanging off one symbol: the batch case the SIMD scanner targets. Only BTCUSDT
+/// updates are replayed, so every update re-scores all 500 paths.
+pub fn bench_hot_symbol_batch(c: &mut Criterion) {
+    let path_count = 500;
+    let n_updates = 5_000;
+    let paths = hot_symbol_paths(path_count);
+    let updates = mock_updates(&["BTCUSDT".to_string()], n_updates);
+
+    let mut group = c.benchmark_group("hot_symbol_batch");
+    group.throughput(Throughput::Elements(n_updates as u64));
+
+    bench_scanner(&mut group, "edge_string", path_count, &updates, warmed(HashMapEdgeScanner::new(paths.clone()), &paths));
+    bench_scanner(&mut group, "rayon_best", path_count, &updates, warmed(RayonBestMatchScanner::new(paths.clone()), &paths));
+    bench_scanner(&mut group, "simd_batch", path_count, &updates, warmed(SimdBatchScanner::new(paths.clone()), &paths));
+
+    group.finish();
+}
+
+/// Hot-symbol updates touching 4 to 1024 paths: the edge scanner should win at the low end,
+/// Rayon at the high end, and the adaptive scanner should track whichever is faster.
+pub fn bench_adaptive_crossover(c: &mut Criterion) {
+    let n_updates = 2_000;
+    let updates = mock_updates(&["BTCUSDT".to_string()], n_updates);
+
+    let mut group = c.benchmark_group("adaptive_crossover");
+    group.throughput(Throughput::Elements(n_updates as u64));
+
+    for path_count in [4, 16, 64, 256, 1024] {
+        let paths = hot_symbol_paths(path_count);
+        bench_scanner(&mut group, "edge_string", path_count, &updates, warmed(HashMapEdgeScanner::new(paths.clone()), &paths));
+        bench_scanner(&mut group, "rayon_first", path_count, &updates, warmed(RayonFirstMatchScanner::new(paths.clone()), &paths));
+        bench_scanner(&mut group, "adaptive", path_count, &updates, warmed(AdaptiveScanner::new(paths.clone()), &paths));
+    }
+
+    group.finish();
+}
+
+
+criterion_group!(scanner_scaling_benches, bench_scanner_scaling, bench_hot_symbol_batch, bench_adaptive_crossover);
+criterion_main!(scanner_scaling_benches);
@@ -0,0 +1,54 @@
+// benches/wire.rs
+
+// Run these in isolation -> cargo bench --bench wire
+
+use std::time::Instant;
+
+use bytes::Bytes;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tri_arb::parse::man_scan::ManualScanParser;
+use tri_arb::parse::wire::{decode, encode, SymbolTable};
+use tri_arb::parse::{BookTickerParser, TopOfBookUpdate};
+
+/// Moves one update across the in-process boundary both ways: JSON as the mock ws server
+/// formats it and the manual scanner parses it, against the packed binary record.
+pub fn bench_round_trip(c: &mut Criterion) {
+    let update = TopOfBookUpdate {
+        symbol: "BTCUSDT".to_string(),
+        bid_price: 30000.12,
+        ask_price: 30001.45,
+        recv_ts: Instant::now(),
+        event_time_ms: None,
+    };
+    let parser = ManualScanParser::new();
+    let mut sender = SymbolTable::from_symbols(["BTCUSDT"]);
+    let receiver = SymbolTable::from_symbols(["BTCUSDT"]);
+
+    let mut group = c.benchmark_group("wire/round_trip");
+    group.bench_function("json", |b| {
+        b.iter(|| {
+            let update = black_box(&update);
+            let json = format!(
+                r#"{{"e":"bookTicker","u":123456,"s":"{}","b":"{}","B":"1.0","a":"{}","A":"2.0"}}"#,
+                update.symbol, update.bid_price, update.ask_price
+            );
+            parser.parse(&Bytes::from(json)).unwrap()
+        })
+    });
+    group.bench_function("packed", |b| {
+        b.iter(|| {
+            let bytes = encode(black_box(&update), &mut sender);
+            decode(&bytes, &receiver).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    wire_benches,
+    bench_round_trip,
+);
+
+criterion_main!(
+    wire_benches
+);
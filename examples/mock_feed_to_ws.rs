@@ -1,71 +1,62 @@
 // examples/mock_feed_to_ws.rs
 
-//! Example: Connect to the mock WebSocket feed and print raw bookTicker messages.
+//! Example: Run the full pipeline against the mock WebSocket feed.
 //!
 //! This example demonstrates how to:
 //! - Build pricing paths for a set of target assets
 //! - Launch the in-process mock Binance-style WebSocket server
-//! - Use the production WebSocket client to subscribe to mock data
-//! - Print raw `bookTicker` JSON messages received from the mock server
+//! - Wire the production ws → parse → arb stages with `Pipeline::builder()`
+//! - Print opportunities as they are found, and how many quotes each symbol has delivered
 //!
 //! Run with:
 //! ```bash
 //! cargo run --example mock_feed_to_ws
 //! ```
 
-use std::collections::HashSet;
+use std::time::Duration;
 
-use bytes::Bytes;
-use tokio::sync::mpsc;
-
-use tri_arb::price_path::find_and_build_price_paths;
+use tri_arb::arb::ArbMode;
 use tri_arb::mock_feed::hot_cache::start_hot_cache_updater;
 use tri_arb::mock_feed::ws_server;
-use tri_arb::ws::start_ws_listener;
+use tri_arb::pipeline::{Endpoint, Pipeline};
+use tri_arb::ws::extract_symbols_from_paths;
 
 
 #[tokio::main]
-async fn main() {
+async fn main() -> anyhow::Result<()> {
     // Home asset (e.g., USDT) used as the base for pricing paths.
     let home_asset = "USDT";
 
     // Target assets we want to trade against the home asset.
     let targets = ["BTC", "ETH", "SOL"];
 
-    // Generate 3-leg arbitrage paths using your production logic.
-    let price_paths = find_and_build_price_paths(home_asset, &targets)
-        .unwrap_or_else(|e| panic!("Unable to build price paths: {e}"));
-
-    // Extract all unique market symbols (e.g., BTCUSDT) from pricing paths.
-    let mut unique_symbols: HashSet<String> = HashSet::new();
-    for path in &price_paths {
-        unique_symbols.extend(path.symbols());
-    }
-    let symbols: Vec<String> = unique_symbols.iter().cloned().collect();
+    // Discover 3-leg arbitrage paths and build the scanner, without connecting yet.
+    let mut pipeline = Pipeline::builder()
+        .with_universe(home_asset, &targets)
+        .with_mode(ArbMode::RayonScan)
+        .with_endpoint(Endpoint::LocalMock)
+        .build()?;
 
-    // Start a high-frequency market data generator (the "hot cache").
-    // This acts as the simulated exchange backend.
-    let cache = start_hot_cache_updater(symbols.clone(), 20);
+    // Start a high-frequency market data generator (the "hot cache") for every symbol the
+    // paths trade. This acts as the simulated exchange backend.
+    let cache = start_hot_cache_updater(extract_symbols_from_paths(pipeline.price_paths()), 20, None);
 
     // Start a WebSocket server that streams from the hot cache.
     // Clients will connect and subscribe just like they would to Binance.
     tokio::spawn(ws_server::run(cache));
+    tokio::time::sleep(Duration::from_millis(100)).await;
 
-    // Create a channel to receive mock data frames from the client.
-    let (tx, mut rx) = mpsc::channel::<Bytes>(100);
-    
-    // Start the production WebSocket client and have it subscribe to mock symbols.
-    tokio::spawn({
-        let paths = price_paths.clone();
-        async move {
-            start_ws_listener(paths, tx, Some(true)).await.unwrap();
-        }
-    });
-
+    // Subscribe, parse and scan; opportunities are printed by the default stdout sink.
+    pipeline.start();
     println!("📡 Listening to mock feed for {:?}", targets);
 
-    // Print each received message (raw `bookTicker` JSON) to stdout.
-    while let Some(msg) = rx.recv().await {
-        println!("📥 {}", String::from_utf8_lossy(&msg));
+    let mut ticker = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => println!("📥 Quotes per symbol: {:?}", pipeline.quote_counts()),
+            _ = tokio::signal::ctrl_c() => break,
+        }
     }
-}
\ No newline at end of file
+    pipeline.shutdown().await?;
+    Ok(())
+}
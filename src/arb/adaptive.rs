@@ -0,0 +1,159 @@
+// src/arb/adaptive.rs
+
+use std::{collections::HashMap, sync::Arc};
+
+use rayon::prelude::*;
+
+use crate::{parse::TopOfBookUpdate, price_path::PricingPath};
+
+use super::{ArbEvaluator, ArbOpportunity, HaltHandle, ImpliedQuote, ScannerSettings};
+use super::cooldown::PathCooldown;
+use super::eviction::PriceEviction;
+use super::scanner_core::ScannerCore;
+use super::quote_filter::store_quote;
+use super::rayon_scan::scan_path;
+
+
+/// Affected-path count from which `AdaptiveScanner` fans an update out over Rayon.
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 64;
+
+/// `AdaptiveScanner` picks, per update, between the edge scanner's serial loop and the Rayon
+/// scanners' parallel one.
+///
+/// An update touching fewer than `parallel_threshold` paths is priced serially, avoiding
+/// Rayon's scheduling overhead where `HashMapEdgeScanner` wins; a hot symbol at or above it
+/// is priced like `RayonFirstMatchScanner`. Both report the first profitable path found.
+pub struct AdaptiveScanner {
+    core: ScannerCore,
+    settings: ScannerSettings,
+    eviction: PriceEviction,
+    cooldown: PathCooldown,
+    parallel_threshold: usize,
+}
+
+impl AdaptiveScanner {
+    pub fn new(price_paths: Vec<PricingPath>) -> Self {
+        Self::with_settings(price_paths, ScannerSettings::default())
+    }
+
+    /// Constructs the scanner with custom evaluation settings (see `ScannerBuilder`).
+    pub fn with_settings(price_paths: Vec<PricingPath>, settings: ScannerSettings) -> Self {
+        Self {
+            core: ScannerCore::new(price_paths, &settings),
+            eviction: PriceEviction::new(settings.price_ttl),
+            cooldown: PathCooldown::new(settings.opportunity_cooldown),
+            settings,
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+        }
+    }
+
+    /// Affected-path count from which updates are priced in parallel (default 64).
+    pub fn with_parallel_threshold(mut self, parallel_threshold: usize) -> Self {
+        self.parallel_threshold = parallel_threshold;
+        self
+    }
+
+    /// The quotes, path index and return averages (see `ScannerCore`).
+    pub fn core(&self) -> &ScannerCore {
+        &self.core
+    }
+
+    /// Whether an update to `symbol` is priced over Rayon rather than serially.
+    pub fn is_parallel(&self, symbol: &str) -> bool {
+        self.core.paths_for_symbol(symbol).len() >= self.parallel_threshold
+    }
+}
+
+
+impl ArbEvaluator for AdaptiveScanner {
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        let now = self.settings.clock.now();
+        if !store_quote(&*self.core.price_store, &self.settings.quote_filter, update, now) {
+            return None;
+        }
+        let path_index = self.core.index();
+        self.eviction.maybe_sweep(&*self.core.price_store, now, |s| path_index.contains_key(s));
+        if self.settings.halt.is_halted() {
+            return None;
+        }
+        let relevant_paths = path_index.get(&update.symbol)?;
+        let scan = |path: &Arc<PricingPath>| {
+            scan_path(&*self.core.price_store, &self.settings, &self.cooldown, &self.core.return_history, path, update, now)
+        };
+        let opportunity = if relevant_paths.len() >= self.parallel_threshold {
+            relevant_paths.par_iter().find_map_any(scan)
+        } else {
+            relevant_paths.iter().find_map(scan)
+        };
+        opportunity.inspect(|opportunity| self.cooldown.record(&opportunity.path, now))
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.core.price_store.snapshot()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        self.settings.halt.clone()
+    }
+
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        self.settings.stored_path_return(&*self.core.price_store, path)
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        self.settings.stored_implied_quote(&*self.core.price_store, path)
+    }
+
+    fn invalidate_all(&self) {
+        self.core.price_store.clear();
+    }
+
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        self.core.price_store.remove_symbols(symbols);
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        self.core.return_history.top(n)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price_path::Side;
+    use crate::testutil::{assert_return_eq, mock_update, MockBuilder};
+
+    #[test]
+    fn detects_opportunities_both_serially_and_in_parallel() {
+        // Eight triangles share BTCUSDT; each alt's cross pair is on only two paths
+        let alts = ["AAA", "BBB", "CCC", "DDD", "EEE", "FFF", "GGG", "HHH"];
+        let mut builder = MockBuilder::new();
+        for alt in alts {
+            builder = builder.triangle("USDT", "BTC", alt);
+        }
+        let paths = builder.paths("USDT", &["BTC"]);
+        let scanner = AdaptiveScanner::new(paths).with_parallel_threshold(4);
+        assert!(scanner.is_parallel("BTCUSDT"));
+        assert!(!scanner.is_parallel("HHHBTC"));
+
+        scanner.process_update(&mock_update("BTCUSDT", 50000.0, 50000.0));
+        for alt in alts {
+            scanner.process_update(&mock_update(&format!("{alt}USDT"), 50.0, 50.0));
+        }
+
+        // Few paths: HHHBTC's update is priced serially and finds the one profitable direction
+        let serial = scanner.process_update(&mock_update("HHHBTC", 0.00099, 0.00099)).expect("serial regime");
+        assert_eq!(serial.path.leg2.symbol.symbol, "HHHBTC");
+        assert_eq!(serial.path.leg1.side, Side::Ask);
+        assert_return_eq(serial.result, 1.0 / 50000.0 / 0.00099 * 50.0, 1e-12);
+
+        // Many paths: a BTCUSDT update fans out; HHH is the only alt with a profitable quote
+        for alt in &alts[..7] {
+            scanner.process_update(&mock_update(&format!("{alt}BTC"), 0.001, 0.001));
+        }
+        let parallel = scanner.process_update(&mock_update("BTCUSDT", 50000.0, 50000.0)).expect("parallel regime");
+        assert_eq!(parallel.path.leg2.symbol.symbol, "HHHBTC");
+        assert_return_eq(parallel.result, 1.0 / 50000.0 / 0.00099 * 50.0, 1e-12);
+    }
+}
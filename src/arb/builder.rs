@@ -0,0 +1,1000 @@
+// src/arb/builder.rs
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{ensure, Result};
+use tracing::info;
+
+use crate::parse::TopOfBookUpdate;
+use crate::price_path::{apply_leg, with_reversed_paths, PricingPath};
+
+use super::clock::{Clock, SystemClock};
+use super::confidence::ConfidenceReference;
+use super::cross_check::CrossCheckEvaluator;
+use super::config::{ArbConfig, OnUpdateReturn};
+use super::fees::FeeSchedule;
+use super::halt::HaltHandle;
+use super::implied::{implied_break_even, ImpliedQuote};
+use super::predicate::{EvalContext, GrossPositive, ProfitPredicate};
+use super::price_store::{DashMapPriceStore, PriceStore};
+use super::quote_filter::{QuoteFilter, StoredQuote};
+use super::adaptive::DEFAULT_PARALLEL_THRESHOLD;
+use super::rayon_scan::DEFAULT_BEST_MATCH_EPSILON;
+use super::{
+    leg_time_spread_ms, AdaptiveScanner, ArbEvaluator, ArbMode, HashMapEdgeScanner, InternedEdgeScanner, NaivePrecompiledScanner,
+    ProfitNormalizer, RayonBestMatchScanner, RayonFirstMatchScanner, RayonOrderedMatchScanner, SimdBatchScanner, TickSizeRounding,
+};
+
+
+/// Evaluation parameters shared by every scanner implementation.
+///
+/// The defaults reproduce the original fee-free, any-profit behaviour on a notional of 1.
+#[derive(Debug, Clone)]
+pub struct ScannerSettings {
+    pub quote_filter: QuoteFilter,
+    /// Taker fee charged on each leg: a default rate plus per-symbol overrides.
+    pub fees: FeeSchedule,
+    /// Asset every path starts and ends in; opportunities report their profit in it.
+    pub home_asset: Arc<str>,
+    /// Smallest net return (`end / start`, after fees) reported as an opportunity by the default
+    /// `GrossPositive` predicate; custom predicates see it in `EvalContext::min_return`.
+    pub min_return: f64,
+    /// Decides which priced paths are reported (`GrossPositive` by default).
+    pub profit_predicate: Arc<dyn ProfitPredicate>,
+    /// Home-asset amount routed through each path.
+    pub start_notional: f64,
+    /// Quotes older than this are treated as missing; `None` accepts quotes of any age.
+    pub max_staleness: Option<Duration>,
+    /// Measure quote age from the exchange event time (`event_time_ms`) against the clock's wall
+    /// time, rather than from local receipt. Quotes without an event time always use receipt.
+    pub event_time_staleness: bool,
+    /// After a path reports, further reports of it are suppressed for this long even if it stays
+    /// profitable; `None` reports on every profitable update.
+    pub opportunity_cooldown: Option<Duration>,
+    /// Profitable paths whose legs' exchange event times span more than this are not reported;
+    /// `None` reports them regardless. Legs without an event time never trigger the reject.
+    pub max_leg_time_spread: Option<Duration>,
+    /// Weight, in `(0, 1]`, of each new return in the per-path moving average behind
+    /// `ArbEvaluator::top_paths`; `None` keeps no history.
+    pub return_ewma_alpha: Option<f64>,
+    /// Shared kill switch; while halted the scanner keeps its prices but reports nothing.
+    pub halt: HaltHandle,
+    /// Spread and age against which each opportunity's `confidence` is scored.
+    pub confidence: ConfidenceReference,
+    /// Stored quotes not refreshed within this TTL are evicted unless a path trades the symbol;
+    /// `None` keeps every quote.
+    pub price_ttl: Option<Duration>,
+    /// Time source for quote timestamps, staleness, confidence, eviction and cooldowns.
+    pub clock: Arc<dyn Clock>,
+    /// Store shared by every scanner built with these settings; `None` gives each its own.
+    pub price_store: Option<Arc<dyn PriceStore>>,
+    /// Returns within this of the best are ties for `RayonBestMatchScanner` and
+    /// `SimdBatchScanner`, which report the lowest-indexed of them.
+    pub best_match_epsilon: f64,
+}
+
+impl Default for ScannerSettings {
+    fn default() -> Self {
+        Self {
+            quote_filter: QuoteFilter::default(),
+            fees: FeeSchedule::default(),
+            home_asset: Arc::from("USDT"),
+            min_return: 1.0,
+            profit_predicate: Arc::new(GrossPositive),
+            start_notional: 1.0,
+            max_staleness: None,
+            event_time_staleness: true,
+            opportunity_cooldown: None,
+            max_leg_time_spread: None,
+            return_ewma_alpha: None,
+            halt: HaltHandle::default(),
+            confidence: ConfidenceReference::default(),
+            price_ttl: None,
+            clock: Arc::new(SystemClock),
+            price_store: None,
+            best_match_epsilon: DEFAULT_BEST_MATCH_EPSILON,
+        }
+    }
+}
+
+impl ScannerSettings {
+    /// The shared `price_store`, or a fresh `DashMapPriceStore` for a scanner of its own.
+    pub(crate) fn scanner_price_store(&self) -> Arc<dyn PriceStore> {
+        self.price_store.clone().unwrap_or_else(|| Arc::new(DashMapPriceStore::new()))
+    }
+
+    /// Fraction of each of `path`'s legs' output kept after the taker fee, in leg order.
+    pub(crate) fn leg_multipliers(&self, path: &PricingPath) -> [f64; 3] {
+        self.fees.path_multipliers(path)
+    }
+
+    /// Whether a quote received at `received`, stamped `event_time_ms` by the exchange if it
+    /// carried one, is recent enough to price a leg.
+    pub(crate) fn is_fresh(&self, received: Instant, event_time_ms: Option<u64>) -> bool {
+        self.max_staleness.is_none_or(|max| {
+            let age = match event_time_ms {
+                Some(event_time) if self.event_time_staleness => {
+                    Duration::from_millis(self.clock.epoch_millis().saturating_sub(event_time))
+                }
+                _ => self.clock.now().saturating_duration_since(received),
+            };
+            age <= max
+        })
+    }
+
+    /// Whether legs quoted at these exchange event times are close enough together to report,
+    /// i.e. their `leg_time_spread_ms` is within `max_leg_time_spread` or unknown.
+    pub(crate) fn legs_in_sync(&self, event_times_ms: [Option<u64>; 3]) -> bool {
+        self.max_leg_time_spread.is_none_or(|max| {
+            leg_time_spread_ms(event_times_ms).is_none_or(|spread| spread <= max.as_millis() as u64)
+        })
+    }
+
+    /// Whether `path`, priced at `leg_prices` and returning `end` from `start_notional`, passes
+    /// `profit_predicate`. A non-finite `end` never does: an infinite return can only come from
+    /// a zero price.
+    pub(crate) fn is_profitable(&self, path: &PricingPath, leg_prices: [f64; 3], end: f64) -> bool {
+        end.is_finite()
+            && self.profit_predicate.accept(&EvalContext {
+                path,
+                leg_prices,
+                start_notional: self.start_notional,
+                end_notional: end,
+                min_return: self.min_return,
+            })
+    }
+
+    /// Confidence of a path priced from the given stored quotes.
+    pub(crate) fn confidence(&self, quotes: [&StoredQuote; 3]) -> f64 {
+        self.confidence.path(quotes.map(|q| (q.bid_price, q.ask_price, q.received)), self.clock.now())
+    }
+
+    /// Net return `end / start_notional` of `path` priced at `quotes` (in leg order), after fees.
+    pub(crate) fn path_return(&self, path: &PricingPath, quotes: [&TopOfBookUpdate; 3]) -> f64 {
+        let start = self.start_notional;
+        let keeps = self.leg_multipliers(path);
+        let end = [&path.leg1, &path.leg2, &path.leg3]
+            .into_iter()
+            .zip(quotes)
+            .zip(keeps)
+            .fold(start, |amount, ((leg, quote), keep)| keep * apply_leg(amount, leg.side, quote));
+        end / start
+    }
+
+    /// `path_return` at the latest quotes in `price_store`, or `None` if a leg was never quoted.
+    pub(crate) fn stored_path_return(&self, price_store: &dyn PriceStore, path: &PricingPath) -> Option<f64> {
+        let p1 = price_store.get(&path.leg1.symbol.symbol)?;
+        let p2 = price_store.get(&path.leg2.symbol.symbol)?;
+        let p3 = price_store.get(&path.leg3.symbol.symbol)?;
+        Some(self.path_return(path, [&p1, &p2, &p3]))
+    }
+
+    /// `implied_break_even` for `path` at the latest quotes in `price_store`, after fees.
+    pub(crate) fn stored_implied_quote(
+        &self,
+        price_store: &dyn PriceStore,
+        path: &PricingPath,
+    ) -> Option<ImpliedQuote> {
+        let quotes = [&path.leg1, &path.leg2, &path.leg3].map(|leg| price_store.get(&leg.symbol.symbol));
+        implied_break_even(path, [0, 1, 2].map(|i| quotes[i].as_deref().map(|q| &q.update)), &self.fees)
+    }
+}
+
+
+/// Fluent construction of any scanner with a shared set of evaluation options.
+///
+/// ```rust,ignore
+/// let evaluator = ScannerBuilder::new(paths)
+///     .with_fee_bps(10.0)
+///     .with_min_return(1.0005)
+///     .with_max_staleness(Duration::from_millis(500))
+///     .build(ArbMode::EdgeMap);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScannerBuilder {
+    price_paths: Vec<PricingPath>,
+    settings: ScannerSettings,
+    on_update_return: OnUpdateReturn,
+    evaluate_reversed: bool,
+    round_to_tick_size: bool,
+    cross_check: Option<ArbMode>,
+    parallel_threshold: usize,
+    reporting_asset: Option<String>,
+    reference_rates: HashMap<String, f64>,
+}
+
+impl ScannerBuilder {
+    pub fn new(price_paths: Vec<PricingPath>) -> Self {
+        Self {
+            price_paths,
+            settings: ScannerSettings::default(),
+            on_update_return: OnUpdateReturn::default(),
+            evaluate_reversed: false,
+            round_to_tick_size: false,
+            cross_check: None,
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+            reporting_asset: None,
+            reference_rates: HashMap::new(),
+        }
+    }
+
+    /// Seeds the builder from `config/arb.toml` sections; missing sections keep defaults.
+    ///
+    /// Fails on a value the matching `with_*` method would reject, so a bad config file is
+    /// reported rather than panicking.
+    pub fn from_config(price_paths: Vec<PricingPath>, config: Option<&ArbConfig>) -> Result<Self> {
+        let mut builder = Self::new(price_paths);
+        let Some(config) = config else {
+            return Ok(builder);
+        };
+
+        if let Some(quote_filter) = config.quote_filter {
+            let ratio = quote_filter.max_spread_ratio;
+            ensure!(
+                ratio.is_finite() && ratio > 0.0,
+                "[quote_filter] max_spread_ratio must be a finite positive ratio, got {ratio}"
+            );
+            builder = builder.with_quote_filter(quote_filter);
+        }
+        if let Some(rayon_scan) = &config.rayon_scan {
+            let epsilon = rayon_scan.best_match_epsilon;
+            ensure!(
+                epsilon.is_finite() && epsilon >= 0.0,
+                "[rayon_scan] best_match_epsilon must be a finite non-negative number, got {epsilon}"
+            );
+            builder = builder
+                .with_on_update_return(rayon_scan.on_update_return.clone())
+                .with_best_match_epsilon(rayon_scan.best_match_epsilon);
+        }
+        if let Some(adaptive) = &config.adaptive {
+            builder = builder.with_parallel_threshold(adaptive.parallel_threshold);
+        }
+        if let Some(evaluation) = &config.evaluation {
+            let notional = evaluation.start_notional;
+            ensure!(
+                notional.is_finite() && notional > 0.0,
+                "[evaluation] start_notional must be a finite positive amount, got {notional}"
+            );
+            let fee_bps = evaluation.fee_bps;
+            ensure!(
+                fee_bps.is_finite() && fee_bps >= 0.0,
+                "[evaluation] fee_bps must be a finite non-negative number, got {fee_bps}"
+            );
+            for (symbol, &fee_bps) in &evaluation.fee_overrides_bps {
+                ensure!(
+                    fee_bps.is_finite() && fee_bps >= 0.0,
+                    "[evaluation.fee_overrides_bps] {symbol} must be a finite non-negative number, got {fee_bps}"
+                );
+            }
+            let min_return = evaluation.min_return;
+            ensure!(
+                min_return.is_finite() && min_return >= 0.0,
+                "[evaluation] min_return must be a finite non-negative number, got {min_return}"
+            );
+            builder = builder
+                .with_reversed_paths(evaluation.evaluate_reversed)
+                .with_tick_size_rounding(evaluation.round_to_tick_size)
+                .with_fee_bps(evaluation.fee_bps)
+                .with_min_return(evaluation.min_return)
+                .with_start_notional(evaluation.start_notional)
+                .with_confidence_reference(
+                    evaluation.confidence_spread_bps / 10_000.0,
+                    Duration::from_millis(evaluation.confidence_age_ms),
+                );
+            if let Some(ms) = evaluation.max_staleness_ms {
+                builder = builder.with_max_staleness(Duration::from_millis(ms));
+            }
+            builder = builder.with_event_time_staleness(evaluation.staleness_from_event_time);
+            for (symbol, &fee_bps) in &evaluation.fee_overrides_bps {
+                builder = builder.with_symbol_fee_bps(symbol, fee_bps);
+            }
+            if let Some(ms) = evaluation.opportunity_cooldown_ms {
+                builder = builder.with_opportunity_cooldown(Duration::from_millis(ms));
+            }
+            if let Some(ms) = evaluation.max_leg_time_spread_ms {
+                builder = builder.with_max_leg_time_spread(Duration::from_millis(ms));
+            }
+            if let Some(alpha) = evaluation.return_ewma_alpha {
+                ensure!(
+                    alpha > 0.0 && alpha <= 1.0,
+                    "[evaluation] return_ewma_alpha must be in (0, 1], got {alpha}"
+                );
+                builder = builder.with_return_ewma(alpha);
+            }
+            if let Some(ms) = evaluation.price_ttl_ms {
+                builder = builder.with_price_ttl(Duration::from_millis(ms));
+            }
+            if let Some(asset) = &evaluation.reporting_asset {
+                builder = builder.with_reporting_asset(asset);
+            }
+            for (asset, &rate) in &evaluation.reference_rates {
+                builder = builder.with_reference_rate(asset, rate);
+            }
+        }
+        Ok(builder)
+    }
+
+    /// Default taker fee per leg in basis points (10 bps = 0.1%).
+    pub fn with_fee_bps(mut self, fee_bps: f64) -> Self {
+        self.settings.fees.default_rate = fee_bps / 10_000.0;
+        self
+    }
+
+    /// Taker fee in basis points on legs trading `symbol`, replacing the default for them.
+    pub fn with_symbol_fee_bps(mut self, symbol: &str, fee_bps: f64) -> Self {
+        self.settings.fees = self.settings.fees.with_override(symbol, fee_bps / 10_000.0);
+        self
+    }
+
+    /// Smallest net return reported; `1.0` reports any profit.
+    pub fn with_min_return(mut self, min_return: f64) -> Self {
+        self.settings.min_return = min_return;
+        self
+    }
+
+    /// Replaces the default `GrossPositive` acceptance rule with `predicate`.
+    pub fn with_profit_predicate(mut self, predicate: impl ProfitPredicate + 'static) -> Self {
+        self.settings.profit_predicate = Arc::new(predicate);
+        self
+    }
+
+    /// Asset the paths start and end in, which `start_notional` and each opportunity's
+    /// `profit_home` are counted in (`USDT` by default).
+    pub fn with_home_asset(mut self, home_asset: &str) -> Self {
+        self.settings.home_asset = Arc::from(home_asset);
+        self
+    }
+
+    /// Home-asset amount routed through each path.
+    pub fn with_start_notional(mut self, start_notional: f64) -> Self {
+        self.settings.start_notional = start_notional;
+        self
+    }
+
+    /// Ignore quotes older than `max_staleness` when pricing a leg.
+    pub fn with_max_staleness(mut self, max_staleness: Duration) -> Self {
+        self.settings.max_staleness = Some(max_staleness);
+        self
+    }
+
+    /// Whether staleness is measured from a quote's exchange event time when it has one
+    /// (the default), or always from local receipt, e.g. when the host clock is not synced.
+    pub fn with_event_time_staleness(mut self, enabled: bool) -> Self {
+        self.settings.event_time_staleness = enabled;
+        self
+    }
+
+    /// Suppress repeat reports of a path for `cooldown` after it reports.
+    pub fn with_opportunity_cooldown(mut self, cooldown: Duration) -> Self {
+        self.settings.opportunity_cooldown = Some(cooldown);
+        self
+    }
+
+    /// Don't report opportunities whose legs' exchange event times span more than `max_spread`.
+    pub fn with_max_leg_time_spread(mut self, max_spread: Duration) -> Self {
+        self.settings.max_leg_time_spread = Some(max_spread);
+        self
+    }
+
+    /// Keep a moving average of every path's return, weighting each new one by `alpha`, and
+    /// rank paths by it in `top_paths`.
+    pub fn with_return_ewma(mut self, alpha: f64) -> Self {
+        assert!(alpha > 0.0 && alpha <= 1.0, "return EWMA alpha must be in (0, 1], got {alpha}");
+        self.settings.return_ewma_alpha = Some(alpha);
+        self
+    }
+
+    /// Replaces the system clock, e.g. with a manually advanced one in tests.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.settings.clock = clock;
+        self
+    }
+
+    /// Price from `price_store` instead of a store of the scanner's own, so every scanner built
+    /// from this builder (or its clones) shares one copy of the quotes.
+    pub fn with_price_store(mut self, price_store: Arc<dyn PriceStore>) -> Self {
+        self.settings.price_store = Some(price_store);
+        self
+    }
+
+    /// Evict stored quotes for symbols no path trades once they are older than `price_ttl`.
+    pub fn with_price_ttl(mut self, price_ttl: Duration) -> Self {
+        self.settings.price_ttl = Some(price_ttl);
+        self
+    }
+
+    /// Spread and quote age that halve an opportunity's `confidence` (see `ConfidenceReference`).
+    pub fn with_confidence_reference(mut self, spread_ratio: f64, age: Duration) -> Self {
+        self.settings.confidence = ConfidenceReference { spread_ratio, age };
+        self
+    }
+
+    pub fn with_quote_filter(mut self, quote_filter: QuoteFilter) -> Self {
+        self.settings.quote_filter = quote_filter;
+        self
+    }
+
+    /// Which opportunity `ArbMode::RayonScan` reports when several paths are profitable.
+    pub fn with_on_update_return(mut self, on_update_return: OnUpdateReturn) -> Self {
+        self.on_update_return = on_update_return;
+        self
+    }
+
+    /// Treat returns within `epsilon` of the best as ties in `OnUpdateReturn::Best` and
+    /// `ArbMode::SimdBatch`, broken towards the lowest path index.
+    pub fn with_best_match_epsilon(mut self, epsilon: f64) -> Self {
+        assert!(epsilon.is_finite() && epsilon >= 0.0, "best match epsilon must be finite and non-negative, got {epsilon}");
+        self.settings.best_match_epsilon = epsilon;
+        self
+    }
+
+    /// Affected-path count from which `ArbMode::Adaptive` prices an update over Rayon.
+    pub fn with_parallel_threshold(mut self, parallel_threshold: usize) -> Self {
+        self.parallel_threshold = parallel_threshold;
+        self
+    }
+
+    /// Report each opportunity's profit in `asset` as well, in `profit_normalized` (see
+    /// `ProfitNormalizer`).
+    pub fn with_reporting_asset(mut self, asset: &str) -> Self {
+        self.reporting_asset = Some(asset.to_string());
+        self
+    }
+
+    /// Value of one unit of `asset` in the reporting asset until its reference symbol is quoted.
+    pub fn with_reference_rate(mut self, asset: &str, rate: f64) -> Self {
+        self.reference_rates.insert(asset.to_string(), rate);
+        self
+    }
+
+    /// Also evaluate the reversal of each path.
+    pub fn with_reversed_paths(mut self, evaluate_reversed: bool) -> Self {
+        self.evaluate_reversed = evaluate_reversed;
+        self
+    }
+
+    /// Round quotes to each symbol's `tickSize` before evaluation.
+    pub fn with_tick_size_rounding(mut self, round_to_tick_size: bool) -> Self {
+        self.round_to_tick_size = round_to_tick_size;
+        self
+    }
+
+    /// Audit the built scanner against a `reference` scanner built from the same options (see
+    /// `CrossCheckEvaluator`); the reference's opportunities are the ones reported.
+    pub fn with_cross_check(mut self, reference: ArbMode) -> Self {
+        self.cross_check = Some(reference);
+        self
+    }
+
+    pub fn settings(&self) -> &ScannerSettings {
+        &self.settings
+    }
+
+    /// Handle to halt or resume the scanner `build` produces (shared by every clone).
+    pub fn halt_handle(&self) -> HaltHandle {
+        self.settings.halt.clone()
+    }
+
+    /// Constructs the scanner selected by `mode` with the accumulated options.
+    pub fn build(mut self, mode: ArbMode) -> Arc<dyn ArbEvaluator + Send + Sync> {
+        if let Some(reference) = self.cross_check.take() {
+            info!(candidate = ?mode, ?reference, "Cross-checking scanners");
+            // Clones share the halt handle and clock, so both scanners see the same switches
+            let reference = self.clone().build(reference);
+            return Arc::new(CrossCheckEvaluator::new(self.build(mode), reference));
+        }
+        info!(?mode, settings = ?self.settings, "Creating arbitrage evaluator");
+
+        let price_paths = if self.evaluate_reversed {
+            let listed = self.price_paths.len();
+            let price_paths = with_reversed_paths(self.price_paths);
+            info!(listed, total = price_paths.len(), "Evaluating reversed path directions");
+            price_paths
+        } else {
+            self.price_paths
+        };
+
+        // Tick sizes must be collected before the paths move into the scanner
+        let tick_rounding_paths = self.round_to_tick_size.then(|| price_paths.clone());
+        let home_asset = Arc::clone(&self.settings.home_asset);
+        let settings = self.settings;
+
+        let evaluator: Arc<dyn ArbEvaluator + Send + Sync> = match mode {
+            ArbMode::Naive => {
+                info!("Using NaivePrecompiledScanner");
+                Arc::new(NaivePrecompiledScanner::with_settings(price_paths, settings))
+            }
+            ArbMode::EdgeMap => {
+                info!("Using HashMapEdgeScanner");
+                Arc::new(HashMapEdgeScanner::with_settings(price_paths, settings))
+            }
+            ArbMode::Interned => {
+                info!("Using InternedEdgeScanner");
+                Arc::new(InternedEdgeScanner::with_settings(price_paths, settings))
+            }
+            ArbMode::RayonScan => {
+                info!(on_update_return = ?self.on_update_return, "Using RayonPathScanner");
+                match self.on_update_return {
+                    OnUpdateReturn::First => Arc::new(RayonFirstMatchScanner::with_settings(price_paths, settings)),
+                    OnUpdateReturn::Best => Arc::new(RayonBestMatchScanner::with_settings(price_paths, settings)),
+                    OnUpdateReturn::Ordered => Arc::new(RayonOrderedMatchScanner::with_settings(price_paths, settings)),
+                }
+            }
+            ArbMode::SimdBatch => {
+                info!("Using SimdBatchScanner");
+                Arc::new(SimdBatchScanner::with_settings(price_paths, settings))
+            }
+            ArbMode::Adaptive => {
+                info!(parallel_threshold = self.parallel_threshold, "Using AdaptiveScanner");
+                Arc::new(AdaptiveScanner::with_settings(price_paths, settings).with_parallel_threshold(self.parallel_threshold))
+            }
+        };
+
+        let evaluator: Arc<dyn ArbEvaluator + Send + Sync> = match tick_rounding_paths {
+            Some(paths) => {
+                info!("Rounding quotes to symbol tick sizes");
+                Arc::new(TickSizeRounding::new(evaluator, &paths))
+            }
+            None => evaluator,
+        };
+
+        match self.reporting_asset {
+            Some(reporting_asset) => {
+                info!(%reporting_asset, "Normalizing profits to the reporting asset");
+                let normalizer = self
+                    .reference_rates
+                    .iter()
+                    .fold(ProfitNormalizer::new(evaluator, &reporting_asset, [&*home_asset]), |normalizer, (asset, &rate)| {
+                        normalizer.with_reference_rate(asset, rate)
+                    });
+                Arc::new(normalizer)
+            }
+            None => evaluator,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::ArbOpportunity;
+    use crate::price_path::Side;
+    use crate::testutil::{assert_return_eq, make_path, mock_reverse_path, mock_update, MockClock, MODES};
+
+    /// Builds every scanner from `builder` and returns what each reports after the three quotes.
+    fn detect_in_every_mode(builder: ScannerBuilder) -> Vec<Option<ArbOpportunity>> {
+        MODES
+            .into_iter()
+            .map(|(mode, on_update_return)| {
+                let evaluator = builder.clone().with_on_update_return(on_update_return).build(mode);
+                evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+                evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+                evaluator.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn defaults_report_fee_free_return() {
+        for opportunity in detect_in_every_mode(ScannerBuilder::new(vec![mock_reverse_path()])) {
+            let opportunity = opportunity.expect("path is profitable without fees");
+            assert_return_eq(opportunity.result, 50000.0 * 0.07 / 3001.0, 1e-12);
+            assert_eq!(opportunity.start_notional, 1.0);
+            assert_eq!(opportunity.leg_time_spread_ms, None);
+        }
+    }
+
+    #[test]
+    fn profit_is_reported_in_the_home_asset() {
+        let builder = ScannerBuilder::new(vec![mock_reverse_path()])
+            .with_home_asset("USDT")
+            .with_start_notional(1000.0);
+
+        // 1000 USDT buys 1000/3001 ETH, sold for 70/3001 BTC, sold for 3_500_000/3001 USDT
+        let gain = 3_500_000.0 / 3001.0 - 1000.0;
+        for opportunity in detect_in_every_mode(builder) {
+            let opportunity = opportunity.expect("path is profitable without fees");
+            assert_eq!(&*opportunity.home_asset, "USDT");
+            assert_return_eq(opportunity.profit_home, gain, 1e-9);
+        }
+    }
+
+    #[test]
+    fn fee_and_notional_are_applied_in_every_mode() {
+        let builder = ScannerBuilder::new(vec![mock_reverse_path()])
+            .with_fee_bps(10.0)
+            .with_start_notional(1000.0);
+        assert_eq!(builder.settings().fees, FeeSchedule::flat(0.001));
+
+        let expected = 50000.0 * 0.07 / 3001.0 * 0.999_f64.powi(3);
+        for opportunity in detect_in_every_mode(builder) {
+            let opportunity = opportunity.expect("path stays profitable after 10 bps per leg");
+            assert_return_eq(opportunity.result, expected, 1e-12);
+            assert_eq!(opportunity.start_notional, 1000.0);
+            assert_return_eq(opportunity.profit_home, 1000.0 * (expected - 1.0), 1e-9);
+            for factor in opportunity.leg_factors {
+                assert!(factor > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn symbol_fee_override_applies_only_to_its_leg() {
+        let config: ArbConfig = toml::from_str(
+            r#"
+            [evaluation]
+            fee_bps = 10.0
+
+            [evaluation.fee_overrides_bps]
+            ethbtc = 0.0
+            "#,
+        )
+        .unwrap();
+        let builder = ScannerBuilder::from_config(vec![mock_reverse_path()], Some(&config)).unwrap();
+        assert_eq!(builder.settings().fees, FeeSchedule::flat(0.001).with_override("ETHBTC", 0.0));
+
+        // ETHUSDT (Ask) and BTCUSDT (Bid) pay 10 bps; the zero-fee ETHBTC leg keeps its full output
+        let expected = 50000.0 * 0.07 / 3001.0 * 0.999_f64.powi(2);
+        for (i, opportunity) in detect_in_every_mode(builder).into_iter().enumerate() {
+            let opportunity = opportunity.unwrap_or_else(|| panic!("{:?} missed the path", MODES[i].0));
+            assert_return_eq(opportunity.result, expected, 1e-12);
+            assert_return_eq(opportunity.leg_factors[0], 0.999 / 3001.0, 1e-15);
+            assert_return_eq(opportunity.leg_factors[1], 0.07, 1e-15);
+            assert_return_eq(opportunity.leg_factors[2], 0.999 * 50000.0, 1e-9);
+        }
+    }
+
+    #[test]
+    fn out_of_range_return_ewma_alpha_in_config_is_an_error() {
+        for alpha in ["0.0", "1.5", "-0.2", "nan"] {
+            let config: ArbConfig = toml::from_str(&format!("[evaluation]\nreturn_ewma_alpha = {alpha}")).unwrap();
+            let error = ScannerBuilder::from_config(vec![mock_reverse_path()], Some(&config)).err();
+            assert!(error.is_some_and(|e| e.to_string().contains("return_ewma_alpha")), "alpha {alpha} accepted");
+        }
+        let config: ArbConfig = toml::from_str("[evaluation]\nreturn_ewma_alpha = 1.0").unwrap();
+        let builder = ScannerBuilder::from_config(vec![mock_reverse_path()], Some(&config)).unwrap();
+        assert_eq!(builder.settings().return_ewma_alpha, Some(1.0));
+    }
+
+    #[test]
+    fn non_positive_or_non_finite_start_notional_in_config_is_an_error() {
+        for notional in ["0.0", "-100.0", "nan", "inf"] {
+            let config: ArbConfig = toml::from_str(&format!("[evaluation]\nstart_notional = {notional}")).unwrap();
+            let error = ScannerBuilder::from_config(vec![mock_reverse_path()], Some(&config)).err();
+            assert!(error.is_some_and(|e| e.to_string().contains("start_notional")), "notional {notional} accepted");
+        }
+        let config: ArbConfig = toml::from_str("[evaluation]\nstart_notional = 250.0").unwrap();
+        assert!(ScannerBuilder::from_config(vec![mock_reverse_path()], Some(&config)).is_ok());
+    }
+
+    #[test]
+    fn non_positive_or_non_finite_max_spread_ratio_in_config_is_an_error() {
+        for ratio in ["0.0", "-0.05", "nan", "inf"] {
+            let config: ArbConfig = toml::from_str(&format!("[quote_filter]\nmax_spread_ratio = {ratio}")).unwrap();
+            let error = ScannerBuilder::from_config(vec![mock_reverse_path()], Some(&config)).err();
+            assert!(error.is_some_and(|e| e.to_string().contains("max_spread_ratio")), "ratio {ratio} accepted");
+        }
+        let config: ArbConfig = toml::from_str("[quote_filter]\nmax_spread_ratio = 0.02").unwrap();
+        assert!(ScannerBuilder::from_config(vec![mock_reverse_path()], Some(&config)).is_ok());
+    }
+
+    #[test]
+    fn negative_or_non_finite_fees_and_min_return_in_config_are_errors() {
+        let cases = [
+            ("fee_bps = -1.0", "fee_bps"),
+            ("fee_bps = nan", "fee_bps"),
+            ("min_return = -0.5", "min_return"),
+            ("min_return = inf", "min_return"),
+            ("[evaluation.fee_overrides_bps]\nBTCUSDT = -2.5", "BTCUSDT"),
+            ("[evaluation.fee_overrides_bps]\nETHBTC = inf", "ETHBTC"),
+        ];
+        for (entry, field) in cases {
+            let config: ArbConfig = toml::from_str(&format!("[evaluation]\n{entry}")).unwrap();
+            let error = ScannerBuilder::from_config(vec![mock_reverse_path()], Some(&config)).err();
+            assert!(error.is_some_and(|e| e.to_string().contains(field)), "{entry:?} accepted");
+        }
+        let config: ArbConfig =
+            toml::from_str("[evaluation]\nfee_bps = 0.0\nmin_return = 1.0\n[evaluation.fee_overrides_bps]\nBTCUSDT = 7.5")
+                .unwrap();
+        assert!(ScannerBuilder::from_config(vec![mock_reverse_path()], Some(&config)).is_ok());
+    }
+
+    #[test]
+    fn non_finite_or_negative_best_match_epsilon_in_config_is_an_error() {
+        let config_with = |epsilon: &str| -> ArbConfig {
+            toml::from_str(&format!("[rayon_scan]\non_update_return = \"best\"\nbest_match_epsilon = {epsilon}")).unwrap()
+        };
+        for epsilon in ["-1e-9", "nan", "inf"] {
+            let error = ScannerBuilder::from_config(vec![mock_reverse_path()], Some(&config_with(epsilon))).err();
+            assert!(error.is_some_and(|e| e.to_string().contains("best_match_epsilon")), "epsilon {epsilon} accepted");
+        }
+        let builder = ScannerBuilder::from_config(vec![mock_reverse_path()], Some(&config_with("0.0"))).unwrap();
+        assert_eq!(builder.settings().best_match_epsilon, 0.0);
+    }
+
+    #[test]
+    fn min_return_suppresses_marginal_opportunities() {
+        let builder = ScannerBuilder::new(vec![mock_reverse_path()]).with_min_return(1.2);
+        assert!(detect_in_every_mode(builder).iter().all(Option::is_none));
+    }
+
+    /// Accepts paths netting more than 0.5% after fees, whatever `min_return` says.
+    #[derive(Debug)]
+    struct HalfPercentNet;
+
+    impl ProfitPredicate for HalfPercentNet {
+        fn accept(&self, ctx: &EvalContext) -> bool {
+            assert_eq!(ctx.leg_prices, [3001.0, 0.07, 50000.0], "{}", ctx.path);
+            ctx.net_return() > 1.005
+        }
+    }
+
+    #[test]
+    fn custom_predicate_filters_in_every_mode() {
+        // 490 bps per leg leaves 1.1663 * 0.951³ ≈ 1.0031: profitable, but under 0.5% net
+        let marginal = ScannerBuilder::new(vec![mock_reverse_path()]).with_fee_bps(490.0);
+        assert!(detect_in_every_mode(marginal.clone()).iter().all(Option::is_some));
+        let filtered = marginal.with_profit_predicate(HalfPercentNet);
+        assert!(detect_in_every_mode(filtered).iter().all(Option::is_none));
+
+        let wide = ScannerBuilder::new(vec![mock_reverse_path()]).with_profit_predicate(HalfPercentNet);
+        for opportunity in detect_in_every_mode(wide) {
+            let opportunity = opportunity.expect("a 16% return clears 0.5%");
+            assert_return_eq(opportunity.result, 50000.0 * 0.07 / 3001.0, 1e-12);
+        }
+    }
+
+    #[test]
+    fn non_finite_returns_are_never_profitable() {
+        let settings = ScannerSettings::default();
+        let path = mock_reverse_path();
+        assert!(settings.is_profitable(&path, [1.0; 3], 1.5));
+        assert!(!settings.is_profitable(&path, [1.0; 3], f64::INFINITY), "a zero ask divides to infinity");
+        assert!(!settings.is_profitable(&path, [1.0; 3], f64::NAN));
+    }
+
+    #[test]
+    fn stale_quotes_are_not_used() {
+        for (mode, on_update_return) in MODES {
+            let clock = Arc::new(MockClock::new());
+            let evaluator = ScannerBuilder::new(vec![mock_reverse_path()])
+                .with_max_staleness(Duration::from_millis(20))
+                .with_clock(clock.clone())
+                .with_on_update_return(on_update_return)
+                .build(mode);
+            evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+            clock.advance(Duration::from_millis(40));
+
+            let result = evaluator.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0));
+            assert!(result.is_none(), "{mode:?} priced a leg with a stale quote");
+
+            // Refreshing the stale legs makes the path usable again
+            evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            let result = evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+            assert!(result.is_some(), "{mode:?} missed the path once quotes were fresh");
+        }
+    }
+
+    #[test]
+    fn opportunities_share_the_scanners_path_allocation() {
+        for (mode, on_update_return) in MODES {
+            let evaluator = ScannerBuilder::new(vec![mock_reverse_path()])
+                .with_on_update_return(on_update_return)
+                .build(mode);
+            evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+            let trigger = mock_update("ETHUSDT", 3000.0, 3001.0);
+            let first = evaluator.process_update(&trigger).expect("profitable");
+            let second = evaluator.process_update(&trigger).expect("profitable");
+            assert!(Arc::ptr_eq(&first.path, &second.path), "{mode:?} cloned the path per report");
+            assert_eq!(first.path.key(), mock_reverse_path().key());
+        }
+    }
+
+    #[test]
+    fn cooldown_suppresses_repeat_reports_until_it_elapses() {
+        for (mode, on_update_return) in MODES {
+            let clock = Arc::new(MockClock::new());
+            let evaluator = ScannerBuilder::new(vec![mock_reverse_path()])
+                .with_opportunity_cooldown(Duration::from_millis(100))
+                .with_clock(clock.clone())
+                .with_on_update_return(on_update_return)
+                .build(mode);
+            evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+            let trigger = mock_update("ETHUSDT", 3000.0, 3001.0);
+            assert!(evaluator.process_update(&trigger).is_some(), "{mode:?} never fired");
+
+            // Still profitable, but within the cooldown
+            assert!(evaluator.process_update(&trigger).is_none(), "{mode:?} fired again immediately");
+            clock.advance(Duration::from_millis(99));
+            assert!(evaluator.process_update(&trigger).is_none(), "{mode:?} fired within the cooldown");
+
+            clock.advance(Duration::from_millis(1));
+            assert!(evaluator.process_update(&trigger).is_some(), "{mode:?} stayed suppressed after the cooldown");
+            assert!(evaluator.process_update(&trigger).is_none(), "{mode:?} didn't restart the cooldown");
+        }
+    }
+
+    #[test]
+    fn staleness_uses_exchange_event_time_when_present() {
+        use crate::parse::{man_scan::ManualScanParser, BookTickerParser};
+
+        for (mode, on_update_return) in MODES {
+            for event_time_staleness in [true, false] {
+                let clock = Arc::new(MockClock::new());
+                let evaluator = ScannerBuilder::new(vec![mock_reverse_path()])
+                    .with_max_staleness(Duration::from_millis(20))
+                    .with_event_time_staleness(event_time_staleness)
+                    .with_clock(clock.clone())
+                    .with_on_update_return(on_update_return.clone())
+                    .build(mode);
+                // Received just now, but stamped by the exchange 40ms ago
+                let lagging = format!(
+                    r#"{{"E":{},"s":"BTCUSDT","b":"50000.0","a":"50010.0"}}"#,
+                    clock.epoch_millis() - 40,
+                );
+                let update = ManualScanParser::new().parse(&lagging.into()).unwrap();
+                assert_eq!(update.event_time_ms, Some(clock.epoch_millis() - 40));
+
+                evaluator.process_update(&update);
+                evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+                let result = evaluator.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0));
+                assert_eq!(
+                    result.is_some(),
+                    !event_time_staleness,
+                    "{mode:?} (event time staleness {event_time_staleness})",
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn leg_time_spread_is_reported_and_rejects_above_the_limit() {
+        let stamped = |symbol, bid, ask, event_time_ms| TopOfBookUpdate {
+            event_time_ms: Some(event_time_ms),
+            ..mock_update(symbol, bid, ask)
+        };
+
+        for (mode, on_update_return) in MODES {
+            for (max_spread, reported) in [(None, true), (Some(500), true), (Some(499), false)] {
+                let mut builder = ScannerBuilder::new(vec![mock_reverse_path()]).with_on_update_return(on_update_return.clone());
+                if let Some(ms) = max_spread {
+                    builder = builder.with_max_leg_time_spread(Duration::from_millis(ms));
+                }
+                let evaluator = builder.build(mode);
+                // BTCUSDT was stamped half a second before the other two legs
+                evaluator.process_update(&stamped("BTCUSDT", 50000.0, 50010.0, 1_000));
+                evaluator.process_update(&stamped("ETHBTC", 0.07, 0.0701, 1_450));
+                let result = evaluator.process_update(&stamped("ETHUSDT", 3000.0, 3001.0, 1_500));
+
+                assert_eq!(result.is_some(), reported, "{mode:?} with max spread {max_spread:?}");
+                if let Some(opportunity) = result {
+                    assert_eq!(opportunity.leg_time_spread_ms, Some(500), "{mode:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn top_paths_rank_by_average_return_even_below_min_return() {
+        // USDT → SOL → BTC → USDT: 1 / 150.1 * 0.003 * 50000 ≈ 0.9993, below mock_path's ≈ 1.1663
+        let sol = make_path([
+            ("SOLUSDT", "SOL", "USDT", Side::Ask),
+            ("SOLBTC", "SOL", "BTC", Side::Bid),
+            ("BTCUSDT", "BTC", "USDT", Side::Bid),
+        ]);
+        let updates = [
+            mock_update("BTCUSDT", 50000.0, 50010.0),
+            mock_update("ETHBTC", 0.07, 0.0701),
+            mock_update("ETHUSDT", 3000.0, 3001.0),
+            mock_update("SOLBTC", 0.003, 0.00301),
+            mock_update("SOLUSDT", 150.0, 150.1),
+        ];
+
+        for (mode, on_update_return) in MODES {
+            // Out-of-reach min_return: nothing reports, so every scan prices every path
+            let builder = ScannerBuilder::new(vec![sol.clone(), mock_reverse_path()])
+                .with_min_return(10.0)
+                .with_on_update_return(on_update_return.clone());
+            let untracked = builder.clone().build(mode);
+            let evaluator = builder.with_return_ewma(0.2).build(mode);
+            for _ in 0..5 {
+                for update in &updates {
+                    assert!(evaluator.process_update(update).is_none());
+                    untracked.process_update(update);
+                }
+            }
+
+            let top = evaluator.top_paths(2);
+            assert_eq!(top.len(), 2, "{mode:?}");
+            assert_eq!(top[0].0.key(), mock_reverse_path().key(), "{mode:?}");
+            assert_return_eq(top[0].1, 50000.0 * 0.07 / 3001.0, 1e-12);
+            assert_eq!(top[1].0.key(), sol.key(), "{mode:?}");
+            assert_return_eq(top[1].1, 0.003 * 50000.0 / 150.1, 1e-12);
+            assert_eq!(evaluator.top_paths(1).len(), 1);
+            assert!(untracked.top_paths(2).is_empty(), "{mode:?} kept history without an alpha");
+        }
+    }
+
+    #[test]
+    fn lowercase_symbols_match_uppercase_paths() {
+        use crate::parse::{man_scan::ManualScanParser, srd_jsn::SerdeJsonParser, BookTickerParser};
+
+        let frames = [
+            r#"{"s":"btcusdt","b":"50000.0","a":"50010.0"}"#,
+            r#"{"s":"ethbtc","b":"0.07","a":"0.0701"}"#,
+            r#"{"s":"EthUsdt","b":"3000.0","a":"3001.0"}"#,
+        ];
+        let parsers: [Box<dyn BookTickerParser>; 2] = [Box::new(ManualScanParser::new()), Box::new(SerdeJsonParser::new())];
+        for parser in &parsers {
+            let updates: Vec<_> = frames.iter().map(|f| parser.parse(&(*f).into()).unwrap()).collect();
+            for (mode, on_update_return) in MODES {
+                let evaluator = ScannerBuilder::new(vec![mock_reverse_path()])
+                    .with_on_update_return(on_update_return)
+                    .build(mode);
+                let results: Vec<_> = updates.iter().map(|u| evaluator.process_update(u)).collect();
+                assert!(results[2].is_some(), "{mode:?} never matched the lowercase legs");
+            }
+        }
+    }
+
+    /// Symbol and quote for each leg of a USDT → BTC → ETH → USDT triangle, oriented so the
+    /// requested side is the one that performs the conversion (e.g. a `Bid` first leg sells
+    /// USDT on `USDTBTC`).
+    fn oriented_leg(leg: usize, side: Side) -> (&'static str, &'static str, &'static str, f64, f64) {
+        match (leg, side) {
+            (0, Side::Ask) => ("BTCUSDT", "BTC", "USDT", 50000.0, 50010.0),
+            (0, Side::Bid) => ("USDTBTC", "USDT", "BTC", 0.0000199, 0.0000201),
+            (1, Side::Ask) => ("ETHBTC", "ETH", "BTC", 0.07, 0.0701),
+            (1, Side::Bid) => ("BTCETH", "BTC", "ETH", 14.2, 14.3),
+            (2, Side::Ask) => ("USDTETH", "USDT", "ETH", 0.000332, 0.000334),
+            (2, Side::Bid) => ("ETHUSDT", "ETH", "USDT", 3000.0, 3001.0),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Discovery only emits `Ask, _, Bid` paths (home is always the quote asset of legs 1
+    /// and 3), but hand-built and exotic-market paths can take any of the eight shapes.
+    #[test]
+    fn every_side_combination_matches_reference_math() {
+        for shape in 0..8 {
+            let sides = [0, 1, 2].map(|bit| if shape >> bit & 1 == 0 { Side::Ask } else { Side::Bid });
+            let legs = [0, 1, 2].map(|leg| oriented_leg(leg, sides[leg]));
+            let path = make_path([0, 1, 2].map(|i| (legs[i].0, legs[i].1, legs[i].2, sides[i])));
+            assert!(path.validate().is_ok());
+
+            // Ask legs buy the base at the ask, Bid legs sell it at the bid
+            let expected_prices = [0, 1, 2].map(|i| match sides[i] {
+                Side::Ask => legs[i].4,
+                Side::Bid => legs[i].3,
+            });
+            let expected_factors = [0, 1, 2].map(|i| match sides[i] {
+                Side::Ask => 1.0 / expected_prices[i],
+                Side::Bid => expected_prices[i],
+            });
+            let expected: f64 = expected_factors.iter().product();
+
+            // A min_return of 0 reports every fully-priced path, profitable or not
+            let builder = ScannerBuilder::new(vec![path]).with_min_return(0.0);
+            for (mode, on_update_return) in MODES {
+                let evaluator = builder.clone().with_on_update_return(on_update_return).build(mode);
+                let results: Vec<_> = legs
+                    .iter()
+                    .map(|&(symbol, _, _, bid, ask)| evaluator.process_update(&mock_update(symbol, bid, ask)))
+                    .collect();
+                let opportunity = results[2].as_ref().unwrap_or_else(|| panic!("{mode:?} missed {sides:?}"));
+
+                assert_eq!(opportunity.leg_prices, expected_prices, "{mode:?} {sides:?}");
+                for (factor, expected_factor) in opportunity.leg_factors.into_iter().zip(expected_factors) {
+                    assert_return_eq(factor, expected_factor, 1e-12);
+                }
+                assert_return_eq(opportunity.result, expected, 1e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,30 @@
+// src/arb/clock.rs
+
+use std::fmt;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+
+/// Time source for the scanners' time-dependent features (staleness, confidence, TTL eviction).
+///
+/// Production uses `SystemClock`; tests substitute a manually advanced clock so time-based
+/// behaviour can be checked without sleeping.
+pub trait Clock: fmt::Debug + Send + Sync {
+    fn now(&self) -> Instant;
+
+    /// Wall-clock time in milliseconds since the Unix epoch, comparable with exchange event times.
+    fn epoch_millis(&self) -> u64;
+}
+
+/// The real monotonic clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn epoch_millis(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_millis() as u64)
+    }
+}
@@ -0,0 +1,104 @@
+// src/arb/confidence.rs
+
+use std::time::{Duration, Instant};
+
+
+/// Default relative spread at which a leg's spread score halves (10 bps).
+pub const DEFAULT_REFERENCE_SPREAD_RATIO: f64 = 0.001;
+
+/// Default quote age at which a leg's age score halves.
+pub const DEFAULT_REFERENCE_AGE: Duration = Duration::from_secs(1);
+
+
+/// Reference spread and age against which `ArbOpportunity::confidence` is scored.
+///
+/// Each leg scores `1 / (1 + spread / spread_ratio) * 1 / (1 + age / age)`: a zero-spread quote
+/// received just now scores `1.0`, and a leg at either reference scores `0.5` on that axis. A
+/// path is only as trustworthy as its weakest leg, so the opportunity takes the minimum.
+#[derive(Debug, Clone, Copy)]
+pub struct ConfidenceReference {
+    /// Relative spread `(ask - bid) / bid` that halves a leg's score.
+    pub spread_ratio: f64,
+    /// Quote age that halves a leg's score.
+    pub age: Duration,
+}
+
+impl Default for ConfidenceReference {
+    fn default() -> Self {
+        Self { spread_ratio: DEFAULT_REFERENCE_SPREAD_RATIO, age: DEFAULT_REFERENCE_AGE }
+    }
+}
+
+impl ConfidenceReference {
+    /// Score in `(0, 1]` for a leg quoted at `bid`/`ask` and received `age` ago.
+    pub fn leg(&self, bid: f64, ask: f64, age: Duration) -> f64 {
+        let spread = ((ask - bid) / bid).max(0.0);
+        let spread_score = 1.0 / (1.0 + spread / self.spread_ratio);
+        let age_score = 1.0 / (1.0 + age.as_secs_f64() / self.age.as_secs_f64());
+        spread_score * age_score
+    }
+
+    /// Weakest-leg score at `now` of a path from each leg's `(bid, ask, received)`.
+    pub fn path(&self, legs: [(f64, f64, Instant); 3], now: Instant) -> f64 {
+        legs.into_iter()
+            .map(|(bid, ask, received)| self.leg(bid, ask, now.saturating_duration_since(received)))
+            .fold(1.0, f64::min)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::arb::{ArbEvaluator, ScannerBuilder};
+    use crate::testutil::{assert_return_eq, mock_reverse_path, mock_update, MockClock, MODES};
+
+    #[test]
+    fn test_leg_score_halves_at_each_reference() {
+        let reference = ConfidenceReference::default();
+
+        assert_eq!(reference.leg(100.0, 100.0, Duration::ZERO), 1.0);
+        assert_return_eq(reference.leg(100.0, 100.1, Duration::ZERO), 0.5, 1e-9);
+        assert_return_eq(reference.leg(100.0, 100.0, Duration::from_secs(1)), 0.5, 1e-12);
+        assert_return_eq(reference.leg(100.0, 100.1, Duration::from_secs(1)), 0.25, 1e-9);
+    }
+
+    #[test]
+    fn confidence_drops_with_wider_spread_and_older_quotes() {
+        for (mode, on_update_return) in MODES {
+            let clock = Arc::new(MockClock::new());
+            let builder = ScannerBuilder::new(vec![mock_reverse_path()])
+                .with_confidence_reference(0.001, Duration::from_millis(20))
+                .with_clock(clock.clone());
+            let build = || builder.clone().with_on_update_return(on_update_return.clone()).build(mode);
+            let detect = |evaluator: &Arc<dyn ArbEvaluator + Send + Sync>, btc_ask: f64| {
+                evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+                evaluator.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0));
+                evaluator
+                    .process_update(&mock_update("BTCUSDT", 50000.0, btc_ask))
+                    .expect("path stays profitable")
+                    .confidence
+            };
+
+            let tight = detect(&build(), 50000.5);
+            assert!(tight > 0.0 && tight <= 1.0);
+
+            // Widening the spread on the (unused) BTCUSDT ask still lowers trust in the leg
+            let wide = detect(&build(), 50500.0);
+            assert!(wide < tight, "{mode:?}: wide spread {wide} vs tight {tight}");
+
+            // Re-pricing off a 40 ms old ETHBTC quote ages the path
+            let evaluator = build();
+            let fresh = detect(&evaluator, 50000.5);
+            clock.advance(Duration::from_millis(40));
+            evaluator.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0));
+            let aged = evaluator
+                .process_update(&mock_update("BTCUSDT", 50000.0, 50000.5))
+                .expect("path stays profitable")
+                .confidence;
+            assert!(aged < fresh * 0.5, "{mode:?}: aged {aged} vs fresh {fresh}");
+        }
+    }
+}
@@ -1,31 +1,495 @@
 // src/arb/config.rs
 
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
 use serde::Deserialize;
+use tracing_subscriber::EnvFilter;
+
+use super::adaptive::DEFAULT_PARALLEL_THRESHOLD;
+use super::confidence::{DEFAULT_REFERENCE_AGE, DEFAULT_REFERENCE_SPREAD_RATIO};
+use super::quote_filter::QuoteFilter;
+use super::rayon_scan::DEFAULT_BEST_MATCH_EPSILON;
+use super::tier::{Tier, TierBands};
+use crate::parse::ParserKind;
+use crate::price_path::{load_volume_24h, SymbolFilter, TargetRanking, DEFAULT_EXCHANGE_INFO_MAX_AGE};
 
 
 /// Top-level arbitrage configuration loaded from `config/arb.toml`.
 #[derive(Debug, Deserialize, Clone)]
 pub struct ArbConfig {
-    pub rayon_scan: Option<RayonScanConfig>
+    pub rayon_scan: Option<RayonScanConfig>,
+    pub adaptive: Option<AdaptiveConfig>,
+    pub evaluation: Option<EvaluationConfig>,
+    pub pipeline: Option<PipelineConfig>,
+    pub quote_filter: Option<QuoteFilter>,
+    pub output: Option<OutputConfig>,
+    pub symbols: Option<SymbolFilter>,
+    pub scanner: Option<ScannerConfig>,
+    pub logging: Option<LoggingConfig>,
+    pub universe: Option<UniverseConfig>,
+}
+
+/// Scanner selection for the binary (see `load_arb_mode`).
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ScannerConfig {
+    /// Any name `ArbMode::from_str` accepts (`"naive"`, `"edge"`, `"rayon"`, ...); unset keeps
+    /// the binary's default.
+    #[serde(default)]
+    pub mode: Option<String>,
+}
+
+/// Targets scanned when `[universe]` lists none and doesn't ask for `top_targets`.
+pub const DEFAULT_TARGETS: [&str; 3] = ["BTC", "ETH", "SOL"];
+
+fn default_home_asset() -> String {
+    "USDT".to_string()
+}
+
+/// The assets the binary discovers paths over (see `load_universe_config`).
+#[derive(Debug, Deserialize, Clone)]
+pub struct UniverseConfig {
+    /// Asset every path starts and ends in.
+    #[serde(default = "default_home_asset")]
+    pub home_asset: String,
+    /// Explicit first-leg targets; when set they override `top_targets`.
+    #[serde(default)]
+    pub targets: Option<Vec<String>>,
+    /// Scan this many of the most active assets instead of listing them, ranked by
+    /// `volume_file` if set, else by the order of `ranked_assets`.
+    #[serde(default)]
+    pub top_targets: Option<usize>,
+    /// Static ranking of assets, most active first.
+    #[serde(default)]
+    pub ranked_assets: Vec<String>,
+    /// Saved Binance `/api/v3/ticker/24hr` response to rank assets by 24h quote volume.
+    #[serde(default)]
+    pub volume_file: Option<PathBuf>,
+    /// Stop discovery once this many paths are found (see `find_path_symbols_capped`).
+    #[serde(default)]
+    pub max_paths: Option<usize>,
+}
+
+impl Default for UniverseConfig {
+    fn default() -> Self {
+        Self {
+            home_asset: default_home_asset(),
+            targets: None,
+            top_targets: None,
+            ranked_assets: Vec::new(),
+            volume_file: None,
+            max_paths: None,
+        }
+    }
+}
+
+impl UniverseConfig {
+    /// How `top_targets` are ranked: by `volume_file`'s volumes when set, else `ranked_assets`.
+    pub fn ranking(&self) -> Result<TargetRanking> {
+        match &self.volume_file {
+            Some(path) => Ok(TargetRanking::Volume24h(load_volume_24h(path)?)),
+            None => Ok(TargetRanking::Assets(self.ranked_assets.clone())),
+        }
+    }
+}
+
+fn default_min_return() -> f64 {
+    1.0
+}
+
+fn default_start_notional() -> f64 {
+    1.0
+}
+
+fn default_staleness_from_event_time() -> bool {
+    true
+}
+
+fn default_confidence_spread_bps() -> f64 {
+    DEFAULT_REFERENCE_SPREAD_RATIO * 10_000.0
+}
+
+fn default_confidence_age_ms() -> u64 {
+    DEFAULT_REFERENCE_AGE.as_millis() as u64
+}
+
+/// Options shared by every scanner, applied when the evaluator is created.
+#[derive(Debug, Deserialize, Clone)]
+pub struct EvaluationConfig {
+    /// Also evaluate the reversal of each path and report whichever direction is profitable.
+    #[serde(default)]
+    pub evaluate_reversed: bool,
+    /// Round each quote to its symbol's `tickSize` (bids down, asks up) before evaluation.
+    #[serde(default)]
+    pub round_to_tick_size: bool,
+    /// Taker fee charged on every leg, in basis points.
+    #[serde(default)]
+    pub fee_bps: f64,
+    /// Per-symbol taker fee in basis points, replacing `fee_bps` on legs trading that symbol.
+    #[serde(default)]
+    pub fee_overrides_bps: HashMap<String, f64>,
+    /// Smallest net return (after fees) reported as an opportunity.
+    #[serde(default = "default_min_return")]
+    pub min_return: f64,
+    /// Home-asset amount routed through each path.
+    #[serde(default = "default_start_notional")]
+    pub start_notional: f64,
+    /// Quotes older than this many milliseconds are ignored; unset accepts any age.
+    #[serde(default)]
+    pub max_staleness_ms: Option<u64>,
+    /// Age quotes by the exchange event time `E` when present, instead of local receipt.
+    #[serde(default = "default_staleness_from_event_time")]
+    pub staleness_from_event_time: bool,
+    /// Suppress repeat reports of a path for this many milliseconds after it reports.
+    #[serde(default)]
+    pub opportunity_cooldown_ms: Option<u64>,
+    /// Reject opportunities whose legs' exchange event times differ by more than this many
+    /// milliseconds; unset reports them regardless.
+    #[serde(default)]
+    pub max_leg_time_spread_ms: Option<u64>,
+    /// Weight of each new return in the per-path moving average ranking `top_paths`; unset
+    /// keeps no history.
+    #[serde(default)]
+    pub return_ewma_alpha: Option<f64>,
+    /// Leg spread, in basis points, that halves an opportunity's confidence.
+    #[serde(default = "default_confidence_spread_bps")]
+    pub confidence_spread_bps: f64,
+    /// Quote age, in milliseconds, that halves an opportunity's confidence.
+    #[serde(default = "default_confidence_age_ms")]
+    pub confidence_age_ms: u64,
+    /// Evict stored quotes of symbols no path trades after this many milliseconds without an update.
+    #[serde(default)]
+    pub price_ttl_ms: Option<u64>,
+    /// Currency every opportunity's `profit_normalized` is reported in; unset leaves it empty.
+    #[serde(default)]
+    pub reporting_asset: Option<String>,
+    /// Value of one unit of an asset in `reporting_asset`, used until its reference symbol
+    /// (e.g. BTCUSDT for BTC into USDT) is quoted.
+    #[serde(default)]
+    pub reference_rates: HashMap<String, f64>,
+}
+
+impl Default for EvaluationConfig {
+    fn default() -> Self {
+        Self {
+            evaluate_reversed: false,
+            round_to_tick_size: false,
+            fee_bps: 0.0,
+            fee_overrides_bps: HashMap::new(),
+            min_return: default_min_return(),
+            start_notional: default_start_notional(),
+            max_staleness_ms: None,
+            staleness_from_event_time: default_staleness_from_event_time(),
+            opportunity_cooldown_ms: None,
+            max_leg_time_spread_ms: None,
+            return_ewma_alpha: None,
+            confidence_spread_bps: default_confidence_spread_bps(),
+            confidence_age_ms: default_confidence_age_ms(),
+            price_ttl_ms: None,
+            reporting_asset: None,
+            reference_rates: HashMap::new(),
+        }
+    }
+}
+
+/// Default capacity of the ws → parser and parser → arb channels.
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 4096;
+
+fn default_channel_capacity() -> usize {
+    DEFAULT_CHANNEL_CAPACITY
+}
+
+/// Default seconds without a WebSocket frame before the listener reconnects.
+pub const DEFAULT_WS_IDLE_TIMEOUT_SECS: u64 = 30;
+
+fn default_ws_idle_timeout_secs() -> u64 {
+    DEFAULT_WS_IDLE_TIMEOUT_SECS
+}
+
+fn default_ws_shards() -> usize {
+    1
+}
+
+fn default_exchange_info_max_age_days() -> u64 {
+    DEFAULT_EXCHANGE_INFO_MAX_AGE.as_secs() / SECS_PER_DAY
+}
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Default address of the `/health` endpoint.
+pub const DEFAULT_HEALTH_ADDR: &str = "127.0.0.1:8080";
+
+fn default_health_addr() -> String {
+    DEFAULT_HEALTH_ADDR.to_string()
+}
+
+/// Default age of the last frame past which `/health` reports unhealthy.
+pub const DEFAULT_HEALTH_MAX_FRAME_AGE_SECS: u64 = 5;
+
+fn default_health_max_frame_age_secs() -> u64 {
+    DEFAULT_HEALTH_MAX_FRAME_AGE_SECS
+}
+
+/// Capacities of the bounded channels connecting the ws, parser and arb stages,
+/// plus the WebSocket listener's stale-data watchdog.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PipelineConfig {
+    /// Raw frames queued between the WebSocket listener and the parser.
+    #[serde(default = "default_channel_capacity")]
+    pub ws_channel_capacity: usize,
+    /// Parsed updates queued between the parser and the arb loop.
+    #[serde(default = "default_channel_capacity")]
+    pub parser_channel_capacity: usize,
+    /// Seconds without any frame before the connection is dropped and re-established (0 disables).
+    #[serde(default = "default_ws_idle_timeout_secs")]
+    pub ws_idle_timeout_secs: u64,
+    /// WebSocket connections the subscribed symbols are split across (see `ConnectOptions::shards`).
+    #[serde(default = "default_ws_shards")]
+    pub ws_shards: usize,
+    /// Days since the exchangeInfo file was modified before path discovery warns that it is
+    /// stale (0 disables).
+    #[serde(default = "default_exchange_info_max_age_days")]
+    pub exchange_info_max_age_days: u64,
+    /// Frame parser; defaults to the one picked by the `manual_parser` / `serde_parser` feature.
+    #[serde(default)]
+    pub parser: ParserKind,
+    /// Address the `/health` endpoint listens on (`health` feature only).
+    #[serde(default = "default_health_addr")]
+    pub health_addr: String,
+    /// Seconds since the last frame before `/health` reports unhealthy.
+    #[serde(default = "default_health_max_frame_age_secs")]
+    pub health_max_frame_age_secs: u64,
+}
+
+impl PipelineConfig {
+    /// The watchdog timeout to hand to the WebSocket listener, `None` when disabled.
+    pub fn ws_idle_timeout(&self) -> Option<Duration> {
+        (self.ws_idle_timeout_secs > 0).then(|| Duration::from_secs(self.ws_idle_timeout_secs))
+    }
+
+    /// The exchangeInfo staleness threshold used by path discovery, `None` when disabled.
+    pub fn exchange_info_max_age(&self) -> Option<Duration> {
+        (self.exchange_info_max_age_days > 0)
+            .then(|| Duration::from_secs(self.exchange_info_max_age_days * SECS_PER_DAY))
+    }
+
+    /// How recent the last frame must be for `/health` to report healthy.
+    pub fn health_max_frame_age(&self) -> Duration {
+        Duration::from_secs(self.health_max_frame_age_secs)
+    }
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            ws_channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+            parser_channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+            ws_idle_timeout_secs: DEFAULT_WS_IDLE_TIMEOUT_SECS,
+            ws_shards: 1,
+            exchange_info_max_age_days: default_exchange_info_max_age_days(),
+            parser: ParserKind::default(),
+            health_addr: default_health_addr(),
+            health_max_frame_age_secs: DEFAULT_HEALTH_MAX_FRAME_AGE_SECS,
+        }
+    }
+}
+
+/// Default number of opportunities queued for the webhook.
+pub const DEFAULT_WEBHOOK_QUEUE_CAPACITY: usize = 1024;
+
+fn default_webhook_queue_capacity() -> usize {
+    DEFAULT_WEBHOOK_QUEUE_CAPACITY
+}
+
+fn default_output_file_path() -> PathBuf {
+    PathBuf::from("opportunities.log")
+}
+
+/// Where detected opportunities are written (see `sink::create_opportunity_sink`).
+#[derive(Debug, Deserialize, Clone)]
+pub struct OutputConfig {
+    #[serde(default)]
+    pub sink: SinkKind,
+    /// File appended to when `sink = "file"`.
+    #[serde(default = "default_output_file_path")]
+    pub file_path: PathBuf,
+    /// Repeats of the same path within this many milliseconds are suppressed (0 disables).
+    #[serde(default)]
+    pub dedup_window_ms: u64,
+    /// Optional file receiving every opportunity, unaffected by `dedup_window_ms`.
+    #[serde(default)]
+    pub full_stream_path: Option<PathBuf>,
+    /// Optional `http://` endpoint each opportunity is POSTed to as JSON (see `WebhookSink`).
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Opportunities queued for the webhook before new ones are dropped.
+    #[serde(default = "default_webhook_queue_capacity")]
+    pub webhook_queue_capacity: usize,
+    /// Lowest tier POSTed to `webhook_url`; smaller opportunities only reach `sink`.
+    #[serde(default)]
+    pub webhook_min_tier: Tier,
+    /// Profit bands classifying opportunities into tiers.
+    #[serde(default)]
+    pub tiers: TierBands,
+}
+
+impl OutputConfig {
+    /// The rate-limit window for repeated opportunities, `None` when disabled.
+    pub fn dedup_window(&self) -> Option<Duration> {
+        (self.dedup_window_ms > 0).then(|| Duration::from_millis(self.dedup_window_ms))
+    }
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            sink: SinkKind::default(),
+            file_path: default_output_file_path(),
+            dedup_window_ms: 0,
+            full_stream_path: None,
+            webhook_url: None,
+            webhook_queue_capacity: DEFAULT_WEBHOOK_QUEUE_CAPACITY,
+            webhook_min_tier: Tier::default(),
+            tiers: TierBands::default(),
+        }
+    }
+}
+
+/// Built-in opportunity sinks selectable from the config file.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SinkKind {
+    /// One line per opportunity on stdout.
+    #[default]
+    Stdout,
+    /// One plain-text line per opportunity appended to `file_path`.
+    File,
+}
+
+fn default_best_match_epsilon() -> f64 {
+    DEFAULT_BEST_MATCH_EPSILON
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct RayonScanConfig {
-    pub on_update_return: OnUpdateReturn
+    pub on_update_return: OnUpdateReturn,
+    /// Returns within this of the best count as ties under `"best"`, which the path listed
+    /// first wins.
+    #[serde(default = "default_best_match_epsilon")]
+    pub best_match_epsilon: f64,
 }
 
-/// Strategy for returning arbitrage results on update.
+fn default_parallel_threshold() -> usize {
+    DEFAULT_PARALLEL_THRESHOLD
+}
+
+/// Options for `ArbMode::Adaptive`.
 #[derive(Debug, Deserialize, Clone)]
+pub struct AdaptiveConfig {
+    /// Updates touching at least this many paths are priced over Rayon, fewer serially.
+    #[serde(default = "default_parallel_threshold")]
+    pub parallel_threshold: usize,
+}
+
+impl Default for AdaptiveConfig {
+    fn default() -> Self {
+        Self { parallel_threshold: DEFAULT_PARALLEL_THRESHOLD }
+    }
+}
+
+/// Strategy for returning arbitrage results on update.
+#[derive(Debug, Deserialize, Clone, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum OnUpdateReturn {
     /// Return the first profitable path found (fastest).
+    #[default]
     First,
     /// Evaluate all paths and return the most profitable one.
-    Best
+    Best,
+    /// Return the profitable path that comes first in path order, identical across runs.
+    Ordered,
 }
 
-impl Default for OnUpdateReturn {
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+/// Log verbosity and format for the binary's tracing subscriber.
+#[derive(Debug, Deserialize, Clone)]
+pub struct LoggingConfig {
+    /// `EnvFilter` directives: a level (`"debug"`) and/or per-module overrides
+    /// (`"info,tri_arb::ws=debug"`). A set `RUST_LOG` takes precedence.
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    #[serde(default)]
+    pub format: LogFormat,
+}
+
+impl LoggingConfig {
+    /// The filter built from `rust_log` when set and non-empty, otherwise from `level`.
+    pub fn env_filter(&self, rust_log: Option<&str>) -> Result<EnvFilter> {
+        let (directives, source) = match rust_log {
+            Some(rust_log) if !rust_log.trim().is_empty() => (rust_log, "RUST_LOG"),
+            _ => (self.level.as_str(), "[logging] level"),
+        };
+        EnvFilter::try_new(directives).with_context(|| format!("Invalid {source} '{directives}'"))
+    }
+}
+
+impl Default for LoggingConfig {
     fn default() -> Self {
-        Self::First
+        Self { level: default_log_level(), format: LogFormat::default() }
+    }
+}
+
+/// How log lines are rendered.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines.
+    #[default]
+    Pretty,
+    /// One JSON object per event, for log shippers.
+    Json,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tracing::level_filters::LevelFilter;
+
+    use super::*;
+
+    #[test]
+    fn logging_level_becomes_env_filter_directives() {
+        let config: ArbConfig = toml::from_str(
+            r#"
+            [logging]
+            level = "warn,tri_arb::ws=debug,tri_arb::arb::sink=trace"
+            format = "json"
+            "#,
+        )
+        .unwrap();
+        let logging = config.logging.unwrap();
+        assert_eq!(logging.format, LogFormat::Json);
+
+        let filter = logging.env_filter(None).unwrap();
+        let directives = filter.to_string();
+        for directive in ["warn", "tri_arb::ws=debug", "tri_arb::arb::sink=trace"] {
+            assert!(directives.split(',').any(|d| d == directive), "{directive} missing from {directives}");
+        }
+        assert_eq!(filter.max_level_hint(), Some(LevelFilter::TRACE));
+
+        let from_env = logging.env_filter(Some("error")).unwrap();
+        assert_eq!(from_env.to_string(), "error", "RUST_LOG wins over the config");
+        assert_eq!(logging.env_filter(Some("  ")).unwrap().to_string(), directives);
+
+        let defaults = LoggingConfig::default();
+        assert_eq!(defaults.format, LogFormat::Pretty);
+        assert_eq!(defaults.env_filter(None).unwrap().max_level_hint(), Some(LevelFilter::INFO));
+        assert!(LoggingConfig { level: "tri_arb=loud".into(), ..defaults }.env_filter(None).is_err());
     }
 }
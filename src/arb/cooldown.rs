@@ -0,0 +1,40 @@
+// src/arb/cooldown.rs
+
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+
+use crate::price_path::{PricingPath, Side};
+
+
+/// Suppresses repeat reports of a path for `period` after it last reported.
+///
+/// A path that stays profitable across a burst of updates would otherwise fire on every one,
+/// and an executor acting on each report would trade it several times over. Scanners skip
+/// cooling paths while selecting, so another profitable path can still report in their place.
+pub(crate) struct PathCooldown {
+    period: Option<Duration>,
+    last_fired: DashMap<[(String, Side); 3], Instant>,
+}
+
+impl PathCooldown {
+    pub(crate) fn new(period: Option<Duration>) -> Self {
+        Self { period, last_fired: DashMap::new() }
+    }
+
+    /// Whether `path` reported less than `period` before `now`.
+    pub(crate) fn is_cooling(&self, path: &PricingPath, now: Instant) -> bool {
+        self.period.is_some_and(|period| {
+            self.last_fired
+                .get(&path.key())
+                .is_some_and(|last| now.saturating_duration_since(*last) < period)
+        })
+    }
+
+    /// Starts `path`'s cooldown at `now`; a no-op when no period is configured.
+    pub(crate) fn record(&self, path: &PricingPath, now: Instant) {
+        if self.period.is_some() {
+            self.last_fired.insert(path.key(), now);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+// src/arb/cross_check.rs
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tracing::warn;
+
+use crate::parse::TopOfBookUpdate;
+use crate::price_path::PricingPath;
+
+use super::{ArbEvaluator, ArbOpportunity, HaltHandle, ImpliedQuote};
+
+
+/// Auditing decorator that runs a candidate scanner alongside a trusted reference on every
+/// update and flags updates where they disagree on whether an opportunity exists.
+///
+/// Only the profitability decision is compared: a first-match candidate reporting a different
+/// profitable path than a best-match reference is not a disagreement. The reference's result is
+/// what gets reported, so a buggy candidate can be audited on a live feed without affecting
+/// output. Both scanners should share settings (including the `HaltHandle`), as they do when
+/// built through `ScannerBuilder::with_cross_check`.
+pub struct CrossCheckEvaluator<C, R> {
+    candidate: C,
+    reference: R,
+    disagreements: AtomicUsize,
+}
+
+impl<C: ArbEvaluator, R: ArbEvaluator> CrossCheckEvaluator<C, R> {
+    pub fn new(candidate: C, reference: R) -> Self {
+        Self { candidate, reference, disagreements: AtomicUsize::new(0) }
+    }
+
+    /// Number of updates on which the candidate and reference disagreed so far.
+    pub fn disagreements(&self) -> usize {
+        self.disagreements.load(Ordering::Relaxed)
+    }
+}
+
+impl<C: ArbEvaluator, R: ArbEvaluator> ArbEvaluator for CrossCheckEvaluator<C, R> {
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        let candidate = self.candidate.process_update(update);
+        let reference = self.reference.process_update(update);
+
+        if candidate.is_some() != reference.is_some() {
+            let total = self.disagreements.fetch_add(1, Ordering::Relaxed) + 1;
+            warn!(
+                symbol = %update.symbol,
+                candidate = ?candidate.as_ref().map(|o| o.result),
+                reference = ?reference.as_ref().map(|o| o.result),
+                path = %reference.as_ref().or(candidate.as_ref()).map(|o| format!("{:#}", o.path)).unwrap_or_default(),
+                total,
+                "Cross-check: scanners disagree on profitability"
+            );
+        }
+        reference
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.reference.price_snapshot()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        self.reference.halt_handle()
+    }
+
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        self.reference.evaluate_path(path)
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        self.reference.implied_quote(path)
+    }
+
+    fn invalidate_all(&self) {
+        self.candidate.invalidate_all();
+        self.reference.invalidate_all();
+    }
+
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        self.candidate.invalidate_symbols(symbols);
+        self.reference.invalidate_symbols(symbols);
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        self.reference.top_paths(n)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::{ArbMode, HashMapEdgeScanner, ScannerBuilder};
+    use crate::testutil::{assert_return_eq, mock_path, mock_reverse_path, mock_update};
+
+    /// Candidate with a deliberate bug: it never reports an opportunity.
+    #[derive(Default)]
+    struct NeverReports {
+        halt: HaltHandle,
+    }
+
+    impl ArbEvaluator for NeverReports {
+        fn process_update(&self, _update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+            None
+        }
+
+        fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+            HashMap::new()
+        }
+
+        fn halt_handle(&self) -> HaltHandle {
+            self.halt.clone()
+        }
+
+        fn evaluate_path(&self, _path: &PricingPath) -> Option<f64> {
+            None
+        }
+
+        fn implied_quote(&self, _path: &PricingPath) -> Option<ImpliedQuote> {
+            None
+        }
+
+        fn invalidate_all(&self) {}
+
+        fn top_paths(&self, _n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn test_buggy_candidate_is_flagged_against_edge_scanner() {
+        // Reverse of mock_path: 1 / 3001 * 0.07 * 50000 ≈ 1.166
+        let path = mock_path().reversed();
+        let cross_check = CrossCheckEvaluator::new(NeverReports::default(), HashMapEdgeScanner::new(vec![path]));
+
+        assert!(cross_check.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0)).is_none());
+        assert!(cross_check.process_update(&mock_update("ETHBTC", 0.07, 0.0701)).is_none());
+        assert_eq!(cross_check.disagreements(), 0, "both agree while the triangle is incomplete");
+
+        let reported = cross_check.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0));
+        assert!(reported.is_some(), "the reference's opportunity should be passed through");
+        assert_eq!(cross_check.disagreements(), 1);
+    }
+
+    #[test]
+    fn cross_checked_modes_agree_and_share_halt() {
+        let builder = ScannerBuilder::new(vec![mock_reverse_path()]).with_cross_check(ArbMode::EdgeMap);
+        let halt = builder.halt_handle();
+        let evaluator = builder.build(ArbMode::SimdBatch);
+
+        halt.halt();
+        assert!(evaluator.halt_handle().is_halted());
+        halt.resume();
+
+        evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+        evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+        let opportunity = evaluator.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0));
+        assert_return_eq(opportunity.expect("both scanners see the triangle").result, 50000.0 * 0.07 / 3001.0, 1e-12);
+    }
+}
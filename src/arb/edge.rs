@@ -1,87 +1,172 @@
 // src/arb/edge.rs
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Instant;
 
-use dashmap::DashMap;
 
-use crate::arb::ArbEvaluator;
+use crate::arb::{latest_per_symbol, ArbEvaluator, ArbOpportunity, HaltHandle, ImpliedQuote, ScannerSettings};
+use crate::arb::cooldown::PathCooldown;
+use crate::arb::eviction::PriceEviction;
+use crate::arb::scanner_core::ScannerCore;
+use crate::arb::quote_filter::store_quote;
 use crate::parse::TopOfBookUpdate;
-use crate::price_path::{PricingPath, Side};
+use crate::price_path::{apply_leg, leg_prices, legs_priced, PricingPath};
 
 /// A fast arbitrage evaluator that indexes triangular paths by symbol (edge)
 /// so only relevant paths are re-evaluated on each update.
 pub struct HashMapEdgeScanner {
-    price_store: DashMap<String, TopOfBookUpdate>,
-    path_index: HashMap<String, Vec<Arc<PricingPath>>>
+    core: ScannerCore,
+    settings: ScannerSettings,
+    eviction: PriceEviction,
+    cooldown: PathCooldown,
 }
 
 impl HashMapEdgeScanner {
     /// Constructs a new HashMapEdgeScanner by indexing all paths by the symbols they reference.
     pub fn new(price_paths: Vec<PricingPath>) -> Self {
-        let wrapped_paths: Vec<Arc<PricingPath>> = price_paths.into_iter().map(Arc::new).collect();
-        let mut index: HashMap<String, Vec<Arc<PricingPath>>> = HashMap::with_capacity(wrapped_paths.len() * 3);
+        Self::with_settings(price_paths, ScannerSettings::default())
+    }
 
-        for path in &wrapped_paths {
-            for symbol in path.symbols() {
-                index.entry(symbol).or_default().push(Arc::clone(path));
-            }
-        }
-        
+    /// Constructs the scanner with custom evaluation settings (see `ScannerBuilder`).
+    pub fn with_settings(price_paths: Vec<PricingPath>, settings: ScannerSettings) -> Self {
         Self {
-            price_store: DashMap::new(),
-            path_index: index,    
+            core: ScannerCore::new(price_paths, &settings),
+            eviction: PriceEviction::new(settings.price_ttl),
+            cooldown: PathCooldown::new(settings.opportunity_cooldown),
+            settings,
         }
     }
+
+    /// The quotes, path index and return averages (see `ScannerCore`).
+    pub fn core(&self) -> &ScannerCore {
+        &self.core
+    }
+
+    /// `path`'s opportunity at the stored quotes if all three legs are fresh, it's profitable and
+    /// not cooling down; a returned opportunity starts the path's cooldown.
+    fn evaluate(&self, path: &Arc<PricingPath>, trigger: &TopOfBookUpdate, now: Instant) -> Option<ArbOpportunity> {
+        let start = self.settings.start_notional;
+
+        let p1 = self.core.price_store.get(&path.leg1.symbol.symbol)?;
+        let p2 = self.core.price_store.get(&path.leg2.symbol.symbol)?;
+        let p3 = self.core.price_store.get(&path.leg3.symbol.symbol)?;
+        if !(self.settings.is_fresh(p1.received, p1.event_time_ms)
+            && self.settings.is_fresh(p2.received, p2.event_time_ms)
+            && self.settings.is_fresh(p3.received, p3.event_time_ms)) {
+            return None;
+        }
+        if !legs_priced(path, [&p1, &p2, &p3]) {
+            return None;
+        }
+
+        let [keep1, keep2, keep3] = self.settings.leg_multipliers(path);
+
+        let step1 = keep1 * apply_leg(start, path.leg1.side, &p1);
+
+        let step2 = keep2 * apply_leg(step1, path.leg2.side, &p2);
+
+        let end = keep3 * apply_leg(step2, path.leg3.side, &p3);
+
+        self.core.return_history.record(path, end / start);
+
+        let reportable = self.settings.is_profitable(path, leg_prices(path, [&p1, &p2, &p3]), end)
+            && self.settings.legs_in_sync([&p1, &p2, &p3].map(|q| q.event_time_ms))
+            && !self.cooldown.is_cooling(path, now);
+        reportable.then(|| {
+            self.cooldown.record(path, now);
+            ArbOpportunity::new(
+                Arc::clone(path),
+                Arc::clone(&self.settings.home_asset),
+                start,
+                end,
+                [step1 / start, step2 / step1, end / step2],
+                self.settings.confidence([&p1, &p2, &p3]),
+                [&p1, &p2, &p3],
+            )
+            .detected(trigger, now)
+        })
+    }
 }
 
 impl ArbEvaluator for HashMapEdgeScanner {
     /// Processes a top-of-book update and checks for arbitrage opportunities
     /// using only paths involving the updated symbol.
-    fn process_update(&self, update: &TopOfBookUpdate) -> Option<(PricingPath, f64)> {
-        self.price_store.insert(update.symbol.clone(), update.clone());
-        const START: f64 = 1.0;
-        if let Some(paths) = self.path_index.get(&update.symbol) {
-            for path in paths {
-                
-                let s1 = &path.leg1.symbol.symbol;
-                let s2 = &path.leg2.symbol.symbol;
-                let s3 = &path.leg3.symbol.symbol;
-
-                // Early filter: skip path if not all 3 symbols are present
-                    if !(self.price_store.contains_key(s1)
-                    && self.price_store.contains_key(s2)
-                    && self.price_store.contains_key(s3))
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        let now = self.settings.clock.now();
+        if !store_quote(&*self.core.price_store, &self.settings.quote_filter, update, now) {
+            return None;
+        }
+        let path_index = self.core.index();
+        self.eviction.maybe_sweep(&*self.core.price_store, now, |s| path_index.contains_key(s));
+        if self.settings.halt.is_halted() {
+            return None;
+        }
+        path_index
+            .get(&update.symbol)?
+            .iter()
+            .find_map(|path| self.evaluate(path, update, now))
+    }
+
+    /// Stores every quote in the batch, then evaluates each path touched by any of its symbols
+    /// exactly once at the post-batch prices, returning every profitable one.
+    ///
+    /// Each opportunity's latency is measured from the last quote of the symbol that brought
+    /// its path into the batch.
+    fn process_batch(&self, updates: &[TopOfBookUpdate]) -> Vec<ArbOpportunity> {
+        let now = self.settings.clock.now();
+        let triggers = latest_per_symbol(
+            updates
+                .iter()
+                .filter(|update| store_quote(&*self.core.price_store, &self.settings.quote_filter, update, now)),
+        );
+        let path_index = self.core.index();
+        self.eviction.maybe_sweep(&*self.core.price_store, now, |s| path_index.contains_key(s));
+        if self.settings.halt.is_halted() {
+            return Vec::new();
+        }
+
+        let mut evaluated = HashSet::new();
+        let mut opportunities = Vec::new();
+        for trigger in triggers {
+            let Some(paths) = path_index.get(&trigger.symbol) else { continue; };
+            for path in paths.iter() {
+                if evaluated.insert(Arc::as_ptr(path))
+                    && let Some(opportunity) = self.evaluate(path, trigger, now)
                 {
-                    continue;
+                    opportunities.push(opportunity);
                 }
-
-                // Safe to unwrap now
-                let p1 = self.price_store.get(s1).unwrap();
-                let p2 = self.price_store.get(s2).unwrap();
-                let p3 = self.price_store.get(s3).unwrap();
-
-                let step1 = match path.leg1.side {
-                    Side::Ask => START / p1.ask_price,
-                    Side::Bid => START * p1.bid_price,
-                };
-
-                let step2 = match path.leg2.side {
-                    Side::Ask => step1 /  p2.ask_price,
-                    Side::Bid => step1 * p2.bid_price 
-                };
-
-                let end = match path.leg3.side {
-                    Side::Ask => step2 / p3.ask_price,
-                    Side::Bid => step2 * p3.bid_price,
-                };
-
-                if end > START {
-                    return Some((path.as_ref().clone(), end));
-                };
             }
         }
-        None
+        opportunities
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.core.price_store.snapshot()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        self.settings.halt.clone()
+    }
+
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        self.settings.stored_path_return(&*self.core.price_store, path)
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        self.settings.stored_implied_quote(&*self.core.price_store, path)
+    }
+
+    fn invalidate_all(&self) {
+        self.core.price_store.clear();
+    }
+
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        self.core.price_store.remove_symbols(symbols);
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        self.core.return_history.top(n)
     }
 }
 
@@ -89,50 +174,61 @@ impl ArbEvaluator for HashMapEdgeScanner {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::price_path::{PathLeg, SymbolInfo};
-
-    fn mock_path() -> PricingPath {
-        let s1 = SymbolInfo {
-            symbol: "BTCUSDT".into(),
-            base_asset: "BTC".into(),
-            quote_asset: "USDT".into(),
-            status: "TRADING".into(),
-        };
-        let s2 = SymbolInfo {
-            symbol: "ETHBTC".into(),
-            base_asset: "ETH".into(),
-            quote_asset: "BTC".into(),
-            status: "TRADING".into(),
-        };
-        let s3 = SymbolInfo {
-            symbol: "ETHUSDT".into(),
-            base_asset: "ETH".into(),
-            quote_asset: "USDT".into(),
-            status: "TRADING".into(),
-        };
-
-        PricingPath {
-            leg1: PathLeg { symbol: s1, side: Side::Ask },
-            leg2: PathLeg { symbol: s2, side: Side::Ask },
-            leg3: PathLeg { symbol: s3, side: Side::Bid },
-        }
-    }
+    use crate::testutil::{mock_update, MockBuilder};
 
     #[test]
-    fn test_indexing_symbols_from_paths() {
-        let path = mock_path();
-        let scanner = HashMapEdgeScanner::new(vec![path]);
+    fn test_batch_matches_sequential_updates() {
+        use rand::{Rng, SeedableRng};
+        use rand_chacha::ChaCha12Rng;
+
+        // Both triangles in both directions
+        let paths = MockBuilder::new()
+            .triangle("USDT", "BTC", "ETH")
+            .triangle("USDT", "BTC", "SOL")
+            .paths("USDT", &["BTC", "ETH", "SOL"]);
+        assert_eq!(paths.len(), 4);
+        let symbols = ["BTCUSDT", "ETHBTC", "ETHUSDT", "SOLUSDT", "SOLBTC"];
+        let mids = [50000.0, 0.07, 3500.0, 150.0, 0.003];
+
+        let mut rng = ChaCha12Rng::seed_from_u64(1119);
+        let mut reported_total = 0;
+        for _ in 0..50 {
+            let batched = HashMapEdgeScanner::new(paths.clone());
+            let sequential = HashMapEdgeScanner::new(paths.clone());
+            // Hot symbols repeat within the burst; prices swing enough for some paths to clear 1.0
+            let burst: Vec<TopOfBookUpdate> = (0..rng.gen_range(1..20))
+                .map(|_| {
+                    let i = rng.gen_range(0..3).min(rng.gen_range(0..symbols.len()));
+                    let mid = mids[i] * rng.gen_range(0.9..1.1);
+                    mock_update(symbols[i], mid * 0.9999, mid * 1.0001)
+                })
+                .collect();
+
+            let found = batched.process_batch(&burst);
+            for update in &burst {
+                sequential.process_update(update);
+            }
 
-        assert!(scanner.path_index.contains_key("BTCUSDT"));
-        assert!(scanner.path_index.contains_key("ETHBTC"));
-        assert!(scanner.path_index.contains_key("ETHUSDT"));
+            let prices = |scanner: &HashMapEdgeScanner| -> HashMap<String, (f64, f64)> {
+                scanner.price_snapshot().into_iter().map(|(s, q)| (s, (q.bid_price, q.ask_price))).collect()
+            };
+            assert_eq!(prices(&batched), prices(&sequential));
+
+            let expected: HashSet<_> = paths
+                .iter()
+                .filter(|p| burst.iter().any(|u| p.symbols().contains(&u.symbol)))
+                .filter(|p| sequential.evaluate_path(p).is_some_and(|r| r > 1.0))
+                .map(PricingPath::key)
+                .collect();
+            let reported: HashSet<_> = found.iter().map(|o| o.path.key()).collect();
+            assert_eq!(reported.len(), found.len(), "a path was reported twice");
+            assert_eq!(reported, expected);
+            for opportunity in &found {
+                assert_eq!(Some(opportunity.result), sequential.evaluate_path(&opportunity.path));
+            }
+            reported_total += found.len();
+        }
+        assert!(reported_total > 0, "no burst produced an opportunity");
     }
 
-    #[test]
-    fn test_no_false_positive_paths() {
-        let path = mock_path();
-        let scanner = HashMapEdgeScanner::new(vec![path]);
-
-        assert!(!scanner.path_index.contains_key("FOOBAR"));
-    }
 }
\ No newline at end of file
@@ -0,0 +1,145 @@
+// src/arb/eviction.rs
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::debug;
+
+use super::price_store::PriceStore;
+
+
+/// Lazily bounds a scanner's price store by evicting quotes not refreshed within a TTL.
+///
+/// A long run over a churning universe otherwise keeps every symbol it ever saw (delisted or
+/// one-off streams). Sweeps run inline from `process_update`, at most once per TTL, and never
+/// evict a symbol that an active path trades.
+pub(crate) struct PriceEviction {
+    ttl: Option<Duration>,
+    next_sweep: Mutex<Option<Instant>>,
+}
+
+impl PriceEviction {
+    pub(crate) fn new(ttl: Option<Duration>) -> Self {
+        Self { ttl, next_sweep: Mutex::new(None) }
+    }
+
+    /// Sweeps `price_store` if a TTL is configured and one has passed since the last sweep.
+    ///
+    /// `now` is the caller's clock reading, so tests can advance time without sleeping.
+    /// Returns the number of evicted symbols.
+    pub(crate) fn maybe_sweep(
+        &self,
+        price_store: &dyn PriceStore,
+        now: Instant,
+        is_referenced: impl Fn(&str) -> bool,
+    ) -> usize {
+        let Some(ttl) = self.ttl else {
+            return 0;
+        };
+        // A concurrent update is already sweeping (or scheduling one); skip rather than wait
+        let Ok(mut next_sweep) = self.next_sweep.try_lock() else {
+            return 0;
+        };
+        match *next_sweep {
+            Some(due) if now < due => 0,
+            Some(_) => {
+                *next_sweep = Some(now + ttl);
+                drop(next_sweep);
+                evict_stale(price_store, now, ttl, is_referenced)
+            }
+            None => {
+                *next_sweep = Some(now + ttl);
+                0
+            }
+        }
+    }
+}
+
+
+/// Removes quotes older than `ttl` at `now`, except for symbols `is_referenced` keeps.
+pub(crate) fn evict_stale(
+    price_store: &dyn PriceStore,
+    now: Instant,
+    ttl: Duration,
+    is_referenced: impl Fn(&str) -> bool,
+) -> usize {
+    let evicted = price_store.retain(&mut |symbol, quote| {
+        is_referenced(symbol) || now.saturating_duration_since(quote.received) <= ttl
+    });
+    if evicted > 0 {
+        debug!(evicted, remaining = price_store.len(), "Evicted stale unreferenced quotes");
+    }
+    evicted
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::price_store::DashMapPriceStore;
+    use crate::arb::quote_filter::StoredQuote;
+    use std::sync::Arc;
+
+    use crate::arb::ScannerBuilder;
+    use crate::testutil::{mock_reverse_path, mock_update, MockClock, MODES};
+
+    fn store(symbols: &[&str], received: Instant) -> DashMapPriceStore {
+        let store = DashMapPriceStore::new();
+        for &symbol in symbols {
+            PriceStore::insert(&store, StoredQuote { update: mock_update(symbol, 1.0, 1.1), received });
+        }
+        store
+    }
+
+    #[test]
+    fn test_unreferenced_symbol_evicted_after_ttl() {
+        let ttl = Duration::from_secs(60);
+        let start = Instant::now();
+        let price_store = store(&["BTCUSDT", "ETHBTC", "DELISTEDUSDT"], start);
+        let is_referenced = |symbol: &str| symbol != "DELISTEDUSDT";
+        let eviction = PriceEviction::new(Some(ttl));
+
+        // The first call only schedules a sweep; nothing is due within the TTL
+        assert_eq!(eviction.maybe_sweep(&price_store, start, is_referenced), 0);
+        assert_eq!(eviction.maybe_sweep(&price_store, start + ttl / 2, is_referenced), 0);
+        assert_eq!(price_store.len(), 3);
+
+        // Past the TTL, the stale unreferenced quote goes; equally stale path symbols stay
+        assert_eq!(eviction.maybe_sweep(&price_store, start + ttl * 2, is_referenced), 1);
+        assert!(!price_store.contains_key("DELISTEDUSDT"));
+        assert!(price_store.contains_key("BTCUSDT") && price_store.contains_key("ETHBTC"));
+    }
+
+    #[test]
+    fn test_fresh_unreferenced_symbol_is_kept() {
+        let ttl = Duration::from_secs(60);
+        let start = Instant::now();
+        let price_store = store(&["ONEOFFUSDT"], start + ttl);
+
+        assert_eq!(evict_stale(&price_store, start + ttl * 3 / 2, ttl, |_| false), 0);
+        assert_eq!(evict_stale(&price_store, start + ttl * 3, ttl, |_| false), 1);
+        assert!(price_store.is_empty());
+    }
+
+    #[test]
+    fn price_ttl_evicts_only_unreferenced_symbols() {
+        for (mode, on_update_return) in MODES {
+            let clock = Arc::new(MockClock::new());
+            let evaluator = ScannerBuilder::new(vec![mock_reverse_path()])
+                .with_price_ttl(Duration::from_secs(60))
+                .with_clock(clock.clone())
+                .with_on_update_return(on_update_return)
+                .build(mode);
+            evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            evaluator.process_update(&mock_update("DELISTEDUSDT", 1.0, 1.01));
+
+            clock.advance(Duration::from_secs(61));
+            evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+            let snapshot = evaluator.price_snapshot();
+
+            // The interned scanner never stores symbols outside its paths
+            assert!(!snapshot.contains_key("DELISTEDUSDT"), "{mode:?} kept an unreferenced quote");
+            assert!(snapshot.contains_key("BTCUSDT"), "{mode:?} evicted a path symbol");
+        }
+    }
+}
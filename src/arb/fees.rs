@@ -0,0 +1,50 @@
+// src/arb/fees.rs
+
+use std::collections::HashMap;
+
+use crate::price_path::{normalize_symbol, PricingPath};
+
+
+/// Taker fee charged per leg: a default rate with per-symbol overrides.
+///
+/// Rates are fractions of the traded amount (10 bps = 0.001). Overrides model symbols that
+/// don't pay the account's usual tier — promotional zero-fee pairs, or a BNB-discounted rate
+/// on some markets — so mixed-fee universes price at their real net return.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeeSchedule {
+    pub default_rate: f64,
+    /// Rate per normalized symbol name, replacing `default_rate` on legs trading that symbol.
+    pub overrides: HashMap<String, f64>,
+}
+
+impl FeeSchedule {
+    /// The same `rate` on every symbol.
+    pub fn flat(rate: f64) -> Self {
+        Self { default_rate: rate, overrides: HashMap::new() }
+    }
+
+    /// Charges `rate` instead of the default on legs trading `symbol`.
+    pub fn with_override(mut self, symbol: &str, rate: f64) -> Self {
+        self.overrides.insert(normalize_symbol(symbol), rate);
+        self
+    }
+
+    /// Fee rate on a leg trading `symbol`.
+    pub fn rate(&self, symbol: &str) -> f64 {
+        // Skip hashing entirely for the common flat schedule
+        if self.overrides.is_empty() {
+            return self.default_rate;
+        }
+        self.overrides.get(symbol).copied().unwrap_or(self.default_rate)
+    }
+
+    /// Fraction of a leg's output on `symbol` kept after its fee.
+    pub fn multiplier(&self, symbol: &str) -> f64 {
+        1.0 - self.rate(symbol)
+    }
+
+    /// `multiplier` for each leg of `path`, in leg order.
+    pub fn path_multipliers(&self, path: &PricingPath) -> [f64; 3] {
+        [&path.leg1, &path.leg2, &path.leg3].map(|leg| self.multiplier(&leg.symbol.symbol))
+    }
+}
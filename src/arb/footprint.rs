@@ -0,0 +1,182 @@
+// src/arb/footprint.rs
+
+//! Rough heap-usage estimates behind each scanner's `approx_memory_bytes`.
+//!
+//! Figures count allocated capacity (strings, vectors, hash table slots) rather than what the
+//! allocator actually reserves, so they are a lower bound good for comparing scanners and
+//! universe sizes, not an exact measurement.
+
+use std::collections::HashSet;
+use std::mem::size_of;
+use std::sync::Arc;
+
+
+use crate::price_path::{PricingPath, SymbolInfo};
+
+use super::path_index::SymbolPaths;
+use super::price_store::DashMapPriceStore;
+use super::quote_filter::StoredQuote;
+
+
+/// Slots of a `HashMap`/`HashSet` with room for `capacity` entries: one `T` plus a control byte each.
+pub(crate) fn table_bytes<T>(capacity: usize) -> usize {
+    capacity * (size_of::<T>() + 1)
+}
+
+/// Heap behind one `Arc<T>`: the value plus the strong and weak counts ahead of it.
+fn arc_bytes<T>() -> usize {
+    2 * size_of::<usize>() + size_of::<T>()
+}
+
+/// Heap behind one shared `SymbolInfo`: its allocation and strings.
+fn symbol_bytes(symbol: &SymbolInfo) -> usize {
+    arc_bytes::<SymbolInfo>()
+        + symbol.symbol.capacity()
+        + symbol.base_asset.capacity()
+        + symbol.quote_asset.capacity()
+        + symbol.status.capacity()
+}
+
+/// Heap behind a set of shared paths, counting each distinct path allocation, and each distinct
+/// symbol its legs share, once however many times they appear.
+pub(crate) fn shared_paths_bytes<'a>(paths: impl IntoIterator<Item = &'a Arc<PricingPath>>) -> usize {
+    let mut distinct_paths = HashSet::new();
+    let mut distinct_symbols = HashSet::new();
+    let mut bytes = 0;
+    for path in paths {
+        if !distinct_paths.insert(Arc::as_ptr(path)) {
+            continue;
+        }
+        bytes += arc_bytes::<PricingPath>();
+        for leg in [&path.leg1, &path.leg2, &path.leg3] {
+            if distinct_symbols.insert(Arc::as_ptr(&leg.symbol)) {
+                bytes += symbol_bytes(&leg.symbol);
+            }
+        }
+    }
+    bytes
+}
+
+/// A symbol → paths index, counting each shared path and symbol allocation once however many
+/// symbols list it.
+pub(crate) fn path_index_bytes(index: &SymbolPaths) -> usize {
+    let mut bytes = table_bytes::<(String, Arc<[Arc<PricingPath>]>)>(index.capacity());
+    for (symbol, paths) in index {
+        bytes += symbol.capacity() + 2 * size_of::<usize>() + paths.len() * size_of::<Arc<PricingPath>>();
+    }
+    bytes + shared_paths_bytes(index.values().flat_map(|paths| paths.iter()))
+}
+
+/// A string-keyed price store, including each entry's key, shared quote and quote symbol.
+pub(crate) fn price_store_bytes(price_store: &DashMapPriceStore) -> usize {
+    let entries: usize = price_store
+        .iter()
+        .map(|entry| arc_bytes::<StoredQuote>() + entry.key().capacity() + entry.value().update.symbol.capacity())
+        .sum();
+    table_bytes::<(String, Arc<StoredQuote>)>(price_store.capacity()) + entries
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::{
+        HashMapEdgeScanner, InternedEdgeScanner, NaivePrecompiledScanner, RayonBestMatchScanner,
+        RayonFirstMatchScanner, SimdBatchScanner, VwapEdgeScanner,
+    };
+    use crate::price_path::Side;
+    use crate::testutil::{make_path, mock_path, mock_update};
+
+    /// `count` USDT → BTC → X{i} → USDT triangles sharing the BTCUSDT leg.
+    fn synthetic_paths(count: usize) -> Vec<PricingPath> {
+        (0..count)
+            .map(|i| {
+                let alt = format!("X{i}");
+                make_path([
+                    ("BTCUSDT", "BTC", "USDT", Side::Ask),
+                    (&format!("{alt}BTC"), &alt, "BTC", Side::Ask),
+                    (&format!("{alt}USDT"), &alt, "USDT", Side::Bid),
+                ])
+            })
+            .collect()
+    }
+
+    /// Builds every scanner over `paths` and reports each one's estimate.
+    fn footprints(paths: &[PricingPath]) -> [(&'static str, usize); 7] {
+        let updates: Vec<_> = paths
+            .iter()
+            .flat_map(PricingPath::symbols)
+            .map(|symbol| mock_update(&symbol, 1.0, 1.0001))
+            .collect();
+        let warm = |scanner: &dyn crate::arb::ArbEvaluator| {
+            for update in &updates {
+                scanner.process_update(update);
+            }
+        };
+
+        let naive = NaivePrecompiledScanner::new(paths.to_vec());
+        let edge = HashMapEdgeScanner::new(paths.to_vec());
+        let interned = InternedEdgeScanner::new(paths.to_vec());
+        let rayon_first = RayonFirstMatchScanner::new(paths.to_vec());
+        let rayon_best = RayonBestMatchScanner::new(paths.to_vec());
+        let simd = SimdBatchScanner::new(paths.to_vec());
+        for scanner in [&naive as &dyn crate::arb::ArbEvaluator, &edge, &interned, &rayon_first, &rayon_best, &simd] {
+            warm(scanner);
+        }
+        let vwap = VwapEdgeScanner::new(paths.to_vec());
+
+        [
+            ("naive", naive.approx_memory_bytes()),
+            ("edge", edge.core().approx_memory_bytes()),
+            ("interned", interned.approx_memory_bytes()),
+            ("rayon_first", rayon_first.core().approx_memory_bytes()),
+            ("rayon_best", rayon_best.core().approx_memory_bytes()),
+            ("simd", simd.core().approx_memory_bytes()),
+            ("vwap", vwap.approx_memory_bytes()),
+        ]
+    }
+
+    #[test]
+    fn test_footprint_grows_with_path_count() {
+        let mut previous: Option<[(&str, usize); 7]> = None;
+        for path_count in [5, 50, 500] {
+            let current = footprints(&synthetic_paths(path_count));
+            if let Some(previous) = previous {
+                for ((name, before), (_, after)) in previous.iter().zip(&current) {
+                    assert!(after > before, "{name}: {after} bytes at {path_count} paths, {before} before");
+                }
+            }
+            previous = Some(current);
+        }
+    }
+
+    #[test]
+    fn test_shared_symbols_shrink_discovered_paths() {
+        let paths = crate::price_path::find_and_build_price_paths("USDT", &["BTC", "ETH", "SOL"]).unwrap();
+        let shared: Vec<Arc<PricingPath>> = paths.iter().cloned().map(Arc::new).collect();
+        // What every leg owning its own SymbolInfo copy used to cost
+        let owned: Vec<Arc<PricingPath>> = paths
+            .into_iter()
+            .map(|mut path| {
+                for leg in [&mut path.leg1, &mut path.leg2, &mut path.leg3] {
+                    leg.symbol = Arc::new((*leg.symbol).clone());
+                }
+                Arc::new(path)
+            })
+            .collect();
+
+        let (shared, owned) = (shared_paths_bytes(&shared), shared_paths_bytes(&owned));
+        assert!(shared * 3 < owned * 2, "{shared} bytes shared vs {owned} bytes owned");
+    }
+
+    #[test]
+    fn test_footprint_is_sane_for_a_single_path() {
+        let path = mock_path();
+        // The path and its three symbols are the least any scanner must hold
+        let floor = shared_paths_bytes([&Arc::new(path.clone())]);
+        for (name, bytes) in footprints(&[path]) {
+            assert!(bytes >= floor, "{name}: {bytes} bytes is below the path's own {floor}");
+            assert!(bytes < 16 * 1024, "{name}: {bytes} bytes for one path and three quotes");
+        }
+    }
+}
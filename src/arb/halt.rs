@@ -0,0 +1,58 @@
+// src/arb/halt.rs
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+
+/// Shared kill switch for a scanner, e.g. for tripping on a risk breach.
+///
+/// While halted, scanners keep storing incoming quotes (so prices stay warm for an
+/// instant resume) but report no opportunities. Clones share the same flag, so the
+/// handle can be passed to whatever monitors risk while the scanner runs in `arb_loop`.
+#[derive(Debug, Clone, Default)]
+pub struct HaltHandle {
+    halted: Arc<AtomicBool>,
+}
+
+impl HaltHandle {
+    /// Stops reporting opportunities until `resume` is called.
+    pub fn halt(&self) {
+        self.halted.store(true, Ordering::Release);
+    }
+
+    pub fn resume(&self) {
+        self.halted.store(false, Ordering::Release);
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::Acquire)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::arb::ScannerBuilder;
+    use crate::testutil::{mock_reverse_path, mock_update, MODES};
+
+    #[test]
+    fn halted_scanner_keeps_prices_but_reports_nothing() {
+        for (mode, on_update_return) in MODES {
+            let builder = ScannerBuilder::new(vec![mock_reverse_path()]).with_on_update_return(on_update_return);
+            let halt = builder.halt_handle();
+            let evaluator = builder.build(mode);
+            evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+
+            halt.halt();
+            assert!(evaluator.halt_handle().is_halted());
+            assert!(evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701)).is_none());
+            assert!(evaluator.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0)).is_none(), "{mode:?} reported while halted");
+            // Quotes received while halted are still stored
+            assert_eq!(evaluator.price_snapshot()["ETHUSDT"].ask_price, 3001.0);
+
+            evaluator.halt_handle().resume();
+            let result = evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            assert!(result.is_some(), "{mode:?} didn't resume detection");
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// src/arb/implied.rs
+
+use serde::Serialize;
+
+use crate::parse::TopOfBookUpdate;
+use crate::price_path::{PricingPath, Side};
+
+use super::fees::FeeSchedule;
+
+
+/// The quote a path's one unquoted leg would need for the path to break even after fees.
+///
+/// For watchlists and dashboards only: it is derived from the other two legs, not observed,
+/// so it never feeds opportunity detection or execution.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImpliedQuote {
+    pub symbol: String,
+    /// Position of the missing leg in the path, `0..3`.
+    pub leg: usize,
+    pub side: Side,
+    /// Highest ask (for an `Ask` leg) or lowest bid (for a `Bid` leg) at which the path returns
+    /// exactly `1.0` net of fees; any better quote makes it profitable.
+    pub break_even_price: f64,
+}
+
+/// Implies the break-even price of the single leg whose quote is `None`.
+///
+/// `None` unless exactly one leg is unquoted: with all three quoted the path is simply priced
+/// (see `ArbEvaluator::evaluate_path`), and with two missing nothing can be implied.
+pub fn implied_break_even(
+    path: &PricingPath,
+    quotes: [Option<&TopOfBookUpdate>; 3],
+    fees: &FeeSchedule,
+) -> Option<ImpliedQuote> {
+    let keeps = fees.path_multipliers(path);
+    let legs = [&path.leg1, &path.leg2, &path.leg3];
+    let mut missing = None;
+    // Product of the quoted legs' factors, fees included; the missing leg must supply 1 / known
+    let mut known = 1.0;
+    for (i, (leg, quote)) in legs.iter().zip(quotes).enumerate() {
+        match quote {
+            Some(q) => known *= keeps[i] * leg.side.convert(1.0, leg.side.price(q.bid_price, q.ask_price)),
+            None if missing.is_none() => missing = Some(i),
+            None => return None,
+        }
+    }
+    let leg = missing?;
+    let side = legs[leg].side;
+    let keep = keeps[leg];
+    // Ask legs convert at keep / price, Bid legs at keep * price
+    let break_even_price = match side {
+        Side::Ask => keep * known,
+        Side::Bid => 1.0 / (keep * known),
+    };
+    break_even_price.is_finite().then(|| ImpliedQuote {
+        symbol: legs[leg].symbol.symbol.clone(),
+        leg,
+        side,
+        break_even_price,
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::ScannerBuilder;
+    use crate::testutil::{assert_return_eq, mock_path, mock_reverse_path, mock_update, MODES};
+
+    #[test]
+    fn missing_last_leg_implies_the_break_even_bid() {
+        // BUY BTCUSDT @ 50010 → BUY ETHBTC @ 0.0701 → SELL ETHUSDT @ p breaks even at p = 50010 * 0.0701
+        let btc = mock_update("BTCUSDT", 50000.0, 50010.0);
+        let eth_btc = mock_update("ETHBTC", 0.07, 0.0701);
+
+        let implied = implied_break_even(&mock_path(), [Some(&btc), Some(&eth_btc), None], &FeeSchedule::default()).unwrap();
+        assert_eq!(implied.symbol, "ETHUSDT");
+        assert_eq!(implied.leg, 2);
+        assert_eq!(implied.side, Side::Bid);
+        assert_return_eq(implied.break_even_price, 3505.701, 1e-9);
+
+        let with_fees = implied_break_even(&mock_path(), [Some(&btc), Some(&eth_btc), None], &FeeSchedule::flat(0.001)).unwrap();
+        assert_return_eq(with_fees.break_even_price, 3505.701 / 0.999_f64.powi(3), 1e-9);
+    }
+
+    #[test]
+    fn missing_ask_leg_implies_the_break_even_ask() {
+        // BUY BTCUSDT @ p → BUY ETHBTC @ 0.0701 → SELL ETHUSDT @ 3600 breaks even at p = 3600 / 0.0701
+        let eth_btc = mock_update("ETHBTC", 0.07, 0.0701);
+        let eth = mock_update("ETHUSDT", 3600.0, 3601.0);
+
+        let implied = implied_break_even(&mock_path(), [None, Some(&eth_btc), Some(&eth)], &FeeSchedule::default()).unwrap();
+        assert_eq!((implied.symbol.as_str(), implied.leg, implied.side), ("BTCUSDT", 0, Side::Ask));
+        assert_return_eq(implied.break_even_price, 3600.0 / 0.0701, 1e-9);
+    }
+
+    #[test]
+    fn nothing_is_implied_unless_exactly_one_leg_is_missing() {
+        let btc = mock_update("BTCUSDT", 50000.0, 50010.0);
+        let eth_btc = mock_update("ETHBTC", 0.07, 0.0701);
+        let eth = mock_update("ETHUSDT", 3600.0, 3601.0);
+
+        assert!(implied_break_even(&mock_path(), [Some(&btc), Some(&eth_btc), Some(&eth)], &FeeSchedule::default()).is_none());
+        assert!(implied_break_even(&mock_path(), [Some(&btc), None, None], &FeeSchedule::default()).is_none());
+    }
+
+    #[test]
+    fn implied_quote_prices_the_one_missing_leg_without_reporting() {
+        for (mode, on_update_return) in MODES {
+            let evaluator = ScannerBuilder::new(vec![mock_reverse_path()])
+                .with_fee_bps(10.0)
+                .with_on_update_return(on_update_return)
+                .build(mode);
+            assert!(evaluator.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0)).is_none());
+            assert!(evaluator.implied_quote(&mock_reverse_path()).is_none(), "{mode:?} implied from one leg");
+            assert!(evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701)).is_none());
+
+            // SELL BTCUSDT @ p after BUY ETHUSDT @ 3001, SELL ETHBTC @ 0.07: p * 0.07 / 3001 * 0.999³ = 1
+            let implied = evaluator.implied_quote(&mock_reverse_path()).expect("BTCUSDT is the only missing leg");
+            assert_eq!((implied.symbol.as_str(), implied.leg, implied.side), ("BTCUSDT", 2, Side::Bid), "{mode:?}");
+            assert_return_eq(implied.break_even_price, 3001.0 / 0.07 / 0.999_f64.powi(3), 1e-6);
+
+            evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            assert!(evaluator.implied_quote(&mock_reverse_path()).is_none(), "{mode:?} implied a quoted leg");
+        }
+    }
+}
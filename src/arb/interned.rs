@@ -0,0 +1,317 @@
+// src/arb/interned.rs
+
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use crate::parse::TopOfBookUpdate;
+use crate::price_path::{PricingPath, Side};
+
+use super::{leg_time_spread_ms, ArbEvaluator, ArbOpportunity, HaltHandle, ImpliedQuote, ScannerSettings};
+use super::cooldown::PathCooldown;
+use super::return_history::ReturnHistory;
+use super::implied::implied_break_even;
+use super::footprint::{shared_paths_bytes, table_bytes};
+use super::quote_filter::log_rejection;
+
+/// Latest top-of-book prices for one interned symbol.
+#[derive(Debug, Clone, Copy)]
+struct Quote {
+    bid: f64,
+    ask: f64,
+    received: Instant,
+    event_time_ms: Option<u64>,
+}
+
+/// A pricing path with its legs resolved to interned symbol ids.
+struct InternedPath {
+    path: Arc<PricingPath>,
+    legs: [(usize, Side); 3],
+    /// Each leg's fee multiplier, resolved from the fee schedule at construction.
+    keeps: [f64; 3],
+}
+
+/// An edge scanner keyed by interned symbol ids instead of `String`s.
+///
+/// Every symbol referenced by a path is assigned a dense id at construction. An update
+/// costs a single string hash (symbol → id); the affected paths then read their three
+/// quotes from a flat price table by index, instead of the three `DashMap<String, _>`
+/// lookups per path paid by `HashMapEdgeScanner`.
+///
+/// Updates for symbols not referenced by any path are ignored and do not appear in
+/// `price_snapshot`.
+pub struct InternedEdgeScanner {
+    symbol_ids: HashMap<String, usize>,
+    symbol_names: Vec<String>,
+    prices: RwLock<Vec<Option<Quote>>>,
+    paths: Vec<InternedPath>,
+    paths_by_symbol: Vec<Vec<usize>>,
+    settings: ScannerSettings,
+    cooldown: PathCooldown,
+    return_history: ReturnHistory,
+}
+
+impl InternedEdgeScanner {
+    pub fn new(price_paths: Vec<PricingPath>) -> Self {
+        Self::with_settings(price_paths, ScannerSettings::default())
+    }
+
+    /// Constructs the scanner with custom evaluation settings (see `ScannerBuilder`).
+    pub fn with_settings(price_paths: Vec<PricingPath>, settings: ScannerSettings) -> Self {
+        let mut symbol_ids: HashMap<String, usize> = HashMap::new();
+        let mut symbol_names: Vec<String> = Vec::new();
+        let mut intern = |symbol: &str| -> usize {
+            if let Some(&id) = symbol_ids.get(symbol) {
+                return id;
+            }
+            let id = symbol_names.len();
+            symbol_ids.insert(symbol.to_string(), id);
+            symbol_names.push(symbol.to_string());
+            id
+        };
+
+        let paths: Vec<InternedPath> = price_paths
+            .into_iter()
+            .map(|path| {
+                let legs = [&path.leg1, &path.leg2, &path.leg3]
+                    .map(|leg| (intern(&leg.symbol.symbol), leg.side));
+                let keeps = settings.leg_multipliers(&path);
+                InternedPath { path: Arc::new(path), legs, keeps }
+            })
+            .collect();
+
+        let mut paths_by_symbol: Vec<Vec<usize>> = vec![Vec::new(); symbol_names.len()];
+        for (index, path) in paths.iter().enumerate() {
+            for (id, _) in path.legs {
+                if !paths_by_symbol[id].contains(&index) {
+                    paths_by_symbol[id].push(index);
+                }
+            }
+        }
+
+        Self {
+            prices: RwLock::new(vec![None; symbol_names.len()]),
+            symbol_ids,
+            symbol_names,
+            paths,
+            paths_by_symbol,
+            cooldown: PathCooldown::new(settings.opportunity_cooldown),
+            return_history: ReturnHistory::new(settings.return_ewma_alpha),
+            settings,
+        }
+    }
+
+    /// Stored quote for each leg of `path`, `None` for a leg never quoted or not interned.
+    fn leg_quotes(&self, path: &PricingPath) -> [Option<TopOfBookUpdate>; 3] {
+        let prices = self.prices.read().unwrap();
+        [&path.leg1, &path.leg2, &path.leg3].map(|leg| {
+            let id = *self.symbol_ids.get(&leg.symbol.symbol)?;
+            prices[id].map(|q| TopOfBookUpdate {
+                symbol: leg.symbol.symbol.clone(),
+                bid_price: q.bid,
+                ask_price: q.ask,
+                recv_ts: q.received,
+                event_time_ms: q.event_time_ms,
+            })
+        })
+    }
+
+    /// Approximate heap used by the symbol interner, price table and path tables (see `footprint`).
+    pub fn approx_memory_bytes(&self) -> usize {
+        let interner = table_bytes::<(String, usize)>(self.symbol_ids.capacity())
+            + self.symbol_names.capacity() * size_of::<String>()
+            // Names are stored twice: as interner keys and in `symbol_names`
+            + 2 * self.symbol_names.iter().map(String::capacity).sum::<usize>();
+        let prices = self.prices.read().unwrap().capacity() * size_of::<Option<Quote>>();
+        let paths = self.paths.capacity() * size_of::<InternedPath>()
+            + shared_paths_bytes(self.paths.iter().map(|p| &p.path));
+        let by_symbol = self.paths_by_symbol.capacity() * size_of::<Vec<usize>>()
+            + self.paths_by_symbol.iter().map(|ids| ids.capacity() * size_of::<usize>()).sum::<usize>();
+        interner + prices + paths + by_symbol
+    }
+}
+
+impl ArbEvaluator for InternedEdgeScanner {
+    /// Stores the quote under its interned id and evaluates only the paths using that id,
+    /// returning the first profitable one.
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        let &id = self.symbol_ids.get(&update.symbol)?;
+
+        let rejection = self.settings.quote_filter.rejection_reason(update);
+        {
+            let mut prices = self.prices.write().unwrap();
+            prices[id] = match rejection {
+                Some(_) => None,
+                None => Some(Quote {
+                    bid: update.bid_price,
+                    ask: update.ask_price,
+                    received: self.settings.clock.now(),
+                    event_time_ms: update.event_time_ms,
+                }),
+            };
+        }
+        if let Some(reason) = rejection {
+            log_rejection(update, reason);
+            return None;
+        }
+        if self.settings.halt.is_halted() {
+            return None;
+        }
+
+        let start = self.settings.start_notional;
+        let prices = self.prices.read().unwrap();
+        for &index in &self.paths_by_symbol[id] {
+            let path = &self.paths[index];
+
+            let mut leg_prices = [0.0; 3];
+            let mut leg_quotes = [(0.0, 0.0, Instant::now()); 3];
+            let mut leg_event_times = [None; 3];
+            let mut complete = true;
+            for (i, &(symbol_id, side)) in path.legs.iter().enumerate() {
+                match prices[symbol_id] {
+                    Some(quote) if self.settings.is_fresh(quote.received, quote.event_time_ms) => {
+                        leg_quotes[i] = (quote.bid, quote.ask, quote.received);
+                        leg_event_times[i] = quote.event_time_ms;
+                        leg_prices[i] = side.price(quote.bid, quote.ask);
+                    }
+                    _ => {
+                        complete = false;
+                        break;
+                    }
+                }
+            }
+            if !complete {
+                continue;
+            }
+
+            let mut end = start;
+            let mut leg_factors = [0.0; 3];
+            for (i, (price, &(_, side))) in leg_prices.iter().zip(&path.legs).enumerate() {
+                let next = path.keeps[i] * side.convert(end, *price);
+                leg_factors[i] = next / end;
+                end = next;
+            }
+            self.return_history.record(&path.path, end / start);
+
+            if self.settings.is_profitable(&path.path, leg_prices, end) && self.settings.legs_in_sync(leg_event_times) {
+                let now = self.settings.clock.now();
+                if self.cooldown.is_cooling(&path.path, now) {
+                    continue;
+                }
+                self.cooldown.record(&path.path, now);
+                return Some(ArbOpportunity {
+                    path: Arc::clone(&path.path),
+                    home_asset: Arc::clone(&self.settings.home_asset),
+                    start_notional: start,
+                    result: end / start,
+                    profit_home: end - start,
+                    profit_normalized: None,
+                    leg_prices,
+                    leg_factors,
+                    confidence: self.settings.confidence.path(leg_quotes, now),
+                    leg_time_spread_ms: leg_time_spread_ms(leg_event_times),
+                    detection_latency: None,
+                }.detected(update, now));
+            }
+        }
+        None
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        let prices = self.prices.read().unwrap();
+        self.symbol_names
+            .iter()
+            .zip(prices.iter())
+            .filter_map(|(symbol, quote)| {
+                quote.map(|q| {
+                    let update = TopOfBookUpdate {
+                        symbol: symbol.clone(),
+                        bid_price: q.bid,
+                        ask_price: q.ask,
+                        recv_ts: q.received,
+                        event_time_ms: q.event_time_ms,
+                    };
+                    (symbol.clone(), update)
+                })
+            })
+            .collect()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        self.settings.halt.clone()
+    }
+
+    /// Only symbols interned at construction can be priced.
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        let [Some(q1), Some(q2), Some(q3)] = self.leg_quotes(path) else { return None; };
+        Some(self.settings.path_return(path, [&q1, &q2, &q3]))
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        let quotes = self.leg_quotes(path);
+        implied_break_even(path, [0, 1, 2].map(|i| quotes[i].as_ref()), &self.settings.fees)
+    }
+
+    fn invalidate_all(&self) {
+        self.prices.write().unwrap().fill(None);
+    }
+
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        let mut prices = self.prices.write().unwrap();
+        for id in symbols.iter().filter_map(|s| self.symbol_ids.get(s)) {
+            prices[*id] = None;
+        }
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        self.return_history.top(n)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::HashMapEdgeScanner;
+    use crate::testutil::{assert_return_eq, mock_path, mock_update};
+
+    fn mock_paths() -> Vec<PricingPath> {
+        let forward = mock_path();
+        let reverse = forward.reversed();
+        vec![forward, reverse]
+    }
+
+    #[test]
+    fn test_interned_scanner_matches_string_keyed_scanner() {
+        let interned = InternedEdgeScanner::new(mock_paths());
+        let edge = HashMapEdgeScanner::new(mock_paths());
+
+        let updates = [
+            mock_update("BTCUSDT", 50000.0, 50010.0),
+            mock_update("ETHBTC", 0.07, 0.0701),
+            mock_update("ETHUSDT", 3000.0, 3001.0),
+            mock_update("ETHBTC", 0.06, 0.0601),
+            mock_update("ETHUSDT", 2990.0, 2991.0),
+        ];
+
+        for update in &updates {
+            let a = interned.process_update(update);
+            let b = edge.process_update(update);
+            assert_eq!(a.is_some(), b.is_some(), "divergence on {}", update.symbol);
+            if let (Some(a), Some(b)) = (a, b) {
+                assert_eq!(a.path.to_string(), b.path.to_string());
+                assert_eq!(a.leg_prices, b.leg_prices);
+                assert_return_eq(a.result, b.result, 1e-12);
+            }
+        }
+        assert_eq!(interned.price_snapshot().len(), 3);
+    }
+
+    #[test]
+    fn test_unknown_symbol_is_ignored() {
+        let scanner = InternedEdgeScanner::new(mock_paths());
+        assert!(scanner.process_update(&mock_update("FOOBAR", 1.0, 1.1)).is_none());
+        assert!(scanner.price_snapshot().is_empty());
+    }
+}
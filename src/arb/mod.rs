@@ -1,22 +1,77 @@
 // src/arb/mod.rs
-use std::{fs, path::Path};
+use std::{collections::HashMap, fmt, fs, path::Path, str::FromStr};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
-use config::OnUpdateReturn;
+use anyhow::{bail, Context, Result};
+use serde::{Serialize, Serializer};
 use tokio::sync::mpsc::Receiver;
-use tracing::info;
-use crate::{parse::TopOfBookUpdate, price_path::PricingPath};
+use tracing::{debug, warn};
+use crate::{parse::TopOfBookUpdate, price_path::{leg_prices, PricingPath, SymbolFilter}};
 
+pub mod adaptive;
+pub mod builder;
+pub mod clock;
 pub mod naive;
 pub mod edge;
+pub mod interned;
 pub mod rayon_scan;
+pub mod simd;
+pub mod confidence;
 pub mod config;
+pub mod cross_check;
+mod cooldown;
+mod eviction;
+pub mod fees;
+mod footprint;
+pub mod halt;
+pub mod implied;
+pub mod normalize;
+mod path_index;
+pub mod predicate;
+pub mod price_store;
+pub mod quote_filter;
+#[cfg(feature = "decision_trace")]
+pub mod recording;
+mod return_history;
+pub mod scanner_core;
+pub mod sink;
+pub mod tick_size;
+pub mod tier;
+pub mod vwap;
 
-pub use config::{ArbConfig, RayonScanConfig};
+pub use adaptive::AdaptiveScanner;
+pub use builder::{ScannerBuilder, ScannerSettings};
+pub use clock::{Clock, SystemClock};
+pub use confidence::ConfidenceReference;
+pub use config::{
+    AdaptiveConfig, ArbConfig, EvaluationConfig, LogFormat, LoggingConfig, OutputConfig, PipelineConfig,
+    RayonScanConfig, ScannerConfig, SinkKind, UniverseConfig, DEFAULT_TARGETS,
+};
+pub use cross_check::CrossCheckEvaluator;
+pub use fees::FeeSchedule;
+pub use halt::HaltHandle;
+pub use implied::ImpliedQuote;
 pub use naive::NaivePrecompiledScanner;
+pub use normalize::ProfitNormalizer;
+pub use predicate::{EvalContext, GrossPositive, ProfitPredicate};
 pub use edge::HashMapEdgeScanner;
-pub use rayon_scan::{RayonFirstMatchScanner, RayonBestMatchScanner};
+pub use interned::InternedEdgeScanner;
+pub use rayon_scan::{RayonFirstMatchScanner, RayonBestMatchScanner, RayonOrderedMatchScanner};
+pub use simd::SimdBatchScanner;
+pub use price_store::{DashMapPriceStore, PriceStore};
+pub use quote_filter::{QuoteFilter, StoredQuote};
+pub use scanner_core::ScannerCore;
+#[cfg(feature = "decision_trace")]
+pub use recording::{Decision, RecordingEvaluator};
+pub use sink::{
+    create_opportunity_sink, ChannelSink, FileSink, OpportunitySink, RateLimitedSink, StdoutSink, TeeSink, TierFilterSink,
+    WebhookSink,
+};
+pub use tick_size::TickSizeRounding;
+pub use tier::{Tier, TierBands};
+pub use vwap::VwapEdgeScanner;
 
 
 const CONFIG_FILE_PATH: &str = "config/arb.toml";
@@ -27,66 +82,430 @@ fn load_arb_config<P: AsRef<Path>>(path: P) -> Option<ArbConfig> {
     toml::from_str(&contents).ok()
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Loads the `[pipeline]` channel capacities from `config/arb.toml`, falling back to defaults.
+///
+/// Tokio channels cannot have zero capacity, so a configured `0` is raised to `1`.
+pub fn load_pipeline_config() -> PipelineConfig {
+    let mut pipeline = load_arb_config(CONFIG_FILE_PATH)
+        .and_then(|c| c.pipeline)
+        .unwrap_or_default();
+
+    for (name, capacity) in [
+        ("ws_channel_capacity", &mut pipeline.ws_channel_capacity),
+        ("parser_channel_capacity", &mut pipeline.parser_channel_capacity),
+    ] {
+        if *capacity == 0 {
+            warn!(name, "Channel capacity must be at least 1; using 1");
+            *capacity = 1;
+        }
+    }
+    pipeline
+}
+
+/// Loads the `[output]` sink selection from `config/arb.toml`, defaulting to stdout.
+pub fn load_output_config() -> OutputConfig {
+    load_arb_config(CONFIG_FILE_PATH)
+        .and_then(|c| c.output)
+        .unwrap_or_default()
+}
+
+/// Loads the `[logging]` level and format from `config/arb.toml`, defaulting to `info` lines.
+pub fn load_logging_config() -> LoggingConfig {
+    load_arb_config(CONFIG_FILE_PATH)
+        .and_then(|c| c.logging)
+        .unwrap_or_default()
+}
+
+/// Loads the `[universe]` home asset and targets from `config/arb.toml`, defaulting to USDT
+/// with `DEFAULT_TARGETS`.
+pub fn load_universe_config() -> UniverseConfig {
+    load_arb_config(CONFIG_FILE_PATH)
+        .and_then(|c| c.universe)
+        .unwrap_or_default()
+}
+
+/// Reads the scanner named by `mode` under `[scanner]` in `config/arb.toml`, `None` if unset.
+///
+/// Unlike the other loaders this fails loudly: an unknown name is an error, not a silent default.
+pub fn load_arb_mode() -> Result<Option<ArbMode>> {
+    load_arb_config(CONFIG_FILE_PATH)
+        .and_then(|c| c.scanner)
+        .and_then(|s| s.mode)
+        .map(|mode| mode.parse().with_context(|| format!("Invalid [scanner] mode in {CONFIG_FILE_PATH}")))
+        .transpose()
+}
+
+/// Loads the `[symbols]` blocklist/allowlist from `config/arb.toml`, defaulting to no filtering.
+pub fn load_symbol_filter() -> SymbolFilter {
+    load_arb_config(CONFIG_FILE_PATH)
+        .and_then(|c| c.symbols)
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ArbMode {
     Naive,
     EdgeMap,
+    Interned,
     RayonScan,
+    SimdBatch,
+    Adaptive,
+}
+
+impl ArbMode {
+    pub const ALL: [ArbMode; 6] =
+        [Self::Naive, Self::EdgeMap, Self::Interned, Self::RayonScan, Self::SimdBatch, Self::Adaptive];
+}
+
+/// Parses a variant name case-insensitively (`edgemap`, `SimdBatch`, ...), also accepting the
+/// short forms `edge`, `rayon` and `simd`.
+impl FromStr for ArbMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "naive" => Ok(Self::Naive),
+            "edgemap" | "edge" => Ok(Self::EdgeMap),
+            "interned" => Ok(Self::Interned),
+            "rayonscan" | "rayon" => Ok(Self::RayonScan),
+            "simdbatch" | "simd" => Ok(Self::SimdBatch),
+            "adaptive" => Ok(Self::Adaptive),
+            _ => bail!("Unknown scanner mode '{s}' (expected naive, edge, interned, rayon, simd or adaptive)"),
+        }
+    }
 }
 
+/// Builds the evaluator for `mode` over paths starting and ending in `home_asset`, with the
+/// options in `config/arb.toml` (see `ScannerBuilder`). Fails on an out-of-range option.
 pub fn create_arb_evaluator(
     mode: ArbMode,
+    home_asset: &str,
     price_paths: Vec<PricingPath>
-) -> Arc<dyn ArbEvaluator + Send + Sync> {
+) -> Result<Arc<dyn ArbEvaluator + Send + Sync>> {
     let config = load_arb_config(CONFIG_FILE_PATH);
-    info!(?mode, "Creating arbitrage evaluator");
-    
-    match mode {
-        
-        ArbMode::Naive => {
-            info!("Using NaivePrecompiledScanner");
-            Arc::new(NaivePrecompiledScanner::new(price_paths))
-        },
-        
-        ArbMode::EdgeMap => {
-            info!("Using HashMapEdgeScanner");
-            Arc::new(HashMapEdgeScanner::new(price_paths))
-        },
-        
-        ArbMode::RayonScan => {
-            
-            let on_update_return = config
-                .as_ref()
-                .and_then(|c| c.rayon_scan.as_ref())
-                .map(|r| r.on_update_return.clone())
-                .unwrap_or_default();
-
-            info!(?on_update_return, "Using RayonPathScanner");
-            
-            match on_update_return {
-                OnUpdateReturn::First => Arc::new(RayonFirstMatchScanner::new(price_paths)),
-                OnUpdateReturn::Best => Arc::new(RayonBestMatchScanner::new(price_paths)),
-            }
+    let builder = ScannerBuilder::from_config(price_paths, config.as_ref())
+        .with_context(|| format!("Invalid scanner options in {CONFIG_FILE_PATH}"))?;
+    Ok(builder.with_home_asset(home_asset).build(mode))
+}
+
+/// A profitable triangle detected by a scanner, together with the prices it was evaluated at.
+///
+/// Serializes to a flat JSON object for structured logging and export; legs carry only their
+/// symbol name and side.
+#[derive(Debug, Clone, Serialize)]
+pub struct ArbOpportunity {
+    pub path: Arc<PricingPath>,
+    /// Asset the path starts and ends in, which `start_notional` and `profit_home` are counted in.
+    pub home_asset: Arc<str>,
+    /// Home-asset amount routed through the path.
+    pub start_notional: f64,
+    /// Net return `end / start_notional` after fees (`> 1.0` is profitable).
+    pub result: f64,
+    /// Expected gain in the home asset after fees, `end - start_notional`.
+    pub profit_home: f64,
+    /// `profit_home` converted to a common reporting currency (see `ProfitNormalizer`), so
+    /// opportunities from different home assets compare directly. `None` when no reporting
+    /// currency is configured or no rate for the home asset is known yet.
+    pub profit_normalized: Option<f64>,
+    /// Top-of-book price used on each leg, in path order (ask for `Side::Ask`, bid for `Side::Bid`).
+    pub leg_prices: [f64; 3],
+    /// Multiplicative contribution of each leg (`step1/start`, `step2/step1`, `end/step2`), fees included.
+    /// Their product is `result`; a factor below `1.0` marks the leg that cost the route.
+    pub leg_factors: [f64; 3],
+    /// Trust in the quotes behind the opportunity, in `(0, 1]`: the weakest leg's score for
+    /// spread and age against the scanner's `ConfidenceReference`.
+    pub confidence: f64,
+    /// Largest gap between the three legs' exchange event times, in milliseconds; a wide gap
+    /// means one leg's quote predates the others and the triangle may never have coexisted.
+    /// `None` if a leg's quote carried no event time.
+    pub leg_time_spread_ms: Option<u64>,
+    /// Time from the triggering quote's `recv_ts` to detection, by the scanner's clock.
+    /// `None` until a scanner stamps it (see `detected`); serialized as whole microseconds.
+    #[serde(rename = "detection_latency_us", serialize_with = "serialize_micros")]
+    pub detection_latency: Option<Duration>,
+}
+
+fn serialize_micros<S: Serializer>(latency: &Option<Duration>, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    latency.map(|l| l.as_micros() as u64).serialize(serializer)
+}
+
+impl ArbOpportunity {
+    /// Captures the side-relevant price of each leg's quote alongside the detected path.
+    pub fn new(
+        path: Arc<PricingPath>,
+        home_asset: Arc<str>,
+        start_notional: f64,
+        end_notional: f64,
+        leg_factors: [f64; 3],
+        confidence: f64,
+        quotes: [&TopOfBookUpdate; 3],
+    ) -> Self {
+        let leg_prices = leg_prices(&path, quotes);
+        let result = end_notional / start_notional;
+        let profit_home = end_notional - start_notional;
+        let leg_time_spread_ms = leg_time_spread_ms(quotes.map(|q| q.event_time_ms));
+        Self {
+            path,
+            home_asset,
+            start_notional,
+            result,
+            profit_home,
+            profit_normalized: None,
+            leg_prices,
+            leg_factors,
+            confidence,
+            leg_time_spread_ms,
+            detection_latency: None,
+        }
+    }
+
+    /// Stamps `detection_latency` as the time from `trigger`'s receipt to `now`.
+    pub fn detected(mut self, trigger: &TopOfBookUpdate, now: Instant) -> Self {
+        self.detection_latency = Some(now.saturating_duration_since(trigger.recv_ts));
+        self
+    }
+
+    /// Profit after fees as a percentage of `start_notional`.
+    pub fn profit_pct(&self) -> f64 {
+        self.profit_home * 100.0 / self.start_notional
+    }
+
+    /// Size class of the opportunity's `profit_pct` under `bands`.
+    pub fn tier(&self, bands: &TierBands) -> Tier {
+        bands.classify(self.profit_pct())
+    }
+}
+
+/// Gap between the earliest and latest of three legs' event times, or `None` if any is missing.
+pub(crate) fn leg_time_spread_ms(event_times_ms: [Option<u64>; 3]) -> Option<u64> {
+    let [Some(t1), Some(t2), Some(t3)] = event_times_ms else { return None; };
+    Some(t1.max(t2).max(t3) - t1.min(t2).min(t3))
+}
+
+/// One line: `BUY BTCUSDT → BUY ETHBTC → SELL ETHUSDT | return 1.002000 | profit 0.2000%`.
+/// The alternate form (`{:#}`) is free of ANSI color codes even with the `colored` feature.
+impl fmt::Display for ArbOpportunity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{:#}", self.path)?;
+        } else {
+            write!(f, "{}", self.path)?;
         }
+        write!(f, " | return {:.6} | profit {:.4}%", self.result, (self.result - 1.0) * 100.0)
     }
 }
 
 pub trait ArbEvaluator: Send + Sync {
-    fn process_update(&self, update: &TopOfBookUpdate) -> Option<(PricingPath, f64)>;
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity>;
+
+    /// Processes a burst of updates, evaluating each symbol once at its last quote in the batch.
+    ///
+    /// The default feeds the last update per symbol through `process_update`, in order of first
+    /// appearance, so a hot symbol updated many times is evaluated once; the stored prices end
+    /// up as after sequential `process_update` calls. Scanners may override it to store every
+    /// quote first and evaluate the union of affected paths once (see `HashMapEdgeScanner`).
+    fn process_batch(&self, updates: &[TopOfBookUpdate]) -> Vec<ArbOpportunity> {
+        latest_per_symbol(updates)
+            .into_iter()
+            .filter_map(|update| self.process_update(update))
+            .collect()
+    }
+
+    /// Clones the scanner's current price store (symbol → latest quote) for debugging.
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate>;
+
+    /// Switch that stops the scanner reporting opportunities without stopping `arb_loop`.
+    fn halt_handle(&self) -> HaltHandle;
+
+    /// Net return (`end / start_notional`, after fees) of `path` at the latest stored quotes,
+    /// whether profitable or not. Staleness is not checked; `None` if a leg has no quote.
+    ///
+    /// For dashboards and debugging: `path` need not be one the scanner evaluates.
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64>;
+
+    /// Break-even quote for the one leg of `path` that has no stored quote, implied from the
+    /// other two (see `implied::implied_break_even`); `None` unless exactly one leg is missing.
+    ///
+    /// Watchlist monitoring only — never part of opportunity detection.
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote>;
+
+    /// Drops every stored quote, e.g. after a feed reconnect where prices may have moved
+    /// during the gap. No path is evaluated again until all three of its legs are re-quoted.
+    fn invalidate_all(&self);
+
+    /// Drops the stored quotes of `symbols` only, e.g. after one connection of a sharded feed
+    /// reconnects while the others kept streaming. Paths using them are not evaluated again
+    /// until re-quoted. Drops every quote unless the scanner can drop them selectively.
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        let _ = symbols;
+        self.invalidate_all();
+    }
+
+    /// The `n` paths with the highest moving-average return since the scanner started, best
+    /// first, for ranking watched triangles. Empty unless `ScannerSettings::return_ewma_alpha`
+    /// is set.
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)>;
+}
+
+/// The last update of each symbol in `updates`, ordered by the symbol's first appearance.
+pub(crate) fn latest_per_symbol<'a>(
+    updates: impl IntoIterator<Item = &'a TopOfBookUpdate>,
+) -> Vec<&'a TopOfBookUpdate> {
+    let mut latest: Vec<&TopOfBookUpdate> = Vec::new();
+    let mut slots: HashMap<&str, usize> = HashMap::new();
+    for update in updates {
+        match slots.get(update.symbol.as_str()) {
+            Some(&slot) => latest[slot] = update,
+            None => {
+                slots.insert(&update.symbol, latest.len());
+                latest.push(update);
+            }
+        }
+    }
+    latest
+}
+
+/// Lets shared evaluators be wrapped by decorators such as `TickSizeRounding`.
+impl<T: ArbEvaluator + ?Sized> ArbEvaluator for Arc<T> {
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        (**self).process_update(update)
+    }
+
+    fn process_batch(&self, updates: &[TopOfBookUpdate]) -> Vec<ArbOpportunity> {
+        (**self).process_batch(updates)
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        (**self).price_snapshot()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        (**self).halt_handle()
+    }
+
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        (**self).evaluate_path(path)
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        (**self).implied_quote(path)
+    }
+
+    fn invalidate_all(&self) {
+        (**self).invalidate_all()
+    }
+
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        (**self).invalidate_symbols(symbols)
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        (**self).top_paths(n)
+    }
+}
+
+/// Progress of an `arb_loop_with_stats`, shared with whoever monitors it (e.g. a health endpoint).
+#[derive(Debug)]
+pub struct ArbLoopStats {
+    updates: AtomicU64,
+    opportunities: AtomicU64,
+    /// Bits of the best `ArbOpportunity::result` emitted; `NO_RETURN` until the first.
+    best_return: AtomicU64,
+    running: AtomicBool,
+}
+
+/// `ArbLoopStats::best_return` before any opportunity (negative infinity, below every return).
+const NO_RETURN: u64 = 0xFFF0_0000_0000_0000;
+
+impl Default for ArbLoopStats {
+    fn default() -> Self {
+        Self {
+            updates: AtomicU64::new(0),
+            opportunities: AtomicU64::new(0),
+            best_return: AtomicU64::new(NO_RETURN),
+            running: AtomicBool::new(false),
+        }
+    }
+}
+
+impl ArbLoopStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates handed to the evaluator so far.
+    pub fn updates_processed(&self) -> u64 {
+        self.updates.load(Ordering::Relaxed)
+    }
+
+    /// Opportunities handed to the sink so far.
+    pub fn opportunities_found(&self) -> u64 {
+        self.opportunities.load(Ordering::Relaxed)
+    }
+
+    /// Highest net return among the opportunities found, `None` before the first.
+    pub fn best_return(&self) -> Option<f64> {
+        match self.best_return.load(Ordering::Relaxed) {
+            NO_RETURN => None,
+            bits => Some(f64::from_bits(bits)),
+        }
+    }
+
+    /// Whether the loop has started and not yet exited (or panicked).
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    fn record_opportunity(&self, result: f64) {
+        self.opportunities.fetch_add(1, Ordering::Relaxed);
+        // Only the arb loop writes, so a plain load-compare-store can't lose a larger value
+        if result > f64::from_bits(self.best_return.load(Ordering::Relaxed)) {
+            self.best_return.store(result.to_bits(), Ordering::Relaxed);
+        }
+    }
+}
+
+/// Clears `ArbLoopStats::running` however the loop ends, including by a panicking evaluator.
+struct RunningGuard<'a>(&'a AtomicBool);
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
 }
 
+/// Feeds every update to `evaluator` and hands each detected opportunity to `sink`.
 pub async fn arb_loop(
+    rx: Receiver<TopOfBookUpdate>,
+    evaluator: Arc<dyn ArbEvaluator>,
+    sink: Arc<dyn OpportunitySink>,
+) -> Result<()> {
+    arb_loop_with_stats(rx, evaluator, sink, Arc::new(ArbLoopStats::new())).await
+}
+
+/// `arb_loop`, counting processed updates and flagging whether it is running in `stats`.
+pub async fn arb_loop_with_stats(
     mut rx: Receiver<TopOfBookUpdate>,
     evaluator: Arc<dyn ArbEvaluator>,
+    sink: Arc<dyn OpportunitySink>,
+    stats: Arc<ArbLoopStats>,
 ) -> Result<()> {
+    stats.running.store(true, Ordering::Relaxed);
+    let _running = RunningGuard(&stats.running);
     while let Some(update) = rx.recv().await {
-        if let Some((_path, _result)) = evaluator.process_update(&update) {
-            // println!(
-            //     "✅ Arbitrage found: {} | Return: {:.6} | Profit: {:.4}%",
-            //     _path,
-            //     _result,
-            //     (_result - 1.0) * 100.0
-            // );
+        stats.updates.fetch_add(1, Ordering::Relaxed);
+        if let Some(opportunity) = evaluator.process_update(&update) {
+            let path = &opportunity.path;
+            debug!(path = %format_args!("{path:#}"), latency = ?opportunity.detection_latency, "Opportunity detected");
+            if tracing::enabled!(tracing::Level::DEBUG) {
+                // The prices the opportunity was evaluated at, not the store's, which may have moved on
+                let legs = [&path.leg1, &path.leg2, &path.leg3];
+                for ((leg, factor), price) in legs.into_iter().zip(opportunity.leg_factors).zip(opportunity.leg_prices) {
+                    debug!(symbol = %leg.symbol.symbol, side = ?leg.side, price, factor, "Opportunity leg quote");
+                }
+            }
+            stats.record_opportunity(opportunity.result);
+            sink.emit(&opportunity);
         }
     }
     Ok(())
@@ -95,53 +514,303 @@ pub async fn arb_loop(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::price_path::{PathLeg, PricingPath, Side, SymbolInfo};
-
-    fn mock_path() -> PricingPath {
-        let s1 = SymbolInfo {
-            symbol: "BTCUSDT".into(),
-            base_asset: "BTC".into(),
-            quote_asset: "USDT".into(),
-            status: "TRADING".into(),
-        };
-        let s2 = SymbolInfo {
-            symbol: "ETHBTC".into(),
-            base_asset: "ETH".into(),
-            quote_asset: "BTC".into(),
-            status: "TRADING".into(),
-        };
-        let s3 = SymbolInfo {
-            symbol: "ETHUSDT".into(),
-            base_asset: "ETH".into(),
-            quote_asset: "USDT".into(),
-            status: "TRADING".into(),
-        };
-
-        PricingPath {
-            leg1: PathLeg { symbol: s1, side: Side::Ask },
-            leg2: PathLeg { symbol: s2, side: Side::Ask },
-            leg3: PathLeg { symbol: s3, side: Side::Bid },
-        }
-    }
-
-    fn mock_update(symbol: &str, bid: f64, ask: f64) -> TopOfBookUpdate {
-        TopOfBookUpdate {
-            symbol: symbol.to_string(),
-            bid_price: bid,
-            ask_price: ask,
+    use crate::price_path::Side;
+    use crate::testutil::{assert_return_eq, mock_path, mock_reverse_path, mock_update, MockClock, RecordingSink, MODES};
+
+    #[test]
+    fn test_reversed_mode_reports_reverse_only_opportunity() {
+        // Forward: 1 / 50010 / 0.0701 * 3000 ≈ 0.856 (loss)
+        // Reverse: 1 / 3001 * 0.07 * 50000 ≈ 1.166 (profit)
+        let updates = [
+            mock_update("BTCUSDT", 50000.0, 50010.0),
+            mock_update("ETHBTC", 0.07, 0.0701),
+            mock_update("ETHUSDT", 3000.0, 3001.0),
+        ];
+
+        // Reversal is opted into by `[evaluation] evaluate_reversed`, for every scanner
+        let config: ArbConfig = toml::from_str("[evaluation]\nevaluate_reversed = true").unwrap();
+        for mode in ArbMode::ALL {
+            let forward_only = ScannerBuilder::new(vec![mock_path()]).build(mode);
+            let forward_results: Vec<_> = updates.iter().map(|u| forward_only.process_update(u)).collect();
+            assert!(forward_results.iter().all(Option::is_none), "{mode:?}");
+
+            let both_directions = ScannerBuilder::from_config(vec![mock_path()], Some(&config)).unwrap().build(mode);
+            both_directions.process_update(&updates[0]);
+            both_directions.process_update(&updates[1]);
+            let opportunity = both_directions
+                .process_update(&updates[2])
+                .unwrap_or_else(|| panic!("{mode:?}: reverse direction should be profitable"));
+
+            assert_eq!(opportunity.path.leg1.symbol.symbol, "ETHUSDT");
+            assert_eq!(opportunity.path.leg1.side, Side::Ask);
+            assert_eq!(opportunity.path.leg3.symbol.symbol, "BTCUSDT");
+            assert_eq!(opportunity.path.leg3.side, Side::Bid);
+            assert_eq!(opportunity.leg_prices, [3001.0, 0.07, 50000.0]);
+            assert!(opportunity.result > 1.0);
+        }
+    }
+
+    #[test]
+    fn test_price_snapshot_reflects_latest_quotes() {
+        let scanners: Vec<Box<dyn ArbEvaluator>> = vec![
+            Box::new(NaivePrecompiledScanner::new(vec![mock_path()])),
+            Box::new(HashMapEdgeScanner::new(vec![mock_path()])),
+            Box::new(RayonFirstMatchScanner::new(vec![mock_path()])),
+            Box::new(RayonBestMatchScanner::new(vec![mock_path()])),
+        ];
+
+        for scanner in scanners {
+            assert!(scanner.price_snapshot().is_empty());
+
+            scanner.process_update(&mock_update("BTCUSDT", 30000.0, 30010.0));
+            scanner.process_update(&mock_update("ETHBTC", 0.065, 0.066));
+            scanner.process_update(&mock_update("BTCUSDT", 30005.0, 30015.0));
+
+            let snapshot = scanner.price_snapshot();
+            assert_eq!(snapshot.len(), 2);
+            assert_eq!(snapshot["BTCUSDT"].bid_price, 30005.0);
+            assert_eq!(snapshot["BTCUSDT"].ask_price, 30015.0);
+            assert_eq!(snapshot["ETHBTC"].bid_price, 0.065);
+            assert_eq!(snapshot["ETHBTC"].ask_price, 0.066);
+        }
+    }
+
+    #[test]
+    fn test_arb_mode_from_str_accepts_names_and_aliases() {
+        let cases = [
+            ("naive", ArbMode::Naive),
+            ("edge", ArbMode::EdgeMap),
+            ("EdgeMap", ArbMode::EdgeMap),
+            ("interned", ArbMode::Interned),
+            ("rayon", ArbMode::RayonScan),
+            ("RAYONSCAN", ArbMode::RayonScan),
+            ("simd", ArbMode::SimdBatch),
+            ("simdbatch", ArbMode::SimdBatch),
+            ("Adaptive", ArbMode::Adaptive),
+        ];
+        for (name, mode) in cases {
+            assert_eq!(name.parse::<ArbMode>().unwrap(), mode, "{name}");
+        }
+        for mode in ArbMode::ALL {
+            assert_eq!(format!("{mode:?}").parse::<ArbMode>().unwrap(), mode);
         }
     }
 
+    #[test]
+    fn test_arb_mode_from_str_rejects_unknown_names() {
+        for name in ["", "quantum", "edge map", "rayon-scan"] {
+            let err = name.parse::<ArbMode>().unwrap_err().to_string();
+            assert!(err.contains(&format!("Unknown scanner mode '{name}'")), "{err}");
+            assert!(err.contains("naive, edge, interned, rayon, simd or adaptive"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_opportunity_json_and_plain_display() {
+        let quotes = [
+            mock_update("BTCUSDT", 50000.0, 50010.0),
+            mock_update("ETHBTC", 0.07, 0.0701),
+            mock_update("ETHUSDT", 3600.0, 3601.0),
+        ];
+        let opportunity =
+            ArbOpportunity::new(Arc::new(mock_path()), Arc::from("USDT"), 100.0, 102.0, [1.0, 1.0, 1.02], 0.5, [&quotes[0], &quotes[1], &quotes[2]]);
+
+        let json = serde_json::to_value(&opportunity).unwrap();
+        assert_eq!(json["path"]["leg1"]["symbol"], "BTCUSDT");
+        assert_eq!(json["path"]["leg3"]["side"], "Bid");
+        assert_eq!(json["start_notional"], 100.0);
+        assert_eq!(json["result"], 1.02);
+        assert_eq!(json["leg_prices"][1], 0.0701);
+        assert_eq!(json["confidence"], 0.5);
+        assert!(json["detection_latency_us"].is_null());
+        let detected = opportunity.clone().detected(&quotes[2], quotes[2].recv_ts + Duration::from_micros(1500));
+        assert_eq!(serde_json::to_value(&detected).unwrap()["detection_latency_us"], 1500);
+        assert!(json["leg_factors"].is_array());
+
+        assert_eq!(opportunity.to_string().contains('\x1b'), cfg!(feature = "colored"));
+        let plain = format!("{opportunity:#}");
+        assert_eq!(plain, "BUY BTCUSDT → BUY ETHBTC → SELL ETHUSDT | return 1.020000 | profit 2.0000%");
+    }
+
+    #[test]
+    fn test_leg_factors_multiply_to_result() {
+        // Reverse of mock_path: 1 / 3001 * 0.07 * 50000 ≈ 1.166
+        let path = mock_path().reversed();
+        let scanners: Vec<Box<dyn ArbEvaluator>> = vec![
+            Box::new(NaivePrecompiledScanner::new(vec![path.clone()])),
+            Box::new(HashMapEdgeScanner::new(vec![path.clone()])),
+            Box::new(InternedEdgeScanner::new(vec![path.clone()])),
+            Box::new(RayonFirstMatchScanner::new(vec![path.clone()])),
+            Box::new(RayonBestMatchScanner::new(vec![path.clone()])),
+            Box::new(SimdBatchScanner::new(vec![path.clone()])),
+        ];
+
+        for scanner in scanners {
+            scanner.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            scanner.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+            let opportunity = scanner
+                .process_update(&mock_update("ETHUSDT", 3000.0, 3001.0))
+                .expect("reverse path should be profitable");
+
+            let [f1, f2, f3] = opportunity.leg_factors;
+            assert_return_eq(f1 * f2 * f3, opportunity.result, 1e-12);
+            assert_return_eq(f1, 1.0 / 3001.0, 1e-12);
+            assert_eq!(f2, 0.07);
+            assert_eq!(f3, 50000.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_arb_loop_emits_opportunities_to_sink() {
+        let (tx, rx) = tokio::sync::mpsc::channel(8);
+        let evaluator = Arc::new(HashMapEdgeScanner::new(vec![mock_path().reversed()]));
+        let sink = Arc::new(RecordingSink::default());
+
+        for update in [
+            mock_update("BTCUSDT", 50000.0, 50010.0),
+            mock_update("ETHBTC", 0.07, 0.0701),
+            mock_update("ETHUSDT", 3000.0, 3001.0),
+            mock_update("ETHBTC", 0.05, 0.0501),
+        ] {
+            tx.send(update).await.unwrap();
+        }
+        drop(tx);
+        arb_loop(rx, evaluator, sink.clone()).await.unwrap();
+
+        // Only the third update completes a profitable path; the last one makes it a loss
+        let emitted = sink.emitted.lock().unwrap();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].path.leg1.symbol.symbol, "ETHUSDT");
+        assert_eq!(emitted[0].leg_prices, [3001.0, 0.07, 50000.0]);
+        assert_return_eq(emitted[0].result, 50000.0 * 0.07 / 3001.0, 1e-12);
+    }
+
     #[test]
     fn test_edge_scanner_accepts_update() {
         let path = mock_path();
-        let scanner = HashMapEdgeScanner::new(vec![path]);
+        let scanner = HashMapEdgeScanner::new(vec![path.clone()]);
+
+        assert!(scanner.process_update(&mock_update("BTCUSDT", 30000.0, 30010.0)).is_none());
+        assert!(scanner.process_update(&mock_update("ETHBTC", 0.065, 0.066)).is_none());
+        // 1 / 30010 / 0.066 * 1980 ≈ 0.99967: every leg is quoted, but the path loses
+        assert!(scanner.process_update(&mock_update("ETHUSDT", 1980.0, 1985.0)).is_none());
+
+        let snapshot = scanner.price_snapshot();
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!((snapshot["ETHBTC"].bid_price, snapshot["ETHBTC"].ask_price), (0.065, 0.066));
+        assert_return_eq(scanner.evaluate_path(&path).unwrap(), 1.0 / 30010.0 / 0.066 * 1980.0, 1e-12);
+    }
+
+    #[test]
+    fn evaluate_path_reports_return_regardless_of_profitability() {
+        // BUY BTCUSDT → BUY ETHBTC → SELL ETHUSDT: 1 / 50010 / 0.0701 * 3000 ≈ 0.8557 before fees
+        let watched = mock_path();
+        let keep: f64 = 1.0 - 0.001;
+        let expected = 1.0 / 50010.0 / 0.0701 * 3000.0 * keep.powi(3);
 
-        scanner.process_update(&mock_update("BTCUSDT", 30000.0, 30010.0));
-        scanner.process_update(&mock_update("ETHBTC", 0.065, 0.066));
-        scanner.process_update(&mock_update("ETHUSDT", 1980.0, 1985.0));
+        for (mode, on_update_return) in MODES {
+            let evaluator = ScannerBuilder::new(vec![mock_reverse_path()])
+                .with_fee_bps(10.0)
+                .with_start_notional(500.0)
+                .with_on_update_return(on_update_return)
+                .build(mode);
+            evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+            assert_eq!(evaluator.evaluate_path(&watched), None, "{mode:?} priced a leg with no quote");
+
+            evaluator.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0));
+            let evaluated = evaluator.evaluate_path(&watched).expect("all legs are quoted");
+            assert_return_eq(evaluated, expected, 1e-12);
+        }
+    }
+
+    #[test]
+    fn invalidated_scanner_rewarms_before_reporting() {
+        for (mode, on_update_return) in MODES {
+            let evaluator = ScannerBuilder::new(vec![mock_reverse_path()]).with_on_update_return(on_update_return).build(mode);
+            evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+            assert!(evaluator.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0)).is_some());
 
-        // There's no assertion here yet, since current logic just prints.
-        // You can add a counter, hook, or event log in future versions to validate detection.
+            evaluator.invalidate_all();
+            assert!(evaluator.price_snapshot().is_empty(), "{mode:?} kept quotes");
+            assert!(evaluator.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0)).is_none());
+            assert!(evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0)).is_none(), "{mode:?} priced a pre-invalidation quote");
+            let rewarmed = evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+            assert!(rewarmed.is_some(), "{mode:?} didn't report once all legs were re-quoted");
+        }
+    }
+
+    #[test]
+    fn invalidating_symbols_keeps_the_other_quotes() {
+        for (mode, on_update_return) in MODES {
+            let evaluator = ScannerBuilder::new(vec![mock_reverse_path()]).with_on_update_return(on_update_return).build(mode);
+            evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+            evaluator.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0));
+
+            evaluator.invalidate_symbols(&["ETHBTC".to_string()]);
+            let snapshot = evaluator.price_snapshot();
+            assert!(!snapshot.contains_key("ETHBTC"), "{mode:?} kept an invalidated quote");
+            assert_eq!(snapshot.len(), 2, "{mode:?} dropped quotes of other symbols");
+            // The surviving legs price the path again as soon as the dropped one is re-quoted
+            assert!(evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701)).is_some(), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn detection_latency_runs_from_trigger_receipt_to_detection() {
+        for (mode, on_update_return) in MODES {
+            let clock = Arc::new(MockClock::new());
+            let evaluator = ScannerBuilder::new(vec![mock_reverse_path()])
+                .with_clock(clock.clone())
+                .with_on_update_return(on_update_return)
+                .build(mode);
+            evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+
+            let mut trigger = mock_update("ETHUSDT", 3000.0, 3001.0);
+            trigger.recv_ts = clock.now();
+            clock.advance(Duration::from_millis(7));
+            let opportunity = evaluator.process_update(&trigger).expect("path is profitable");
+            assert_eq!(opportunity.detection_latency, Some(Duration::from_millis(7)), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn detection_latency_is_plausible_on_the_system_clock() {
+        for (mode, on_update_return) in MODES {
+            let evaluator = ScannerBuilder::new(vec![mock_reverse_path()]).with_on_update_return(on_update_return).build(mode);
+            evaluator.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            evaluator.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+
+            let mut trigger = mock_update("ETHUSDT", 3000.0, 3001.0);
+            trigger.recv_ts -= Duration::from_millis(50);
+            let latency = evaluator
+                .process_update(&trigger)
+                .and_then(|o| o.detection_latency)
+                .expect("scanner stamps a latency");
+            assert!(latency >= Duration::from_millis(50), "{mode:?}: {latency:?}");
+            assert!(latency < Duration::from_secs(5), "{mode:?}: {latency:?}");
+        }
+    }
+
+    #[test]
+    fn batch_evaluates_hot_symbol_once_and_keeps_last_prices() {
+        for (mode, on_update_return) in MODES {
+            let evaluator = ScannerBuilder::new(vec![mock_reverse_path()]).with_on_update_return(on_update_return).build(mode);
+            let burst = [
+                mock_update("BTCUSDT", 40000.0, 40010.0),
+                mock_update("ETHBTC", 0.07, 0.0701),
+                mock_update("BTCUSDT", 45000.0, 45010.0),
+                mock_update("ETHUSDT", 3000.0, 3001.0),
+                mock_update("BTCUSDT", 50000.0, 50010.0),
+            ];
+
+            let found = evaluator.process_batch(&burst);
+            assert_eq!(found.len(), 1, "{mode:?}");
+            assert_return_eq(found[0].result, 50000.0 * 0.07 / 3001.0, 1e-12);
+            assert_eq!(evaluator.price_snapshot()["BTCUSDT"].bid_price, 50000.0, "{mode:?}");
+        }
     }
 }
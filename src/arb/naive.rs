@@ -1,57 +1,143 @@
 // src/arb/naive.rs
 
-use dashmap::DashMap;
+use std::collections::{HashMap, HashSet};
+use std::mem::size_of;
+use std::sync::Arc;
+
 
 use crate::parse::TopOfBookUpdate;
-use crate::price_path::{PricingPath, Side};
+use crate::price_path::{apply_leg, leg_prices, PricingPath};
 
-use super::ArbEvaluator;
+use super::{ArbEvaluator, ArbOpportunity, HaltHandle, ImpliedQuote, ScannerSettings};
+use super::cooldown::PathCooldown;
+use super::return_history::ReturnHistory;
+use super::eviction::PriceEviction;
+use super::footprint::{shared_paths_bytes, table_bytes};
+use super::price_store::PriceStore;
+use super::quote_filter::store_quote;
 
 pub struct NaivePrecompiledScanner {
-    paths: Vec<PricingPath>,
-    price_store: DashMap<String, TopOfBookUpdate>,
+    paths: Vec<Arc<PricingPath>>,
+    price_store: Arc<dyn PriceStore>,
+    settings: ScannerSettings,
+    /// Every symbol traded by `paths`, protected from eviction.
+    path_symbols: HashSet<String>,
+    eviction: PriceEviction,
+    cooldown: PathCooldown,
+    return_history: ReturnHistory,
 }
 
 impl ArbEvaluator for NaivePrecompiledScanner {
-    fn process_update(&self, update: &TopOfBookUpdate) -> Option<(PricingPath, f64)> {
-        self.price_store.insert(update.symbol.clone(), update.clone());
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        let now = self.settings.clock.now();
+        if !store_quote(&*self.price_store, &self.settings.quote_filter, update, now) {
+            return None;
+        }
+        self.eviction.maybe_sweep(&*self.price_store, now, |s| self.path_symbols.contains(s));
+        if self.settings.halt.is_halted() {
+            return None;
+        }
+
+        let start = self.settings.start_notional;
 
         for path in self.paths.iter() {
             let Some(p1) = self.price_store.get(&path.leg1.symbol.symbol) else { continue; };
             let Some(p2) = self.price_store.get(&path.leg2.symbol.symbol) else { continue; };
             let Some(p3) = self.price_store.get(&path.leg3.symbol.symbol) else { continue; };
-            
-            const START: f64 = 1.0;
-            
-            let step1 = match path.leg1.side {
-                Side::Ask => START / p1.ask_price,
-                Side::Bid => START * p1.bid_price,
-            };
+            if !(self.settings.is_fresh(p1.received, p1.event_time_ms)
+                && self.settings.is_fresh(p2.received, p2.event_time_ms)
+                && self.settings.is_fresh(p3.received, p3.event_time_ms)) {
+                continue;
+            }
 
-            let step2 = match path.leg2.side {
-                Side::Ask => step1 / p2.ask_price,
-                Side::Bid => step1 * p2.bid_price,
-            };
+            let [keep1, keep2, keep3] = self.settings.leg_multipliers(path);
 
-            let end = match path.leg3.side {
-                Side::Ask => step2 / p3.ask_price,
-                Side::Bid => step2 * p3.bid_price
-            };
+            let step1 = keep1 * apply_leg(start, path.leg1.side, &p1);
 
-            if end > START {
-                return Some((path.clone(), end));
+            let step2 = keep2 * apply_leg(step1, path.leg2.side, &p2);
+
+            let end = keep3 * apply_leg(step2, path.leg3.side, &p3);
+
+            self.return_history.record(path, end / start);
+
+            if self.settings.is_profitable(path, leg_prices(path, [&p1, &p2, &p3]), end)
+                && self.settings.legs_in_sync([&p1, &p2, &p3].map(|q| q.event_time_ms))
+                && !self.cooldown.is_cooling(path, now)
+            {
+                self.cooldown.record(path, now);
+                return Some(ArbOpportunity::new(
+                    Arc::clone(path),
+                    Arc::clone(&self.settings.home_asset),
+                    start,
+                    end,
+                    [step1 / start, step2 / step1, end / step2],
+                    self.settings.confidence([&p1, &p2, &p3]),
+                    [&p1, &p2, &p3],
+                ).detected(update, now));
             };
         }
         None
     }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.price_store.snapshot()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        self.settings.halt.clone()
+    }
+
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        self.settings.stored_path_return(&*self.price_store, path)
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        self.settings.stored_implied_quote(&*self.price_store, path)
+    }
+
+    fn invalidate_all(&self) {
+        self.price_store.clear();
+    }
+
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        self.price_store.remove_symbols(symbols);
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        self.return_history.top(n)
+    }
 }
 
 impl NaivePrecompiledScanner {
     pub fn new(paths: Vec<PricingPath>) -> Self {
-        let price_store = DashMap::new();
+        Self::with_settings(paths, ScannerSettings::default())
+    }
+
+    /// Constructs the scanner with custom evaluation settings (see `ScannerBuilder`).
+    pub fn with_settings(paths: Vec<PricingPath>, settings: ScannerSettings) -> Self {
+        let price_store = settings.scanner_price_store();
+        let path_symbols = paths.iter().flat_map(PricingPath::symbols).collect();
+        let paths = paths.into_iter().map(Arc::new).collect();
+        let eviction = PriceEviction::new(settings.price_ttl);
+        let cooldown = PathCooldown::new(settings.opportunity_cooldown);
+        let return_history = ReturnHistory::new(settings.return_ewma_alpha);
         Self {
             paths,
-            price_store
+            price_store,
+            settings,
+            path_symbols,
+            eviction,
+            cooldown,
+            return_history,
         }
     }
+
+    /// Approximate heap used by the path list, price store and protected symbols (see `footprint`).
+    pub fn approx_memory_bytes(&self) -> usize {
+        let paths = self.paths.capacity() * size_of::<Arc<PricingPath>>()
+            + shared_paths_bytes(&self.paths);
+        let symbols = table_bytes::<String>(self.path_symbols.capacity())
+            + self.path_symbols.iter().map(String::capacity).sum::<usize>();
+        paths + symbols + self.price_store.approx_memory_bytes()
+    }
 }
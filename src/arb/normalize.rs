@@ -0,0 +1,174 @@
+// src/arb/normalize.rs
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+
+use crate::parse::TopOfBookUpdate;
+use crate::price_path::PricingPath;
+
+use super::{ArbEvaluator, ArbOpportunity, HaltHandle, ImpliedQuote};
+
+
+/// How a reference symbol's quote converts one unit of an asset into the reporting currency.
+#[derive(Debug, Clone)]
+struct Reference {
+    asset: Arc<str>,
+    /// `true` for `{asset}{reporting}` (sell the asset at the bid), `false` for
+    /// `{reporting}{asset}` (buy the reporting currency at the ask).
+    asset_is_base: bool,
+}
+
+
+/// Evaluator decorator that fills in each opportunity's `profit_normalized` by converting its
+/// `profit_home` into a single reporting currency.
+///
+/// Rates come from the live quotes of the reference symbols (`BTCUSDT` converts BTC to USDT at
+/// the bid, `USDTEUR` would convert EUR at `1 / ask`) as they pass through `process_update`.
+/// Until a reference symbol has been quoted, the static rate from `with_reference_rate` is used;
+/// with neither, `profit_normalized` stays `None`.
+pub struct ProfitNormalizer<E> {
+    inner: E,
+    reporting_asset: Arc<str>,
+    references: HashMap<String, Reference>,
+    live_rates: DashMap<Arc<str>, f64>,
+    reference_rates: HashMap<String, f64>,
+}
+
+impl<E: ArbEvaluator> ProfitNormalizer<E> {
+    /// Wraps `inner`, converting the profit of opportunities in any of `home_assets` into
+    /// `reporting_asset`.
+    pub fn new<'a>(inner: E, reporting_asset: &str, home_assets: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut references = HashMap::new();
+        for asset in home_assets.into_iter().filter(|&asset| asset != reporting_asset) {
+            let asset: Arc<str> = Arc::from(asset);
+            references.insert(format!("{asset}{reporting_asset}"), Reference { asset: Arc::clone(&asset), asset_is_base: true });
+            references.insert(format!("{reporting_asset}{asset}"), Reference { asset, asset_is_base: false });
+        }
+        Self {
+            inner,
+            reporting_asset: Arc::from(reporting_asset),
+            references,
+            live_rates: DashMap::new(),
+            reference_rates: HashMap::new(),
+        }
+    }
+
+    /// Fallback value of one unit of `asset` in the reporting currency, used until a reference
+    /// symbol for it is quoted.
+    pub fn with_reference_rate(mut self, asset: &str, rate: f64) -> Self {
+        self.reference_rates.insert(asset.to_string(), rate);
+        self
+    }
+
+    /// Value of one unit of `asset` in the reporting currency: `1.0` for the reporting currency
+    /// itself, else the live rate if one was quoted, else the reference rate.
+    pub fn rate(&self, asset: &str) -> Option<f64> {
+        if asset == &*self.reporting_asset {
+            return Some(1.0);
+        }
+        self.live_rates.get(asset).map(|rate| *rate).or_else(|| self.reference_rates.get(asset).copied())
+    }
+
+    fn observe(&self, update: &TopOfBookUpdate) {
+        let Some(reference) = self.references.get(&update.symbol) else {
+            return;
+        };
+        let rate = if reference.asset_is_base { update.bid_price } else { 1.0 / update.ask_price };
+        if rate.is_finite() && rate > 0.0 {
+            self.live_rates.insert(Arc::clone(&reference.asset), rate);
+        }
+    }
+
+    fn normalized(&self, mut opportunity: ArbOpportunity) -> ArbOpportunity {
+        opportunity.profit_normalized = self.rate(&opportunity.home_asset).map(|rate| opportunity.profit_home * rate);
+        opportunity
+    }
+}
+
+impl<E: ArbEvaluator> ArbEvaluator for ProfitNormalizer<E> {
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        self.observe(update);
+        self.inner.process_update(update).map(|opportunity| self.normalized(opportunity))
+    }
+
+    fn process_batch(&self, updates: &[TopOfBookUpdate]) -> Vec<ArbOpportunity> {
+        updates.iter().for_each(|update| self.observe(update));
+        self.inner.process_batch(updates).into_iter().map(|opportunity| self.normalized(opportunity)).collect()
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.inner.price_snapshot()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        self.inner.halt_handle()
+    }
+
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        self.inner.evaluate_path(path)
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        self.inner.implied_quote(path)
+    }
+
+    fn invalidate_all(&self) {
+        self.live_rates.clear();
+        self.inner.invalidate_all()
+    }
+
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        for reference in symbols.iter().filter_map(|s| self.references.get(s)) {
+            self.live_rates.remove(&reference.asset);
+        }
+        self.inner.invalidate_symbols(symbols)
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        self.inner.top_paths(n)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::{ArbMode, ScannerBuilder};
+    use crate::testutil::{assert_return_eq, mock_update, MockBuilder};
+
+    #[test]
+    fn btc_home_profit_is_converted_at_the_btcusdt_bid() {
+        let paths = MockBuilder::new().triangle("BTC", "ETH", "SOL").paths("BTC", &["ETH"]);
+        let scanner = ScannerBuilder::new(paths).with_home_asset("BTC").build(ArbMode::EdgeMap);
+        let normalizer = ProfitNormalizer::new(scanner, "USDT", ["BTC"]).with_reference_rate("BTC", 60000.0);
+
+        normalizer.process_update(&mock_update("BTCUSDT", 65000.0, 65010.0));
+        normalizer.process_update(&mock_update("ETHBTC", 0.05, 0.05));
+        normalizer.process_update(&mock_update("SOLETH", 0.05, 0.05));
+        let opportunity = normalizer
+            .process_update(&mock_update("SOLBTC", 0.0026, 0.0026))
+            .expect("profitable in BTC");
+
+        assert_eq!(&*opportunity.home_asset, "BTC");
+        let profit = opportunity.profit_normalized.expect("BTCUSDT was quoted");
+        assert_return_eq(profit, opportunity.profit_home * 65000.0, 1e-9);
+    }
+
+    #[test]
+    fn rates_fall_back_to_reference_then_none() {
+        let paths = MockBuilder::new().triangle("BTC", "ETH", "SOL").paths("BTC", &["ETH"]);
+        let scanner = ScannerBuilder::new(paths).build(ArbMode::EdgeMap);
+        let normalizer = ProfitNormalizer::new(scanner, "EUR", ["BTC", "USDT"]).with_reference_rate("BTC", 55000.0);
+
+        assert_eq!(normalizer.rate("EUR"), Some(1.0));
+        assert_eq!(normalizer.rate("BTC"), Some(55000.0));
+        assert_eq!(normalizer.rate("USDT"), None);
+
+        normalizer.process_update(&mock_update("EURUSDT", 1.07, 1.08));
+        normalizer.process_update(&mock_update("BTCEUR", 56000.0, 56010.0));
+        assert_return_eq(normalizer.rate("USDT").unwrap(), 1.0 / 1.08, 1e-12);
+        assert_eq!(normalizer.rate("BTC"), Some(56000.0));
+    }
+}
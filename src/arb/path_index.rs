@@ -0,0 +1,60 @@
+// src/arb/path_index.rs
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::price_path::PricingPath;
+
+
+/// Symbol → the paths trading it, each symbol's list in the order the paths were given. Lists
+/// are shared slices, so handing one out (`ScannerCore::paths_for_symbol`) never copies it.
+pub(crate) type SymbolPaths = HashMap<String, Arc<[Arc<PricingPath>]>>;
+
+
+/// A scanner's symbol → paths index, swappable for a new universe while updates flow.
+///
+/// Updates price against a snapshot (`load`) without holding the lock, so an update never sees
+/// half an index and a `replace` only waits for other swaps, not for in-flight evaluations.
+pub(crate) struct PathIndex {
+    current: RwLock<Arc<SymbolPaths>>,
+}
+
+impl PathIndex {
+    pub(crate) fn new(price_paths: Vec<PricingPath>) -> Self {
+        let index = index_by_symbol(price_paths.into_iter().map(Arc::new));
+        Self { current: RwLock::new(Arc::new(index)) }
+    }
+
+    /// The current index; later `replace` calls don't affect the returned snapshot.
+    pub(crate) fn load(&self) -> Arc<SymbolPaths> {
+        Arc::clone(&self.current.read().unwrap())
+    }
+
+    /// Swaps in an index of `price_paths` and returns it.
+    ///
+    /// A path already indexed keeps its `Arc`, so state keyed by path identity (the scanner's
+    /// `ReturnHistory`) carries over for paths that stay in the universe.
+    pub(crate) fn replace(&self, price_paths: Vec<PricingPath>) -> Arc<SymbolPaths> {
+        let mut current = self.current.write().unwrap();
+        let existing: HashMap<_, &Arc<PricingPath>> =
+            current.values().flat_map(|paths| paths.iter()).map(|path| (path.key(), path)).collect();
+        let paths = price_paths
+            .into_iter()
+            .map(|path| existing.get(&path.key()).map_or_else(|| Arc::new(path), |&kept| Arc::clone(kept)));
+        let index = Arc::new(index_by_symbol(paths));
+        *current = Arc::clone(&index);
+        index
+    }
+}
+
+
+pub(crate) fn index_by_symbol(paths: impl ExactSizeIterator<Item = Arc<PricingPath>>) -> SymbolPaths {
+    // Preallocate with 3x paths since each path maps to 3 symbols
+    let mut index: HashMap<String, Vec<Arc<PricingPath>>> = HashMap::with_capacity(paths.len() * 3);
+    for path in paths {
+        for symbol in path.symbols() {
+            index.entry(symbol).or_default().push(Arc::clone(&path));
+        }
+    }
+    index.into_iter().map(|(symbol, paths)| (symbol, paths.into())).collect()
+}
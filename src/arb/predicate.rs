@@ -0,0 +1,47 @@
+// src/arb/predicate.rs
+
+use std::fmt;
+
+use crate::price_path::PricingPath;
+
+
+/// A priced path offered to a `ProfitPredicate`.
+#[derive(Debug, Clone, Copy)]
+pub struct EvalContext<'a> {
+    pub path: &'a PricingPath,
+    /// Price used on each leg, in path order (ask for `Side::Ask`, bid for `Side::Bid`).
+    pub leg_prices: [f64; 3],
+    /// Home-asset amount routed through the path.
+    pub start_notional: f64,
+    /// Home-asset amount returned, after fees.
+    pub end_notional: f64,
+    /// The scanner's configured `min_return`.
+    pub min_return: f64,
+}
+
+impl EvalContext<'_> {
+    /// Net return `end_notional / start_notional` after fees.
+    pub fn net_return(&self) -> f64 {
+        self.end_notional / self.start_notional
+    }
+}
+
+
+/// Acceptance rule deciding whether a priced path is reported, decoupling strategy from the
+/// scanners (see `ScannerBuilder::with_profit_predicate`).
+///
+/// Scanners only consult it for paths with a finite return whose legs are all priced, and
+/// still apply cooldowns and leg time spread checks on top.
+pub trait ProfitPredicate: fmt::Debug + Send + Sync {
+    fn accept(&self, ctx: &EvalContext) -> bool;
+}
+
+/// The default rule: the net return beats `min_return` (any profit after fees, by default).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GrossPositive;
+
+impl ProfitPredicate for GrossPositive {
+    fn accept(&self, ctx: &EvalContext) -> bool {
+        ctx.net_return() > ctx.min_return
+    }
+}
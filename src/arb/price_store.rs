@@ -0,0 +1,125 @@
+// src/arb/price_store.rs
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+
+use crate::parse::TopOfBookUpdate;
+
+use super::footprint::price_store_bytes;
+use super::quote_filter::StoredQuote;
+
+
+/// Latest accepted quote per symbol, read by the symbol-keyed scanners.
+///
+/// Each scanner owns a fresh `DashMapPriceStore` unless one is shared through
+/// `ScannerBuilder::with_price_store`, in which case every scanner built from it reads (and
+/// writes) the same quotes: one copy of the book feeds several evaluators. Clearing, rejecting
+/// or evicting a quote through any of them is then visible to all.
+pub trait PriceStore: fmt::Debug + Send + Sync {
+    /// The latest quote for `symbol`, if one is stored.
+    fn get(&self, symbol: &str) -> Option<Arc<StoredQuote>>;
+
+    /// Stores `quote` under its update's symbol, replacing any earlier quote.
+    fn insert(&self, quote: StoredQuote);
+
+    fn contains(&self, symbol: &str) -> bool;
+
+    fn remove(&self, symbol: &str);
+
+    /// Removes the quote of every symbol in `symbols`.
+    fn remove_symbols(&self, symbols: &[String]) {
+        for symbol in symbols {
+            self.remove(symbol);
+        }
+    }
+
+    fn clear(&self);
+
+    /// Keeps only the quotes `keep` accepts, returning how many were removed.
+    fn retain(&self, keep: &mut dyn FnMut(&str, &StoredQuote) -> bool) -> usize;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every stored quote's update, keyed by symbol.
+    fn snapshot(&self) -> HashMap<String, TopOfBookUpdate>;
+
+    /// Rough heap held by the store (see `footprint`).
+    fn approx_memory_bytes(&self) -> usize;
+}
+
+/// The default `PriceStore`: a concurrent map from symbol to its latest quote.
+pub type DashMapPriceStore = DashMap<String, Arc<StoredQuote>>;
+
+impl PriceStore for DashMapPriceStore {
+    fn get(&self, symbol: &str) -> Option<Arc<StoredQuote>> {
+        DashMap::get(self, symbol).map(|quote| Arc::clone(&quote))
+    }
+
+    fn insert(&self, quote: StoredQuote) {
+        DashMap::insert(self, quote.update.symbol.clone(), Arc::new(quote));
+    }
+
+    fn contains(&self, symbol: &str) -> bool {
+        self.contains_key(symbol)
+    }
+
+    fn remove(&self, symbol: &str) {
+        DashMap::remove(self, symbol);
+    }
+
+    fn clear(&self) {
+        DashMap::clear(self);
+    }
+
+    fn retain(&self, keep: &mut dyn FnMut(&str, &StoredQuote) -> bool) -> usize {
+        let before = DashMap::len(self);
+        DashMap::retain(self, |symbol, quote| keep(symbol, quote));
+        before.saturating_sub(DashMap::len(self))
+    }
+
+    fn len(&self) -> usize {
+        DashMap::len(self)
+    }
+
+    fn snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.iter().map(|entry| (entry.key().clone(), entry.value().update.clone())).collect()
+    }
+
+    fn approx_memory_bytes(&self) -> usize {
+        price_store_bytes(self)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::{ArbMode, ScannerBuilder};
+    use crate::testutil::{mock_path, mock_update};
+
+    #[test]
+    fn scanners_sharing_a_store_see_each_others_inserts() {
+        let store: Arc<dyn PriceStore> = Arc::new(DashMapPriceStore::new());
+        let builder = ScannerBuilder::new(vec![mock_path()]).with_price_store(Arc::clone(&store));
+        let edge = builder.clone().build(ArbMode::EdgeMap);
+        let naive = builder.build(ArbMode::Naive);
+
+        // A single update through one scanner is visible to the other and to the store itself
+        edge.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+        assert!(store.contains("BTCUSDT"));
+        assert_eq!(naive.price_snapshot()["BTCUSDT"].ask_price, 50010.0);
+        assert_eq!(store.len(), 1);
+
+        naive.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+        naive.process_update(&mock_update("ETHUSDT", 3600.0, 3601.0));
+        assert_eq!(edge.price_snapshot().len(), 3);
+        assert_eq!(edge.evaluate_path(&mock_path()), naive.evaluate_path(&mock_path()));
+    }
+}
@@ -0,0 +1,179 @@
+// src/arb/quote_filter.rs
+
+use std::ops::Deref;
+use std::time::Instant;
+
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::parse::TopOfBookUpdate;
+
+use super::price_store::PriceStore;
+
+
+/// Default widest relative spread `(ask - bid) / bid` accepted by `QuoteFilter`.
+pub const DEFAULT_MAX_SPREAD_RATIO: f64 = 0.05;
+
+fn default_max_spread_ratio() -> f64 {
+    DEFAULT_MAX_SPREAD_RATIO
+}
+
+
+/// Sanity filter applied to every incoming quote before it can be used on a path leg.
+///
+/// Crossed (`bid > ask`), non-positive/non-finite, or implausibly wide quotes make the
+/// arbitrage math produce phantom multipliers, so they are rejected.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct QuoteFilter {
+    /// Widest accepted relative spread `(ask - bid) / bid`.
+    #[serde(default = "default_max_spread_ratio")]
+    pub max_spread_ratio: f64,
+}
+
+impl Default for QuoteFilter {
+    fn default() -> Self {
+        Self { max_spread_ratio: DEFAULT_MAX_SPREAD_RATIO }
+    }
+}
+
+impl QuoteFilter {
+    /// Returns why `quote` is unusable, or `None` if it passes the filter.
+    pub fn rejection_reason(&self, quote: &TopOfBookUpdate) -> Option<&'static str> {
+        let (bid, ask) = (quote.bid_price, quote.ask_price);
+        if !(bid.is_finite() && ask.is_finite() && bid > 0.0 && ask > 0.0) {
+            return Some("non-positive or non-finite price");
+        }
+        if bid > ask {
+            return Some("crossed quote (bid > ask)");
+        }
+        if (ask - bid) / bid > self.max_spread_ratio {
+            return Some("spread wider than max_spread_ratio");
+        }
+        None
+    }
+}
+
+
+/// A quote accepted into a scanner's price store, stamped with its arrival time.
+#[derive(Debug, Clone)]
+pub struct StoredQuote {
+    pub update: TopOfBookUpdate,
+    pub received: Instant,
+}
+
+impl Deref for StoredQuote {
+    type Target = TopOfBookUpdate;
+
+    fn deref(&self) -> &TopOfBookUpdate {
+        &self.update
+    }
+}
+
+
+/// Stores `update`, stamped `received`, in `price_store` if it passes `filter`.
+///
+/// A rejected quote is logged and its symbol evicted, so every path using that symbol is
+/// skipped until a sane quote arrives. Returns whether the update was accepted.
+pub(crate) fn store_quote(
+    price_store: &dyn PriceStore,
+    filter: &QuoteFilter,
+    update: &TopOfBookUpdate,
+    received: Instant,
+) -> bool {
+    if let Some(reason) = filter.rejection_reason(update) {
+        log_rejection(update, reason);
+        price_store.remove(&update.symbol);
+        return false;
+    }
+    price_store.insert(StoredQuote { update: update.clone(), received });
+    true
+}
+
+
+/// Logged at debug level: a bad feed can reject a quote on every tick, which would flood a
+/// warn-level log.
+pub(crate) fn log_rejection(update: &TopOfBookUpdate, reason: &str) {
+    debug!(
+        symbol = %update.symbol,
+        bid = update.bid_price,
+        ask = update.ask_price,
+        reason,
+        "Rejected quote"
+    );
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::{
+        ArbEvaluator, HashMapEdgeScanner, NaivePrecompiledScanner, RayonBestMatchScanner,
+        RayonFirstMatchScanner,
+    };
+    use crate::testutil::{mock_path, mock_update};
+
+    fn all_scanners() -> Vec<Box<dyn ArbEvaluator>> {
+        vec![
+            Box::new(NaivePrecompiledScanner::new(vec![mock_path()])),
+            Box::new(HashMapEdgeScanner::new(vec![mock_path()])),
+            Box::new(RayonFirstMatchScanner::new(vec![mock_path()])),
+            Box::new(RayonBestMatchScanner::new(vec![mock_path()])),
+        ]
+    }
+
+    #[test]
+    fn test_filter_classifies_quotes() {
+        let filter = QuoteFilter::default();
+        assert_eq!(filter.rejection_reason(&mock_update("BTCUSDT", 30000.0, 30010.0)), None);
+        assert!(filter.rejection_reason(&mock_update("BTCUSDT", 30010.0, 30000.0)).is_some());
+        assert!(filter.rejection_reason(&mock_update("BTCUSDT", 0.0, 30000.0)).is_some());
+        assert!(filter.rejection_reason(&mock_update("BTCUSDT", -1.0, 30000.0)).is_some());
+        assert!(filter.rejection_reason(&mock_update("BTCUSDT", f64::NAN, 30000.0)).is_some());
+        assert!(filter.rejection_reason(&mock_update("BTCUSDT", 100.0, 200.0)).is_some());
+    }
+
+    #[test]
+    fn test_crossed_quote_reports_no_opportunity() {
+        for scanner in all_scanners() {
+            scanner.process_update(&mock_update("BTCUSDT", 30000.0, 30010.0));
+            scanner.process_update(&mock_update("ETHBTC", 0.065, 0.066));
+
+            // Bid far above ask would otherwise yield a huge phantom return on the SELL leg
+            let result = scanner.process_update(&mock_update("ETHUSDT", 5000.0, 1980.0));
+            assert!(result.is_none(), "crossed quote produced an opportunity");
+            assert!(!scanner.price_snapshot().contains_key("ETHUSDT"));
+        }
+    }
+
+    #[test]
+    fn test_zero_price_quote_reports_no_opportunity() {
+        for scanner in all_scanners() {
+            scanner.process_update(&mock_update("ETHBTC", 0.065, 0.066));
+            scanner.process_update(&mock_update("ETHUSDT", 1980.0, 1985.0));
+
+            // A zero ask on the BUY leg would otherwise divide to infinity
+            let result = scanner.process_update(&mock_update("BTCUSDT", 0.0, 0.0));
+            assert!(result.is_none(), "zero-price quote produced an opportunity");
+        }
+    }
+
+    #[test]
+    fn test_nan_price_quote_reports_no_opportunity_and_does_not_panic() {
+        for scanner in all_scanners() {
+            scanner.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+            scanner.process_update(&mock_update("ETHBTC", 0.065, 0.066));
+
+            let result = scanner.process_update(&mock_update("ETHUSDT", f64::NAN, 1985.0));
+            assert!(result.is_none(), "NaN-price quote produced an opportunity");
+            assert!(scanner.top_paths(5).iter().all(|(_, average)| average.is_finite()));
+        }
+    }
+
+    #[test]
+    fn test_rejected_quote_evicts_previous_price() {
+        let scanner = HashMapEdgeScanner::new(vec![mock_path()]);
+        scanner.process_update(&mock_update("BTCUSDT", 30000.0, 30010.0));
+        scanner.process_update(&mock_update("BTCUSDT", 30010.0, 30000.0));
+        assert!(scanner.price_snapshot().is_empty());
+    }
+}
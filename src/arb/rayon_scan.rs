@@ -1,15 +1,21 @@
 // src/arb/rayon_scan.rs
 
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Instant};
 
-use dashmap::DashMap;
 use rayon::prelude::*;
 
-use crate::{parse::TopOfBookUpdate, price_path::{PricingPath, Side}};
+use crate::{parse::TopOfBookUpdate, price_path::{apply_leg, leg_prices, legs_priced, PricingPath}};
 
-use super::ArbEvaluator;
+use super::{ArbEvaluator, ArbOpportunity, HaltHandle, ImpliedQuote, ScannerSettings};
+use super::cooldown::PathCooldown;
+use super::eviction::PriceEviction;
+use super::return_history::ReturnHistory;
+use super::scanner_core::ScannerCore;
+use super::price_store::PriceStore;
+use super::quote_filter::store_quote;
 
-const START: f64 = 1.0;
+/// Default tolerance within which `RayonBestMatchScanner` treats two returns as tied.
+pub const DEFAULT_BEST_MATCH_EPSILON: f64 = 1e-12;
 
 /// `RayonPathScanner` evaluates arbitrage opportunities across all known pricing paths
 /// using data-parallelism via the Rayon library.
@@ -23,29 +29,33 @@ const START: f64 = 1.0;
 ///
 /// Internally uses a `DashMap` for concurrent price storage and `Arc<PricingPath>` for safe parallel access.
 pub struct RayonFirstMatchScanner {
-    price_store: DashMap<String, TopOfBookUpdate>,
-    symbol_to_paths: HashMap<String, Vec<Arc<PricingPath>>>,
+    core: ScannerCore,
+    settings: ScannerSettings,
+    eviction: PriceEviction,
+    cooldown: PathCooldown,
 }
 
 impl RayonFirstMatchScanner {
     /// Constructs a new `RayonFirstMatchScanner`, wrapping the provided paths in `Arc`
     /// for safe access across threads.
     pub fn new(price_paths: Vec<PricingPath>) -> Self {
-        let paths: Vec<Arc<PricingPath>> = price_paths.into_iter().map(Arc::new).collect();
-
-        // Preallocate with 3x paths since each path maps to 3 symbols
-        let mut symbol_to_paths: HashMap<String, Vec<Arc<PricingPath>>> = HashMap::with_capacity(paths.len() * 3);
-        
-        for path in &paths {
-            for symbol in path.symbols() {
-                symbol_to_paths.entry(symbol).or_default().push(Arc::clone(path));
-            }
-        }
+        Self::with_settings(price_paths, ScannerSettings::default())
+    }
+
+    /// Constructs the scanner with custom evaluation settings (see `ScannerBuilder`).
+    pub fn with_settings(price_paths: Vec<PricingPath>, settings: ScannerSettings) -> Self {
         Self {
-            price_store: DashMap::new(),
-            symbol_to_paths,
+            core: ScannerCore::new(price_paths, &settings),
+            eviction: PriceEviction::new(settings.price_ttl),
+            cooldown: PathCooldown::new(settings.opportunity_cooldown),
+            settings,
         }
     }
+
+    /// The quotes, path index and return averages (see `ScannerCore`).
+    pub fn core(&self) -> &ScannerCore {
+        &self.core
+    }
 }
 
 
@@ -53,52 +63,51 @@ impl ArbEvaluator for RayonFirstMatchScanner {
     /// Evaluates only the pricing paths involving the updated symbol in parallel using Rayon.
     /// Returns the first profitable match, if any. 
     /// This is a fast, non-deterministic approach ideal for high-frequency updates.
-    fn process_update(&self, update: &TopOfBookUpdate) -> Option<(PricingPath, f64)> {
-        self.price_store.insert(update.symbol.clone(), update.clone());
-        let Some(relevant_paths) = self.symbol_to_paths.get(&update.symbol) else {
-            return None
-        };
-
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        let now = self.settings.clock.now();
+        if !store_quote(&*self.core.price_store, &self.settings.quote_filter, update, now) {
+            return None;
+        }
+        let path_index = self.core.index();
+        self.eviction.maybe_sweep(&*self.core.price_store, now, |s| path_index.contains_key(s));
+        if self.settings.halt.is_halted() {
+            return None;
+        }
+        let relevant_paths = path_index.get(&update.symbol)?;
         relevant_paths
             .par_iter()
             .find_map_any(|path| {
-                let s1 = &path.leg1.symbol.symbol;
-                let s2 = &path.leg2.symbol.symbol;
-                let s3 = &path.leg3.symbol.symbol;
-
-                // Early filter: skip path if not all 3 symbols are present
-                if !(self.price_store.contains_key(s1)
-                    && self.price_store.contains_key(s2)
-                    && self.price_store.contains_key(s3)) {
-                    return None;
-                }
-
-                // Safe to unwrap now
-                let p1 = self.price_store.get(s1).unwrap();
-                let p2 = self.price_store.get(s2).unwrap();
-                let p3 = self.price_store.get(s3).unwrap();
-
-                let step1 = match path.leg1.side {
-                    Side::Ask => START / p1.ask_price,
-                    Side::Bid => START * p1.bid_price,
-                };
-
-                let step2 = match path.leg2.side {
-                    Side::Ask => step1 / p2.ask_price,
-                    Side::Bid => step1 * p2.bid_price,
-                };
-
-                let end = match path.leg3.side {
-                    Side::Ask => step2 / p3.ask_price,
-                    Side::Bid => step2 * p3.bid_price,
-                };
-
-                if end > START {
-                    Some((path.as_ref().clone(), end))
-                } else {
-                    None
-                }
-        })
+                scan_path(&*self.core.price_store, &self.settings, &self.cooldown, &self.core.return_history, path, update, now)
+            })
+            .inspect(|opportunity| self.cooldown.record(&opportunity.path, now))
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.core.price_store.snapshot()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        self.settings.halt.clone()
+    }
+
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        self.settings.stored_path_return(&*self.core.price_store, path)
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        self.settings.stored_implied_quote(&*self.core.price_store, path)
+    }
+
+    fn invalidate_all(&self) {
+        self.core.price_store.clear();
+    }
+
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        self.core.price_store.remove_symbols(symbols);
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        self.core.return_history.top(n)
     }
 }
 
@@ -107,10 +116,14 @@ impl ArbEvaluator for RayonFirstMatchScanner {
 /// the **most profitable** arbitrage opportunity, rather than the first one found.
 ///
 /// This strategy incurs slightly more overhead per update than `RayonFirstMatchScanner`
-/// but ensures the best available opportunity is returned.
+/// but ensures the best available opportunity is returned. Returns within
+/// `ScannerSettings::best_match_epsilon` of the best are ties, won by the path that comes
+/// first in path order, so the same quotes always report the same path.
 pub struct RayonBestMatchScanner {
-    price_store: DashMap<String, TopOfBookUpdate>,
-    symbol_to_paths: HashMap<String, Vec<Arc<PricingPath>>>,
+    core: ScannerCore,
+    settings: ScannerSettings,
+    eviction: PriceEviction,
+    cooldown: PathCooldown,
 }
 
 
@@ -118,21 +131,23 @@ impl RayonBestMatchScanner {
     /// Constructs a new `RayonBestMatchScanner`, wrapping the provided paths in `Arc`
     /// for safe access across threads.
     pub fn new(price_paths: Vec<PricingPath>) -> Self {
-        let paths: Vec<Arc<PricingPath>> = price_paths.into_iter().map(Arc::new).collect();
-
-        // Preallocate with 3x paths since each path maps to 3 symbols
-        let mut symbol_to_paths: HashMap<String, Vec<Arc<PricingPath>>> = HashMap::with_capacity(paths.len() * 3);
-        
-        for path in &paths {
-            for symbol in path.symbols() {
-                symbol_to_paths.entry(symbol).or_default().push(Arc::clone(path));
-            }
-        }
+        Self::with_settings(price_paths, ScannerSettings::default())
+    }
+
+    /// Constructs the scanner with custom evaluation settings (see `ScannerBuilder`).
+    pub fn with_settings(price_paths: Vec<PricingPath>, settings: ScannerSettings) -> Self {
         Self {
-            price_store: DashMap::new(),
-            symbol_to_paths,
+            core: ScannerCore::new(price_paths, &settings),
+            eviction: PriceEviction::new(settings.price_ttl),
+            cooldown: PathCooldown::new(settings.opportunity_cooldown),
+            settings,
         }
     }
+
+    /// The quotes, path index and return averages (see `ScannerCore`).
+    pub fn core(&self) -> &ScannerCore {
+        &self.core
+    }
 }
 
 
@@ -140,96 +155,239 @@ impl ArbEvaluator for RayonBestMatchScanner {
     /// Evaluates all relevant paths involving the updated symbol in parallel,
     /// returning the most profitable opportunity (if any).
     /// This ensures deterministic selection of the best opportunity but incurs slightly higher cost than early-exit scanning.
-    fn process_update(&self, update: &TopOfBookUpdate) -> Option<(PricingPath, f64)> {
-        self.price_store.insert(update.symbol.clone(), update.clone());
-        let Some(relevant_paths) = self.symbol_to_paths.get(&update.symbol) else {
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        let now = self.settings.clock.now();
+        if !store_quote(&*self.core.price_store, &self.settings.quote_filter, update, now) {
             return None;
-        };
-        relevant_paths
+        }
+        let path_index = self.core.index();
+        self.eviction.maybe_sweep(&*self.core.price_store, now, |s| path_index.contains_key(s));
+        if self.settings.halt.is_halted() {
+            return None;
+        }
+        let relevant_paths = path_index.get(&update.symbol)?;
+        let candidates: Vec<(usize, ArbOpportunity)> = relevant_paths
             .par_iter()
-            .filter_map(|path| {
-                let s1 = &path.leg1.symbol.symbol;
-                let s2 = &path.leg2.symbol.symbol;
-                let s3 = &path.leg3.symbol.symbol;
-
-                // Early filter: skip path if not all 3 symbols are present
-                if !(self.price_store.contains_key(s1)
-                    && self.price_store.contains_key(s2)
-                    && self.price_store.contains_key(s3)) {
-                    return None;
-                }
-
-                // Safe to unwrap now
-                let p1 = self.price_store.get(s1).unwrap();
-                let p2 = self.price_store.get(s2).unwrap();
-                let p3 = self.price_store.get(s3).unwrap();
-
-                let step1 = match path.leg1.side {
-                    Side::Ask => START / p1.ask_price,
-                    Side::Bid => START * p1.bid_price,
-                };
-
-                let step2 = match path.leg2.side {
-                    Side::Ask => step1 / p2.ask_price,
-                    Side::Bid => step1 * p2.bid_price,
-                };
-
-                let end = match path.leg3.side {
-                    Side::Ask => step2 / p3.ask_price,
-                    Side::Bid => step2 * p3.bid_price,
-                };
-
-                if end > START {
-                    Some((path.as_ref().clone(), end))
-                } else {
-                    None
-                }
+            .enumerate()
+            .filter_map(|(index, path)| {
+                scan_path(&*self.core.price_store, &self.settings, &self.cooldown, &self.core.return_history, path, update, now)
+                    .map(|opportunity| (index, opportunity))
             })
-            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .collect();
+        best_match(candidates, self.settings.best_match_epsilon)
+            .inspect(|opportunity| self.cooldown.record(&opportunity.path, now))
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.core.price_store.snapshot()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        self.settings.halt.clone()
+    }
+
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        self.settings.stored_path_return(&*self.core.price_store, path)
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        self.settings.stored_implied_quote(&*self.core.price_store, path)
+    }
+
+    fn invalidate_all(&self) {
+        self.core.price_store.clear();
+    }
+
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        self.core.price_store.remove_symbols(symbols);
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        self.core.return_history.top(n)
     }
 }
 
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::price_path::{PathLeg, PricingPath, Side, SymbolInfo};
-    
-    fn mock_path() -> PricingPath {
-        let s1 = SymbolInfo {
-            symbol: "BTCUSDT".into(),
-            base_asset: "BTC".into(),
-            quote_asset: "USDT".into(),
-            status: "TRADING".into(),
-        };
-        let s2 = SymbolInfo {
-            symbol: "ETHBTC".into(),
-            base_asset: "ETH".into(),
-            quote_asset: "BTC".into(),
-            status: "TRADING".into(),
-        };
-        let s3 = SymbolInfo {
-            symbol: "ETHUSDT".into(),
-            base_asset: "ETH".into(),
-            quote_asset: "USDT".into(),
-            status: "TRADING".into(),
-        };
-
-        PricingPath {
-            leg1: PathLeg { symbol: s1, side: Side::Ask },
-            leg2: PathLeg { symbol: s2, side: Side::Ask },
-            leg3: PathLeg { symbol: s3, side: Side::Bid },
+/// `RayonOrderedMatchScanner` prices paths in parallel like `RayonFirstMatchScanner`, but
+/// returns the profitable path that comes first in path order rather than whichever thread
+/// finishes first.
+///
+/// The same quotes always produce the same opportunity, which keeps tests and audit logs
+/// reproducible at the cost of waiting for every earlier path to be priced.
+pub struct RayonOrderedMatchScanner {
+    core: ScannerCore,
+    settings: ScannerSettings,
+    eviction: PriceEviction,
+    cooldown: PathCooldown,
+}
+
+impl RayonOrderedMatchScanner {
+    /// Constructs a new `RayonOrderedMatchScanner`, wrapping the provided paths in `Arc`
+    /// for safe access across threads.
+    pub fn new(price_paths: Vec<PricingPath>) -> Self {
+        Self::with_settings(price_paths, ScannerSettings::default())
+    }
+
+    /// Constructs the scanner with custom evaluation settings (see `ScannerBuilder`).
+    pub fn with_settings(price_paths: Vec<PricingPath>, settings: ScannerSettings) -> Self {
+        Self {
+            core: ScannerCore::new(price_paths, &settings),
+            eviction: PriceEviction::new(settings.price_ttl),
+            cooldown: PathCooldown::new(settings.opportunity_cooldown),
+            settings,
         }
     }
 
-    fn mock_update(symbol: &str, bid: f64, ask: f64) -> TopOfBookUpdate {
-        TopOfBookUpdate {
-            symbol: symbol.to_string(),
-            bid_price: bid,
-            ask_price: ask,
+    /// The quotes, path index and return averages (see `ScannerCore`).
+    pub fn core(&self) -> &ScannerCore {
+        &self.core
+    }
+}
+
+
+impl ArbEvaluator for RayonOrderedMatchScanner {
+    /// Evaluates the paths involving the updated symbol in parallel and returns the
+    /// profitable one with the lowest path index, so repeated runs agree.
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        let now = self.settings.clock.now();
+        if !store_quote(&*self.core.price_store, &self.settings.quote_filter, update, now) {
+            return None;
         }
+        let path_index = self.core.index();
+        self.eviction.maybe_sweep(&*self.core.price_store, now, |s| path_index.contains_key(s));
+        if self.settings.halt.is_halted() {
+            return None;
+        }
+        let relevant_paths = path_index.get(&update.symbol)?;
+        relevant_paths
+            .par_iter()
+            .find_map_first(|path| {
+                scan_path(&*self.core.price_store, &self.settings, &self.cooldown, &self.core.return_history, path, update, now)
+            })
+            .inspect(|opportunity| self.cooldown.record(&opportunity.path, now))
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.core.price_store.snapshot()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        self.settings.halt.clone()
+    }
+
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        self.settings.stored_path_return(&*self.core.price_store, path)
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        self.settings.stored_implied_quote(&*self.core.price_store, path)
+    }
+
+    fn invalidate_all(&self) {
+        self.core.price_store.clear();
     }
 
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        self.core.price_store.remove_symbols(symbols);
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        self.core.return_history.top(n)
+    }
+}
+
+
+/// Picks the opportunity with the highest finite return from `candidates`, each tagged with
+/// its path index, by the rule of `best_index`.
+pub(super) fn best_match(candidates: Vec<(usize, ArbOpportunity)>, epsilon: f64) -> Option<ArbOpportunity> {
+    let index = best_index(candidates.iter().map(|(index, opportunity)| (*index, opportunity.result)), epsilon)?;
+    candidates.into_iter().find(|(candidate, _)| *candidate == index).map(|(_, opportunity)| opportunity)
+}
+
+/// Index of the best of `(path index, return)` candidates. Every return within `epsilon` of
+/// the highest finite one counts as a tie, and the tie goes to the lowest index, so the pick
+/// doesn't depend on the order candidates arrive in. Non-finite returns are never picked.
+pub(super) fn best_index(candidates: impl Iterator<Item = (usize, f64)> + Clone, epsilon: f64) -> Option<usize> {
+    let best = candidates
+        .clone()
+        .map(|(_, result)| result)
+        .filter(|result| result.is_finite())
+        .max_by(f64::total_cmp)?;
+    candidates
+        .filter(|(_, result)| result.is_finite() && best - result <= epsilon)
+        .map(|(index, _)| index)
+        .min()
+}
+
+
+/// Prices `path` from the stored quotes and records its return, yielding an opportunity when
+/// it is fresh, profitable, in sync and not cooling down. Shared by every Rayon scanner, which
+/// differ only in how they pick among the paths this accepts.
+pub(super) fn scan_path(
+    price_store: &dyn PriceStore,
+    settings: &ScannerSettings,
+    cooldown: &PathCooldown,
+    return_history: &ReturnHistory,
+    path: &Arc<PricingPath>,
+    update: &TopOfBookUpdate,
+    now: Instant,
+) -> Option<ArbOpportunity> {
+    let start = settings.start_notional;
+    let s1 = &path.leg1.symbol.symbol;
+    let s2 = &path.leg2.symbol.symbol;
+    let s3 = &path.leg3.symbol.symbol;
+
+    // A reconnect may clear the store mid-scan, so a missing leg is skipped rather than assumed away
+    let p1 = price_store.get(s1)?;
+    let p2 = price_store.get(s2)?;
+    let p3 = price_store.get(s3)?;
+    if !(settings.is_fresh(p1.received, p1.event_time_ms)
+        && settings.is_fresh(p2.received, p2.event_time_ms)
+        && settings.is_fresh(p3.received, p3.event_time_ms)) {
+        return None;
+    }
+    if !legs_priced(path, [&p1, &p2, &p3]) {
+        return None;
+    }
+
+    let [keep1, keep2, keep3] = settings.leg_multipliers(path);
+
+    let step1 = keep1 * apply_leg(start, path.leg1.side, &p1);
+
+    let step2 = keep2 * apply_leg(step1, path.leg2.side, &p2);
+
+    let end = keep3 * apply_leg(step2, path.leg3.side, &p3);
+
+    return_history.record(path, end / start);
+
+    if settings.is_profitable(path, leg_prices(path, [&p1, &p2, &p3]), end)
+        && settings.legs_in_sync([&p1, &p2, &p3].map(|q| q.event_time_ms))
+        && !cooldown.is_cooling(path, now)
+    {
+        Some(ArbOpportunity::new(
+            Arc::clone(path),
+            Arc::clone(&settings.home_asset),
+            start,
+            end,
+            [step1 / start, step2 / step1, end / step2],
+            settings.confidence([&p1, &p2, &p3]),
+            [&p1, &p2, &p3],
+        ).detected(update, now))
+    } else {
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use crate::arb::config::OnUpdateReturn;
+    use crate::arb::{ArbMode, DashMapPriceStore, ScannerBuilder, StoredQuote};
+    use crate::price_path::Side;
+    use crate::testutil::{assert_return_eq, mock_path, mock_update, MockBuilder};
+
     #[test]
     fn test_rayon_scanner_detects_arb() {
         let path = mock_path();
@@ -240,35 +398,19 @@ mod tests {
         scanner.process_update(&mock_update("ETHUSDT",1827.6, 1827.7)); // bid = ask = 1985
 
         let result = scanner.process_update(&mock_update("ETHUSDT", 1980.0, 1985.0));
-        assert!(result.is_some());
+        let opportunity = result.expect("ETHUSDT bid jump should open the triangle");
+        assert_return_eq(opportunity.result, 1.0 / 95461.0 / 0.01915 * 1980.0, 1e-12);
     }
 
     #[test]
     fn test_best_path_is_selected_from_multiple_profitable_paths() {
-        use crate::price_path::{SymbolInfo, PathLeg, Side};
-
-        fn make_symbol(symbol: &str, base: &str, quote: &str) -> SymbolInfo {
-            SymbolInfo {
-                symbol: symbol.to_string(),
-                base_asset: base.to_string(),
-                quote_asset: quote.to_string(),
-                status: "TRADING".into(),
-            }
-        }
-
         // Path 1: BTC → ETH → USDT
-        let path1 = PricingPath {
-            leg1: PathLeg { symbol: make_symbol("BTCUSDT", "BTC", "USDT"), side: Side::Ask },
-            leg2: PathLeg { symbol: make_symbol("ETHBTC", "ETH", "BTC"), side: Side::Ask },
-            leg3: PathLeg { symbol: make_symbol("ETHUSDT", "ETH", "USDT"), side: Side::Bid },
-        };
+        let path1 = mock_path();
 
         // Path 2: BTC → SOL → USDT (intentionally better ROI)
-        let path2 = PricingPath {
-            leg1: PathLeg { symbol: make_symbol("BTCUSDT", "BTC", "USDT"), side: Side::Ask },
-            leg2: PathLeg { symbol: make_symbol("SOLBTC", "SOL", "BTC"), side: Side::Ask },
-            leg3: PathLeg { symbol: make_symbol("SOLUSDT", "SOL", "USDT"), side: Side::Bid },
-        };
+        let path2 = MockBuilder::new()
+            .triangle("USDT", "BTC", "SOL")
+            .path([("BTCUSDT", Side::Ask), ("SOLBTC", Side::Ask), ("SOLUSDT", Side::Bid)]);
 
         let scanner = RayonBestMatchScanner::new(vec![path1.clone(), path2.clone()]);
 
@@ -284,10 +426,194 @@ mod tests {
         let result = scanner.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
         assert!(result.is_some());
 
-        let (best_path, return_val) = result.unwrap();
+        let best = result.unwrap();
 
         // Assert that the selected path is path2 (the SOL one)
-        assert_eq!(best_path.leg2.symbol.symbol, "SOLBTC");
-        assert!(return_val > 1.0);
+        assert_eq!(best.path.leg2.symbol.symbol, "SOLBTC");
+        assert_return_eq(best.result, 1.0 / 50010.0 / 0.0051 * 260.0, 1e-12);
+    }
+
+    /// The ETH and SOL triangles through BTCUSDT, in that order, with SOL's return
+    /// `1.2 * (1 + 1e-10)` just above ETH's `1.2`.
+    fn near_tied_paths() -> (Vec<PricingPath>, Vec<TopOfBookUpdate>) {
+        let builder = MockBuilder::new().triangle("USDT", "BTC", "ETH").triangle("USDT", "BTC", "SOL");
+        let paths = ["ETH", "SOL"]
+            .iter()
+            .map(|alt| builder.path([
+                ("BTCUSDT", Side::Ask),
+                (&format!("{alt}BTC"), Side::Ask),
+                (&format!("{alt}USDT"), Side::Bid),
+            ]))
+            .collect();
+        let quotes = vec![
+            mock_update("ETHBTC", 0.001, 0.001),
+            mock_update("ETHUSDT", 60.0, 61.0),
+            mock_update("SOLBTC", 0.001, 0.001),
+            mock_update("SOLUSDT", 60.0 * (1.0 + 1e-10), 61.0),
+            mock_update("BTCUSDT", 50000.0, 50000.0),
+        ];
+        (paths, quotes)
+    }
+
+    fn best_of(paths: Vec<PricingPath>, quotes: &[TopOfBookUpdate], settings: ScannerSettings) -> ArbOpportunity {
+        let scanner = RayonBestMatchScanner::with_settings(paths, settings);
+        quotes.iter().filter_map(|quote| scanner.process_update(quote)).last().expect("both paths are profitable")
+    }
+
+    #[test]
+    fn best_scanner_breaks_near_ties_towards_the_first_listed_path() {
+        let (paths, quotes) = near_tied_paths();
+        let tolerant = || ScannerSettings { best_match_epsilon: 1e-9, ..ScannerSettings::default() };
+
+        // Within epsilon the returns tie, and the path listed first wins whichever is higher
+        for _ in 0..20 {
+            assert_eq!(best_of(paths.clone(), &quotes, tolerant()).path.leg2.symbol.symbol, "ETHBTC");
+        }
+        let reversed: Vec<_> = paths.iter().rev().cloned().collect();
+        assert_eq!(best_of(reversed, &quotes, tolerant()).path.leg2.symbol.symbol, "SOLBTC");
+
+        // With no tolerance the strictly higher return wins
+        let exact = ScannerSettings { best_match_epsilon: 0.0, ..ScannerSettings::default() };
+        assert_eq!(best_of(paths, &quotes, exact).path.leg2.symbol.symbol, "SOLBTC");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn best_scanner_excludes_paths_with_a_nan_return() {
+        let (paths, quotes) = near_tied_paths();
+        // A NaN fee on a SOL leg makes that path's return NaN, which must not win or tie
+        let settings = ScannerBuilder::new(Vec::new()).with_symbol_fee_bps("SOLUSDT", f64::NAN).settings().clone();
+        let scanner = RayonBestMatchScanner::with_settings(paths.clone(), settings.clone());
+        for quote in &quotes {
+            scanner.process_update(quote);
+        }
+        assert!(scanner.evaluate_path(&paths[1]).is_some_and(f64::is_nan));
+
+        let best = best_of(paths, &quotes, ScannerSettings { best_match_epsilon: f64::INFINITY, ..settings });
+        assert_eq!(best.path.leg2.symbol.symbol, "ETHBTC");
+        assert!(best.result.is_finite());
+    }
+
+    #[test]
+    fn ordered_scanner_returns_the_lowest_indexed_match_every_time() {
+        // Many triangles through BTCUSDT, all profitable; the last one is the most profitable
+        let mut builder = MockBuilder::new();
+        let alts = ["AAA", "BBB", "CCC", "DDD", "EEE", "FFF", "GGG", "HHH"];
+        for alt in alts {
+            builder = builder.triangle("USDT", "BTC", alt);
+        }
+        let paths: Vec<PricingPath> = alts
+            .iter()
+            .map(|alt| builder.path([
+                ("BTCUSDT", Side::Ask),
+                (&format!("{alt}BTC"), Side::Ask),
+                (&format!("{alt}USDT"), Side::Bid),
+            ]))
+            .collect();
+        let quotes: Vec<TopOfBookUpdate> = alts
+            .iter()
+            .enumerate()
+            .flat_map(|(i, alt)| [
+                mock_update(&format!("{alt}BTC"), 0.001, 0.001),
+                mock_update(&format!("{alt}USDT"), 60.0 + i as f64, 61.0 + i as f64),
+            ])
+            .collect();
+        let trigger = mock_update("BTCUSDT", 50000.0, 50000.0);
+
+        for _ in 0..50 {
+            let scanner = RayonOrderedMatchScanner::new(paths.clone());
+            for quote in &quotes {
+                scanner.process_update(quote);
+            }
+            let first = scanner.process_update(&trigger).expect("every path is profitable");
+            assert_eq!(first.path.leg2.symbol.symbol, "AAABTC");
+            assert_return_eq(first.result, 1.0 / 50000.0 / 0.001 * 60.0, 1e-12);
+            // Same scanner, same quotes, same answer
+            let again = scanner.process_update(&trigger).unwrap();
+            assert_eq!(again.path.leg2.symbol.symbol, "AAABTC");
+        }
+    }
+
+    /// A store whose next `get` clears it, as a reconnect's `invalidate_all` landing mid-scan would.
+    #[derive(Debug, Default)]
+    struct ClearedMidScan {
+        inner: DashMapPriceStore,
+        armed: AtomicBool,
+    }
+
+    impl PriceStore for ClearedMidScan {
+        fn get(&self, symbol: &str) -> Option<Arc<StoredQuote>> {
+            let quote = PriceStore::get(&self.inner, symbol);
+            if self.armed.swap(false, Ordering::SeqCst) {
+                PriceStore::clear(&self.inner);
+            }
+            quote
+        }
+
+        fn insert(&self, quote: StoredQuote) {
+            PriceStore::insert(&self.inner, quote);
+        }
+
+        fn contains(&self, symbol: &str) -> bool {
+            PriceStore::contains(&self.inner, symbol)
+        }
+
+        fn remove(&self, symbol: &str) {
+            PriceStore::remove(&self.inner, symbol);
+        }
+
+        fn clear(&self) {
+            PriceStore::clear(&self.inner);
+        }
+
+        fn retain(&self, keep: &mut dyn FnMut(&str, &StoredQuote) -> bool) -> usize {
+            PriceStore::retain(&self.inner, keep)
+        }
+
+        fn len(&self) -> usize {
+            PriceStore::len(&self.inner)
+        }
+
+        fn snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+            self.inner.snapshot()
+        }
+
+        fn approx_memory_bytes(&self) -> usize {
+            self.inner.approx_memory_bytes()
+        }
+    }
+
+    #[test]
+    fn invalidate_all_between_lookups_skips_the_path_instead_of_panicking() {
+        let quotes = [
+            mock_update("BTCUSDT", 50000.0, 50010.0),
+            mock_update("ETHBTC", 0.06, 0.061),
+            mock_update("ETHUSDT", 3100.0, 3101.0),
+        ];
+        for on_update_return in [OnUpdateReturn::First, OnUpdateReturn::Best, OnUpdateReturn::Ordered] {
+            let store = Arc::new(ClearedMidScan::default());
+            let scanner = ScannerBuilder::new(vec![mock_path()])
+                .with_price_store(Arc::clone(&store) as Arc<dyn PriceStore>)
+                .with_on_update_return(on_update_return.clone())
+                .build(ArbMode::RayonScan);
+            for quote in &quotes {
+                scanner.process_update(quote);
+            }
+            assert_eq!(store.len(), 3, "every leg is quoted before the reconnect");
+
+            // The store empties after the first leg is read: the path is skipped, not unwrapped
+            store.armed.store(true, Ordering::SeqCst);
+            assert!(scanner.process_update(&quotes[2]).is_none());
+            assert!(store.is_empty());
+
+            // The scanner re-warms and reports again once every leg is re-quoted
+            assert!(scanner.process_update(&quotes[0]).is_none());
+            assert!(scanner.process_update(&quotes[1]).is_none());
+            let opportunity = scanner
+                .process_update(&quotes[2])
+                .unwrap_or_else(|| panic!("{on_update_return:?} should report once re-warmed"));
+            assert_eq!(opportunity.leg_prices, [50010.0, 0.061, 3100.0]);
+            assert_eq!(store.len(), 3);
+        }
+    }
+
+}
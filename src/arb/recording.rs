@@ -0,0 +1,195 @@
+// src/arb/recording.rs
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{LineWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use tracing::warn;
+
+use crate::parse::TopOfBookUpdate;
+use crate::price_path::PricingPath;
+
+use super::{ArbEvaluator, ArbOpportunity, HaltHandle, ImpliedQuote};
+
+
+/// One path evaluated on an update, as seen by `RecordingEvaluator`.
+#[derive(Debug, Clone)]
+pub struct Decision {
+    /// Symbol of the update that triggered the evaluation.
+    pub symbol: String,
+    pub path: Arc<PricingPath>,
+    /// Net return after fees at the stored quotes, `None` while a leg has no quote.
+    pub path_return: Option<f64>,
+    /// Whether this path is the opportunity the inner evaluator reported.
+    pub reported: bool,
+}
+
+
+/// Debugging decorator that records every path evaluated on each update, not just the
+/// profitable ones, to answer "why wasn't this opportunity caught?".
+///
+/// After the inner evaluator processes an update, every path trading the updated symbol is
+/// re-priced through `evaluate_path` and recorded, newest last, in a ring buffer of `capacity`
+/// decisions and optionally appended to a file. Only built with the `decision_trace` feature,
+/// so production builds carry none of this.
+pub struct RecordingEvaluator<E> {
+    inner: E,
+    symbol_to_paths: HashMap<String, Vec<Arc<PricingPath>>>,
+    capacity: usize,
+    decisions: Mutex<VecDeque<Decision>>,
+    file: Option<Mutex<LineWriter<File>>>,
+}
+
+impl<E: ArbEvaluator> RecordingEvaluator<E> {
+    /// Wraps `inner`, which must evaluate `price_paths`, keeping the last `capacity` decisions.
+    pub fn new(inner: E, price_paths: &[PricingPath], capacity: usize) -> Self {
+        let mut symbol_to_paths: HashMap<String, Vec<Arc<PricingPath>>> = HashMap::new();
+        for path in price_paths.iter().cloned().map(Arc::new) {
+            for symbol in path.symbols() {
+                symbol_to_paths.entry(symbol).or_default().push(Arc::clone(&path));
+            }
+        }
+        Self {
+            inner,
+            symbol_to_paths,
+            capacity,
+            decisions: Mutex::new(VecDeque::with_capacity(capacity)),
+            file: None,
+        }
+    }
+
+    /// Also appends one line per decision to `path`, creating it if needed.
+    pub fn with_file<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        self.file = Some(Mutex::new(LineWriter::new(file)));
+        Ok(self)
+    }
+
+    /// The buffered decisions, oldest first.
+    pub fn decisions(&self) -> Vec<Decision> {
+        self.decisions.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Takes the buffered decisions, oldest first, leaving the buffer empty.
+    pub fn drain(&self) -> Vec<Decision> {
+        self.decisions.lock().unwrap().drain(..).collect()
+    }
+
+    fn record(&self, update: &TopOfBookUpdate, reported: Option<&ArbOpportunity>) {
+        let Some(paths) = self.symbol_to_paths.get(&update.symbol) else {
+            return;
+        };
+        let mut decisions = self.decisions.lock().unwrap();
+        for path in paths {
+            let decision = Decision {
+                symbol: update.symbol.clone(),
+                path: Arc::clone(path),
+                path_return: self.inner.evaluate_path(path),
+                reported: reported.is_some_and(|o| o.path.key() == path.key()),
+            };
+            if let Some(file) = &self.file
+                && let Err(e) = writeln!(
+                    file.lock().unwrap(),
+                    "{} | {:#} | return {} | reported {}",
+                    decision.symbol,
+                    decision.path,
+                    decision.path_return.map_or("-".to_string(), |r| format!("{r:.6}")),
+                    decision.reported
+                )
+            {
+                warn!(error = %e, "Failed to write decision to file");
+            }
+            if self.capacity == 0 {
+                continue;
+            }
+            if decisions.len() == self.capacity {
+                decisions.pop_front();
+            }
+            decisions.push_back(decision);
+        }
+    }
+}
+
+impl<E: ArbEvaluator> ArbEvaluator for RecordingEvaluator<E> {
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        let opportunity = self.inner.process_update(update);
+        self.record(update, opportunity.as_ref());
+        opportunity
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.inner.price_snapshot()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        self.inner.halt_handle()
+    }
+
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        self.inner.evaluate_path(path)
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        self.inner.implied_quote(path)
+    }
+
+    fn invalidate_all(&self) {
+        self.inner.invalidate_all();
+    }
+
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        self.inner.invalidate_symbols(symbols);
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        self.inner.top_paths(n)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::HashMapEdgeScanner;
+    use crate::testutil::{assert_return_eq, mock_path, mock_update};
+
+    #[test]
+    fn every_evaluated_path_and_return_is_recorded() {
+        // mock_path returns 3000 / 50010 / 0.0701 ≈ 0.856; its reversal 50000 * 0.07 / 3001 ≈ 1.166
+        let paths = vec![mock_path(), mock_path().reversed()];
+        let recorder = RecordingEvaluator::new(HashMapEdgeScanner::new(paths.clone()), &paths, 16);
+
+        recorder.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+        recorder.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+        let reported = recorder.process_update(&mock_update("ETHUSDT", 3000.0, 3001.0));
+        assert!(reported.is_some());
+
+        let decisions = recorder.drain();
+        assert_eq!(decisions.len(), 6, "two paths on each of three updates");
+        assert!(decisions[..4].iter().all(|d| d.path_return.is_none() && !d.reported));
+
+        let last: Vec<_> = decisions[4..].iter().map(|d| (d.path.key(), d.path_return.unwrap(), d.reported)).collect();
+        assert_eq!(last[0].0, paths[0].key());
+        assert_return_eq(last[0].1, 3000.0 / 50010.0 / 0.0701, 1e-12);
+        assert!(!last[0].2, "an unprofitable path is recorded but not reported");
+        assert_eq!(last[1].0, paths[1].key());
+        assert_return_eq(last[1].1, 50000.0 * 0.07 / 3001.0, 1e-12);
+        assert!(last[1].2, "the reported path is flagged");
+        assert!(decisions.iter().all(|d| ["BTCUSDT", "ETHBTC", "ETHUSDT"].contains(&d.symbol.as_str())));
+        assert!(recorder.decisions().is_empty());
+    }
+
+    #[test]
+    fn ring_buffer_keeps_the_newest_decisions() {
+        let paths = vec![mock_path()];
+        let recorder = RecordingEvaluator::new(HashMapEdgeScanner::new(paths.clone()), &paths, 2);
+        for symbol in ["BTCUSDT", "ETHBTC", "ETHUSDT"] {
+            recorder.process_update(&mock_update(symbol, 1.0, 1.0));
+        }
+        let symbols: Vec<_> = recorder.decisions().into_iter().map(|d| d.symbol).collect();
+        assert_eq!(symbols, ["ETHBTC", "ETHUSDT"]);
+    }
+}
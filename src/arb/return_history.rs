@@ -0,0 +1,56 @@
+// src/arb/return_history.rs
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+
+use crate::price_path::PricingPath;
+
+use super::path_index::SymbolPaths;
+
+
+/// Exponentially weighted moving average of each path's net return, updated every time the
+/// scanner prices the path, whether or not it clears `min_return`.
+///
+/// A triangle that crossed the line once says little; one that steadily prices close to it is
+/// structurally rich and worth watching. Paths are keyed by the identity of the scanner's own
+/// `Arc<PricingPath>`, so recording never hashes symbol names. Disabled, it records nothing.
+pub(crate) struct ReturnHistory {
+    alpha: Option<f64>,
+    averages: DashMap<usize, (Arc<PricingPath>, f64)>,
+}
+
+impl ReturnHistory {
+    /// `alpha` is the weight of each new return, in `(0, 1]`; `None` disables the history.
+    pub(crate) fn new(alpha: Option<f64>) -> Self {
+        Self { alpha, averages: DashMap::new() }
+    }
+
+    /// Folds `path_return` into `path`'s average; the first return seeds it. Non-finite returns
+    /// are ignored so one bad quote cannot poison the average.
+    pub(crate) fn record(&self, path: &Arc<PricingPath>, path_return: f64) {
+        let Some(alpha) = self.alpha else { return };
+        if !path_return.is_finite() {
+            return;
+        }
+        self.averages
+            .entry(Arc::as_ptr(path) as usize)
+            .and_modify(|(_, average)| *average += alpha * (path_return - *average))
+            .or_insert_with(|| (Arc::clone(path), path_return));
+    }
+
+    /// The `n` paths with the highest average return, best first.
+    pub(crate) fn top(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        let mut ranked: Vec<(Arc<PricingPath>, f64)> = self.averages.iter().map(|entry| entry.value().clone()).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(n);
+        ranked
+    }
+
+    /// Forgets the averages of paths not in `index`, after the scanner's universe changed.
+    pub(crate) fn retain_indexed(&self, index: &SymbolPaths) {
+        let indexed: HashSet<usize> = index.values().flat_map(|paths| paths.iter()).map(|path| Arc::as_ptr(path) as usize).collect();
+        self.averages.retain(|key, _| indexed.contains(key));
+    }
+}
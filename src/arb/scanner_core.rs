@@ -0,0 +1,116 @@
+// src/arb/scanner_core.rs
+
+use std::sync::Arc;
+
+use crate::price_path::PricingPath;
+
+use super::footprint::path_index_bytes;
+use super::path_index::{PathIndex, SymbolPaths};
+use super::price_store::PriceStore;
+use super::return_history::ReturnHistory;
+use super::ScannerSettings;
+
+
+/// The state every symbol-indexed scanner (edge, Rayon, SIMD, adaptive) keeps besides its
+/// evaluation loop: the stored quotes, the swappable symbol → paths index and each path's
+/// moving-average return. Reached through the scanner's `core()`.
+pub struct ScannerCore {
+    pub(crate) price_store: Arc<dyn PriceStore>,
+    pub(crate) path_index: PathIndex,
+    pub(crate) return_history: ReturnHistory,
+}
+
+impl ScannerCore {
+    pub(crate) fn new(price_paths: Vec<PricingPath>, settings: &ScannerSettings) -> Self {
+        Self {
+            price_store: settings.scanner_price_store(),
+            path_index: PathIndex::new(price_paths),
+            return_history: ReturnHistory::new(settings.return_ewma_alpha),
+        }
+    }
+
+    /// Approximate heap used by the path index and price store (see `footprint`).
+    pub fn approx_memory_bytes(&self) -> usize {
+        path_index_bytes(&self.path_index.load()) + self.price_store.approx_memory_bytes()
+    }
+
+    /// Paths trading `symbol`, in construction order; empty for a symbol no path uses. The list
+    /// is the index's own, shared rather than copied, and unaffected by a later `rebuild`.
+    pub fn paths_for_symbol(&self, symbol: &str) -> Arc<[Arc<PricingPath>]> {
+        self.path_index.load().get(symbol).map_or_else(|| Arc::from([]), Arc::clone)
+    }
+
+    /// Swaps in `new_paths` as the scanner's universe, e.g. after a listing or blocklist change.
+    ///
+    /// Stored quotes are kept, so a new path over already-quoted symbols is priced on the next
+    /// update to any of them without re-warming. Return averages of dropped paths are forgotten.
+    pub fn rebuild(&self, new_paths: Vec<PricingPath>) {
+        let index = self.path_index.replace(new_paths);
+        self.return_history.retain_indexed(&index);
+    }
+
+    /// The current index, for one update to price against (see `PathIndex::load`).
+    pub(crate) fn index(&self) -> Arc<SymbolPaths> {
+        self.path_index.load()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::{ArbEvaluator, HashMapEdgeScanner};
+    use crate::testutil::{assert_return_eq, mock_path, mock_update, MockBuilder};
+
+    #[test]
+    fn index_holds_exactly_the_symbols_the_paths_trade() {
+        let core = ScannerCore::new(vec![mock_path()], &ScannerSettings::default());
+        let index = core.index();
+        for symbol in ["BTCUSDT", "ETHBTC", "ETHUSDT"] {
+            assert!(index.contains_key(symbol), "{symbol} missing");
+        }
+        assert!(!index.contains_key("FOOBAR"));
+    }
+
+    #[test]
+    fn paths_for_symbol_lists_exactly_the_paths_trading_it() {
+        let paths = MockBuilder::new()
+            .triangle("USDT", "BTC", "ETH")
+            .triangle("USDT", "BTC", "SOL")
+            .paths("USDT", &["BTC", "ETH", "SOL"]);
+        let core = ScannerCore::new(paths.clone(), &ScannerSettings::default());
+
+        let expected: Vec<_> = paths.iter().filter(|p| p.symbols().contains(&"SOLBTC".to_string())).map(|p| p.key()).collect();
+        let found: Vec<_> = core.paths_for_symbol("SOLBTC").iter().map(|p| p.key()).collect();
+        assert_eq!(found.len(), 2, "both directions of the SOL triangle");
+        assert_eq!(found, expected);
+        assert_eq!(core.paths_for_symbol("BTCUSDT").len(), paths.len(), "every path trades BTCUSDT");
+        assert!(core.paths_for_symbol("FOOBAR").is_empty());
+
+        // Shared with the index, not copied out of it
+        assert!(Arc::ptr_eq(&core.paths_for_symbol("SOLBTC"), &core.index()["SOLBTC"]));
+    }
+
+    #[test]
+    fn rebuild_evaluates_new_paths_at_retained_prices() {
+        let builder = MockBuilder::new().triangle("USDT", "BTC", "ETH").triangle("USDT", "BTC", "SOL");
+        let scanner = HashMapEdgeScanner::new(builder.paths("USDT", &["BTC", "ETH"]));
+
+        scanner.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+        scanner.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+        assert!(scanner.process_update(&mock_update("SOLUSDT", 150.0, 150.1)).is_none(), "no path trades SOL yet");
+        let before = scanner.core().paths_for_symbol("ETHBTC");
+
+        scanner.core().rebuild(builder.paths("USDT", &["BTC", "ETH", "SOL"]));
+        assert_eq!(scanner.core().paths_for_symbol("SOLBTC").len(), 2);
+        assert!(Arc::ptr_eq(&scanner.core().paths_for_symbol("ETHBTC")[0], &before[0]), "kept paths keep their identity");
+        assert_eq!(before.len(), 2, "a list handed out before the rebuild is unchanged");
+
+        // BTCUSDT and SOLUSDT were quoted before the rebuild; one SOLBTC quote completes the path
+        let opportunity = scanner
+            .process_update(&mock_update("SOLBTC", 0.0025, 0.0025))
+            .expect("USDT → BTC → SOL → USDT returns 150 / (50010 * 0.0025)");
+        assert!(opportunity.path.symbols().contains(&"SOLBTC".to_string()));
+        assert_return_eq(opportunity.result, 150.0 / (50010.0 * 0.0025), 1e-12);
+    }
+}
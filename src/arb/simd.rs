@@ -0,0 +1,317 @@
+// src/arb/simd.rs
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use wide::{f64x4, CmpEq};
+
+use crate::parse::TopOfBookUpdate;
+use crate::price_path::{apply_leg, PricingPath, Side};
+
+use super::cooldown::PathCooldown;
+use super::eviction::PriceEviction;
+use super::scanner_core::ScannerCore;
+use super::quote_filter::{store_quote, StoredQuote};
+use super::rayon_scan::best_index;
+use super::{ArbEvaluator, ArbOpportunity, HaltHandle, ImpliedQuote, ScannerSettings};
+
+
+/// Paths evaluated per vectorized pass.
+const LANES: usize = 4;
+
+
+/// Edge scanner that evaluates a symbol's paths four at a time in SIMD lanes.
+///
+/// Each batch lays the paths' leg prices and sides out as structure-of-arrays `f64x4` lanes
+/// and walks all three legs at once, blending the Ask (divide) and Bid (multiply) results per
+/// lane. Every lane performs the same IEEE operations in the same order as `apply_leg`, so end
+/// multipliers, and therefore profitability decisions, are bit-identical to the scalar
+/// scanners. Reports the most profitable path, breaking ties within
+/// `ScannerSettings::best_match_epsilon` towards the lowest index like `RayonBestMatchScanner`.
+pub struct SimdBatchScanner {
+    core: ScannerCore,
+    settings: ScannerSettings,
+    eviction: PriceEviction,
+    cooldown: PathCooldown,
+}
+
+impl SimdBatchScanner {
+    pub fn new(price_paths: Vec<PricingPath>) -> Self {
+        Self::with_settings(price_paths, ScannerSettings::default())
+    }
+
+    /// Constructs the scanner with custom evaluation settings (see `ScannerBuilder`).
+    pub fn with_settings(price_paths: Vec<PricingPath>, settings: ScannerSettings) -> Self {
+        Self {
+            core: ScannerCore::new(price_paths, &settings),
+            eviction: PriceEviction::new(settings.price_ttl),
+            cooldown: PathCooldown::new(settings.opportunity_cooldown),
+            settings,
+        }
+    }
+
+    /// The quotes, path index and return averages (see `ScannerCore`).
+    pub fn core(&self) -> &ScannerCore {
+        &self.core
+    }
+
+    /// Calls `visit(index, end, leg_prices)` for every path in `paths` whose three quotes are
+    /// present and fresh, with `end` the net amount returned from `start_notional`.
+    fn for_each_end(&self, paths: &[Arc<PricingPath>], mut visit: impl FnMut(usize, f64, [f64; 3])) {
+        let ask = f64x4::splat(1.0);
+
+        for (batch, chunk) in paths.chunks(LANES).enumerate() {
+            // Unused and unpriceable lanes run on dummy prices and are skipped afterwards
+            let mut prices = [[1.0; LANES]; 3];
+            let mut asks = [[0.0; LANES]; 3];
+            let mut keeps = [[1.0; LANES]; 3];
+            let mut priced = [false; LANES];
+
+            for (lane, path) in chunk.iter().enumerate() {
+                let Some(quotes) = self.fresh_quotes(path) else { continue; };
+                let path_keeps = self.settings.leg_multipliers(path);
+                for (leg, (quote, side)) in quotes.iter().zip(leg_sides(path)).enumerate() {
+                    prices[leg][lane] = side.price(quote.bid_price, quote.ask_price);
+                    asks[leg][lane] = if side == Side::Ask { 1.0 } else { 0.0 };
+                    keeps[leg][lane] = path_keeps[leg];
+                }
+                priced[lane] = true;
+            }
+
+            let mut amount = f64x4::splat(self.settings.start_notional);
+            for leg in 0..3 {
+                let price = f64x4::from(prices[leg]);
+                let is_ask = f64x4::from(asks[leg]).cmp_eq(ask);
+                amount = f64x4::from(keeps[leg]) * is_ask.blend(amount / price, amount * price);
+            }
+
+            let ends = amount.to_array();
+            for lane in (0..chunk.len()).filter(|&lane| priced[lane]) {
+                visit(batch * LANES + lane, ends[lane], [prices[0][lane], prices[1][lane], prices[2][lane]]);
+            }
+        }
+    }
+
+    /// The three legs' stored quotes, if all are present and fresh.
+    fn fresh_quotes(&self, path: &PricingPath) -> Option<[Arc<StoredQuote>; 3]> {
+        let quotes = [&path.leg1, &path.leg2, &path.leg3].map(|leg| self.core.price_store.get(&leg.symbol.symbol));
+        let [Some(q1), Some(q2), Some(q3)] = quotes else { return None; };
+        let quotes = [q1, q2, q3];
+        quotes.iter().all(|q| self.settings.is_fresh(q.received, q.event_time_ms)).then_some(quotes)
+    }
+
+    /// Exchange event times of the three legs' stored quotes, in leg order.
+    fn event_times(&self, path: &PricingPath) -> [Option<u64>; 3] {
+        [&path.leg1, &path.leg2, &path.leg3]
+            .map(|leg| self.core.price_store.get(&leg.symbol.symbol).and_then(|q| q.event_time_ms))
+    }
+
+    /// Re-walks the winning path on the scalar path to report its per-leg factors.
+    fn opportunity(&self, path: &Arc<PricingPath>) -> Option<ArbOpportunity> {
+        let [p1, p2, p3] = self.fresh_quotes(path)?;
+        let start = self.settings.start_notional;
+        let [keep1, keep2, keep3] = self.settings.leg_multipliers(path);
+
+        let step1 = keep1 * apply_leg(start, path.leg1.side, &p1);
+        let step2 = keep2 * apply_leg(step1, path.leg2.side, &p2);
+        let end = keep3 * apply_leg(step2, path.leg3.side, &p3);
+
+        Some(ArbOpportunity::new(
+            Arc::clone(path),
+            Arc::clone(&self.settings.home_asset),
+            start,
+            end,
+            [step1 / start, step2 / step1, end / step2],
+            self.settings.confidence([&p1, &p2, &p3]),
+            [&p1, &p2, &p3],
+        ))
+    }
+}
+
+fn leg_sides(path: &PricingPath) -> [Side; 3] {
+    [path.leg1.side, path.leg2.side, path.leg3.side]
+}
+
+impl ArbEvaluator for SimdBatchScanner {
+    /// Scores every path using the updated symbol in vectorized batches and returns the most
+    /// profitable one.
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        let now = self.settings.clock.now();
+        if !store_quote(&*self.core.price_store, &self.settings.quote_filter, update, now) {
+            return None;
+        }
+        let path_index = self.core.index();
+        self.eviction.maybe_sweep(&*self.core.price_store, now, |s| path_index.contains_key(s));
+        if self.settings.halt.is_halted() {
+            return None;
+        }
+        let paths = path_index.get(&update.symbol)?;
+
+        let mut candidates = Vec::new();
+        self.for_each_end(paths, |index, end, leg_prices| {
+            let result = end / self.settings.start_notional;
+            self.core.return_history.record(&paths[index], result);
+            if self.settings.is_profitable(&paths[index], leg_prices, end)
+                && self.settings.legs_in_sync(self.event_times(&paths[index]))
+                && !self.cooldown.is_cooling(&paths[index], now)
+            {
+                candidates.push((index, result));
+            }
+        });
+        let index = best_index(candidates.into_iter(), self.settings.best_match_epsilon)?;
+        let opportunity = self.opportunity(&paths[index])?.detected(update, now);
+        self.cooldown.record(&paths[index], now);
+        Some(opportunity)
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.core.price_store.snapshot()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        self.settings.halt.clone()
+    }
+
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        self.settings.stored_path_return(&*self.core.price_store, path)
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        self.settings.stored_implied_quote(&*self.core.price_store, path)
+    }
+
+    fn invalidate_all(&self) {
+        self.core.price_store.clear();
+    }
+
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        self.core.price_store.remove_symbols(symbols);
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        self.core.return_history.top(n)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha12Rng;
+
+    use super::*;
+    use crate::arb::{FeeSchedule, HashMapEdgeScanner};
+    use crate::testutil::{make_path, mock_update};
+
+    /// `count` triangles sharing the hot BTCUSDT leg, with mixed second-leg sides.
+    fn hot_symbol_paths(count: usize) -> Vec<PricingPath> {
+        (0..count)
+            .map(|i| {
+                let (alt_btc, alt_usdt) = (format!("X{i}BTC"), format!("X{i}USDT"));
+                let alt = format!("X{i}");
+                let side2 = if i % 3 == 0 { Side::Bid } else { Side::Ask };
+                make_path([
+                    ("BTCUSDT", "BTC", "USDT", Side::Ask),
+                    (&alt_btc, &alt, "BTC", side2),
+                    (&alt_usdt, &alt, "USDT", Side::Bid),
+                ])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_ends_are_bit_identical_to_scalar() {
+        // 10 paths: two full batches and a partial one
+        let paths = hot_symbol_paths(10);
+        // Per-symbol overrides give lanes of the same batch different fee multipliers
+        let fees = FeeSchedule::flat(0.001).with_override("X2BTC", 0.0).with_override("X5USDT", 0.00075);
+        let settings = ScannerSettings { fees, start_notional: 250.0, ..ScannerSettings::default() };
+        let scanner = SimdBatchScanner::with_settings(paths.clone(), settings.clone());
+        let mut rng = ChaCha12Rng::seed_from_u64(3);
+
+        for (i, path) in paths.iter().enumerate() {
+            // Leave one path unpriced to exercise skipped lanes
+            if i == 6 {
+                continue;
+            }
+            for leg in [&path.leg2, &path.leg3] {
+                let bid = rng.gen_range(0.5..2.0);
+                scanner.process_update(&mock_update(&leg.symbol.symbol, bid, bid * 1.0003));
+            }
+        }
+        scanner.process_update(&mock_update("BTCUSDT", 0.999, 1.0001));
+
+        let path_index = scanner.core.index();
+        let indexed = &path_index["BTCUSDT"];
+        let mut visited = Vec::new();
+        scanner.for_each_end(indexed, |index, end, _| {
+            let path = &indexed[index];
+            let quotes = scanner.fresh_quotes(path).unwrap();
+            let scalar = leg_sides(path)
+                .into_iter()
+                .zip(&quotes)
+                .zip(settings.leg_multipliers(path))
+                .fold(settings.start_notional, |amount, ((side, quote), keep)| keep * apply_leg(amount, side, quote));
+            assert_eq!(end.to_bits(), scalar.to_bits(), "lane for path {index} diverged");
+            visited.push(index);
+        });
+        assert_eq!(visited.len(), 9);
+    }
+
+    #[test]
+    fn test_profitability_decisions_match_edge_scanner() {
+        let paths = hot_symbol_paths(37);
+        let simd = SimdBatchScanner::new(paths.clone());
+        let edge = HashMapEdgeScanner::new(paths.clone());
+        let mut rng = ChaCha12Rng::seed_from_u64(11);
+
+        let symbols: Vec<String> = paths.iter().flat_map(PricingPath::symbols).collect();
+        let mut profitable_updates = 0;
+        for _ in 0..2_000 {
+            let symbol = &symbols[rng.gen_range(0..symbols.len())];
+            let bid = rng.gen_range(0.9..1.1);
+            let update = mock_update(symbol, bid, bid * 1.0001);
+
+            let from_simd = simd.process_update(&update);
+            let from_edge = edge.process_update(&update);
+            assert_eq!(from_simd.is_some(), from_edge.is_some(), "decisions diverged on {symbol}");
+
+            // Edge reports the first profitable path; SIMD the best, which can't be worse
+            if let (Some(best), Some(first)) = (from_simd, from_edge) {
+                assert!(best.result >= first.result);
+                profitable_updates += 1;
+            }
+        }
+        assert!(profitable_updates > 0, "stream should contain some opportunities");
+    }
+
+    #[test]
+    fn test_near_ties_go_to_the_lowest_indexed_path() {
+        // ETH and SOL triangles through BTCUSDT; SOL's return is 1e-10 above ETH's
+        let paths: Vec<PricingPath> = ["ETH", "SOL"]
+            .iter()
+            .map(|alt| make_path([
+                ("BTCUSDT", "BTC", "USDT", Side::Ask),
+                (&format!("{alt}BTC"), alt, "BTC", Side::Ask),
+                (&format!("{alt}USDT"), alt, "USDT", Side::Bid),
+            ]))
+            .collect();
+        let quotes = [
+            mock_update("ETHBTC", 0.001, 0.001),
+            mock_update("ETHUSDT", 60.0, 61.0),
+            mock_update("SOLBTC", 0.001, 0.001),
+            mock_update("SOLUSDT", 60.0 * (1.0 + 1e-10), 61.0),
+            mock_update("BTCUSDT", 50000.0, 50000.0),
+        ];
+        let best_of = |paths: Vec<PricingPath>, best_match_epsilon| {
+            let scanner = SimdBatchScanner::with_settings(paths, ScannerSettings { best_match_epsilon, ..ScannerSettings::default() });
+            let best = quotes.iter().filter_map(|quote| scanner.process_update(quote)).last();
+            best.expect("both paths are profitable").path.leg2.symbol.symbol.clone()
+        };
+
+        assert_eq!(best_of(paths.clone(), 1e-9), "ETHBTC");
+        let reversed: Vec<_> = paths.iter().rev().cloned().collect();
+        assert_eq!(best_of(reversed, 1e-9), "SOLBTC");
+        assert_eq!(best_of(paths, 0.0), "SOLBTC");
+    }
+}
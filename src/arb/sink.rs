@@ -0,0 +1,431 @@
+// src/arb/sink.rs
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{LineWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::header::CONTENT_TYPE;
+use hyper::{Method, Request, Uri};
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{self, Sender};
+use tracing::{info, warn};
+
+use crate::price_path::Side;
+
+use super::config::{OutputConfig, SinkKind};
+use super::tier::{Tier, TierBands};
+use super::ArbOpportunity;
+
+
+/// Where `arb_loop` delivers detected opportunities.
+///
+/// `emit` is called inline on the arb loop, so implementations should return quickly.
+pub trait OpportunitySink: Send + Sync {
+    fn emit(&self, opportunity: &ArbOpportunity);
+}
+
+/// Builds the sink selected by `[output]` in `config/arb.toml`.
+///
+/// `webhook_url` adds a `WebhookSink` next to the selected sink, fed only opportunities at or
+/// above `webhook_min_tier`. With `dedup_window_ms` set,
+/// both are rate limited per path; `full_stream_path` adds an unrated `FileSink` alongside
+/// them that still receives every opportunity.
+pub fn create_opportunity_sink(config: &OutputConfig) -> Result<Arc<dyn OpportunitySink>> {
+    let mut sink: Arc<dyn OpportunitySink> = match config.sink {
+        SinkKind::Stdout => Arc::new(StdoutSink),
+        SinkKind::File => Arc::new(FileSink::create(&config.file_path)?),
+    };
+    if let Some(url) = &config.webhook_url {
+        let mut webhook: Arc<dyn OpportunitySink> =
+            Arc::new(WebhookSink::spawn(url, config.webhook_queue_capacity.max(1))?);
+        if config.webhook_min_tier > Tier::Small {
+            webhook = Arc::new(TierFilterSink::new(webhook, config.tiers, config.webhook_min_tier));
+        }
+        sink = Arc::new(TeeSink::new(vec![sink, webhook]));
+    }
+    if let Some(window) = config.dedup_window() {
+        sink = Arc::new(RateLimitedSink::new(sink, window));
+    }
+    if let Some(path) = &config.full_stream_path {
+        sink = Arc::new(TeeSink::new(vec![sink, Arc::new(FileSink::create(path)?)]));
+    }
+    Ok(sink)
+}
+
+
+/// Prints each opportunity on one line (colored with the `colored` feature).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink;
+
+impl OpportunitySink for StdoutSink {
+    fn emit(&self, opportunity: &ArbOpportunity) {
+        println!("✅ Arbitrage found: {opportunity}");
+    }
+}
+
+
+/// Appends one plain-text line per opportunity to a file.
+#[derive(Debug)]
+pub struct FileSink {
+    writer: Mutex<LineWriter<File>>,
+}
+
+impl FileSink {
+    /// Opens `path` for appending, creating it if needed.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { writer: Mutex::new(LineWriter::new(file)) })
+    }
+}
+
+impl OpportunitySink for FileSink {
+    fn emit(&self, opportunity: &ArbOpportunity) {
+        let path = &opportunity.path;
+        // Legs are spelled out from the enum so the file format doesn't depend on `colored`
+        let legs = [&path.leg1, &path.leg2, &path.leg3].map(|leg| format!("{:?} {}", leg.side, leg.symbol.symbol));
+        let mut writer = self.writer.lock().unwrap();
+        if let Err(e) = writeln!(
+            writer,
+            "{} | return {:.6} | profit {:+.6} {} | prices {:?}",
+            legs.join(" → "),
+            opportunity.result,
+            opportunity.profit_home,
+            opportunity.home_asset,
+            opportunity.leg_prices
+        ) {
+            warn!(error = %e, "Failed to write opportunity to file");
+        }
+    }
+}
+
+
+/// Forwards opportunities to a channel for downstream consumers (execution, recording, tests).
+///
+/// Uses `try_send`, so a full channel drops the opportunity rather than stalling the arb loop.
+#[derive(Debug, Clone)]
+pub struct ChannelSink {
+    tx: Sender<ArbOpportunity>,
+}
+
+impl ChannelSink {
+    pub fn new(tx: Sender<ArbOpportunity>) -> Self {
+        Self { tx }
+    }
+}
+
+impl OpportunitySink for ChannelSink {
+    fn emit(&self, opportunity: &ArbOpportunity) {
+        match self.tx.try_send(opportunity.clone()) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => warn!("Opportunity channel full; dropping opportunity"),
+            Err(TrySendError::Closed(_)) => warn!("Opportunity channel closed; dropping opportunity"),
+        }
+    }
+}
+
+
+/// POSTs each opportunity as JSON to an HTTP endpoint, e.g. an alerting relay.
+///
+/// `emit` only serializes and queues; a background task delivers the queue one request at a
+/// time. When the endpoint falls behind and the queue fills, new opportunities are dropped and
+/// counted (see `dropped`) so a slow endpoint never stalls the arb loop. Only plain `http://`
+/// URLs are supported.
+#[derive(Debug)]
+pub struct WebhookSink {
+    tx: Sender<Bytes>,
+    dropped: AtomicU64,
+}
+
+impl WebhookSink {
+    /// Spawns the delivery task for `url` on the current Tokio runtime, queueing at most
+    /// `capacity` opportunities.
+    pub fn spawn(url: &str, capacity: usize) -> Result<Self> {
+        let uri: Uri = url.parse().with_context(|| format!("Invalid webhook URL '{url}'"))?;
+        if uri.scheme_str() != Some("http") {
+            bail!("Webhook URL '{url}' must use http://");
+        }
+        let runtime = tokio::runtime::Handle::try_current()
+            .context("WebhookSink must be created inside a Tokio runtime")?;
+
+        let (tx, mut rx) = mpsc::channel::<Bytes>(capacity);
+        let client = Client::builder(TokioExecutor::new()).build_http::<Full<Bytes>>();
+        runtime.spawn(async move {
+            while let Some(body) = rx.recv().await {
+                let request = Request::builder()
+                    .method(Method::POST)
+                    .uri(uri.clone())
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(Full::new(body))
+                    .expect("method, URI and header are valid");
+                match client.request(request).await {
+                    Ok(response) if response.status().is_success() => {}
+                    Ok(response) => warn!(status = %response.status(), %uri, "Webhook rejected opportunity"),
+                    Err(e) => warn!(error = %e, %uri, "Webhook delivery failed"),
+                }
+            }
+        });
+        Ok(Self { tx, dropped: AtomicU64::new(0) })
+    }
+
+    /// Opportunities dropped because the queue was full or the delivery task had stopped.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl OpportunitySink for WebhookSink {
+    fn emit(&self, opportunity: &ArbOpportunity) {
+        let body = match serde_json::to_vec(opportunity) {
+            Ok(body) => Bytes::from(body),
+            Err(e) => {
+                warn!(error = %e, "Failed to serialize opportunity for webhook");
+                return;
+            }
+        };
+        if let Err(e) = self.tx.try_send(body) {
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            let reason = match e {
+                TrySendError::Full(_) => "queue full",
+                TrySendError::Closed(_) => "delivery stopped",
+            };
+            warn!(dropped, reason, "Dropping webhook opportunity");
+        }
+    }
+}
+
+
+/// Forwards each path's opportunity at most once per `window`.
+///
+/// A persistent opportunity (a stuck market, or mock data) would otherwise be emitted on every
+/// update. Repeats inside the window are counted instead, and the next forwarded emission of
+/// that path logs a "still active" summary with the number suppressed in between.
+pub struct RateLimitedSink {
+    inner: Arc<dyn OpportunitySink>,
+    window: Duration,
+    seen: Mutex<HashMap<[(String, Side); 3], RateLimitState>>,
+}
+
+#[derive(Default)]
+struct RateLimitState {
+    last_emitted: Option<Instant>,
+    suppressed: usize,
+}
+
+impl RateLimitedSink {
+    pub fn new(inner: Arc<dyn OpportunitySink>, window: Duration) -> Self {
+        Self { inner, window, seen: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl OpportunitySink for RateLimitedSink {
+    fn emit(&self, opportunity: &ArbOpportunity) {
+        let now = Instant::now();
+        let mut seen = self.seen.lock().unwrap();
+        let state = seen.entry(opportunity.path.key()).or_default();
+
+        if state.last_emitted.is_some_and(|last| now.duration_since(last) < self.window) {
+            state.suppressed += 1;
+            return;
+        }
+        if state.suppressed > 0 {
+            info!(
+                path = %opportunity.path,
+                times = state.suppressed,
+                window = ?self.window,
+                "Opportunity still active"
+            );
+        }
+        state.last_emitted = Some(now);
+        state.suppressed = 0;
+        drop(seen);
+        self.inner.emit(opportunity);
+    }
+}
+
+
+/// Emits every opportunity to each of `sinks`, e.g. a rate-limited console next to a full file.
+pub struct TeeSink {
+    sinks: Vec<Arc<dyn OpportunitySink>>,
+}
+
+impl TeeSink {
+    pub fn new(sinks: Vec<Arc<dyn OpportunitySink>>) -> Self {
+        Self { sinks }
+    }
+}
+
+impl OpportunitySink for TeeSink {
+    fn emit(&self, opportunity: &ArbOpportunity) {
+        for sink in &self.sinks {
+            sink.emit(opportunity);
+        }
+    }
+}
+
+
+/// Forwards only opportunities classified at or above `min_tier`, e.g. webhook just `Large` ones.
+pub struct TierFilterSink {
+    inner: Arc<dyn OpportunitySink>,
+    bands: TierBands,
+    min_tier: Tier,
+}
+
+impl TierFilterSink {
+    pub fn new(inner: Arc<dyn OpportunitySink>, bands: TierBands, min_tier: Tier) -> Self {
+        Self { inner, bands, min_tier }
+    }
+}
+
+impl OpportunitySink for TierFilterSink {
+    fn emit(&self, opportunity: &ArbOpportunity) {
+        if opportunity.tier(&self.bands) >= self.min_tier {
+            self.inner.emit(opportunity);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{mock_path, mock_update, RecordingSink};
+
+    fn opportunity() -> ArbOpportunity {
+        let quotes = [
+            mock_update("BTCUSDT", 50000.0, 50010.0),
+            mock_update("ETHBTC", 0.07, 0.0701),
+            mock_update("ETHUSDT", 3600.0, 3601.0),
+        ];
+        ArbOpportunity::new(Arc::new(mock_path()), Arc::from("USDT"), 1.0, 1.02, [1.0, 1.0, 1.02], 1.0, [&quotes[0], &quotes[1], &quotes[2]])
+    }
+
+    #[test]
+    fn test_file_sink_appends_plain_lines() {
+        let path = std::env::temp_dir().join(format!("triarb-sink-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let sink = FileSink::create(&path).unwrap();
+        sink.emit(&opportunity());
+        sink.emit(&opportunity());
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<_> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("Ask BTCUSDT → Ask ETHBTC → Bid ETHUSDT | return 1.020000"));
+        assert!(!lines[0].contains('\x1b'), "file output shouldn't carry color codes");
+    }
+
+    #[tokio::test]
+    async fn test_channel_sink_forwards_and_drops_when_full() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let sink = ChannelSink::new(tx);
+
+        sink.emit(&opportunity());
+        sink.emit(&opportunity());
+
+        assert_eq!(rx.recv().await.unwrap().leg_prices, [50010.0, 0.0701, 3600.0]);
+        assert!(rx.try_recv().is_err(), "second opportunity should have been dropped");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink_posts_opportunity_json() {
+        use hyper::body::Incoming;
+        use hyper::service::service_fn;
+        use hyper_util::rt::TokioIo;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (posted_tx, mut posted) = mpsc::channel::<(String, Bytes)>(4);
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let service = service_fn(move |request: Request<Incoming>| {
+                let posted_tx = posted_tx.clone();
+                async move {
+                    use http_body_util::BodyExt;
+                    let content_type = request.headers()[CONTENT_TYPE].to_str().unwrap().to_string();
+                    let body = request.into_body().collect().await?.to_bytes();
+                    posted_tx.send((content_type, body)).await.unwrap();
+                    Ok::<_, hyper::Error>(hyper::Response::new(Full::new(Bytes::new())))
+                }
+            });
+            let _ = hyper::server::conn::http1::Builder::new().serve_connection(TokioIo::new(stream), service).await;
+        });
+
+        let sink = WebhookSink::spawn(&format!("http://{addr}/alerts"), 8).unwrap();
+        sink.emit(&opportunity());
+
+        let (content_type, body) = tokio::time::timeout(Duration::from_secs(5), posted.recv()).await.unwrap().unwrap();
+        assert_eq!(content_type, "application/json");
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["home_asset"], "USDT");
+        assert_eq!(json["result"], 1.02);
+        assert_eq!(json["leg_prices"], serde_json::json!([50010.0, 0.0701, 3600.0]));
+        assert_eq!(json["path"]["leg1"]["symbol"], "BTCUSDT");
+        assert_eq!(sink.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink_drops_and_counts_when_queue_is_full() {
+        // Nothing listens here, and the current-thread runtime doesn't run the delivery task
+        // until this test yields, so only the first opportunity fits in the queue
+        let sink = WebhookSink::spawn("http://127.0.0.1:9/alerts", 1).unwrap();
+        for _ in 0..3 {
+            sink.emit(&opportunity());
+        }
+        assert_eq!(sink.dropped(), 2);
+
+        assert!(WebhookSink::spawn("https://example.com/alerts", 1).is_err());
+        assert!(WebhookSink::spawn("not a url", 1).is_err());
+    }
+
+    #[test]
+    fn test_rate_limited_sink_suppresses_repeats_within_window() {
+        let limited = Arc::new(RecordingSink::default());
+        let full = Arc::new(RecordingSink::default());
+        let rate_limited = Arc::new(RateLimitedSink::new(limited.clone(), Duration::from_millis(50)));
+        let sink = TeeSink::new(vec![rate_limited, full.clone()]);
+
+        for _ in 0..1_000 {
+            sink.emit(&opportunity());
+        }
+        // A different path isn't held back by the first one's window
+        let mut reversed = opportunity();
+        reversed.path = Arc::new(reversed.path.reversed());
+        sink.emit(&reversed);
+
+        assert_eq!(limited.emitted.lock().unwrap().len(), 2);
+        assert_eq!(full.emitted.lock().unwrap().len(), 1_001);
+
+        // Once the window has passed, the persistent path is emitted again
+        std::thread::sleep(Duration::from_millis(60));
+        sink.emit(&opportunity());
+        assert_eq!(limited.emitted.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_tier_filter_sink_forwards_only_tiers_at_or_above_minimum() {
+        let large = Arc::new(RecordingSink::default());
+        let medium = Arc::new(RecordingSink::default());
+        let bands = TierBands { medium_pct: 1.0, large_pct: 3.0 };
+        let sink = TeeSink::new(vec![
+            Arc::new(TierFilterSink::new(large.clone(), bands, Tier::Large)),
+            Arc::new(TierFilterSink::new(medium.clone(), bands, Tier::Medium)),
+        ]);
+
+        // 2% profit sits in the Medium band
+        sink.emit(&opportunity());
+
+        assert!(large.emitted.lock().unwrap().is_empty());
+        assert_eq!(medium.emitted.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,161 @@
+// src/arb/tick_size.rs
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::parse::TopOfBookUpdate;
+use crate::price_path::PricingPath;
+
+use super::{ArbEvaluator, ArbOpportunity, HaltHandle, ImpliedQuote};
+
+/// Tolerance (in ticks) absorbing float error when a price already sits on the tick grid.
+const TICK_EPSILON: f64 = 1e-9;
+
+
+/// Rounds `price` down to the nearest multiple of `tick`.
+pub fn round_down_to_tick(price: f64, tick: f64) -> f64 {
+    (price / tick + TICK_EPSILON).floor() * tick
+}
+
+/// Rounds `price` up to the nearest multiple of `tick`.
+pub fn round_up_to_tick(price: f64, tick: f64) -> f64 {
+    (price / tick - TICK_EPSILON).ceil() * tick
+}
+
+
+/// Evaluator decorator that rounds every quote to its symbol's `tickSize` before the
+/// wrapped scanner sees it.
+///
+/// Rounding is conservative — bids down, asks up — so profit is only reported at prices
+/// Binance would actually accept. Symbols without a known tick size pass through unchanged.
+pub struct TickSizeRounding<E> {
+    inner: E,
+    tick_sizes: HashMap<String, f64>,
+}
+
+impl<E: ArbEvaluator> TickSizeRounding<E> {
+    /// Wraps `inner`, taking tick sizes from the symbols referenced by `price_paths`.
+    pub fn new(inner: E, price_paths: &[PricingPath]) -> Self {
+        let tick_sizes = price_paths
+            .iter()
+            .flat_map(|p| [&p.leg1, &p.leg2, &p.leg3])
+            .filter_map(|leg| leg.symbol.tick_size.map(|tick| (leg.symbol.symbol.clone(), tick)))
+            .collect();
+        Self { inner, tick_sizes }
+    }
+
+    /// `update` with its bid rounded down and ask rounded up to the symbol's tick, if it has one.
+    fn rounded(&self, update: &TopOfBookUpdate) -> Option<TopOfBookUpdate> {
+        let &tick = self.tick_sizes.get(&update.symbol)?;
+        Some(TopOfBookUpdate {
+            symbol: update.symbol.clone(),
+            bid_price: round_down_to_tick(update.bid_price, tick),
+            ask_price: round_up_to_tick(update.ask_price, tick),
+            recv_ts: update.recv_ts,
+            event_time_ms: update.event_time_ms,
+        })
+    }
+}
+
+impl<E: ArbEvaluator> ArbEvaluator for TickSizeRounding<E> {
+    fn process_update(&self, update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        match self.rounded(update) {
+            Some(rounded) => self.inner.process_update(&rounded),
+            None => self.inner.process_update(update),
+        }
+    }
+
+    fn process_batch(&self, updates: &[TopOfBookUpdate]) -> Vec<ArbOpportunity> {
+        let rounded: Vec<TopOfBookUpdate> = updates
+            .iter()
+            .map(|update| self.rounded(update).unwrap_or_else(|| update.clone()))
+            .collect();
+        self.inner.process_batch(&rounded)
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.inner.price_snapshot()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        self.inner.halt_handle()
+    }
+
+    fn evaluate_path(&self, path: &PricingPath) -> Option<f64> {
+        self.inner.evaluate_path(path)
+    }
+
+    fn implied_quote(&self, path: &PricingPath) -> Option<ImpliedQuote> {
+        self.inner.implied_quote(path)
+    }
+
+    fn invalidate_all(&self) {
+        self.inner.invalidate_all()
+    }
+
+    fn invalidate_symbols(&self, symbols: &[String]) {
+        self.inner.invalidate_symbols(symbols)
+    }
+
+    fn top_paths(&self, n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        self.inner.top_paths(n)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::HashMapEdgeScanner;
+    use crate::price_path::Side;
+    use crate::testutil::{mock_update, MockBuilder};
+
+    fn mock_paths() -> Vec<PricingPath> {
+        let mock = MockBuilder::new()
+            .pair_with_tick_size("BTCUSDT", "BTC", "USDT", 0.01)
+            .pair_with_tick_size("ETHBTC", "ETH", "BTC", 0.00001)
+            .pair_with_tick_size("ETHUSDT", "ETH", "USDT", 0.01);
+        vec![mock.path([("BTCUSDT", Side::Ask), ("ETHBTC", Side::Ask), ("ETHUSDT", Side::Bid)])]
+    }
+
+    // Unrounded: 3000.0049 / (50000.004 * 0.06) ≈ 1.0000015 (marginal profit)
+    // Rounded:   3000.00   / (50000.01  * 0.06) ≈ 0.9999998 (loss)
+    fn marginal_updates() -> [TopOfBookUpdate; 3] {
+        [
+            mock_update("BTCUSDT", 49999.994, 50000.004),
+            mock_update("ETHBTC", 0.059995, 0.06),
+            mock_update("ETHUSDT", 3000.0049, 3000.0151),
+        ]
+    }
+
+    #[test]
+    fn test_rounding_is_conservative() {
+        assert!((round_down_to_tick(3000.0049, 0.01) - 3000.00).abs() < 1e-9);
+        assert!((round_up_to_tick(50000.004, 0.01) - 50000.01).abs() < 1e-9);
+        // Prices already on the grid are left alone despite float representation error
+        assert!((round_down_to_tick(0.29, 0.01) - 0.29).abs() < 1e-12);
+        assert!((round_up_to_tick(0.07, 0.01) - 0.07).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_marginal_opportunity_disappears_after_rounding() {
+        let [btc, eth_btc, eth] = marginal_updates();
+
+        let unrounded = HashMapEdgeScanner::new(mock_paths());
+        unrounded.process_update(&btc);
+        unrounded.process_update(&eth_btc);
+        assert!(unrounded.process_update(&eth).is_some(), "unrounded prices should be marginally profitable");
+
+        let rounded = TickSizeRounding::new(HashMapEdgeScanner::new(mock_paths()), &mock_paths());
+        rounded.process_update(&btc);
+        rounded.process_update(&eth_btc);
+        assert!(rounded.process_update(&eth).is_none(), "rounded prices should not be profitable");
+
+        // The effective prices used are the rounded ones
+        let snapshot = rounded.price_snapshot();
+        assert!((snapshot["BTCUSDT"].ask_price - 50000.01).abs() < 1e-9);
+        assert!((snapshot["BTCUSDT"].bid_price - 49999.99).abs() < 1e-9);
+        assert!((snapshot["ETHUSDT"].bid_price - 3000.00).abs() < 1e-9);
+        assert!((snapshot["ETHUSDT"].ask_price - 3000.02).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,105 @@
+// src/arb/tier.rs
+
+use serde::{Deserialize, Serialize};
+
+
+/// Default lower bound of the `Medium` tier, in percent profit after fees.
+pub const DEFAULT_MEDIUM_PCT: f64 = 0.1;
+
+/// Default lower bound of the `Large` tier, in percent profit after fees.
+pub const DEFAULT_LARGE_PCT: f64 = 0.5;
+
+fn default_medium_pct() -> f64 {
+    DEFAULT_MEDIUM_PCT
+}
+
+fn default_large_pct() -> f64 {
+    DEFAULT_LARGE_PCT
+}
+
+/// Size class of an opportunity, ordered `Small < Medium < Large`, for routing alerts
+/// (see `ArbOpportunity::tier` and `TierFilterSink`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tier {
+    #[default]
+    Small,
+    Medium,
+    Large,
+}
+
+/// Profit percentages at which opportunities move up a tier, from `[output.tiers]`.
+///
+/// Each bound is inclusive: a profit of exactly `large_pct` is `Large`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct TierBands {
+    #[serde(default = "default_medium_pct")]
+    pub medium_pct: f64,
+    #[serde(default = "default_large_pct")]
+    pub large_pct: f64,
+}
+
+impl TierBands {
+    /// The tier of an opportunity making `profit_pct` percent after fees.
+    pub fn classify(&self, profit_pct: f64) -> Tier {
+        if profit_pct >= self.large_pct {
+            Tier::Large
+        } else if profit_pct >= self.medium_pct {
+            Tier::Medium
+        } else {
+            Tier::Small
+        }
+    }
+}
+
+impl Default for TierBands {
+    fn default() -> Self {
+        Self { medium_pct: DEFAULT_MEDIUM_PCT, large_pct: DEFAULT_LARGE_PCT }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::arb::ArbOpportunity;
+    use crate::testutil::{mock_path, mock_update};
+
+    /// An opportunity routing 100 USDT to `100 + profit_pct`, so the percentages are exact.
+    fn opportunity_making(profit_pct: f64) -> ArbOpportunity {
+        let quotes = [
+            mock_update("BTCUSDT", 50000.0, 50010.0),
+            mock_update("ETHBTC", 0.07, 0.0701),
+            mock_update("ETHUSDT", 3600.0, 3601.0),
+        ];
+        let end = 100.0 + profit_pct;
+        let quotes = [&quotes[0], &quotes[1], &quotes[2]];
+        ArbOpportunity::new(Arc::new(mock_path()), Arc::from("USDT"), 100.0, end, [1.0, 1.0, end / 100.0], 1.0, quotes)
+    }
+
+    #[test]
+    fn opportunity_tier_follows_configured_bands_at_the_boundaries() {
+        let bands = TierBands { medium_pct: 0.25, large_pct: 1.0 };
+        for (profit_pct, expected) in [
+            (0.0, Tier::Small),
+            (0.125, Tier::Small),
+            (0.24609375, Tier::Small),
+            (0.25, Tier::Medium),
+            (0.5, Tier::Medium),
+            (0.99609375, Tier::Medium),
+            (1.0, Tier::Large),
+            (5.0, Tier::Large),
+        ] {
+            let opportunity = opportunity_making(profit_pct);
+            assert_eq!(opportunity.profit_pct(), profit_pct);
+            assert_eq!(opportunity.tier(&bands), expected, "{profit_pct}% profit");
+        }
+
+        // The defaults start Medium at 0.1% and Large at 0.5%
+        assert_eq!(opportunity_making(0.0625).tier(&TierBands::default()), Tier::Small);
+        assert_eq!(opportunity_making(0.125).tier(&TierBands::default()), Tier::Medium);
+        assert_eq!(opportunity_making(0.5).tier(&TierBands::default()), Tier::Large);
+    }
+}
@@ -0,0 +1,172 @@
+// src/arb/vwap.rs
+
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+
+use crate::parse::depth::{vwap, vwap_for_notional, DepthLevel, DepthSnapshot};
+use crate::parse::TopOfBookUpdate;
+use crate::price_path::{PricingPath, Side};
+
+use super::footprint::{path_index_bytes, table_bytes};
+use super::path_index::{index_by_symbol, SymbolPaths};
+use super::{ArbOpportunity, ScannerSettings};
+
+
+/// An edge scanner that prices each leg at the VWAP needed to fill `start_notional`,
+/// walking the top N depth levels instead of trusting the level-1 price.
+///
+/// Returns degrade with size: a path that looks profitable at the touch may not be once
+/// the second and third levels are consumed. Paths whose visible depth can't fill a leg
+/// are skipped. Driven by `DepthSnapshot`s rather than `TopOfBookUpdate`s, so it doesn't
+/// implement `ArbEvaluator`; the quote filter and staleness settings are not applied.
+pub struct VwapEdgeScanner {
+    books: DashMap<String, DepthSnapshot>,
+    path_index: SymbolPaths,
+    settings: ScannerSettings,
+}
+
+impl VwapEdgeScanner {
+    pub fn new(price_paths: Vec<PricingPath>) -> Self {
+        Self::with_settings(price_paths, ScannerSettings::default())
+    }
+
+    /// Constructs the scanner with custom evaluation settings (see `ScannerBuilder`).
+    pub fn with_settings(price_paths: Vec<PricingPath>, settings: ScannerSettings) -> Self {
+        let path_index = index_by_symbol(price_paths.into_iter().map(Arc::new));
+        Self { books: DashMap::new(), path_index, settings }
+    }
+
+    /// Approximate heap used by the path index and stored books (see `footprint`).
+    pub fn approx_memory_bytes(&self) -> usize {
+        let books: usize = self.books
+            .iter()
+            .map(|entry| {
+                let book = entry.value();
+                entry.key().capacity()
+                    + book.symbol.capacity()
+                    + (book.bids.capacity() + book.asks.capacity()) * size_of::<DepthLevel>()
+            })
+            .sum();
+        path_index_bytes(&self.path_index) + table_bytes::<(String, DepthSnapshot)>(self.books.capacity()) + books
+    }
+
+    /// Stores the book and evaluates the paths that trade its symbol, returning the first
+    /// one profitable at VWAP for `start_notional`.
+    pub fn process_depth(&self, book: &DepthSnapshot) -> Option<ArbOpportunity> {
+        self.books.insert(book.symbol.clone(), book.clone());
+
+        let start = self.settings.start_notional;
+
+        'paths: for path in self.path_index.get(&book.symbol)?.iter() {
+            let mut amount = start;
+            let mut leg_prices = [0.0; 3];
+            let mut leg_factors = [0.0; 3];
+            let mut confidence: f64 = 1.0;
+
+            for (i, leg) in [&path.leg1, &path.leg2, &path.leg3].into_iter().enumerate() {
+                let Some(leg_book) = self.books.get(&leg.symbol.symbol) else { continue 'paths; };
+                let Some(top) = leg_book.top_of_book() else { continue 'paths; };
+                // Depth snapshots aren't timestamped, so only the touch spread is scored
+                let leg_confidence = self.settings.confidence.leg(top.bid_price, top.ask_price, Duration::ZERO);
+                confidence = confidence.min(leg_confidence);
+
+                // Ask legs spend `amount` of the quote asset; Bid legs sell `amount` of the base
+                let price = match leg.side {
+                    Side::Ask => vwap_for_notional(&leg_book.asks, amount),
+                    Side::Bid => vwap(&leg_book.bids, amount),
+                };
+                let Some(price) = price else { continue 'paths; };
+
+                let next = self.settings.fees.multiplier(&leg.symbol.symbol) * leg.side.convert(amount, price);
+                leg_prices[i] = price;
+                leg_factors[i] = next / amount;
+                amount = next;
+            }
+
+            if self.settings.is_profitable(path, leg_prices, amount) {
+                return Some(ArbOpportunity {
+                    path: Arc::clone(path),
+                    home_asset: Arc::clone(&self.settings.home_asset),
+                    start_notional: start,
+                    result: amount / start,
+                    profit_home: amount - start,
+                    profit_normalized: None,
+                    leg_prices,
+                    leg_factors,
+                    confidence,
+                    // Depth snapshots carry no receive or event time
+                    leg_time_spread_ms: None,
+                    detection_latency: None,
+                });
+            }
+        }
+        None
+    }
+
+    /// Level-1 prices of every stored book.
+    pub fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        self.books
+            .iter()
+            .filter_map(|entry| Some((entry.key().clone(), entry.value().top_of_book()?)))
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::depth::DepthLevel;
+    use crate::testutil::{assert_return_eq, make_path};
+
+    fn book(symbol: &str, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> DepthSnapshot {
+        let levels = |raw: &[(f64, f64)]| raw.iter().map(|&(price, qty)| DepthLevel { price, qty }).collect();
+        DepthSnapshot { symbol: symbol.to_string(), bids: levels(bids), asks: levels(asks) }
+    }
+
+    /// USDT → ETH → BTC → USDT: 1 / 3001 * 0.07 * 50000 ≈ 1.166 at level 1.
+    fn path() -> PricingPath {
+        make_path([
+            ("ETHUSDT", "ETH", "USDT", Side::Ask),
+            ("ETHBTC", "ETH", "BTC", Side::Bid),
+            ("BTCUSDT", "BTC", "USDT", Side::Bid),
+        ])
+    }
+
+    fn feed(scanner: &VwapEdgeScanner) -> Option<ArbOpportunity> {
+        scanner.process_depth(&book("BTCUSDT", &[(50000.0, 1.0), (49500.0, 10.0)], &[(50010.0, 1.0)]));
+        scanner.process_depth(&book("ETHBTC", &[(0.07, 1.0), (0.069, 50.0)], &[(0.0701, 1.0)]));
+        scanner.process_depth(&book("ETHUSDT", &[(3000.0, 1.0)], &[(3001.0, 1.0), (3050.0, 50.0)]))
+    }
+
+    fn scanner_for_notional(start_notional: f64) -> VwapEdgeScanner {
+        let settings = ScannerSettings { start_notional, ..ScannerSettings::default() };
+        VwapEdgeScanner::with_settings(vec![path()], settings)
+    }
+
+    #[test]
+    fn test_small_size_prices_at_level_one() {
+        let opportunity = feed(&scanner_for_notional(100.0)).expect("profitable at the touch");
+
+        assert_eq!(opportunity.leg_prices, [3001.0, 0.07, 50000.0]);
+        assert_return_eq(opportunity.result, 50000.0 * 0.07 / 3001.0, 1e-12);
+    }
+
+    #[test]
+    fn test_return_degrades_with_size() {
+        let small = feed(&scanner_for_notional(100.0)).unwrap();
+        // 30 000 USDT sweeps past level 1 on the ETH legs, still profitable but less so
+        let large = feed(&scanner_for_notional(30_000.0)).unwrap();
+
+        assert!(large.leg_prices[0] > small.leg_prices[0], "ask VWAP should rise with size");
+        assert!(large.leg_prices[1] < small.leg_prices[1], "bid VWAP should fall with size");
+        assert!(large.result < small.result);
+
+        // Beyond the visible depth the path can't be priced at all
+        assert!(feed(&scanner_for_notional(1_000_000.0)).is_none());
+    }
+}
@@ -0,0 +1,71 @@
+// src/bin/throughput.rs
+
+// cargo run --release --bin throughput -- --mode simd --paths 500 --secs 5
+
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+
+use tri_arb::arb::ArbMode;
+use tri_arb::devtools::path_sampler::{sample_paths_with, PathSelection};
+use tri_arb::devtools::throughput::measure_throughput;
+
+
+const HOME_ASSET: &str = "USDT";
+const USAGE: &str = "Usage: throughput [--mode naive|edge|interned|rayon|simd|adaptive|all] [--paths N] [--secs S] [--priority SYM,SYM,...]";
+
+struct Args {
+    modes: Vec<ArbMode>,
+    paths: usize,
+    secs: f64,
+    selection: PathSelection,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut args = Args { modes: ArbMode::ALL.to_vec(), paths: 500, secs: 5.0, selection: PathSelection::FirstDiscovered };
+    let mut raw = std::env::args().skip(1);
+    while let Some(flag) = raw.next() {
+        let mut value = || raw.next().with_context(|| format!("{flag} needs a value\n{USAGE}"));
+        match flag.as_str() {
+            "--mode" => {
+                let mode = value()?;
+                if mode != "all" {
+                    args.modes = vec![mode.parse()?];
+                }
+            }
+            "--paths" => args.paths = value()?.parse().context("--paths must be a whole number")?,
+            "--secs" => args.secs = value()?.parse().context("--secs must be a number")?,
+            "--priority" => {
+                let symbols = value()?.split(',').map(|s| s.trim().to_uppercase()).collect();
+                args.selection = PathSelection::Priority(symbols);
+            }
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            other => bail!("Unknown argument '{other}'\n{USAGE}"),
+        }
+    }
+    if !args.secs.is_finite() || args.secs <= 0.0 {
+        bail!("--secs must be a positive number");
+    }
+    Ok(args)
+}
+
+fn main() -> Result<()> {
+    let args = parse_args()?;
+    let (paths, symbols) = sample_paths_with(HOME_ASSET, args.paths, &args.selection)?;
+    let duration = Duration::from_secs_f64(args.secs);
+    println!("{} paths over {} symbols, {:.1}s per mode", paths.len(), symbols.len(), args.secs);
+
+    for mode in args.modes {
+        let report = measure_throughput(mode, HOME_ASSET, paths.clone(), &symbols, duration)?;
+        println!(
+            "{:<10} updates/s {:>12.0} | opportunities/s {:>10.0}",
+            format!("{:?}", report.mode),
+            report.updates_per_sec(),
+            report.opportunities_per_sec()
+        );
+    }
+    Ok(())
+}
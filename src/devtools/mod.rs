@@ -1,21 +1,27 @@
 // src/devtools/mod.rs
 
 pub mod path_sampler;
+pub mod throughput;
 
 use std::fs;
 use std::path::Path;
 use anyhow::{Context, Result};
 
-use crate::price_path::ExchangeInfo;
+use crate::price_path::{stale_fixture_age, ExchangeInfo, DEFAULT_EXCHANGE_INFO_MAX_AGE, EXCHANGE_INFO_FIXTURE};
 
 
 /// Load the exchangeInfo fixture from disk.
 ///
-/// Panics if the file is missing or invalid.
+/// Fails with a hint if the file is missing or invalid.
 pub fn load_exchange_info() -> Result<ExchangeInfo> {
-    let path = Path::new("fixtures/exchangeInfoSpot.json");
+    load_exchange_info_from(EXCHANGE_INFO_FIXTURE)
+}
 
-    let contents = fs::read_to_string(&path).with_context(|| {
+/// Load exchangeInfo from `path` instead of the bundled fixture.
+pub fn load_exchange_info_from<P: AsRef<Path>>(path: P) -> Result<ExchangeInfo> {
+    let path = path.as_ref();
+
+    let contents = fs::read_to_string(path).with_context(|| {
         format!(
             "❌ Failed to read '{}'.\n\
              Please ensure the fixture exists.\n\
@@ -23,6 +29,7 @@ pub fn load_exchange_info() -> Result<ExchangeInfo> {
             path.display()
         )
     })?;
+    stale_fixture_age(path, DEFAULT_EXCHANGE_INFO_MAX_AGE);
 
     let parsed: ExchangeInfo = serde_json::from_str(&contents).with_context(|| {
         format!(
@@ -1,9 +1,22 @@
 // src/devtools/path_sampler.rs
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use anyhow::Result;
 use super::load_exchange_info;
-use crate::price_path::{find_and_build_price_paths, PricingPath};
+use crate::price_path::{ExchangeInfo, PricePathIter, PricingPath};
+
+
+/// How `sample_paths_with` picks which paths to keep when more than `path_count` exist.
+#[derive(Debug, Clone, Default)]
+pub enum PathSelection {
+    /// The first `path_count` paths in discovery order. Cheapest: only those paths are built.
+    #[default]
+    FirstDiscovered,
+    /// Symbols in descending priority (e.g. by volume); unlisted symbols rank last. A path
+    /// ranks by its lowest-priority leg, since a triangle is only as liquid as its thinnest
+    /// market. Ties keep discovery order. Every path is built to rank them.
+    Priority(Vec<String>),
+}
 
 
 /// Sample up to `n` triangular arbitrage paths that start and end with the given `home_asset`.
@@ -13,26 +26,99 @@ use crate::price_path::{find_and_build_price_paths, PricingPath};
 ///
 /// Returns:
 /// - A list of pricing paths (up to `n`)
-/// - A flattened, deduplicated list of symbols used in those paths
+/// - A flattened, deduplicated list of symbols used in those paths, sorted so benches cycle
+///   through them in the same order every run
 pub fn sample_paths(home_asset: &str, path_count: usize) -> Result<(Vec<PricingPath>, Vec<String>)> {
+    sample_paths_with(home_asset, path_count, &PathSelection::FirstDiscovered)
+}
+
+/// `sample_paths`, keeping the `path_count` paths ranked highest by `selection`.
+pub fn sample_paths_with(
+    home_asset: &str,
+    path_count: usize,
+    selection: &PathSelection,
+) -> Result<(Vec<PricingPath>, Vec<String>)> {
     let info = load_exchange_info()?;
+    let sampled_paths = select_paths(info, home_asset, path_count, selection);
+
+    let mut symbol_set = BTreeSet::new();
+    for path in &sampled_paths {
+        symbol_set.extend(path.symbols());
+    }
+
+    let symbols: Vec<String> = symbol_set.into_iter().collect();
+
+    Ok((sampled_paths, symbols))
+}
 
+/// Up to `path_count` paths from `info` through `home_asset`, chosen by `selection`.
+///
+/// Discovery walks `info.symbols` in order, so the result doesn't depend on target hashing.
+pub fn select_paths(info: ExchangeInfo, home_asset: &str, path_count: usize, selection: &PathSelection) -> Vec<PricingPath> {
     // Collect all unique base assets from the exchange info
     let mut target_assets = HashSet::new();
     for symbol in &info.symbols {
         target_assets.insert(symbol.base_asset.clone());
     }
     let targets: Vec<&str> = target_assets.iter().map(String::as_str).collect();
+    let paths = PricePathIter::new(info, home_asset, &targets);
 
-    let all_paths = find_and_build_price_paths(home_asset, &targets)?;
-    let sampled_paths = all_paths.into_iter().take(path_count).collect::<Vec<_>>();
+    match selection {
+        // Paths are discovered lazily, so only the first `path_count` are ever built
+        PathSelection::FirstDiscovered => paths.take(path_count).collect(),
+        PathSelection::Priority(symbols) => {
+            let rank: HashMap<&str, usize> = symbols.iter().enumerate().map(|(i, s)| (s.as_str(), i)).collect();
+            let path_rank = |path: &PricingPath| {
+                [&path.leg1, &path.leg2, &path.leg3]
+                    .map(|leg| rank.get(leg.symbol.symbol.as_str()).copied().unwrap_or(usize::MAX))
+                    .into_iter()
+                    .max()
+            };
+            let mut ranked: Vec<PricingPath> = paths.collect();
+            ranked.sort_by_key(path_rank);
+            ranked.truncate(path_count);
+            ranked
+        }
+    }
+}
 
-    let mut symbol_set = HashSet::new();
-    for path in &sampled_paths {
-        symbol_set.extend(path.symbols());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::MockBuilder;
+
+    #[test]
+    fn priority_selection_keeps_the_highest_priority_paths() {
+        let info = || {
+            MockBuilder::new()
+                .triangle("USDT", "BTC", "ETH")
+                .triangle("USDT", "BTC", "SOL")
+                .triangle("USDT", "BTC", "XRP")
+                .exchange_info()
+        };
+        let all = select_paths(info(), "USDT", usize::MAX, &PathSelection::FirstDiscovered);
+        assert_eq!(all.len(), 6, "three triangles in both directions");
+        // XRP is the lowest-priority asset, so prioritising it proves the order was not kept
+        let priority = ["BTCUSDT", "XRPBTC", "XRPUSDT", "ETHBTC", "ETHUSDT"].map(String::from).to_vec();
+
+        let kept = select_paths(info(), "USDT", 2, &PathSelection::Priority(priority.clone()));
+        assert_eq!(kept.len(), 2);
+        assert!(kept.iter().all(|p| p.symbols().contains(&"XRPBTC".to_string())), "kept {kept:?}");
+
+        let kept = select_paths(info(), "USDT", 4, &PathSelection::Priority(priority));
+        assert!(kept[2..].iter().all(|p| p.symbols().contains(&"ETHBTC".to_string())));
+        assert!(kept.iter().all(|p| !p.symbols().contains(&"SOLBTC".to_string())), "unlisted symbols rank last");
     }
 
-    let symbols: Vec<String> = symbol_set.into_iter().collect();
+    #[test]
+    fn sample_paths_returns_the_same_sorted_symbols_every_call() {
+        let (first_paths, first) = sample_paths("USDT", 50).unwrap();
+        let (second_paths, second) = sample_paths("USDT", 50).unwrap();
 
-    Ok((sampled_paths, symbols))
-}
\ No newline at end of file
+        assert_eq!(first, second);
+        assert!(first.windows(2).all(|w| w[0] < w[1]), "symbols are sorted and unique");
+        let keys = |paths: &[PricingPath]| paths.iter().map(PricingPath::key).collect::<Vec<_>>();
+        assert_eq!(keys(&first_paths), keys(&second_paths));
+    }
+}
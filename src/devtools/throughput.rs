@@ -0,0 +1,67 @@
+// src/devtools/throughput.rs
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::arb::{create_arb_evaluator, ArbMode};
+use crate::mock_feed::hot_cache::RandomTickGenerator;
+use crate::price_path::PricingPath;
+
+
+/// Updates processed between clock checks, so timing stays out of the hot loop.
+const CLOCK_CHECK_INTERVAL: u64 = 1024;
+
+
+/// Outcome of one `measure_throughput` run.
+#[derive(Debug, Clone)]
+pub struct ThroughputReport {
+    pub mode: ArbMode,
+    pub paths: usize,
+    pub updates: u64,
+    pub opportunities: u64,
+    pub elapsed: Duration,
+}
+
+impl ThroughputReport {
+    pub fn updates_per_sec(&self) -> f64 {
+        self.updates as f64 / self.elapsed.as_secs_f64()
+    }
+
+    pub fn opportunities_per_sec(&self) -> f64 {
+        self.opportunities as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+
+/// Feeds `RandomTickGenerator` quotes for `symbols` (round robin, no channel or parser) into
+/// the `mode` scanner over `price_paths` (starting and ending in `home_asset`) as fast as it
+/// accepts them for `duration`.
+///
+/// The scanner is built by `create_arb_evaluator`, so `config/arb.toml` options apply, and an
+/// invalid one fails the run.
+pub fn measure_throughput(
+    mode: ArbMode,
+    home_asset: &str,
+    price_paths: Vec<PricingPath>,
+    symbols: &[String],
+    duration: Duration,
+) -> Result<ThroughputReport> {
+    let paths = price_paths.len();
+    let evaluator = create_arb_evaluator(mode, home_asset, price_paths)?;
+    let mut generator = RandomTickGenerator::new(Some(42));
+
+    let mut updates = 0;
+    let mut opportunities = 0;
+    let start = Instant::now();
+    for symbol in symbols.iter().cycle() {
+        if evaluator.process_update(&generator.next_quote(symbol)).is_some() {
+            opportunities += 1;
+        }
+        updates += 1;
+        if updates % CLOCK_CHECK_INTERVAL == 0 && start.elapsed() >= duration {
+            break;
+        }
+    }
+    Ok(ThroughputReport { mode, paths, updates, opportunities, elapsed: start.elapsed() })
+}
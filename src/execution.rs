@@ -0,0 +1,497 @@
+// src/execution.rs
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+
+use crate::arb::{ArbOpportunity, FeeSchedule};
+use crate::parse::{RecordedUpdate, TopOfBookUpdate};
+use crate::price_path::{PathLeg, PricingPath, Side};
+
+
+/// Binance spot taker fee (0.1%), charged on every leg by `SimulationParams::default()`.
+pub const DEFAULT_TAKER_FEE: f64 = 0.001;
+
+
+/// A market order implied by one leg of an `ArbOpportunity`.
+///
+/// Produced by the dry-run simulator only — nothing here is ever sent to an exchange.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedOrder {
+    pub symbol: String,
+    pub side: Side,
+    /// Order size in the symbol's base asset.
+    pub quantity: f64,
+    /// Top-of-book price the order is assumed to fill at.
+    pub price: f64,
+    /// Asset and amount spent on this leg.
+    pub input_asset: String,
+    pub input_amount: f64,
+    /// Asset and amount received on this leg, net of fees.
+    pub output_asset: String,
+    pub output_amount: f64,
+    /// Fee charged on this leg, denominated in `output_asset`.
+    pub fee: f64,
+}
+
+
+/// How simulated orders fill: the fee each leg pays, how much worse than quoted it fills and
+/// how long after the signal it lands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationParams {
+    /// Fee charged on each leg's received asset, at the rate for the leg's symbol.
+    pub fees: FeeSchedule,
+    /// Fraction (e.g. `0.0005`) every leg fills worse than its price: asks are paid higher and
+    /// bids received lower.
+    pub slippage: f64,
+    /// When each leg fills after the signal. Only `simulate_with_latency`, which has the quotes
+    /// of that time, applies it; `simulate` fills at the signaled prices.
+    pub latency: ExecutionLatency,
+}
+
+impl Default for SimulationParams {
+    /// `DEFAULT_TAKER_FEE` on every leg, no slippage and instant fills.
+    fn default() -> Self {
+        Self {
+            fees: FeeSchedule::flat(DEFAULT_TAKER_FEE),
+            slippage: 0.0,
+            latency: ExecutionLatency::default(),
+        }
+    }
+}
+
+impl SimulationParams {
+    pub fn with_fees(mut self, fees: FeeSchedule) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    pub fn with_slippage(mut self, slippage: f64) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    pub fn with_latency(mut self, latency: ExecutionLatency) -> Self {
+        self.latency = latency;
+        self
+    }
+}
+
+
+/// Turns an opportunity into the three market orders it implies for `start_notional` units of
+/// the home asset, filling each leg at the price the scanner evaluated it at, less `params`'
+/// slippage, and charging its fee on the received asset.
+///
+/// - `Side::Ask` legs buy the base asset: `quantity = input / ask`.
+/// - `Side::Bid` legs sell the base asset: `quantity = input`, receiving `input * bid`.
+///
+/// Purely computational — no network I/O. The expected end balance (in the home asset) is the
+/// last order's `output_amount`; see `end_balance`.
+pub fn simulate(opp: &ArbOpportunity, start_notional: f64, params: &SimulationParams) -> Vec<SimulatedOrder> {
+    let prices = slipped_prices(&opp.path, opp.leg_prices, params.slippage);
+    fill_legs(&opp.path, prices, start_notional, &params.fees)
+}
+
+
+/// When each leg's order fills, measured from the moment the opportunity was signaled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExecutionLatency {
+    /// Delay to each leg's fill, in path order.
+    pub fill_delays: [Duration; 3],
+}
+
+impl ExecutionLatency {
+    /// Legs sent one after another, each filling `per_leg` after the previous one.
+    pub fn sequential(per_leg: Duration) -> Self {
+        Self { fill_delays: [per_leg, per_leg * 2, per_leg * 3] }
+    }
+
+    /// All three legs sent at once, each filling `latency` after the signal.
+    pub fn concurrent(latency: Duration) -> Self {
+        Self { fill_delays: [latency; 3] }
+    }
+}
+
+
+/// Recorded or mock quotes per symbol in time order, to look up the book a delayed leg
+/// actually fills against.
+#[derive(Debug, Clone, Default)]
+pub struct QuoteTimeline {
+    by_symbol: HashMap<String, Vec<RecordedUpdate>>,
+}
+
+impl QuoteTimeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexes a recording (see `mock_feed::replay`), which need not be in time order.
+    pub fn from_recording(recording: &[RecordedUpdate]) -> Self {
+        let mut timeline = Self::new();
+        for recorded in recording {
+            timeline.insert(recorded.timestamp_ms, recorded.update.clone());
+        }
+        timeline
+    }
+
+    /// Adds `update` as the book for its symbol from `timestamp_ms` until its next update.
+    pub fn insert(&mut self, timestamp_ms: u64, update: TopOfBookUpdate) {
+        let updates = self.by_symbol.entry(update.symbol.clone()).or_default();
+        // After any update at the same millisecond, so the later-inserted one wins
+        let at = updates.partition_point(|u| u.timestamp_ms <= timestamp_ms);
+        updates.insert(at, RecordedUpdate { timestamp_ms, update });
+    }
+
+    /// The latest quote for `symbol` at or before `timestamp_ms`.
+    pub fn quote_at(&self, symbol: &str, timestamp_ms: u64) -> Option<&TopOfBookUpdate> {
+        let updates = self.by_symbol.get(symbol)?;
+        let at = updates.partition_point(|u| u.timestamp_ms <= timestamp_ms);
+        at.checked_sub(1).map(|i| &updates[i].update)
+    }
+}
+
+
+/// An opportunity filled after its `ExecutionLatency`, against the quotes of that time.
+#[derive(Debug, Clone)]
+pub struct LatencyFill {
+    /// The orders as they would fill once each leg lands.
+    pub orders: Vec<SimulatedOrder>,
+    /// Net return, after fees, at the prices the opportunity was signaled at.
+    pub signaled_return: f64,
+    /// Net return, after fees and slippage, at the prices the legs actually fill at.
+    pub realized_return: f64,
+}
+
+impl LatencyFill {
+    /// Whether the opportunity is still worth taking by the time its orders land.
+    pub fn still_profitable(&self) -> bool {
+        self.realized_return > 1.0
+    }
+
+    /// Return lost between signal and fill; negative when the book moved in the route's favour.
+    pub fn decay(&self) -> f64 {
+        self.signaled_return - self.realized_return
+    }
+}
+
+
+/// Re-prices `opp`, signaled at `signaled_at_ms`, with each leg filled at the `timeline`'s quote
+/// once that leg's delay in `params.latency` has passed, less `params`' slippage, and compares
+/// the result with the signaled return. Quantifies how much of a signal survives the trip to
+/// the exchange.
+///
+/// `None` if `timeline` has no quote for a leg by its fill time.
+pub fn simulate_with_latency(
+    opp: &ArbOpportunity,
+    signaled_at_ms: u64,
+    timeline: &QuoteTimeline,
+    params: &SimulationParams,
+) -> Option<LatencyFill> {
+    let legs = [&opp.path.leg1, &opp.path.leg2, &opp.path.leg3];
+    let mut prices = [0.0; 3];
+    for (i, leg) in legs.into_iter().enumerate() {
+        let fill_at_ms = signaled_at_ms + params.latency.fill_delays[i].as_millis() as u64;
+        let quote = timeline.quote_at(&leg.symbol.symbol, fill_at_ms)?;
+        prices[i] = leg.side.price(quote.bid_price, quote.ask_price);
+    }
+
+    let signaled_return = end_balance(&fill_legs(&opp.path, opp.leg_prices, 1.0, &params.fees));
+    let prices = slipped_prices(&opp.path, prices, params.slippage);
+    let orders = fill_legs(&opp.path, prices, opp.start_notional, &params.fees);
+    let realized_return = end_balance(&orders) / opp.start_notional;
+    Some(LatencyFill { orders, signaled_return, realized_return })
+}
+
+
+/// Returns the balance left after the final simulated order, or `0.0` if there are none.
+pub fn end_balance(orders: &[SimulatedOrder]) -> f64 {
+    orders.last().map(|o| o.output_amount).unwrap_or(0.0)
+}
+
+
+/// One opportunity taken by a `PaperLedger`.
+#[derive(Debug, Clone)]
+pub struct PaperTrade {
+    pub path: Arc<PricingPath>,
+    /// Home-asset amount routed through the path.
+    pub notional: f64,
+    pub orders: Vec<SimulatedOrder>,
+    /// Net change in the home-asset balance, after fees and slippage.
+    pub profit: f64,
+    pub balance_after: f64,
+}
+
+
+/// A simulated home-asset account for backtesting against recorded or mock feeds.
+///
+/// Each opportunity passed to `take` is filled with `simulate` under the ledger's
+/// `SimulationParams`, paying each leg's fee from its `FeeSchedule`, and its net result applied
+/// to the balance, with a trade log kept in order. Nothing is sent to an exchange.
+#[derive(Debug, Clone)]
+pub struct PaperLedger {
+    home_asset: String,
+    balance: f64,
+    params: SimulationParams,
+    trades: Vec<PaperTrade>,
+}
+
+impl PaperLedger {
+    /// Starts with `balance` units of `home_asset`, the default taker fee on every symbol and no
+    /// slippage.
+    pub fn new(home_asset: &str, balance: f64) -> Self {
+        Self {
+            home_asset: home_asset.to_string(),
+            balance,
+            params: SimulationParams::default(),
+            trades: Vec::new(),
+        }
+    }
+
+    /// Charges each leg the rate `fees` gives its symbol, e.g. the scanner's own schedule so
+    /// backtests pay the fees the opportunity was detected under.
+    pub fn with_fee_schedule(mut self, fees: FeeSchedule) -> Self {
+        self.params.fees = fees;
+        self
+    }
+
+    /// Fills every leg this fraction worse than quoted (see `SimulationParams::slippage`).
+    pub fn with_slippage(mut self, slippage: f64) -> Self {
+        self.params.slippage = slippage;
+        self
+    }
+
+    pub fn home_asset(&self) -> &str {
+        &self.home_asset
+    }
+
+    pub fn balance(&self) -> f64 {
+        self.balance
+    }
+
+    pub fn trades(&self) -> &[PaperTrade] {
+        &self.trades
+    }
+
+    /// Routes `notional` of the home asset through `opp` and applies the result.
+    ///
+    /// Fails, leaving the ledger untouched, if the opportunity is counted in another asset or
+    /// `notional` is not positive or exceeds the balance — the ledger never goes negative.
+    pub fn take(&mut self, opp: &ArbOpportunity, notional: f64) -> Result<&PaperTrade> {
+        if *opp.home_asset != *self.home_asset {
+            bail!("Opportunity is counted in {}, ledger holds {}", opp.home_asset, self.home_asset);
+        }
+        if !(notional > 0.0 && notional.is_finite()) {
+            bail!("Notional must be positive, got {notional}");
+        }
+        if notional > self.balance {
+            bail!("Notional {notional} exceeds balance {} {}", self.balance, self.home_asset);
+        }
+
+        let orders = simulate(opp, notional, &self.params);
+        let profit = end_balance(&orders) - notional;
+        self.balance += profit;
+        self.trades.push(PaperTrade {
+            path: Arc::clone(&opp.path),
+            notional,
+            orders,
+            profit,
+            balance_after: self.balance,
+        });
+        Ok(self.trades.last().expect("just pushed"))
+    }
+}
+
+
+/// `path`'s leg `prices`, each `slippage` worse: asks higher, bids lower.
+fn slipped_prices(path: &PricingPath, prices: [f64; 3], slippage: f64) -> [f64; 3] {
+    let legs = [&path.leg1, &path.leg2, &path.leg3];
+    [0, 1, 2].map(|i| match legs[i].side {
+        Side::Ask => prices[i] * (1.0 + slippage),
+        Side::Bid => prices[i] * (1.0 - slippage),
+    })
+}
+
+/// Chains `start_notional` through `path`'s legs at `prices`, in path order, each paying the
+/// rate `fees` gives its symbol.
+fn fill_legs(path: &PricingPath, prices: [f64; 3], start_notional: f64, fees: &FeeSchedule) -> Vec<SimulatedOrder> {
+    let mut balance = start_notional;
+    let mut orders = Vec::with_capacity(prices.len());
+    for (leg, price) in [&path.leg1, &path.leg2, &path.leg3].into_iter().zip(prices) {
+        let order = simulate_leg(leg, price, balance, fees.rate(&leg.symbol.symbol));
+        balance = order.output_amount;
+        orders.push(order);
+    }
+    orders
+}
+
+fn simulate_leg(leg: &PathLeg, price: f64, input_amount: f64, fee_rate: f64) -> SimulatedOrder {
+    let symbol = &leg.symbol;
+    let gross = leg.side.convert(input_amount, price);
+    let (quantity, input_asset, output_asset) = match leg.side {
+        Side::Ask => (gross, &symbol.quote_asset, &symbol.base_asset),
+        Side::Bid => (input_amount, &symbol.base_asset, &symbol.quote_asset),
+    };
+    let fee = gross * fee_rate;
+
+    SimulatedOrder {
+        symbol: symbol.symbol.clone(),
+        side: leg.side,
+        quantity,
+        price,
+        input_asset: input_asset.clone(),
+        input_amount,
+        output_asset: output_asset.clone(),
+        output_amount: gross - fee,
+        fee,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arb::{ArbEvaluator, HashMapEdgeScanner};
+    use crate::testutil::{assert_return_eq, make_path, mock_update};
+
+    /// USDT → ETH → BTC → USDT, profitable at the quotes below.
+    fn detected_opportunity() -> ArbOpportunity {
+        let path = make_path([
+            ("ETHUSDT", "ETH", "USDT", Side::Ask),
+            ("ETHBTC", "ETH", "BTC", Side::Bid),
+            ("BTCUSDT", "BTC", "USDT", Side::Bid),
+        ]);
+        let scanner = HashMapEdgeScanner::new(vec![path]);
+        scanner.process_update(&mock_update("BTCUSDT", 50000.0, 50010.0));
+        scanner.process_update(&mock_update("ETHBTC", 0.07, 0.0701));
+        scanner
+            .process_update(&mock_update("ETHUSDT", 3000.0, 3001.0))
+            .expect("path should be profitable")
+    }
+
+    #[test]
+    fn simulated_orders_follow_path_sides_and_assets() {
+        let opp = detected_opportunity();
+        let orders = simulate(&opp, 1000.0, &SimulationParams::default());
+
+        assert_eq!(orders.len(), 3);
+        let legs = [&opp.path.leg1, &opp.path.leg2, &opp.path.leg3];
+        for (order, leg) in orders.iter().zip(legs) {
+            assert_eq!(order.symbol, leg.symbol.symbol);
+            assert_eq!(order.side, leg.side);
+        }
+
+        assert_eq!(orders[0].input_asset, "USDT");
+        assert_eq!(orders[0].output_asset, "ETH");
+        assert_eq!(orders[1].output_asset, "BTC");
+        assert_eq!(orders[2].output_asset, "USDT");
+
+        // Each leg spends exactly what the previous leg received
+        assert_eq!(orders[1].input_amount, orders[0].output_amount);
+        assert_eq!(orders[2].input_amount, orders[1].output_amount);
+        assert_return_eq(orders[0].quantity, 1000.0 / 3001.0, 1e-12);
+    }
+
+    #[test]
+    fn end_balance_matches_scanner_return() {
+        let opp = detected_opportunity();
+
+        let fee_free = simulate(&opp, 1000.0, &SimulationParams::default().with_fees(FeeSchedule::flat(0.0)));
+        assert_return_eq(end_balance(&fee_free), 1000.0 * opp.result, 1e-9);
+
+        let with_fees = simulate(&opp, 1000.0, &SimulationParams::default());
+        let expected = 1000.0 * opp.result * (1.0 - DEFAULT_TAKER_FEE).powi(3);
+        assert_return_eq(end_balance(&with_fees), expected, 1e-9);
+    }
+
+    #[test]
+    fn latency_fills_legs_at_the_quotes_of_their_fill_time() {
+        let opp = detected_opportunity();
+        // The detection quotes, then BTCUSDT's bid collapses 30ms after the signal
+        let signaled_at_ms = 1_700_000_000_000;
+        let mut timeline = QuoteTimeline::from_recording(&[
+            RecordedUpdate { timestamp_ms: signaled_at_ms - 5, update: mock_update("BTCUSDT", 50000.0, 50010.0) },
+            RecordedUpdate { timestamp_ms: signaled_at_ms - 3, update: mock_update("ETHBTC", 0.07, 0.0701) },
+            RecordedUpdate { timestamp_ms: signaled_at_ms, update: mock_update("ETHUSDT", 3000.0, 3001.0) },
+        ]);
+        timeline.insert(signaled_at_ms + 30, mock_update("BTCUSDT", 42000.0, 42010.0));
+
+        let fee_free = SimulationParams::default().with_fees(FeeSchedule::flat(0.0));
+        // Filled instantly, the signal is exactly what the book offered
+        let instant = simulate_with_latency(&opp, signaled_at_ms, &timeline, &fee_free).unwrap();
+        assert_return_eq(instant.signaled_return, opp.result, 1e-12);
+        assert_return_eq(instant.realized_return, opp.result, 1e-12);
+
+        // All legs landing within 10ms still beat the drop
+        let fast = fee_free.clone().with_latency(ExecutionLatency::concurrent(Duration::from_millis(10)));
+        assert!(simulate_with_latency(&opp, signaled_at_ms, &timeline, &fast).unwrap().still_profitable());
+
+        // Sent one by one at 20ms each, the last leg sells BTC at 60ms, into the lower bid
+        let slow = SimulationParams::default().with_latency(ExecutionLatency::sequential(Duration::from_millis(20)));
+        let fill = simulate_with_latency(&opp, signaled_at_ms, &timeline, &slow).unwrap();
+        let fees = (1.0 - DEFAULT_TAKER_FEE).powi(3);
+        assert_return_eq(fill.signaled_return, opp.result * fees, 1e-12);
+        assert_return_eq(fill.realized_return, 1.0 / 3001.0 * 0.07 * 42000.0 * fees, 1e-12);
+        assert!(fill.signaled_return > 1.0 && !fill.still_profitable());
+        assert!(fill.decay() > 0.0);
+        assert_eq!(fill.orders[2].price, 42000.0);
+        assert_return_eq(end_balance(&fill.orders), opp.start_notional * fill.realized_return, 1e-12);
+
+        // Slippage widens the gap between the signal and the fill, but not the signal itself
+        let slipped = simulate_with_latency(&opp, signaled_at_ms, &timeline, &slow.clone().with_slippage(0.0005)).unwrap();
+        assert_eq!(slipped.signaled_return, fill.signaled_return);
+        assert!(slipped.realized_return < fill.realized_return);
+
+        // No quote for a leg by its fill time means no fill to report
+        assert!(simulate_with_latency(&opp, signaled_at_ms - 1, &timeline, &fee_free).is_none());
+    }
+
+    #[test]
+    fn paper_ledger_books_the_net_profit_of_a_taken_opportunity() {
+        let opp = detected_opportunity();
+        let mut ledger = PaperLedger::new("USDT", 10_000.0);
+
+        let trade = ledger.take(&opp, 1000.0).unwrap();
+        let expected_profit = 1000.0 * (opp.result * (1.0 - DEFAULT_TAKER_FEE).powi(3) - 1.0);
+        assert!(expected_profit > 0.0);
+        assert_return_eq(trade.profit, expected_profit, 1e-9);
+        assert_return_eq(ledger.balance(), 10_000.0 + expected_profit, 1e-9);
+        assert_eq!(ledger.trades().len(), 1);
+        assert_eq!(ledger.trades()[0].orders.len(), 3);
+
+        // Slippage on every leg eats into the next trade's profit
+        let mut slipped = PaperLedger::new("USDT", 10_000.0).with_slippage(0.0005);
+        let slipped_profit = slipped.take(&opp, 1000.0).unwrap().profit;
+        assert!(slipped_profit < expected_profit);
+    }
+
+    #[test]
+    fn paper_ledger_charges_each_leg_its_own_fee() {
+        let opp = detected_opportunity();
+        // Only the ETHBTC leg is fee-free
+        let fees = FeeSchedule::flat(DEFAULT_TAKER_FEE).with_override("ETHBTC", 0.0);
+        let mut ledger = PaperLedger::new("USDT", 10_000.0).with_fee_schedule(fees);
+
+        let trade = ledger.take(&opp, 1000.0).unwrap();
+        assert_eq!(trade.orders[1].fee, 0.0);
+        assert!(trade.orders[0].fee > 0.0 && trade.orders[2].fee > 0.0);
+        let expected_profit = 1000.0 * (opp.result * (1.0 - DEFAULT_TAKER_FEE).powi(2) - 1.0);
+        assert_return_eq(trade.profit, expected_profit, 1e-9);
+    }
+
+    #[test]
+    fn paper_ledger_refuses_trades_it_cannot_fund() {
+        let opp = detected_opportunity();
+        let mut ledger = PaperLedger::new("USDT", 500.0).with_fee_schedule(FeeSchedule::flat(0.0));
+
+        assert!(ledger.take(&opp, 500.01).is_err());
+        assert!(ledger.take(&opp, 0.0).is_err());
+        assert!(PaperLedger::new("BTC", 1.0).take(&opp, 0.5).is_err());
+        assert_eq!(ledger.balance(), 500.0);
+        assert!(ledger.trades().is_empty());
+
+        ledger.take(&opp, 500.0).unwrap();
+        assert_return_eq(ledger.balance(), 500.0 * opp.result, 1e-9);
+    }
+}
@@ -0,0 +1,139 @@
+// src/feed.rs
+
+//! One ordered stream of everything the feed does: quotes, rejected frames, and the
+//! connection dropping, going quiet and coming back. For supervisors and dashboards that would
+//! otherwise poll `ConnectionState` and tail the parser's logs.
+//!
+//! A sharded listener runs one connection per shard, so every lifecycle event names the shard
+//! it happened on (its index into `ws::shard_symbols`); the others keep quoting meanwhile.
+//!
+//! ```rust,ignore
+//! let (raw_tx, raw_rx) = mpsc::channel(1024);
+//! let (event_tx, mut event_rx) = mpsc::channel(1024);
+//! tokio::spawn(start_ws_feed(paths, raw_tx, ConnectOptions::default().with_reconnect_delay(delay)));
+//! tokio::spawn(feed_event_loop(raw_rx, event_tx, ParserKind::Manual, Arc::new(QuoteCounter::new())));
+//! while let Some(event) = event_rx.recv().await {
+//!     match event {
+//!         FeedEvent::Quote(update) => { /* ... */ }
+//!         other => tracing::info!(?other, "Feed lifecycle"),
+//!     }
+//! }
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use bytes::Bytes;
+use tokio::sync::mpsc::{Receiver, Sender};
+
+use crate::parse::quote_counter::QuoteCounter;
+use crate::parse::{create_parser, ParserKind, TopOfBookUpdate};
+
+
+/// Something that happened on the feed, in the order it happened.
+#[derive(Debug, Clone)]
+pub enum FeedEvent {
+    /// A quote parsed from a data frame.
+    Quote(TopOfBookUpdate),
+    /// A data frame the parser rejected.
+    ParseError { error: String },
+    /// No frame arrived for `silent_for` (the idle timeout) on `shard`'s connection while it
+    /// was still open, so the listener is replacing it. Its quotes in that window were missed.
+    Gap { shard: usize, silent_for: Duration },
+    /// `shard`'s connection ended: the server closed it, the socket failed or it went idle.
+    Disconnected { shard: usize },
+    /// A new connection for `shard` is subscribed after its last `Disconnected`, `downtime`
+    /// later. Quotes for its symbols sent in between were missed.
+    Reconnected { shard: usize, downtime: Duration },
+}
+
+/// What the listener sends in feed mode (see `ws::start_ws_feed`): a data
+/// frame still to be parsed, or a lifecycle event ready to pass through.
+#[derive(Debug, Clone)]
+pub enum RawFeedEvent {
+    Frame(Bytes),
+    Event(FeedEvent),
+}
+
+
+/// Parses the listener's frames into `FeedEvent::Quote`s (one per update in the frame) or a
+/// `FeedEvent::ParseError`, passing lifecycle events through between them, so `tx` sees
+/// everything in the order the listener saw it. Parsed symbols and rejected frames are counted
+/// in `counter` as in `parse::parser_loop_with_quote_counter`.
+///
+/// Unlike the parser loop, every event is awaited onto `tx` rather than dropped when it is
+/// full: a consumer missing a `Disconnected` would misreport the feed. Returns when the
+/// listener's channel closes or `tx`'s receiver is dropped.
+pub async fn feed_event_loop(
+    mut rx: Receiver<RawFeedEvent>,
+    tx: Sender<FeedEvent>,
+    kind: ParserKind,
+    counter: Arc<QuoteCounter>,
+) -> Result<()> {
+    let parser = create_parser(kind);
+
+    while let Some(raw) = rx.recv().await {
+        let events = match raw {
+            RawFeedEvent::Frame(frame) => match parser.parse_batch(&frame) {
+                Ok(updates) => updates
+                    .into_iter()
+                    .inspect(|update| counter.record(&update.symbol))
+                    .map(FeedEvent::Quote)
+                    .collect(),
+                Err(e) => {
+                    counter.record_parse_error();
+                    vec![FeedEvent::ParseError { error: format!("{e:#}") }]
+                }
+            },
+            RawFeedEvent::Event(event) => vec![event],
+        };
+        for event in events {
+            if tx.send(event).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn frames_and_lifecycle_events_come_out_in_order() {
+        let (raw_tx, raw_rx) = mpsc::channel(16);
+        let (tx, mut rx) = mpsc::channel(16);
+        let counter = Arc::new(QuoteCounter::new());
+        let quote = |symbol: &str| {
+            RawFeedEvent::Frame(Bytes::from(format!(r#"{{"u":1,"s":"{symbol}","b":"1.0","B":"1","a":"1.1","A":"1"}}"#)))
+        };
+
+        for raw in [
+            quote("BTCUSDT"),
+            RawFeedEvent::Frame(Bytes::from_static(b"not json")),
+            RawFeedEvent::Event(FeedEvent::Disconnected { shard: 1 }),
+            RawFeedEvent::Event(FeedEvent::Reconnected { shard: 1, downtime: Duration::from_millis(5) }),
+            quote("ETHBTC"),
+        ] {
+            raw_tx.send(raw).await.unwrap();
+        }
+        drop(raw_tx);
+        feed_event_loop(raw_rx, tx, ParserKind::Manual, Arc::clone(&counter)).await.unwrap();
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        assert!(matches!(&events[0], FeedEvent::Quote(update) if update.symbol == "BTCUSDT"));
+        assert!(matches!(&events[1], FeedEvent::ParseError { .. }));
+        assert!(matches!(events[2], FeedEvent::Disconnected { shard: 1 }));
+        assert!(matches!(events[3], FeedEvent::Reconnected { shard: 1, downtime } if downtime == Duration::from_millis(5)));
+        assert!(matches!(&events[4], FeedEvent::Quote(update) if update.symbol == "ETHBTC"));
+        assert_eq!(events.len(), 5);
+        assert_eq!(counter.parse_errors(), 1);
+    }
+}
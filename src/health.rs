@@ -0,0 +1,117 @@
+// src/health.rs
+
+//! Minimal HTTP `/health` endpoint for load balancers and Kubernetes probes.
+//!
+//! ```rust,ignore
+//! let check = HealthCheck::for_pipeline(&pipeline, Duration::from_secs(5));
+//! let listener = TcpListener::bind("127.0.0.1:8080").await?;
+//! tokio::spawn(serve_health(listener, check));
+//! ```
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use bytes::Bytes;
+use http::header::CONTENT_TYPE;
+use http::{Request, Response, StatusCode};
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+use tracing::{debug, warn};
+
+use crate::arb::ArbLoopStats;
+use crate::pipeline::Pipeline;
+use crate::ws::{ConnectionState, ConnectionStatus};
+
+
+/// Decides whether the pipeline is healthy from its connection state and arb loop stats.
+///
+/// Healthy means the WebSocket is connected, its last frame arrived within `max_frame_age`,
+/// and the arb loop is running.
+#[derive(Debug, Clone)]
+pub struct HealthCheck {
+    connection: Arc<ConnectionState>,
+    arb_stats: Arc<ArbLoopStats>,
+    max_frame_age: Duration,
+}
+
+impl HealthCheck {
+    pub fn new(connection: Arc<ConnectionState>, arb_stats: Arc<ArbLoopStats>, max_frame_age: Duration) -> Self {
+        Self { connection, arb_stats, max_frame_age }
+    }
+
+    /// Checks `pipeline`'s connection and arb loop.
+    pub fn for_pipeline(pipeline: &Pipeline, max_frame_age: Duration) -> Self {
+        Self::new(Arc::clone(pipeline.connection()), Arc::clone(pipeline.arb_stats()), max_frame_age)
+    }
+
+    /// `Ok` when healthy, otherwise why not.
+    pub fn check(&self) -> Result<(), String> {
+        match self.connection.status() {
+            ConnectionStatus::Connected => {}
+            ConnectionStatus::Connecting => return Err("websocket is connecting".into()),
+            ConnectionStatus::Closed => return Err("websocket is closed".into()),
+        }
+        let Some(last_frame) = self.connection.last_frame_at() else {
+            return Err("no frame received yet".into());
+        };
+        let age = last_frame.elapsed();
+        if age > self.max_frame_age {
+            return Err(format!("last frame {}ms ago, limit {}ms", age.as_millis(), self.max_frame_age.as_millis()));
+        }
+        if !self.arb_stats.is_running() {
+            return Err("arb loop is not running".into());
+        }
+        Ok(())
+    }
+
+    fn respond(&self, request: &Request<Incoming>) -> Response<Full<Bytes>> {
+        if request.uri().path() != "/health" {
+            return json_response(StatusCode::NOT_FOUND, serde_json::json!({ "status": "not found" }));
+        }
+        match self.check() {
+            Ok(()) => json_response(
+                StatusCode::OK,
+                serde_json::json!({ "status": "ok", "updates_processed": self.arb_stats.updates_processed() }),
+            ),
+            Err(reason) => json_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                serde_json::json!({ "status": "unavailable", "reason": reason }),
+            ),
+        }
+    }
+}
+
+fn json_response(status: StatusCode, body: serde_json::Value) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body.to_string())));
+    *response.status_mut() = status;
+    response.headers_mut().insert(CONTENT_TYPE, "application/json".parse().unwrap());
+    response
+}
+
+
+/// Serves `GET /health` on `listener` until the task is dropped or accepting fails.
+///
+/// Responds 200 with `{"status":"ok",...}` when `check` passes and 503 with
+/// `{"status":"unavailable","reason":...}` otherwise; any other path is a 404.
+pub async fn serve_health(listener: TcpListener, check: HealthCheck) -> Result<()> {
+    let check = Arc::new(check);
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        debug!(%peer, "Health check connection");
+        let check = Arc::clone(&check);
+        tokio::spawn(async move {
+            let service = service_fn(move |request: Request<Incoming>| {
+                let response = check.respond(&request);
+                async move { Ok::<_, Infallible>(response) }
+            });
+            if let Err(e) = hyper::server::conn::http1::Builder::new().serve_connection(TokioIo::new(stream), service).await {
+                warn!(error = %e, "Health check connection failed");
+            }
+        });
+    }
+}
@@ -13,6 +13,18 @@ pub mod arb;
 #[doc = include_str!("../doc/pricing_path.md")]
 pub mod price_path;
 
+pub mod execution;
+
+pub mod pipeline;
+
+pub mod feed;
+
+#[cfg(feature = "health")]
+pub mod health;
+
 pub mod mock_feed;
 
-pub mod devtools;
\ No newline at end of file
+pub mod devtools;
+
+#[cfg(test)]
+mod testutil;
\ No newline at end of file
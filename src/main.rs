@@ -1,41 +1,125 @@
 // src/main.rs
 
-use bytes::Bytes;
-use anyhow::Result;
-use tri_arb::parse::{parser_loop, TopOfBookUpdate};
-use tri_arb::ws::start_ws_listener;
-use tri_arb::arb::{create_arb_evaluator, arb_loop, ArbMode};
-use tri_arb::price_path::find_and_build_price_paths;
-use tokio::sync::mpsc;
+use anyhow::{bail, Context, Result};
 
+use tri_arb::arb::{
+    create_opportunity_sink, load_arb_mode, load_logging_config, load_output_config, load_pipeline_config,
+    load_symbol_filter, load_universe_config, ArbMode, LogFormat, DEFAULT_TARGETS,
+};
+use tri_arb::pipeline::{Endpoint, Pipeline};
+use tri_arb::ws::extract_symbols_from_paths;
+
+
+/// Command-line options.
+#[derive(Default)]
+struct Args {
+    /// `--mode <name>` (or `--mode=<name>`), overriding `[scanner] mode`.
+    mode: Option<ArbMode>,
+    /// `--discover-only`: print the discovered paths and exit without connecting.
+    discover_only: bool,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut args = std::env::args().skip(1);
+    let mut parsed = Args::default();
+    while let Some(arg) = args.next() {
+        if arg == "--discover-only" {
+            parsed.discover_only = true;
+            continue;
+        }
+        let value = match arg.strip_prefix("--mode=") {
+            Some(value) => value.to_string(),
+            None if arg == "--mode" => args.next().context("--mode needs a value")?,
+            None => bail!(
+                "Unknown argument '{arg}'\nUsage: tri_arb [--mode naive|edge|interned|rayon|simd|adaptive] [--discover-only]"
+            ),
+        };
+        parsed.mode = Some(value.parse()?);
+    }
+    Ok(parsed)
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
-        .init();
+    let logging = load_logging_config();
+    let filter = logging.env_filter(std::env::var("RUST_LOG").ok().as_deref())?;
+    match logging.format {
+        LogFormat::Pretty => tracing_subscriber::fmt().with_env_filter(filter).init(),
+        LogFormat::Json => tracing_subscriber::fmt().json().with_env_filter(filter).init(),
+    }
     tracing::info!("Starting TriArb");
     
     // Config inputs
-    let home_asset = "USDT";
-    let targets = ["BTC", "ETH", "SOL"];
-    let arb_eval_mode = ArbMode::RayonScan;
+    let universe = load_universe_config();
+    let home_asset = universe.home_asset.as_str();
     println!("Home asset: {}", home_asset);
-    println!("Target assets: {:?}", targets);
-    
-    // Create resources
-    let price_paths = find_and_build_price_paths(home_asset, &targets)?;
-    let evaluator = create_arb_evaluator(arb_eval_mode, price_paths.clone());
-    let (ws_tx, ws_rx) = mpsc::channel::<Bytes>(4096);
-    let (parser_tx, parser_rx) = mpsc::channel::<TopOfBookUpdate>(4096);
-    
-    // Start loops
-    tokio::spawn(arb_loop(parser_rx, evaluator));
-    tokio::spawn(parser_loop(ws_rx, parser_tx));
-    tokio::spawn(start_ws_listener(price_paths.clone(), ws_tx, Some(true)));
+
+    let args = parse_args()?;
+    let mode = match args.mode {
+        Some(mode) => mode,
+        None => load_arb_mode()?.unwrap_or(ArbMode::RayonScan),
+    };
+    tracing::info!(?mode, "Scanner");
+    let pipeline_config = load_pipeline_config();
+    tracing::info!(?pipeline_config, "Pipeline settings");
+    #[cfg(feature = "health")]
+    let health = (pipeline_config.health_addr.clone(), pipeline_config.health_max_frame_age());
+    let output = load_output_config();
+    tracing::info!(?output, "Opportunity output");
+    let symbol_filter = load_symbol_filter();
+    tracing::info!(?symbol_filter, "Symbol filter");
+
+    let builder = match (&universe.targets, universe.top_targets) {
+        (None, Some(count)) => {
+            println!("Target assets: top {count}");
+            Pipeline::builder().with_top_targets(home_asset, count, universe.ranking()?)
+        }
+        (targets, _) => {
+            let targets: Vec<&str> = match targets {
+                Some(targets) => targets.iter().map(String::as_str).collect(),
+                None => DEFAULT_TARGETS.to_vec(),
+            };
+            println!("Target assets: {:?}", targets);
+            Pipeline::builder().with_universe(home_asset, &targets)
+        }
+    };
+    let builder = match universe.max_paths {
+        Some(max_paths) => builder.with_max_paths(max_paths),
+        None => builder,
+    };
+    let builder = builder
+        .with_mode(mode)
+        .with_endpoint(Endpoint::LocalMock)
+        .with_config(pipeline_config)
+        .with_symbol_filter(symbol_filter);
+
+    if args.discover_only {
+        let paths = builder.discover_paths()?;
+        for path in &paths {
+            println!("{path}");
+        }
+        println!("{} paths, {} symbols", paths.len(), extract_symbols_from_paths(&paths).len());
+        return Ok(());
+    }
+
+    let mut pipeline = builder
+        .with_sink(create_opportunity_sink(&output)?)
+        .build()?;
+    pipeline.start();
+
+    #[cfg(feature = "health")]
+    {
+        let (addr, max_frame_age) = health;
+        let listener = tokio::net::TcpListener::bind(&addr)
+            .await
+            .with_context(|| format!("Failed to bind health endpoint on {addr}"))?;
+        tracing::info!(%addr, "Serving /health");
+        let check = tri_arb::health::HealthCheck::for_pipeline(&pipeline, max_frame_age);
+        tokio::spawn(tri_arb::health::serve_health(listener, check));
+    }
     
     tokio::signal::ctrl_c().await?;
     tracing::info!("Shutdown signal received");
-    
+    pipeline.shutdown().await?;
     Ok(())
 }
@@ -0,0 +1,85 @@
+// src/mock_feed/generator.rs
+
+use std::collections::HashMap;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::OsRng;
+use rand_chacha::ChaCha12Rng;
+
+use crate::price_path::PricingPath;
+
+
+/// Source of mock bid/ask prices, pluggable into the hot cache and top-of-book feeds.
+///
+/// Implement this for scenario-specific feeds (random walks, replays, injected arbs);
+/// `UniformGenerator` is the default.
+pub trait PriceGenerator: Send {
+    /// Next `(bid, ask)` for `symbol`.
+    fn next_tick(&mut self, symbol: &str) -> (f64, f64);
+}
+
+/// Independent uniform prices: bid in `10000..30000`, ask 0.01–0.05 above it.
+///
+/// With a seed, the emitted sequence is fully reproducible for a given call order.
+pub struct UniformGenerator {
+    rng: ChaCha12Rng,
+}
+
+impl UniformGenerator {
+    /// Seeds deterministically when `seed` is `Some`, otherwise from the OS RNG.
+    pub fn new(seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+            None => ChaCha12Rng::from_rng(OsRng).unwrap(),
+        };
+        Self { rng }
+    }
+}
+
+impl PriceGenerator for UniformGenerator {
+    fn next_tick(&mut self, _symbol: &str) -> (f64, f64) {
+        let bid = self.rng.gen_range(10000.0..30000.0);
+        let ask = bid + self.rng.gen_range(0.01..0.05);
+        (bid, ask)
+    }
+}
+
+
+/// Arbitrage-free prices: every asset gets a fixed value, and each symbol quotes
+/// `value(base) / value(quote)` widened by `half_spread` on both sides.
+///
+/// Crossing any spread loses money and the mids are mutually consistent, so every triangle
+/// returns less than 1 and a correct scanner reports nothing. The control case for
+/// end-to-end correctness tests. Asset values fall in `1..1000`, so cross rates stay well
+/// within `DEFAULT_PRICE_DECIMALS` of precision.
+pub struct ConsistentGenerator {
+    mids: HashMap<String, f64>,
+    half_spread: f64,
+}
+
+impl ConsistentGenerator {
+    /// Values every asset traded by `price_paths`, seeded deterministically when `seed` is `Some`.
+    pub fn from_paths(price_paths: &[PricingPath], half_spread: f64, seed: Option<u64>) -> Self {
+        let mut rng = match seed {
+            Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+            None => ChaCha12Rng::from_rng(OsRng).unwrap(),
+        };
+        let mut values: HashMap<&str, f64> = HashMap::new();
+        let mut mids = HashMap::new();
+        for leg in price_paths.iter().flat_map(|p| [&p.leg1, &p.leg2, &p.leg3]) {
+            let symbol = &leg.symbol;
+            let base = *values.entry(&symbol.base_asset).or_insert_with(|| rng.gen_range(1.0..1000.0));
+            let quote = *values.entry(&symbol.quote_asset).or_insert_with(|| rng.gen_range(1.0..1000.0));
+            mids.insert(symbol.symbol.clone(), base / quote);
+        }
+        Self { mids, half_spread }
+    }
+}
+
+impl PriceGenerator for ConsistentGenerator {
+    /// Symbols no path trades quote around 1.
+    fn next_tick(&mut self, symbol: &str) -> (f64, f64) {
+        let mid = self.mids.get(symbol).copied().unwrap_or(1.0);
+        (mid * (1.0 - self.half_spread), mid * (1.0 + self.half_spread))
+    }
+}
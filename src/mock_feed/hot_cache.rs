@@ -3,7 +3,7 @@
 use std::{
     collections::HashMap,
     sync::Arc,
-    time::Duration
+    time::{Duration, Instant}
 };
 
 
@@ -11,46 +11,260 @@ use serde_json::json;
 use tokio::sync::RwLock;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha12Rng;
-use rand::rngs::OsRng;
+
+use crate::arb::{Clock, SystemClock};
+use crate::parse::TopOfBookUpdate;
+use crate::price_path::PricingPath;
+
+use super::generator::{PriceGenerator, UniformGenerator};
 
 /// A shared, concurrent map of symbol → pre-serialized bookTicker messages.
 pub type HotCache = Arc<RwLock<HashMap<String, String>>>;
 
+/// Decimal places of prices for symbols without a configured precision, and of quantities.
+pub const DEFAULT_PRICE_DECIMALS: usize = 8;
+
+/// Decimal places Binance quotes a symbol with, given its `PRICE_FILTER` `tickSize`
+/// (`0.01` → 2, `0.00000001` → 8, `1.0` → 0), capped at `DEFAULT_PRICE_DECIMALS`.
+pub fn decimals_for_tick_size(tick_size: f64) -> usize {
+    (0..DEFAULT_PRICE_DECIMALS)
+        .find(|&decimals| {
+            // Whole ticks at this many places; the tolerance absorbs `0.01 * 100 = 1.0000000000000002`
+            let scaled = tick_size * 10f64.powi(decimals as i32);
+            scaled.round() >= 1.0 && (scaled - scaled.round()).abs() < 1e-6
+        })
+        .unwrap_or(DEFAULT_PRICE_DECIMALS)
+}
+
+/// Per-symbol price decimals for every symbol in `price_paths` with a known tick size, for
+/// `RandomTickGenerator::with_price_decimals`.
+pub fn price_decimals_from_paths(price_paths: &[PricingPath]) -> HashMap<String, usize> {
+    price_paths
+        .iter()
+        .flat_map(|path| [&path.leg1, &path.leg2, &path.leg3])
+        .filter_map(|leg| Some((leg.symbol.symbol.clone(), decimals_for_tick_size(leg.symbol.tick_size?))))
+        .collect()
+}
+
+/// Generates synthetic bookTicker messages with per-symbol update IDs, pricing them from a
+/// `PriceGenerator` (uniform random prices by default).
+///
+/// Quantities come from a fixed-seed RNG, so the emitted sequence is reproducible whenever
+/// the price generator is. Prices are formatted with `DEFAULT_PRICE_DECIMALS` places unless a
+/// symbol's precision is set (see `with_price_decimals`).
+pub struct RandomTickGenerator {
+    prices: Box<dyn PriceGenerator>,
+    rng: ChaCha12Rng,
+    update_ids: HashMap<String, u64>,
+    price_decimals: HashMap<String, usize>,
+}
+
+impl RandomTickGenerator {
+    /// Uniform random prices, seeded deterministically when `seed` is `Some`.
+    pub fn new(seed: Option<u64>) -> Self {
+        Self::with_generator(Box::new(UniformGenerator::new(seed)))
+    }
+
+    pub fn with_generator(prices: Box<dyn PriceGenerator>) -> Self {
+        Self {
+            prices,
+            rng: ChaCha12Rng::seed_from_u64(0),
+            update_ids: HashMap::new(),
+            price_decimals: HashMap::new(),
+        }
+    }
+
+    /// Formats each listed symbol's prices with its own number of decimal places, like real
+    /// Binance messages (e.g. from `price_decimals_from_paths`).
+    pub fn with_price_decimals(mut self, price_decimals: HashMap<String, usize>) -> Self {
+        self.price_decimals = price_decimals;
+        self
+    }
+
+    /// Produces the next serialized bookTicker message for `symbol`.
+    pub fn next_tick(&mut self, symbol: &str) -> String {
+        // Get and increment the update ID
+        let counter = self.update_ids.entry(symbol.to_string()).or_insert(1);
+        let u = *counter;
+        *counter +=1;
+        let (bid, ask) = self.prices.next_tick(symbol);
+        let decimals = self.price_decimals.get(symbol).copied().unwrap_or(DEFAULT_PRICE_DECIMALS);
+        let tick = json!({
+            "E": SystemClock.epoch_millis(),
+            "u": u,
+            "s": symbol,
+            "b": format!("{bid:.decimals$}"),
+            "B": format!("{:.8}", self.rng.gen_range(1.0..100.0)),
+            "a": format!("{ask:.decimals$}"),
+            "A": format!("{:.8}", self.rng.gen_range(1.0..100.0))
+        });
+        tick.to_string()
+    }
+
+    /// Produces the next quote for `symbol` as a parsed update, skipping JSON entirely.
+    pub fn next_quote(&mut self, symbol: &str) -> TopOfBookUpdate {
+        let (bid_price, ask_price) = self.prices.next_tick(symbol);
+        TopOfBookUpdate {
+            symbol: symbol.to_string(),
+            bid_price,
+            ask_price,
+            recv_ts: Instant::now(),
+            event_time_ms: Some(SystemClock.epoch_millis()),
+        }
+    }
+}
+
 /// Spawns the background task that updates the hot cache every `interval_ms`.
-pub fn start_hot_cache_updater(symbols: Vec<String>, interval_ms: u64) -> HotCache {
+///
+/// Pass `seed: Some(..)` for reproducible prices (tests, benchmarks); `None` seeds from the OS.
+pub fn start_hot_cache_updater(symbols: Vec<String>, interval_ms: u64, seed: Option<u64>) -> HotCache {
+    start_hot_cache_updater_with_generator(symbols, interval_ms, Box::new(UniformGenerator::new(seed)))
+}
+
+/// Like `start_hot_cache_updater`, but prices every tick from `prices`.
+pub fn start_hot_cache_updater_with_generator(
+    symbols: Vec<String>,
+    interval_ms: u64,
+    prices: Box<dyn PriceGenerator>,
+) -> HotCache {
+    start_hot_cache_updater_with_ticks(symbols, interval_ms, RandomTickGenerator::with_generator(prices))
+}
+
+/// Like `start_hot_cache_updater`, serializing every tick with `generator`, e.g. one configured
+/// with per-symbol price decimals.
+///
+/// Locking discipline: each round serializes its ticks before taking the write lock, holds it
+/// only to insert them, and releases it before sleeping, so `ws_server` readers are blocked for
+/// the inserts alone and never for the interval.
+pub fn start_hot_cache_updater_with_ticks(
+    symbols: Vec<String>,
+    interval_ms: u64,
+    mut generator: RandomTickGenerator,
+) -> HotCache {
     let cache: HotCache = Arc::new(RwLock::new(HashMap::new()));
     let cache_clone = Arc::clone(&cache);
 
     tokio::spawn(async move {
-        let mut rng = ChaCha12Rng::from_rng(OsRng).unwrap();
         let interval = Duration::from_millis(interval_ms);
-        let mut update_ids: HashMap<String, u64> = HashMap::new();
         
         loop {
-            {
-                let mut guard = cache_clone.write().await;
-
-                for symbol in &symbols {
-                    // Get and increment the update ID
-                    let counter = update_ids.entry(symbol.clone()).or_insert(1);
-                    let u = *counter;
-                    *counter +=1;
-                    let bid = rng.gen_range(10000.0..30000.0);
-                    let ask = bid + rng.gen_range(0.01..0.05);
-                    let tick = json!({
-                        "u": u,
-                        "s": symbol,
-                        "b": format!("{:.8}", bid),
-                        "B": format!("{:.8}", rng.gen_range(1.0..100.0)),
-                        "a": format!("{:.8}", ask),
-                        "A": format!("{:.8}", rng.gen_range(1.0..100.0))
-                    });
-
-                    guard.insert(symbol.clone(), tick.to_string());
-                }
-                tokio::time::sleep(interval).await;
-            }
+            let ticks: Vec<(String, String)> =
+                symbols.iter().map(|symbol| (symbol.clone(), generator.next_tick(symbol))).collect();
+            cache_clone.write().await.extend(ticks);
+            tokio::time::sleep(interval).await;
         }
     });
     cache
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price_path::Side;
+
+    /// BTCUSDT ticks with the wall-clock event time `E` dropped, so runs are comparable.
+    fn emitted_sequence(seed: Option<u64>) -> Vec<serde_json::Value> {
+        let symbols = ["BTCUSDT", "ETHBTC", "ETHUSDT"];
+        let mut generator = RandomTickGenerator::new(seed);
+        let mut btc_ticks = Vec::new();
+        for _ in 0..50 {
+            for symbol in symbols {
+                let mut tick: serde_json::Value = serde_json::from_str(&generator.next_tick(symbol)).unwrap();
+                assert!(tick.as_object_mut().unwrap().remove("E").is_some_and(|e| e.is_u64()));
+                if symbol == "BTCUSDT" {
+                    btc_ticks.push(tick);
+                }
+            }
+        }
+        btc_ticks
+    }
+
+    #[test]
+    fn test_prices_use_each_symbols_decimal_places() {
+        let paths = vec![crate::testutil::MockBuilder::new()
+            .pair_with_tick_size("BTCUSDT", "BTC", "USDT", 0.01)
+            .pair_with_tick_size("ETHBTC", "ETH", "BTC", 0.00001)
+            .pair("ETHUSDT", "ETH", "USDT")
+            .path([("BTCUSDT", Side::Ask), ("ETHBTC", Side::Ask), ("ETHUSDT", Side::Bid)])];
+        let decimals = price_decimals_from_paths(&paths);
+        assert_eq!(decimals, HashMap::from([("BTCUSDT".to_string(), 2), ("ETHBTC".to_string(), 5)]));
+
+        let mut generator = RandomTickGenerator::new(Some(7)).with_price_decimals(decimals);
+        for _ in 0..20 {
+            for (symbol, expected) in [("BTCUSDT", 2), ("ETHBTC", 5), ("ETHUSDT", DEFAULT_PRICE_DECIMALS)] {
+                let tick: serde_json::Value = serde_json::from_str(&generator.next_tick(symbol)).unwrap();
+                for key in ["b", "a"] {
+                    let price = tick[key].as_str().unwrap();
+                    let (_, fraction) = price.split_once('.').unwrap();
+                    assert_eq!(fraction.len(), expected, "{symbol} {key} = {price}");
+                }
+                assert_eq!(tick["B"].as_str().unwrap().split_once('.').unwrap().1.len(), 8);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decimals_for_tick_size() {
+        for (tick, decimals) in [(1.0, 0), (0.1, 1), (0.01, 2), (0.00001, 5), (0.00000001, 8), (10.0, 0)] {
+            assert_eq!(decimals_for_tick_size(tick), decimals, "tick {tick}");
+        }
+        assert_eq!(decimals_for_tick_size(1e-10), DEFAULT_PRICE_DECIMALS);
+    }
+
+    #[test]
+    fn test_same_seed_replays_identical_sequence() {
+        assert_eq!(emitted_sequence(Some(42)), emitted_sequence(Some(42)));
+    }
+
+    #[test]
+    fn test_different_seeds_diverge() {
+        assert_ne!(emitted_sequence(Some(42)), emitted_sequence(Some(43)));
+    }
+
+    /// Prices each symbol from a per-symbol counter: bid `n`, ask `n + 0.5`.
+    #[derive(Default)]
+    struct CountingGenerator {
+        ticks: HashMap<String, u32>,
+    }
+
+    impl PriceGenerator for CountingGenerator {
+        fn next_tick(&mut self, symbol: &str) -> (f64, f64) {
+            let n = self.ticks.entry(symbol.to_string()).or_default();
+            *n += 1;
+            (f64::from(*n), f64::from(*n) + 0.5)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_reflects_plugged_in_generator() {
+        let symbols = vec!["BTCUSDT".to_string(), "ETHBTC".to_string()];
+        let cache = start_hot_cache_updater_with_generator(symbols, 1, Box::new(CountingGenerator::default()));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let guard = cache.read().await;
+        for symbol in ["BTCUSDT", "ETHBTC"] {
+            let tick: serde_json::Value = serde_json::from_str(&guard[symbol]).unwrap();
+            let bid: f64 = tick["b"].as_str().unwrap().parse().unwrap();
+            let ask: f64 = tick["a"].as_str().unwrap().parse().unwrap();
+            // Whole-number bids, one step per update id, each half a unit below the ask
+            assert_eq!(tick["s"], symbol);
+            assert_eq!(bid, tick["u"].as_u64().unwrap() as f64);
+            assert_eq!(ask, bid + 0.5);
+            assert!(tick["E"].as_u64().is_some_and(|e| e > 0));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_readers_are_not_blocked_during_the_update_interval() {
+        let symbols = vec!["BTCUSDT".to_string(), "ETHBTC".to_string()];
+        let cache = start_hot_cache_updater_with_generator(symbols, 1_000, Box::new(CountingGenerator::default()));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // The first round is written and the updater is sleeping: the lock must be free
+        let guard = tokio::time::timeout(Duration::from_millis(100), cache.read())
+            .await
+            .expect("the write lock should not be held across the sleep");
+        assert_eq!(guard.len(), 2);
+    }
+}
@@ -1,6 +1,10 @@
 // src/dummy/mod.rs
 
+pub mod generator;
 pub mod hot_cache;
+pub mod replay;
+pub mod topbook;
 pub mod ws_server;
 
+pub use generator::{ConsistentGenerator, PriceGenerator, UniformGenerator};
 pub use hot_cache::HotCache;
\ No newline at end of file
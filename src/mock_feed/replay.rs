@@ -0,0 +1,102 @@
+// src/mock_feed/replay.rs
+
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc::Sender;
+
+use crate::parse::TopOfBookUpdate;
+
+pub use crate::parse::RecordedUpdate;
+
+
+/// How fast `replay` plays a recording back.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Back to back with no sleeps, for throughput benchmarks.
+    Max,
+    /// The original inter-message timing, for realistic latency testing.
+    Realtime,
+    /// The original timing sped up by the factor (`2.0` plays twice as fast, `0.5` half as
+    /// fast). A factor that is not positive and finite plays at `Max`.
+    Scaled(f64),
+}
+
+impl ReplaySpeed {
+    /// Wall-clock delay before a message recorded `elapsed_ms` after the first one.
+    fn offset(self, elapsed_ms: u64) -> Option<Duration> {
+        let factor = match self {
+            ReplaySpeed::Max => return None,
+            ReplaySpeed::Realtime => 1.0,
+            ReplaySpeed::Scaled(factor) if factor.is_finite() && factor > 0.0 => factor,
+            ReplaySpeed::Scaled(_) => return None,
+        };
+        Some(Duration::from_secs_f64(elapsed_ms as f64 / 1000.0 / factor))
+    }
+}
+
+/// Plays `recording` into `tx` at `speed`, returning how many updates were sent.
+///
+/// Each message is scheduled relative to the start of the replay rather than to the previous
+/// send, so slow consumers do not accumulate drift. `recv_ts` is re-stamped as each update is
+/// sent, keeping detection latency meaningful for the replayed run. Stops early once the
+/// receiver is dropped.
+pub async fn replay(recording: &[RecordedUpdate], tx: &Sender<TopOfBookUpdate>, speed: ReplaySpeed) -> usize {
+    let Some(first) = recording.first() else {
+        return 0;
+    };
+    let start = tokio::time::Instant::now();
+
+    for (sent, recorded) in recording.iter().enumerate() {
+        let elapsed_ms = recorded.timestamp_ms.saturating_sub(first.timestamp_ms);
+        if let Some(offset) = speed.offset(elapsed_ms) {
+            tokio::time::sleep_until(start + offset).await;
+        }
+        let update = TopOfBookUpdate { recv_ts: Instant::now(), ..recorded.update.clone() };
+        if tx.send(update).await.is_err() {
+            return sent;
+        }
+    }
+    recording.len()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::testutil::mock_update;
+
+    fn two_message_recording(delta_ms: u64) -> Vec<RecordedUpdate> {
+        vec![
+            RecordedUpdate { timestamp_ms: 1_700_000_000_000, update: mock_update("BTCUSDT", 50000.0, 50010.0) },
+            RecordedUpdate { timestamp_ms: 1_700_000_000_000 + delta_ms, update: mock_update("ETHBTC", 0.07, 0.0701) },
+        ]
+    }
+
+    #[tokio::test]
+    async fn realtime_replay_keeps_the_recorded_inter_arrival_delay() {
+        let recording = two_message_recording(200);
+        let (tx, mut rx) = mpsc::channel(4);
+
+        assert_eq!(replay(&recording, &tx, ReplaySpeed::Realtime).await, 2);
+        let first = rx.recv().await.unwrap();
+        let second = rx.recv().await.unwrap();
+
+        assert_eq!((first.symbol.as_str(), second.symbol.as_str()), ("BTCUSDT", "ETHBTC"));
+        let observed = second.recv_ts - first.recv_ts;
+        assert!(
+            observed >= Duration::from_millis(190) && observed < Duration::from_millis(400),
+            "expected ~200ms between messages, observed {observed:?}"
+        );
+    }
+
+    #[test]
+    fn speeds_scale_the_recorded_offsets() {
+        assert_eq!(ReplaySpeed::Max.offset(1000), None);
+        assert_eq!(ReplaySpeed::Realtime.offset(1000), Some(Duration::from_secs(1)));
+        assert_eq!(ReplaySpeed::Scaled(4.0).offset(1000), Some(Duration::from_millis(250)));
+        assert_eq!(ReplaySpeed::Scaled(0.5).offset(1000), Some(Duration::from_secs(2)));
+        assert_eq!(ReplaySpeed::Scaled(0.0).offset(1000), None);
+    }
+}
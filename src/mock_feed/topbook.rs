@@ -0,0 +1,158 @@
+// src/mock_feed/topbook.rs
+
+use std::time::Duration;
+
+use tokio::sync::mpsc::Sender;
+use tokio::task::JoinHandle;
+
+use crate::parse::wire::{encode, SymbolTable, PACKED_UPDATE_LEN};
+use crate::parse::TopOfBookUpdate;
+
+use super::hot_cache::RandomTickGenerator;
+
+
+/// Spawns an in-process feed that pushes `TopOfBookUpdate`s straight into `tx`.
+///
+/// The in-process alternative to `start_hot_cache_updater` + `ws_server::run`: the same
+/// `RandomTickGenerator` prices are produced, but there is no JSON, socket or parser in
+/// between, so arb benchmarks measure evaluation rather than transport. Every
+/// `interval_ms` one update per symbol is sent; the task ends once the receiver is dropped.
+pub fn start_topbook_feed(
+    symbols: Vec<String>,
+    interval_ms: u64,
+    seed: Option<u64>,
+    tx: Sender<TopOfBookUpdate>,
+) -> JoinHandle<()> {
+    spawn_feed(symbols, interval_ms, seed, tx, |update| update)
+}
+
+/// Same as `start_topbook_feed`, but each update crosses the channel as a 32-byte
+/// `parse::wire::PackedUpdate` record, measuring the binary transport's cost on the way into
+/// the arb loop. Symbols are interned in `symbols` order, so the receiver decodes with
+/// `SymbolTable::from_symbols(&symbols)`, e.g. through `parse::wire::unpack_loop`.
+pub fn start_packed_topbook_feed(
+    symbols: Vec<String>,
+    interval_ms: u64,
+    seed: Option<u64>,
+    tx: Sender<[u8; PACKED_UPDATE_LEN]>,
+) -> JoinHandle<()> {
+    let mut table = SymbolTable::from_symbols(&symbols);
+    spawn_feed(symbols, interval_ms, seed, tx, move |update| encode(&update, &mut table))
+}
+
+fn spawn_feed<T: Send + 'static>(
+    symbols: Vec<String>,
+    interval_ms: u64,
+    seed: Option<u64>,
+    tx: Sender<T>,
+    mut to_message: impl FnMut(TopOfBookUpdate) -> T + Send + 'static,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut generator = RandomTickGenerator::new(seed);
+        let interval = Duration::from_millis(interval_ms);
+
+        loop {
+            for symbol in &symbols {
+                if tx.send(to_message(generator.next_quote(symbol))).await.is_err() {
+                    return;
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::arb::{arb_loop, ArbEvaluator, ArbOpportunity, HaltHandle, ImpliedQuote, StdoutSink};
+    use crate::parse::wire::unpack_loop;
+    use crate::price_path::PricingPath;
+
+    #[derive(Default)]
+    struct CountingEvaluator {
+        processed: AtomicUsize,
+    }
+
+    impl ArbEvaluator for CountingEvaluator {
+        fn process_update(&self, _update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+            self.processed.fetch_add(1, Ordering::Relaxed);
+            None
+        }
+
+        fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+            HashMap::new()
+        }
+
+        fn halt_handle(&self) -> HaltHandle {
+            HaltHandle::default()
+        }
+
+        fn evaluate_path(&self, _path: &PricingPath) -> Option<f64> {
+            None
+        }
+
+        fn implied_quote(&self, _path: &PricingPath) -> Option<ImpliedQuote> {
+            None
+        }
+
+        fn invalidate_all(&self) {}
+
+        fn top_paths(&self, _n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+            Vec::new()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_feed_drives_arb_loop_without_sockets() {
+        let symbols: Vec<String> = ["BTCUSDT", "ETHBTC", "ETHUSDT"].map(String::from).to_vec();
+        let (tx, rx) = mpsc::channel::<TopOfBookUpdate>(16);
+        let evaluator = Arc::new(CountingEvaluator::default());
+
+        let feed = start_topbook_feed(symbols.clone(), 1, Some(7), tx);
+        let arb = tokio::spawn(arb_loop(rx, evaluator.clone(), Arc::new(StdoutSink)));
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while evaluator.processed.load(Ordering::Relaxed) < 30 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("arb loop should receive updates from the in-process feed");
+
+        // Dropping the receiver (by ending the arb loop) stops the feed
+        arb.abort();
+        let _ = arb.await;
+        tokio::time::timeout(Duration::from_secs(1), feed)
+            .await
+            .expect("feed should stop once the receiver is gone")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_packed_feed_decodes_to_the_same_quotes() {
+        let symbols: Vec<String> = ["BTCUSDT", "ETHBTC", "ETHUSDT"].map(String::from).to_vec();
+        let (packed_tx, packed_rx) = mpsc::channel(16);
+        let (tx, mut rx) = mpsc::channel::<TopOfBookUpdate>(16);
+
+        start_packed_topbook_feed(symbols.clone(), 1, Some(7), packed_tx);
+        tokio::spawn(unpack_loop(packed_rx, SymbolTable::from_symbols(&symbols), tx));
+
+        // The same seed produces the same prices as the unpacked feed
+        let mut generator = RandomTickGenerator::new(Some(7));
+        for symbol in symbols.iter().cycle().take(9) {
+            let expected = generator.next_quote(symbol);
+            let update = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+            assert_eq!(update.symbol, expected.symbol);
+            assert_eq!(update.bid_price.to_bits(), expected.bid_price.to_bits());
+            assert_eq!(update.ask_price.to_bits(), expected.ask_price.to_bits());
+        }
+    }
+}
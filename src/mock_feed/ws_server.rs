@@ -1,59 +1,251 @@
 // src/dummy/ws_server.rs
 
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
-use tokio::{net::TcpListener, time::{sleep, Duration}};
-use tokio_tungstenite::{accept_async, tungstenite::{Message, Utf8Bytes}};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::Semaphore,
+    task::{JoinHandle, JoinSet},
+    time::{interval, Duration},
+};
+use tokio_tungstenite::{
+    accept_async, accept_hdr_async,
+    tungstenite::{
+        handshake::server::{Callback, ErrorResponse, Request, Response},
+        protocol::{frame::coding::CloseCode, CloseFrame},
+        Message, Utf8Bytes,
+    },
+};
 use futures_util::{StreamExt, SinkExt};
 
+use crate::price_path::normalize_symbol;
 
 use super::hot_cache::HotCache;
 
 
+/// Concurrent client connections served by `run` and `run_silent_after`.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 64;
+
+/// How often `run` sweeps the cache for changed symbols to push.
+pub const DEFAULT_SWEEP_INTERVAL: Duration = Duration::from_millis(100);
+
+
 pub async fn run(cache: HotCache) {
+    run_silent_after(cache, None).await
+}
+
+/// Like `run`, but each connection goes silent (open, yet sending nothing) after
+/// `tick_rounds` rounds of data. Simulates a stalled stream for watchdog testing.
+pub async fn run_silent_after(cache: HotCache, tick_rounds: Option<usize>) {
+    serve(cache, tick_rounds, DEFAULT_MAX_CONNECTIONS, DEFAULT_SWEEP_INTERVAL).await
+}
+
+/// Like `run`, sweeping the cache every `sweep_interval` instead of `DEFAULT_SWEEP_INTERVAL`.
+///
+/// Only symbols whose cached tick changed since they were last sent are pushed, so an interval
+/// shorter than the cache's update rate forwards every update as it lands, without repeats.
+pub async fn run_with_sweep_interval(cache: HotCache, sweep_interval: Duration) {
+    serve(cache, None, DEFAULT_MAX_CONNECTIONS, sweep_interval).await
+}
+
+/// Like `run`, serving at most `max_connections` clients at once.
+///
+/// Connections beyond the limit complete the WebSocket handshake and are immediately closed
+/// with code 1013 (Try Again Later); a slot frees up as soon as a served client disconnects.
+pub async fn run_with_max_connections(cache: HotCache, max_connections: usize) {
+    serve(cache, None, max_connections, DEFAULT_SWEEP_INTERVAL).await
+}
+
+/// A mock server on an address of the caller's choosing that can be stopped, e.g. to test
+/// client reconnects or to run beside another server in the same test process.
+///
+/// Dropping it stops it too.
+pub struct MockServer {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Serves `cache` like `run` on `addr`; `127.0.0.1:0` picks an ephemeral port.
+    pub async fn start(cache: HotCache, addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let task = tokio::spawn(serve_on(listener, cache, None, DEFAULT_MAX_CONNECTIONS, DEFAULT_SWEEP_INTERVAL));
+        Ok(Self { addr, task })
+    }
+
+    /// The bound address, with the actual port when started on port 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Stops accepting and drops every open connection without a close handshake, as a crashed
+    /// server would. The address is free to `start` again once this returns.
+    pub async fn stop(mut self) {
+        self.task.abort();
+        let _ = (&mut self.task).await;
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve(cache: HotCache, tick_rounds: Option<usize>, max_connections: usize, sweep_interval: Duration) {
     let listener = TcpListener::bind("127.0.0.1:9001").await.unwrap();
-    println!("🟢 Dummy WebSocket server on ws://127.0.0.1:9001");
+    serve_on(listener, cache, tick_rounds, max_connections, sweep_interval).await
+}
+
+/// Accepts clients on `listener` until dropped, which also drops every connection it serves.
+async fn serve_on(
+    listener: TcpListener,
+    cache: HotCache,
+    tick_rounds: Option<usize>,
+    max_connections: usize,
+    sweep_interval: Duration,
+) {
+    if let Ok(addr) = listener.local_addr() {
+        println!("🟢 Dummy WebSocket server on ws://{addr} (max {max_connections} connections)");
+    }
+    let slots = Arc::new(Semaphore::new(max_connections));
+    let mut connections = JoinSet::new();
     while let Ok((stream, _)) = listener.accept().await {
+        while connections.try_join_next().is_some() {}
+        let Ok(permit) = Arc::clone(&slots).try_acquire_owned() else {
+            eprintln!("Connection limit ({max_connections}) reached; rejecting client");
+            connections.spawn(reject_connection(stream));
+            continue;
+        };
         let cache = Arc::clone(&cache);
-        tokio::spawn(handle_connection(stream, cache));
+        connections.spawn(async move {
+            handle_connection(stream, cache, tick_rounds, sweep_interval).await;
+            drop(permit);
+        });
     }
 }
 
-async fn handle_connection(stream: tokio::net::TcpStream, cache: HotCache) {
-    let mut ws_stream = accept_async(stream).await.unwrap();
-    println!("New connection!");
-    
-    let msg = match ws_stream.next().await {
-        Some(Ok(Message::Text(txt))) => txt,
-        _ => {
-            eprintln!("No valid subscribe message received");
-            return;
-        }
+/// Completes the handshake only to tell the client why it is being turned away.
+async fn reject_connection(stream: TcpStream) {
+    let Ok(mut ws_stream) = accept_async(stream).await else {
+        return;
     };
+    let close = CloseFrame { code: CloseCode::Again, reason: "connection limit reached".into() };
+    let _ = ws_stream.close(Some(close)).await;
+}
 
-    let parsed: serde_json::Value = serde_json::from_str(&msg).expect("Invalid JSON");
-    let symbols: Vec<String>  = parsed["params"]
-        .as_array()
-        .unwrap_or(&vec![])
-        .iter()
-        .filter_map(|v| v.as_str())
-        .map(|s| s.trim_end_matches("@bookTicker").to_uppercase())
-        .collect();
+/// Serves one client: streams named in a raw-stream URL (`/ws/btcusdt@bookTicker/...`) are
+/// subscribed on connect, every SUBSCRIBE frame adds its symbols and is acknowledged
+/// Binance-style (`{"result":null,"id":N}`), while every `sweep_interval` the cached tick of
+/// each subscribed symbol is pushed if its update id `u` changed since it was last sent, until
+/// `tick_rounds` (if set) rounds have sent something.
+async fn handle_connection(stream: TcpStream, cache: HotCache, tick_rounds: Option<usize>, sweep_interval: Duration) {
+    let mut request_path = String::new();
+    let mut ws_stream = accept_hdr_async(stream, CapturePath(&mut request_path)).await.unwrap();
+    println!("New connection!");
 
-    println!("Client subscribed to: {:?}", symbols);
+    let mut symbols = parse_stream_path(&request_path);
+    if !symbols.is_empty() {
+        println!("Client subscribed by URL to: {:?}", symbols);
+    }
+    let mut last_sent: HashMap<String, u64> = HashMap::new();
+    let mut ticker = interval(sweep_interval);
+    let mut rounds_sent = 0;
 
     loop {
-        let guard = cache.read().await;
+        tokio::select! {
+            msg = ws_stream.next() => {
+                let txt = match msg {
+                    Some(Ok(Message::Text(txt))) => txt,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => {
+                        eprintln!("Client disconnected");
+                        return;
+                    }
+                    Some(Ok(_)) => continue,
+                };
 
-        for symbol in &symbols {
-            if let Some(msg) = guard.get(symbol) {
-                if ws_stream.send(Message::Text(Utf8Bytes::from(msg))).await.is_err() {
+                let Some((id, subscribed)) = parse_subscribe(&txt) else {
+                    eprintln!("No valid subscribe message received");
+                    continue;
+                };
+                println!("Client subscribed to: {:?}", subscribed);
+                symbols.extend(subscribed);
+
+                let ack = serde_json::json!({ "result": null, "id": id }).to_string();
+                if ws_stream.send(Message::Text(Utf8Bytes::from(ack))).await.is_err() {
                     eprintln!("Client disconnected");
                     return;
                 }
             }
+            _ = ticker.tick() => {
+                if tick_rounds.is_some_and(|max| rounds_sent >= max) {
+                    continue;
+                }
+                let guard = cache.read().await;
+
+                let mut sent_any = false;
+                for symbol in &symbols {
+                    let Some(msg) = guard.get(symbol) else { continue };
+                    let update_id = update_id(msg);
+                    if update_id.is_some() && update_id == last_sent.get(symbol).copied() {
+                        continue;
+                    }
+                    if ws_stream.send(Message::Text(Utf8Bytes::from(msg))).await.is_err() {
+                        eprintln!("Client disconnected");
+                        return;
+                    }
+                    if let Some(update_id) = update_id {
+                        last_sent.insert(symbol.clone(), update_id);
+                    }
+                    sent_any = true;
+                }
+                if sent_any {
+                    rounds_sent += 1;
+                }
+            }
         }
+    }
+}
+
+/// The `u` (order book update id) of a cached bookTicker message; `None` sends it every sweep.
+fn update_id(msg: &str) -> Option<u64> {
+    let parsed: serde_json::Value = serde_json::from_str(msg).ok()?;
+    parsed["u"].as_u64()
+}
+
+/// Handshake callback recording the request path, which names raw-stream subscriptions.
+struct CapturePath<'a>(&'a mut String);
 
-        sleep(Duration::from_millis(100)).await;
+impl Callback for CapturePath<'_> {
+    fn on_request(self, request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+        *self.0 = request.uri().path().to_string();
+        Ok(response)
     }
-}
\ No newline at end of file
+}
+
+/// Normalized symbols named in a raw-stream request path such as `/ws/btcusdt@bookTicker`.
+fn parse_stream_path(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter_map(|stream| stream.strip_suffix("@bookTicker"))
+        .map(normalize_symbol)
+        .collect()
+}
+
+/// Extracts the request id and normalized (upper-cased) symbols from a `SUBSCRIBE` message.
+fn parse_subscribe(msg: &str) -> Option<(serde_json::Value, Vec<String>)> {
+    let parsed: serde_json::Value = serde_json::from_str(msg).ok()?;
+    if parsed["method"] != "SUBSCRIBE" {
+        return None;
+    }
+    let symbols: Vec<String> = parsed["params"]
+        .as_array()?
+        .iter()
+        .filter_map(|v| v.as_str())
+        .map(|s| normalize_symbol(s.trim_end_matches("@bookTicker")))
+        .collect();
+    Some((parsed["id"].clone(), symbols))
+}
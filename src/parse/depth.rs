@@ -0,0 +1,157 @@
+// src/parse/depth.rs
+use std::time::Instant;
+
+use anyhow::Result;
+use serde::Deserialize;
+
+
+/// One price level of an order book side.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthLevel {
+    pub price: f64,
+    pub qty: f64,
+}
+
+/// The top N levels of one symbol's book, best first on each side.
+#[derive(Debug, Clone)]
+pub struct DepthSnapshot {
+    pub symbol: String,
+    pub bids: Vec<DepthLevel>,
+    pub asks: Vec<DepthLevel>,
+}
+
+impl DepthSnapshot {
+    /// Level-1 prices as a top-of-book update, if both sides are populated.
+    ///
+    /// Snapshots aren't timestamped, so `recv_ts` is the time of the call.
+    pub fn top_of_book(&self) -> Option<super::TopOfBookUpdate> {
+        Some(super::TopOfBookUpdate {
+            symbol: self.symbol.clone(),
+            bid_price: self.bids.first()?.price,
+            ask_price: self.asks.first()?.price,
+            recv_ts: Instant::now(),
+            event_time_ms: None,
+        })
+    }
+}
+
+
+/// Parses a Binance partial book depth payload (`<symbol>@depth5` / `@depth10` / `@depth20`).
+///
+/// The payload doesn't name its symbol, so the caller supplies the one it subscribed to:
+/// `{"lastUpdateId":160,"bids":[["0.0024","10"]],"asks":[["0.0026","100"]]}`
+pub fn parse_depth(raw: &[u8], symbol: &str) -> Result<DepthSnapshot> {
+    let parsed: PartialDepthWs = serde_json::from_slice(raw)?;
+    Ok(DepthSnapshot {
+        symbol: symbol.to_string(),
+        bids: parse_levels(parsed.bids)?,
+        asks: parse_levels(parsed.asks)?,
+    })
+}
+
+fn parse_levels(levels: Vec<(String, String)>) -> Result<Vec<DepthLevel>> {
+    levels
+        .into_iter()
+        .map(|(price, qty)| Ok(DepthLevel { price: price.parse()?, qty: qty.parse()? }))
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct PartialDepthWs {
+    bids: Vec<(String, String)>,
+    asks: Vec<(String, String)>,
+}
+
+
+/// Volume-weighted average price to fill `qty` (base units) walking `levels` best-first.
+///
+/// Returns `None` if the visible levels hold less than `qty` or `qty` isn't positive.
+pub fn vwap(levels: &[DepthLevel], qty: f64) -> Option<f64> {
+    if qty <= 0.0 {
+        return None;
+    }
+    let mut remaining = qty;
+    let mut cost = 0.0;
+    for level in levels {
+        let fill = remaining.min(level.qty);
+        cost += fill * level.price;
+        remaining -= fill;
+        if remaining <= 0.0 {
+            return Some(cost / qty);
+        }
+    }
+    None
+}
+
+/// Volume-weighted average price when spending `notional` (quote units) walking `levels`.
+///
+/// The quote-denominated counterpart of `vwap`, for legs that buy the base asset with a
+/// known amount of the quote asset. Returns `None` if the levels can't absorb `notional`.
+pub fn vwap_for_notional(levels: &[DepthLevel], notional: f64) -> Option<f64> {
+    if notional <= 0.0 {
+        return None;
+    }
+    let mut remaining = notional;
+    let mut filled_qty = 0.0;
+    for level in levels {
+        let spend = remaining.min(level.price * level.qty);
+        filled_qty += spend / level.price;
+        remaining -= spend;
+        if remaining <= 0.0 {
+            return Some(notional / filled_qty);
+        }
+    }
+    None
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::assert_return_eq;
+
+    fn levels(raw: &[(f64, f64)]) -> Vec<DepthLevel> {
+        raw.iter().map(|&(price, qty)| DepthLevel { price, qty }).collect()
+    }
+
+    #[test]
+    fn test_vwap_walks_levels_with_size() {
+        let asks = levels(&[(100.0, 1.0), (101.0, 2.0), (103.0, 5.0)]);
+
+        // Tiny size fills entirely at level 1
+        assert_return_eq(vwap(&asks, 0.001).unwrap(), 100.0, 1e-12);
+        assert_return_eq(vwap(&asks, 1.0).unwrap(), 100.0, 1e-12);
+
+        // 1 @ 100 + 2 @ 101 + 1 @ 103 = 405 over 4 units
+        assert_return_eq(vwap(&asks, 4.0).unwrap(), 101.25, 1e-12);
+        assert!(vwap(&asks, 8.0).unwrap() > vwap(&asks, 4.0).unwrap());
+
+        // More than the visible book can fill
+        assert!(vwap(&asks, 8.5).is_none());
+        assert!(vwap(&asks, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_vwap_for_notional_matches_qty_vwap() {
+        let asks = levels(&[(100.0, 1.0), (101.0, 2.0), (103.0, 5.0)]);
+
+        assert_return_eq(vwap_for_notional(&asks, 50.0).unwrap(), 100.0, 1e-12);
+        // Spending 405 buys exactly the 4 units priced above
+        assert_return_eq(vwap_for_notional(&asks, 405.0).unwrap(), 101.25, 1e-12);
+        assert!(vwap_for_notional(&asks, 1_000.0).is_none());
+    }
+
+    #[test]
+    fn test_parse_partial_depth() {
+        let raw = br#"{"lastUpdateId":160,"bids":[["0.0024","10"],["0.0023","5.5"]],"asks":[["0.0026","100"]]}"#;
+        let book = parse_depth(raw, "BNBBTC").unwrap();
+
+        assert_eq!(book.symbol, "BNBBTC");
+        assert_eq!(book.bids, levels(&[(0.0024, 10.0), (0.0023, 5.5)]));
+        assert_eq!(book.asks, levels(&[(0.0026, 100.0)]));
+
+        let top = book.top_of_book().unwrap();
+        assert_eq!(top.bid_price, 0.0024);
+        assert_eq!(top.ask_price, 0.0026);
+    }
+}
@@ -0,0 +1,54 @@
+// src/parse/fallback.rs
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::Result;
+use bytes::Bytes;
+use tracing::warn;
+
+use super::man_scan::ManualScanParser;
+use super::srd_jsn::SerdeJsonParser;
+use super::{BookTickerParser, TopOfBookUpdate};
+
+
+/// Parses with `ManualScanParser` and retries a frame it rejects with `SerdeJsonParser`.
+///
+/// Well-formed frames take the fast path; only frames the manual scanner can't handle pay for
+/// full deserialization. A frame both parsers reject returns serde's error.
+#[derive(Debug, Default)]
+pub struct FallbackParser {
+    fallbacks: AtomicU64,
+}
+
+impl FallbackParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Frames the manual parser rejected and serde was retried on, successful or not.
+    pub fn fallback_count(&self) -> u64 {
+        self.fallbacks.load(Ordering::Relaxed)
+    }
+
+    fn record_fallback(&self, raw: &Bytes, error: &anyhow::Error) {
+        self.fallbacks.fetch_add(1, Ordering::Relaxed);
+        warn!(%error, frame = %String::from_utf8_lossy(raw), "Manual parser failed; retrying with serde");
+    }
+}
+
+impl BookTickerParser for FallbackParser {
+    fn parse(&self, raw: &Bytes) -> Result<TopOfBookUpdate> {
+        ManualScanParser::new().parse(raw).or_else(|e| {
+            self.record_fallback(raw, &e);
+            SerdeJsonParser::new().parse(raw)
+        })
+    }
+
+    /// Falls back for the whole frame, so an array is never half manual, half serde.
+    fn parse_batch(&self, raw: &Bytes) -> Result<Vec<TopOfBookUpdate>> {
+        ManualScanParser::new().parse_batch(raw).or_else(|e| {
+            self.record_fallback(raw, &e);
+            SerdeJsonParser::new().parse_batch(raw)
+        })
+    }
+}
@@ -1,43 +1,157 @@
 // src/parse/man_scan.rs
 
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use bytes::Bytes;
 
-use super::{TopOfBookUpdate, BookTickerParser};
+use crate::price_path::normalize_symbol;
 
+use super::{is_json_array, parse_price, TopOfBookUpdate, BookTickerParser};
 
-#[allow(dead_code)]
-pub struct ManualScanParser;
+
+/// Byte-scanning bookTicker parser. By default an object whose `e` names another event is
+/// rejected with `UnexpectedEventType` before any other field is read.
+#[derive(Debug, Clone, Copy)]
+pub struct ManualScanParser {
+    check_event_type: bool,
+}
+
+impl Default for ManualScanParser {
+    fn default() -> Self {
+        Self { check_event_type: true }
+    }
+}
+
+impl ManualScanParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turns the `e` check off (or back on), e.g. for a feed known to carry only bookTicker.
+    pub fn with_event_type_check(mut self, check_event_type: bool) -> Self {
+        self.check_event_type = check_event_type;
+        self
+    }
+}
 
 impl BookTickerParser for ManualScanParser {
     fn parse(&self, raw: &Bytes) -> Result<TopOfBookUpdate> {
         let text = std::str::from_utf8(raw)?;
+        parse_object(text, self.check_event_type)
+    }
 
-        let symbol = extract_json_field(text, "\"s\":\"")?;
-        let bid_str = extract_json_field(text, "\"b\":\"")?;
-        let ask_str = extract_json_field(text, "\"a\":\"")?;
-
-        let bid_price: f64 = bid_str.parse()?;
-        let ask_price: f64 = ask_str.parse()?;
+    /// Scans each `{...}` object of a `!bookTicker@arr` frame (bookTicker objects are flat,
+    /// so braces never nest); single-object frames go through `parse`.
+    fn parse_batch(&self, raw: &Bytes) -> Result<Vec<TopOfBookUpdate>> {
+        if !is_json_array(raw) {
+            return Ok(vec![self.parse(raw)?]);
+        }
+        let text = std::str::from_utf8(raw)?;
+        let mut updates = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| anyhow!("Unterminated object in array frame"))? + start;
+            updates.push(parse_object(&rest[start..=end], self.check_event_type)?);
+            rest = &rest[end + 1..];
+        }
+        Ok(updates)
+    }
+}
 
-        Ok(TopOfBookUpdate {
-            symbol,
-            bid_price,
-            ask_price
-        })
+/// Extracts the symbol and top-of-book prices from a single bookTicker object, first checking
+/// its `e` when `check_event_type` is set.
+fn parse_object(text: &str, check_event_type: bool) -> Result<TopOfBookUpdate> {
+    if check_event_type {
+        let event_type = find_json_value(text, "\"e\"").map(|_| extract_json_field(text, "\"e\"")).transpose()?;
+        super::check_event_type(event_type)?;
     }
+    let symbol = extract_json_field(text, "\"s\"")?;
+    let bid_str = extract_json_field(text, "\"b\"")?;
+    let ask_str = extract_json_field(text, "\"a\"")?;
+    let event_time_ms = extract_json_u64(text, "\"E\"")?;
+
+    let bid_price = parse_price(bid_str)?;
+    let ask_price = parse_price(ask_str)?;
+
+    Ok(TopOfBookUpdate::try_new(normalize_symbol(symbol), bid_price, ask_price)?.with_event_time(event_time_ms))
 }
 
-#[allow(dead_code)]
-fn extract_json_field(
-    text: &str,
+/// Returns the string value of the quoted `key` (e.g. `"\"s\""`) in a flat JSON object.
+///
+/// Field order doesn't matter and whitespace is allowed around the colon and between fields.
+/// The key only matches at a field boundary (after `{` or `,`), so the same text inside a value
+/// is skipped. Values containing a JSON escape are rejected rather than returned undecoded, so
+/// the scanner never disagrees with serde (`FallbackParser` recovers such frames).
+fn extract_json_field<'a>(
+    text: &'a str,
     key: &str
-) -> Result<String> {
-    let start = text.find(key)
-        .ok_or_else(|| anyhow!("Key not found: {}", key))? + key.len();
+) -> Result<&'a str> {
+    let quote = find_json_value(text, key).ok_or_else(|| anyhow!("Key not found: {}", key))?;
+    if text.as_bytes()[quote] != b'"' {
+        bail!("Value of key {} is not a string", key);
+    }
+    let start = quote + 1;
     let end = text[start..]
         .find('"')
         .ok_or_else(|| anyhow!("No ending quote after key: {}", key))? + start;
+    let value = &text[start..end];
+    if value.contains('\\') {
+        bail!("Escaped value of key {} is not supported", key);
+    }
+    Ok(value)
+}
+
+/// Returns the unsigned integer value of the quoted `key`, or `None` if the object lacks it
+/// or the value is `null`.
+///
+/// Anything but plain digits up to the next field (a sign, fraction, exponent or string) is
+/// rejected, as serde would for a `u64`.
+fn extract_json_u64(text: &str, key: &str) -> Result<Option<u64>> {
+    let Some(start) = find_json_value(text, key) else { return Ok(None) };
+    let bytes = text.as_bytes();
+    let null = text[start..].starts_with("null");
+    let end = if null {
+        start + "null".len()
+    } else {
+        start + bytes[start..].iter().take_while(|b| b.is_ascii_digit()).count()
+    };
+    let next = skip_whitespace(bytes, end);
+    if !matches!(bytes.get(next), Some(b',' | b'}')) {
+        bail!("Value of key {} is not an unsigned integer", key);
+    }
+    if null {
+        return Ok(None);
+    }
+    text[start..end].parse().map(Some).with_context(|| format!("Value of key {} is out of range", key))
+}
 
-    Ok(text[start..end].to_string())
-}
\ No newline at end of file
+/// Index of the first byte of the quoted `key`'s value, matching only at a field boundary.
+fn find_json_value(text: &str, key: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut from = 0;
+    while let Some(offset) = text[from..].find(key) {
+        let key_start = from + offset;
+        from = key_start + 1;
+
+        let preceding = bytes[..key_start].iter().rev().find(|b| !b.is_ascii_whitespace());
+        if !matches!(preceding, Some(b'{' | b',')) {
+            continue;
+        }
+        let colon = skip_whitespace(bytes, key_start + key.len());
+        if bytes.get(colon) != Some(&b':') {
+            continue;
+        }
+        let value = skip_whitespace(bytes, colon + 1);
+        return (value < bytes.len()).then_some(value);
+    }
+    None
+}
+
+/// Index of the first non-whitespace byte at or after `from`.
+fn skip_whitespace(bytes: &[u8], from: usize) -> usize {
+    bytes[from.min(bytes.len())..]
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .map_or(bytes.len(), |offset| from + offset)
+}
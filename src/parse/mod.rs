@@ -2,40 +2,150 @@
 
 pub mod srd_jsn;
 pub mod man_scan;
+pub mod depth;
+pub mod fallback;
+pub mod quote_counter;
+pub mod wire;
 
 use std::sync::Arc;
-use anyhow::Result;
+use std::time::Instant;
+use anyhow::{bail, Context, Result};
 use bytes::Bytes;
+use serde::Deserialize;
 use tokio::sync::mpsc::{Receiver, Sender};
 
+use quote_counter::QuoteCounter;
+
 
 #[derive(Debug, Clone)]
 pub struct TopOfBookUpdate {
     pub symbol: String,
     pub bid_price: f64,
     pub ask_price: f64,
+    /// When the quote entered the process: parsed off its WebSocket frame, or generated by a
+    /// mock feed. Scanners measure `ArbOpportunity::detection_latency` from here.
+    pub recv_ts: Instant,
+    /// Exchange event time (`E`, milliseconds since the Unix epoch) when the payload carries
+    /// one; scanners judge staleness by it, falling back to local receipt when absent.
+    pub event_time_ms: Option<u64>,
+}
+
+impl TopOfBookUpdate {
+    /// A quote for `symbol` received now, if its prices are usable: finite, positive and not
+    /// crossed (`bid <= ask`). The parsers build every update through this, so a malformed
+    /// quote becomes a parse error instead of reaching the scanners.
+    pub fn try_new(symbol: impl Into<String>, bid_price: f64, ask_price: f64) -> Result<Self, QuoteError> {
+        if !(bid_price.is_finite() && ask_price.is_finite()) {
+            return Err(QuoteError::NonFinite { bid_price, ask_price });
+        }
+        if bid_price <= 0.0 || ask_price <= 0.0 {
+            return Err(QuoteError::NonPositive { bid_price, ask_price });
+        }
+        if bid_price > ask_price {
+            return Err(QuoteError::Crossed { bid_price, ask_price });
+        }
+        Ok(Self { symbol: symbol.into(), bid_price, ask_price, recv_ts: Instant::now(), event_time_ms: None })
+    }
+
+    /// Stamps the exchange event time carried by the payload, if any.
+    pub fn with_event_time(mut self, event_time_ms: Option<u64>) -> Self {
+        self.event_time_ms = event_time_ms;
+        self
+    }
+}
+
+/// One recorded update and the wall-clock time it was captured at, in milliseconds. Replayed
+/// by `mock_feed::replay` and looked up by `execution::QuoteTimeline`.
+#[derive(Debug, Clone)]
+pub struct RecordedUpdate {
+    pub timestamp_ms: u64,
+    pub update: TopOfBookUpdate,
 }
 
+/// Why `TopOfBookUpdate::try_new` refused a quote. Parsers return it inside the
+/// `anyhow::Error`, so callers can `downcast_ref::<QuoteError>()` to tell it from a malformed frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuoteError {
+    NonFinite { bid_price: f64, ask_price: f64 },
+    NonPositive { bid_price: f64, ask_price: f64 },
+    /// The bid is above the ask.
+    Crossed { bid_price: f64, ask_price: f64 },
+}
 
+impl std::fmt::Display for QuoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NonFinite { bid_price, ask_price } => write!(f, "Non-finite quote (bid {bid_price}, ask {ask_price})"),
+            Self::NonPositive { bid_price, ask_price } => write!(f, "Non-positive quote (bid {bid_price}, ask {ask_price})"),
+            Self::Crossed { bid_price, ask_price } => write!(f, "Crossed quote (bid {bid_price} > ask {ask_price})"),
+        }
+    }
+}
+
+impl std::error::Error for QuoteError {}
+
+
+/// Which `BookTickerParser` decodes frames, selectable at runtime via `[pipeline] parser`.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ParserKind {
+    /// `ManualScanParser`: byte scanning, fastest.
+    Manual,
+    /// `SerdeJsonParser`: full deserialization, most tolerant.
+    Serde,
+    /// `FallbackParser`: manual first, serde for frames the manual scanner rejects.
+    Fallback,
+}
+
+/// The parser chosen by the `manual_parser` / `serde_parser` cargo feature.
+impl Default for ParserKind {
+    fn default() -> Self {
+        if cfg!(feature = "serde_parser") { Self::Serde } else { Self::Manual }
+    }
+}
+
+/// Parses frames with the feature-selected parser (see `ParserKind::default`).
 pub async fn parser_loop(
+    ws_rx: Receiver<Bytes>,
+    parser_tx: Sender<TopOfBookUpdate>,
+) -> Result<()> {
+    parser_loop_with_kind(ws_rx, parser_tx, ParserKind::default()).await
+}
+
+pub async fn parser_loop_with_kind(
+    ws_rx: Receiver<Bytes>,
+    parser_tx: Sender<TopOfBookUpdate>,
+    kind: ParserKind,
+) -> Result<()> {
+    parser_loop_with_quote_counter(ws_rx, parser_tx, kind, Arc::new(QuoteCounter::new())).await
+}
+
+/// Like `parser_loop_with_kind`, recording every parsed update's symbol in `counter`
+/// (before the hand-off to the arb channel, so quotes dropped there still count).
+pub async fn parser_loop_with_quote_counter(
     mut ws_rx: Receiver<Bytes>,
     parser_tx: Sender<TopOfBookUpdate>,
+    kind: ParserKind,
+    counter: Arc<QuoteCounter>,
 ) -> Result<()> {
-    
-    let parser: Arc<dyn BookTickerParser + Send + Sync> = create_parser();
+    let parser = create_parser(kind);
 
     while let Some(raw_msg) = ws_rx.recv().await {
-        match parser.parse(&raw_msg) {
-            Ok(update) => {
-                #[cfg(feature = "print_parsed")]
-                {
-                    println!("{:?}", update);
-                }
-                if let Err(e) = parser_tx.try_send(update) {
-                    eprintln!("Failed to send parsed update: {e}");
+        match parser.parse_batch(&raw_msg) {
+            Ok(updates) => {
+                for update in updates {
+                    counter.record(&update.symbol);
+                    #[cfg(feature = "print_parsed")]
+                    {
+                        println!("{:?}", update);
+                    }
+                    if let Err(e) = parser_tx.try_send(update) {
+                        eprintln!("Failed to send parsed update: {e}");
+                    }
                 }
             }
             Err(e) => {
+                counter.record_parse_error();
                 eprintln!("Failed to parse incoming message: {e}");
             }
         }
@@ -45,26 +155,77 @@ pub async fn parser_loop(
 
 pub trait BookTickerParser {
     fn parse(&self, raw: &Bytes) -> Result<TopOfBookUpdate>;
+
+    /// Parses a frame that may carry several bookTicker objects, such as the
+    /// array-wrapped `!bookTicker@arr` all-market stream.
+    ///
+    /// The default implementation treats the frame as a single object.
+    fn parse_batch(&self, raw: &Bytes) -> Result<Vec<TopOfBookUpdate>> {
+        Ok(vec![self.parse(raw)?])
+    }
 }
 
-fn create_parser() -> Arc<dyn BookTickerParser + Send + Sync> {
-    #[cfg(all(feature = "serde_parser", not(feature = "manual_parser")))]
-    {
-        return Arc::new(srd_jsn::SerdeJsonParser);
+/// The `e` value of the only stream both parsers decode.
+pub const BOOK_TICKER_EVENT: &str = "bookTicker";
+
+/// A frame from a stream other than bookTicker (e.g. `trade` or `depthUpdate`), rejected by a
+/// parser with its event-type check on. Returned inside the `anyhow::Error`, so callers can
+/// `downcast_ref::<UnexpectedEventType>()` to tell it from a malformed frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnexpectedEventType {
+    pub event_type: String,
+}
+
+impl std::fmt::Display for UnexpectedEventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unexpected event type '{}', expected '{BOOK_TICKER_EVENT}'", self.event_type)
     }
+}
 
-    #[cfg(all(feature = "manual_parser", not(feature = "serde_parser")))]
-    {
-        return Arc::new(man_scan::ManualScanParser);
+impl std::error::Error for UnexpectedEventType {}
+
+/// Rejects an `e` other than `bookTicker`. A missing `e` passes: Binance's spot bookTicker
+/// payloads carry none, while every other stream names its event.
+fn check_event_type(event_type: Option<&str>) -> Result<()> {
+    match event_type {
+        Some(event_type) if event_type != BOOK_TICKER_EVENT => {
+            Err(UnexpectedEventType { event_type: event_type.to_string() }.into())
+        }
+        _ => Ok(()),
     }
+}
 
-    #[cfg(not(any(feature = "serde_parser", feature = "manual_parser")))]
-    compile_error!("At least one parser feature (`serde_parser` or `manual_parser`) must be enabled.");
+/// Parses a price string as both parsers must: Binance's fixed decimals (`"0.00000100"`) and
+/// anything else `f64::from_str` reads as a finite number (`"1E-8"`, `"1.5e+3"`).
+///
+/// `"NaN"` and `"inf"` parse as `f64` but are never prices, so they are rejected here too.
+fn parse_price(text: &str) -> Result<f64> {
+    let price: f64 = text.parse().with_context(|| format!("Invalid price '{text}'"))?;
+    if !price.is_finite() {
+        bail!("Non-finite price '{text}'");
+    }
+    Ok(price)
+}
+
+/// Returns true if the frame's first non-whitespace byte opens a JSON array.
+fn is_json_array(raw: &[u8]) -> bool {
+    raw.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'[')
+}
 
-    #[cfg(all(feature = "serde_parser", feature = "manual_parser"))]
-    compile_error!("Cannot enable both `serde_parser` and `manual_parser` features at the same time.");
+pub fn create_parser(kind: ParserKind) -> Arc<dyn BookTickerParser + Send + Sync> {
+    match kind {
+        ParserKind::Manual => Arc::new(man_scan::ManualScanParser::new()),
+        ParserKind::Serde => Arc::new(srd_jsn::SerdeJsonParser::new()),
+        ParserKind::Fallback => Arc::new(fallback::FallbackParser::new()),
+    }
 }
 
+#[cfg(not(any(feature = "serde_parser", feature = "manual_parser")))]
+compile_error!("At least one parser feature (`serde_parser` or `manual_parser`) must be enabled.");
+
+#[cfg(all(feature = "serde_parser", feature = "manual_parser"))]
+compile_error!("Cannot enable both `serde_parser` and `manual_parser` features at the same time.");
+
 
 #[cfg(test)]
 mod tests {
@@ -75,7 +236,7 @@ mod tests {
 
     #[test]
     fn test_serde_json_parser() {
-        let parser = srd_jsn::SerdeJsonParser;
+        let parser = srd_jsn::SerdeJsonParser::new();
         let input = Bytes::from(SAMPLE_MSG);
         let result = parser.parse(&input).expect("Serde parser failed");
 
@@ -86,7 +247,7 @@ mod tests {
 
     #[test]
     fn test_manual_scan_parser() {
-        let parser = man_scan::ManualScanParser;
+        let parser = man_scan::ManualScanParser::new();
         let input = Bytes::from(SAMPLE_MSG);
         let result = parser.parse(&input).expect("Manual parser failed");
 
@@ -95,12 +256,47 @@ mod tests {
         assert!((result.ask_price - 30001.45).abs() < 1e-6);
     }
 
+    const SAMPLE_ARRAY_MSG: &str = r#"[{"u":400900217,"s":"BNBUSDT","b":"25.35190000","B":"31.21000000","a":"25.36520000","A":"40.66000000"},{"u":400900218,"s":"ETHBTC","b":"0.01914000","B":"5.00000000","a":"0.01915000","A":"3.20000000"}]"#;
+
+    #[test]
+    fn test_parse_batch_array_frame() {
+        let input = Bytes::from(SAMPLE_ARRAY_MSG);
+        let parsers: [(&str, Box<dyn BookTickerParser>); 2] = [
+            ("serde", Box::new(srd_jsn::SerdeJsonParser::new())),
+            ("manual", Box::new(man_scan::ManualScanParser::new())),
+        ];
+
+        for (name, parser) in parsers {
+            let updates = parser.parse_batch(&input).unwrap_or_else(|e| panic!("{name} parser failed: {e}"));
+
+            assert_eq!(updates.len(), 2, "{name} parser");
+            assert_eq!(updates[0].symbol, "BNBUSDT");
+            assert!((updates[0].bid_price - 25.3519).abs() < 1e-9);
+            assert!((updates[0].ask_price - 25.3652).abs() < 1e-9);
+            assert_eq!(updates[1].symbol, "ETHBTC");
+            assert!((updates[1].bid_price - 0.01914).abs() < 1e-9);
+            assert!((updates[1].ask_price - 0.01915).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_parse_batch_single_object_frame() {
+        let input = Bytes::from(SAMPLE_MSG);
+        let serde_updates = srd_jsn::SerdeJsonParser::new().parse_batch(&input).expect("Serde parser failed");
+        let manual_updates = man_scan::ManualScanParser::new().parse_batch(&input).expect("Manual parser failed");
+
+        assert_eq!(serde_updates.len(), 1);
+        assert_eq!(manual_updates.len(), 1);
+        assert_eq!(serde_updates[0].symbol, "BTCUSDT");
+        assert_eq!(manual_updates[0].symbol, "BTCUSDT");
+    }
+
     #[test]
     fn test_parsers_consistency() {
         let input = Bytes::from(SAMPLE_MSG);
 
-        let serde_parser = srd_jsn::SerdeJsonParser;
-        let manual_parser = man_scan::ManualScanParser;
+        let serde_parser = srd_jsn::SerdeJsonParser::new();
+        let manual_parser = man_scan::ManualScanParser::new();
 
         let serde_result = serde_parser.parse(&input).expect("Serde parser failed");
         let manual_result = manual_parser.parse(&input).expect("Manual parser failed");
@@ -110,4 +306,224 @@ mod tests {
         assert!((serde_result.ask_price - manual_result.ask_price).abs() < 1e-6, "Ask prices do not match");
     }
 
+    #[test]
+    fn test_manual_parser_compact_format_regression() {
+        let update = man_scan::ManualScanParser::new().parse(&Bytes::from(SAMPLE_MSG)).unwrap();
+        assert_eq!(update.symbol, "BTCUSDT");
+        assert_eq!(update.bid_price, 30000.12);
+        assert_eq!(update.ask_price, 30001.45);
+    }
+
+    #[test]
+    fn test_manual_parser_tolerates_reordering_and_whitespace() {
+        let variants = [
+            // Reordered fields
+            r#"{"a":"30001.45","A":"2.0","b":"30000.12","B":"1.0","s":"BTCUSDT","u":123456}"#,
+            // Whitespace around colons and between fields
+            r#"{ "s" : "BTCUSDT" , "b":  "30000.12", "a" :"30001.45" }"#,
+            // Pretty-printed
+            "{\n  \"u\": 123456,\n  \"s\": \"BTCUSDT\",\n  \"b\": \"30000.12\",\n  \"a\": \"30001.45\"\n}",
+            // Key text inside an earlier value must not be mistaken for the field
+            r#"{"m":"s","x":"b","s":"BTCUSDT","b":"30000.12","a":"30001.45"}"#,
+        ];
+
+        for raw in variants {
+            let input = Bytes::from(raw);
+            let manual = man_scan::ManualScanParser::new().parse(&input).unwrap_or_else(|e| panic!("{raw}: {e}"));
+            let serde = srd_jsn::SerdeJsonParser::new().parse(&input).unwrap();
+            assert_eq!(manual.symbol, "BTCUSDT", "{raw}");
+            assert_eq!(manual.bid_price, 30000.12, "{raw}");
+            assert_eq!(manual.ask_price, 30001.45, "{raw}");
+            assert_eq!((manual.symbol, manual.bid_price, manual.ask_price), (serde.symbol, serde.bid_price, serde.ask_price));
+        }
+
+        let missing = Bytes::from(r#"{"s":"BTCUSDT","x":"b","a":"30001.45"}"#);
+        assert!(man_scan::ManualScanParser::new().parse(&missing).is_err(), "a value equal to the key isn't the field");
+    }
+
+    #[test]
+    fn test_fallback_parser_recovers_frames_the_manual_parser_rejects() {
+        // A `}` inside a string value splits the manual scanner's array objects early
+        let raw = Bytes::from(r#"[{"x":"book}Ticker","s":"BNBUSDT","b":"25.3519","a":"25.3652"}]"#);
+        assert!(man_scan::ManualScanParser::new().parse_batch(&raw).is_err());
+
+        let parser = fallback::FallbackParser::new();
+        let updates = parser.parse_batch(&raw).expect("serde handles the frame");
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].symbol, "BNBUSDT");
+        assert_eq!(updates[0].bid_price, 25.3519);
+        assert_eq!(updates[0].ask_price, 25.3652);
+        assert_eq!(parser.fallback_count(), 1);
+
+        // Frames the manual scanner handles never reach serde
+        parser.parse_batch(&Bytes::from(SAMPLE_ARRAY_MSG)).unwrap();
+        parser.parse(&Bytes::from(SAMPLE_MSG)).unwrap();
+        assert_eq!(parser.fallback_count(), 1);
+
+        assert!(parser.parse(&Bytes::from("not json")).is_err());
+        assert_eq!(parser.fallback_count(), 2);
+    }
+
+    #[test]
+    fn test_parsers_read_event_time() {
+        let parsers: [(&str, Box<dyn BookTickerParser>); 2] = [
+            ("serde", Box::new(srd_jsn::SerdeJsonParser::new())),
+            ("manual", Box::new(man_scan::ManualScanParser::new())),
+        ];
+        let stamped = Bytes::from(r#"{"e":"bookTicker","E":1700000000123,"s":"BTCUSDT","b":"1.0","a":"2.0"}"#);
+        let trailing = Bytes::from(r#"{"s":"BTCUSDT","b":"1.0","a":"2.0","E" : 1700000000123 }"#);
+        for (name, parser) in &parsers {
+            assert_eq!(parser.parse(&stamped).unwrap().event_time_ms, Some(1_700_000_000_123), "{name}");
+            assert_eq!(parser.parse(&trailing).unwrap().event_time_ms, Some(1_700_000_000_123), "{name}");
+            assert_eq!(parser.parse(&Bytes::from(SAMPLE_MSG)).unwrap().event_time_ms, None, "{name}");
+            let null = Bytes::from(r#"{"s":"BTCUSDT","b":"1.0","a":"2.0","E":null}"#);
+            assert_eq!(parser.parse(&null).unwrap().event_time_ms, None, "{name}");
+            for bad in [r#""1700000000123""#, "-1", "1.5", "1e3", "nullx"] {
+                let input = Bytes::from(format!(r#"{{"s":"BTCUSDT","b":"1.0","a":"2.0","E":{bad}}}"#));
+                assert!(parser.parse(&input).is_err(), "{name} accepted E={bad}");
+            }
+        }
+    }
+
+
+    fn frame_with_prices(bid: &str, ask: &str) -> Bytes {
+        Bytes::from(format!(r#"{{"s":"BTCUSDT","b":"{bid}","a":"{ask}"}}"#))
+    }
+
+    #[test]
+    fn test_parsers_read_fixed_and_scientific_prices() {
+        let cases = [
+            ("0.00000100", 0.000001),
+            ("30000.12000000", 30000.12),
+            ("1E-8", 1e-8),
+            ("1e-8", 1e-8),
+            ("1.5E+3", 1500.0),
+            ("2.5e3", 2500.0),
+            ("42", 42.0),
+        ];
+        for (text, expected) in cases {
+            let input = frame_with_prices(text, text);
+            let manual = man_scan::ManualScanParser::new().parse(&input).unwrap_or_else(|e| panic!("{text}: {e}"));
+            let serde = srd_jsn::SerdeJsonParser::new().parse(&input).unwrap_or_else(|e| panic!("{text}: {e}"));
+            assert_eq!(manual.bid_price, expected, "{text}");
+            assert_eq!(serde.bid_price, expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_parsers_reject_malformed_prices() {
+        for text in ["1.2.3", "", "abc", "1e", "--1", ".", "1,5", " 1.0", "NaN", "inf", "-infinity"] {
+            let input = frame_with_prices("1.0", text);
+            assert!(man_scan::ManualScanParser::new().parse(&input).is_err(), "manual accepted '{text}'");
+            assert!(srd_jsn::SerdeJsonParser::new().parse(&input).is_err(), "serde accepted '{text}'");
+        }
+    }
+
+    #[test]
+    fn test_try_new_rejects_crossed_and_non_positive_quotes() {
+        let update = TopOfBookUpdate::try_new("BTCUSDT", 30000.12, 30001.45).unwrap();
+        assert_eq!((update.bid_price, update.ask_price, update.event_time_ms), (30000.12, 30001.45, None));
+        assert!(TopOfBookUpdate::try_new("BTCUSDT", 1.0, 1.0).is_ok(), "a locked quote isn't crossed");
+
+        assert_eq!(
+            TopOfBookUpdate::try_new("BTCUSDT", 30001.45, 30000.12).unwrap_err(),
+            QuoteError::Crossed { bid_price: 30001.45, ask_price: 30000.12 }
+        );
+        for (bid, ask) in [(-1.0, 2.0), (1.0, -2.0), (-2.0, -1.0), (0.0, 1.0)] {
+            assert_eq!(
+                TopOfBookUpdate::try_new("BTCUSDT", bid, ask).unwrap_err(),
+                QuoteError::NonPositive { bid_price: bid, ask_price: ask }
+            );
+        }
+        assert!(matches!(TopOfBookUpdate::try_new("BTCUSDT", f64::NAN, 1.0), Err(QuoteError::NonFinite { .. })));
+    }
+
+    #[test]
+    fn test_parsers_reject_crossed_and_negative_quotes() {
+        let parsers: [(&str, Box<dyn BookTickerParser>); 3] = [
+            ("serde", Box::new(srd_jsn::SerdeJsonParser::new())),
+            ("manual", Box::new(man_scan::ManualScanParser::new())),
+            ("fallback", Box::new(fallback::FallbackParser::new())),
+        ];
+        for (name, parser) in parsers {
+            for (bid, ask) in [("30001.45", "30000.12"), ("-1.0", "2.0"), ("1.0", "-2.0"), ("0", "0")] {
+                let error = parser.parse(&frame_with_prices(bid, ask)).expect_err(name);
+                assert!(error.downcast_ref::<QuoteError>().is_some(), "{name} bid {bid} ask {ask}: {error}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_escaped_values_defer_to_serde() {
+        // "\u0031.5" is "1.5" once decoded; the manual scanner refuses rather than misreading it
+        let input = Bytes::from(r#"{"s":"BTCUSDT","b":"\u0031.5","a":"2.0"}"#);
+        assert!(man_scan::ManualScanParser::new().parse(&input).is_err());
+        assert_eq!(srd_jsn::SerdeJsonParser::new().parse(&input).unwrap().bid_price, 1.5);
+        assert_eq!(fallback::FallbackParser::new().parse(&input).unwrap().bid_price, 1.5);
+    }
+
+    #[test]
+    fn test_parsers_agree_on_generated_decimal_strings() {
+        use rand::{Rng, SeedableRng};
+        use rand_chacha::ChaCha12Rng;
+
+        let mut rng = ChaCha12Rng::seed_from_u64(1118);
+        for _ in 0..2_000 {
+            let digits: String = (0..rng.gen_range(1..=12)).map(|_| char::from(b'0' + rng.gen_range(0..10))).collect();
+            let point = rng.gen_range(0..=digits.len());
+            let mut text = format!("{}.{}", &digits[..point], &digits[point..]);
+            if point == 0 {
+                text.insert(0, '0');
+            }
+            if rng.gen_bool(0.3) {
+                let exponent: i32 = rng.gen_range(-12..=12);
+                let marker = if rng.gen_bool(0.5) { 'E' } else { 'e' };
+                text = format!("{text}{marker}{exponent}");
+            }
+
+            let input = frame_with_prices(&text, &text);
+            let expected: f64 = text.parse().unwrap();
+            if expected == 0.0 {
+                // All-zero digits: a zero price is never a valid quote
+                assert!(man_scan::ManualScanParser::new().parse(&input).is_err(), "{text}");
+                assert!(srd_jsn::SerdeJsonParser::new().parse(&input).is_err(), "{text}");
+                continue;
+            }
+            let manual = man_scan::ManualScanParser::new().parse(&input).unwrap_or_else(|e| panic!("{text}: {e}"));
+            let serde = srd_jsn::SerdeJsonParser::new().parse(&input).unwrap_or_else(|e| panic!("{text}: {e}"));
+            assert_eq!(manual.bid_price.to_bits(), serde.bid_price.to_bits(), "{text}");
+            assert_eq!(serde.bid_price.to_bits(), expected.to_bits(), "{text}");
+        }
+    }
+
+    const TRADE_MSG: &str = r#"{"e":"trade","E":1672515782136,"s":"BNBBTC","t":12345,"p":"0.001","q":"100","T":1672515782136,"m":true,"M":true}"#;
+    // A 24hr ticker carries string `b`/`a` fields, so without the check it passes for a quote
+    const TICKER_24HR_MSG: &str = r#"{"e":"24hrTicker","E":1672515782136,"s":"BNBBTC","c":"0.0025","b":"0.0024","B":"10","a":"0.0026","A":"100"}"#;
+
+    #[test]
+    fn test_other_event_types_are_rejected_with_a_typed_error() {
+        let parsers: [(&str, Box<dyn BookTickerParser>); 3] = [
+            ("serde", Box::new(srd_jsn::SerdeJsonParser::new())),
+            ("manual", Box::new(man_scan::ManualScanParser::new())),
+            ("fallback", Box::new(fallback::FallbackParser::new())),
+        ];
+        for (name, parser) in parsers {
+            for (raw, event_type) in [(TRADE_MSG, "trade"), (TICKER_24HR_MSG, "24hrTicker")] {
+                let error = parser.parse_batch(&Bytes::from(raw)).expect_err(name);
+                let rejected = error.downcast_ref::<UnexpectedEventType>().unwrap_or_else(|| panic!("{name}: {error}"));
+                assert_eq!(rejected.event_type, event_type, "{name}");
+            }
+            let update = parser.parse(&Bytes::from(SAMPLE_MSG)).unwrap_or_else(|e| panic!("{name}: {e}"));
+            assert_eq!(update.symbol, "BTCUSDT", "{name} still parses bookTicker");
+        }
+    }
+
+    #[test]
+    fn test_event_type_check_can_be_turned_off() {
+        let input = Bytes::from(TICKER_24HR_MSG);
+        let manual = man_scan::ManualScanParser::new().with_event_type_check(false).parse(&input).unwrap();
+        let serde = srd_jsn::SerdeJsonParser::new().with_event_type_check(false).parse(&input).unwrap();
+        assert_eq!((manual.bid_price, manual.ask_price), (0.0024, 0.0026));
+        assert_eq!((serde.bid_price, serde.ask_price), (0.0024, 0.0026));
+    }
 }
@@ -0,0 +1,108 @@
+// src/parse/quote_counter.rs
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dashmap::DashMap;
+
+
+/// Per-symbol count of quotes parsed since start, for spotting subscriptions that never deliver,
+/// plus the number of frames that failed to parse.
+///
+/// Shared between `parser_loop_with_quote_counter` (which records every parsed update) and
+/// whoever reports on the run, e.g. `Pipeline::dead_symbols`.
+#[derive(Debug, Default)]
+pub struct QuoteCounter {
+    counts: DashMap<String, u64>,
+    parse_errors: AtomicU64,
+}
+
+impl QuoteCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, symbol: &str) {
+        match self.counts.get_mut(symbol) {
+            Some(mut count) => *count += 1,
+            None => *self.counts.entry(symbol.to_string()).or_default() += 1,
+        }
+    }
+
+    pub fn record_parse_error(&self) {
+        self.parse_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Frames the parser rejected since start.
+    pub fn parse_errors(&self) -> u64 {
+        self.parse_errors.load(Ordering::Relaxed)
+    }
+
+    /// Quotes received per symbol; symbols never quoted are absent.
+    pub fn quote_counts(&self) -> HashMap<String, u64> {
+        self.counts.iter().map(|entry| (entry.key().clone(), *entry.value())).collect()
+    }
+
+    /// The `subscribed` symbols that haven't delivered a single quote, sorted.
+    pub fn dead_symbols<S: AsRef<str>>(&self, subscribed: &[S]) -> Vec<String> {
+        let mut dead: Vec<String> = subscribed
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|symbol| !self.counts.contains_key(*symbol))
+            .map(str::to_string)
+            .collect();
+        dead.sort();
+        dead.dedup();
+        dead
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::parse::{parser_loop_with_quote_counter, ParserKind, TopOfBookUpdate};
+
+    fn frame(symbol: &str) -> Bytes {
+        Bytes::from(format!(r#"{{"s":"{symbol}","b":"1.0","a":"1.1"}}"#))
+    }
+
+    #[tokio::test]
+    async fn symbols_without_quotes_are_reported_dead() {
+        let subscribed = ["BTCUSDT", "ETHBTC", "ETHUSDT", "SOLUSDT"];
+        let (ws_tx, ws_rx) = mpsc::channel::<Bytes>(16);
+        let (parser_tx, mut parser_rx) = mpsc::channel::<TopOfBookUpdate>(16);
+        let counter = Arc::new(QuoteCounter::new());
+        let parser = tokio::spawn(parser_loop_with_quote_counter(
+            ws_rx,
+            parser_tx,
+            ParserKind::Manual,
+            Arc::clone(&counter),
+        ));
+
+        for symbol in ["BTCUSDT", "ETHUSDT", "BTCUSDT", "BTCUSDT"] {
+            ws_tx.send(frame(symbol)).await.unwrap();
+        }
+        // An unparseable frame counts for nothing
+        ws_tx.send(Bytes::from("garbage")).await.unwrap();
+        drop(ws_tx);
+        parser.await.unwrap().unwrap();
+
+        let mut parsed = 0;
+        while parser_rx.try_recv().is_ok() {
+            parsed += 1;
+        }
+        assert_eq!(parsed, 4);
+
+        let counts = counter.quote_counts();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts["BTCUSDT"], 3);
+        assert_eq!(counts["ETHUSDT"], 1);
+        assert_eq!(counter.dead_symbols(&subscribed), ["ETHBTC", "SOLUSDT"]);
+    }
+}
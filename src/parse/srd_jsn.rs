@@ -1,28 +1,97 @@
 // src/parse/srd_jsn.rs
+
 use anyhow::Result;
 use serde_json;
 use serde::Deserialize;
 use bytes::Bytes;
 
-use super::{TopOfBookUpdate, BookTickerParser};
+use crate::price_path::normalize_symbol;
+
+use super::{check_event_type, is_json_array, parse_price, TopOfBookUpdate, BookTickerParser};
+
+/// serde_json bookTicker parser. By default an object whose `e` names another event is
+/// rejected with `UnexpectedEventType`, even when its fields happen to deserialize.
+#[derive(Debug, Clone, Copy)]
+pub struct SerdeJsonParser {
+    check_event_type: bool,
+}
+
+impl Default for SerdeJsonParser {
+    fn default() -> Self {
+        Self { check_event_type: true }
+    }
+}
+
+impl SerdeJsonParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-pub struct SerdeJsonParser;
+    /// Turns the `e` check off (or back on), e.g. for a feed known to carry only bookTicker.
+    pub fn with_event_type_check(mut self, check_event_type: bool) -> Self {
+        self.check_event_type = check_event_type;
+        self
+    }
+
+    fn convert(&self, parsed: BookTickerWs) -> Result<TopOfBookUpdate> {
+        if self.check_event_type {
+            check_event_type(parsed.e.as_deref())?;
+        }
+        parsed.try_into()
+    }
+}
 
 /// Simple serde_json parser implementation
 impl BookTickerParser for SerdeJsonParser {
     fn parse(&self, raw: &Bytes) -> Result<TopOfBookUpdate> {
-        let parsed: BookTickerWs = serde_json::from_slice(raw)?;
-        Ok(TopOfBookUpdate {
-            symbol: parsed.s,
-            bid_price: parsed.b.parse()?,
-            ask_price: parsed.a.parse()?,
-        })
+        match serde_json::from_slice::<BookTickerWs>(raw) {
+            Ok(parsed) => self.convert(parsed),
+            // Another stream's fields rarely fit `BookTickerWs`; name its event rather than
+            // the field that failed
+            Err(e) => {
+                if self.check_event_type
+                    && let Ok(EventOnly { e: event_type }) = serde_json::from_slice(raw)
+                {
+                    check_event_type(event_type.as_deref())?;
+                }
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Deserializes either a single bookTicker object or a `!bookTicker@arr` array of them.
+    fn parse_batch(&self, raw: &Bytes) -> Result<Vec<TopOfBookUpdate>> {
+        if !is_json_array(raw) {
+            return Ok(vec![self.parse(raw)?]);
+        }
+        let parsed: Vec<BookTickerWs> = serde_json::from_slice(raw)?;
+        parsed.into_iter().map(|parsed| self.convert(parsed)).collect()
+    }
+}
+
+impl TryFrom<BookTickerWs> for TopOfBookUpdate {
+    type Error = anyhow::Error;
+
+    fn try_from(parsed: BookTickerWs) -> Result<Self> {
+        let update = TopOfBookUpdate::try_new(normalize_symbol(&parsed.s), parse_price(&parsed.b)?, parse_price(&parsed.a)?)?;
+        Ok(update.with_event_time(parsed.event_time))
     }
 }
 
 #[derive(Debug, Deserialize)]
 struct BookTickerWs {
+    #[serde(default)]
+    pub e: Option<String>,
     pub s: String,
     pub b: String,
     pub a: String,
-}
\ No newline at end of file
+    #[serde(rename = "E", default)]
+    pub event_time: Option<u64>,
+}
+
+/// Just the event type of a frame that failed to deserialize as `BookTickerWs`.
+#[derive(Debug, Deserialize)]
+struct EventOnly {
+    #[serde(default)]
+    e: Option<String>,
+}
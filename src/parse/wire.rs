@@ -0,0 +1,220 @@
+// src/parse/wire.rs
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use anyhow::{bail, Context, Result};
+use tokio::sync::mpsc::{Receiver, Sender};
+
+use super::TopOfBookUpdate;
+
+
+/// Size of an encoded `PackedUpdate`.
+pub const PACKED_UPDATE_LEN: usize = std::mem::size_of::<PackedUpdate>();
+
+/// Dense `u32` ids for symbol names, shared by both ends of a binary transport.
+///
+/// Ids are assigned in first-seen order, so two tables built from the same symbols in the same
+/// order agree without exchanging anything.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    ids: HashMap<String, u32>,
+    names: Vec<String>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns every symbol in `symbols`, in order.
+    pub fn from_symbols<I, S>(symbols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut table = Self::new();
+        for symbol in symbols {
+            table.intern(symbol.as_ref());
+        }
+        table
+    }
+
+    /// Returns `symbol`'s id, assigning the next one if it is new.
+    pub fn intern(&mut self, symbol: &str) -> u32 {
+        if let Some(&id) = self.ids.get(symbol) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        self.ids.insert(symbol.to_string(), id);
+        self.names.push(symbol.to_string());
+        id
+    }
+
+    pub fn id(&self, symbol: &str) -> Option<u32> {
+        self.ids.get(symbol).copied()
+    }
+
+    pub fn name(&self, id: u32) -> Option<&str> {
+        self.names.get(id as usize).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+
+/// Fixed-size binary form of a `TopOfBookUpdate`, carried by the packed in-process feed
+/// (`mock_feed::topbook::start_packed_topbook_feed` into `unpack_loop`).
+///
+/// The symbol travels as its `SymbolTable` id, so a record is 32 bytes with no padding and no
+/// JSON to format or parse. `recv_ts` is not carried: it is local to each process and is set
+/// when the record is unpacked. The realistic WebSocket path keeps Binance's JSON.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PackedUpdate {
+    pub symbol_id: u32,
+    /// 1 when `event_time_ms` holds the exchange event time, 0 when the update had none.
+    has_event_time: u32,
+    pub bid_price: f64,
+    pub ask_price: f64,
+    event_time_ms: u64,
+}
+
+impl PackedUpdate {
+    pub fn pack(update: &TopOfBookUpdate, symbol_id: u32) -> Self {
+        Self {
+            symbol_id,
+            has_event_time: update.event_time_ms.is_some() as u32,
+            bid_price: update.bid_price,
+            ask_price: update.ask_price,
+            event_time_ms: update.event_time_ms.unwrap_or(0),
+        }
+    }
+
+    pub fn event_time_ms(&self) -> Option<u64> {
+        (self.has_event_time != 0).then_some(self.event_time_ms)
+    }
+
+    /// Little-endian encoding, field by field in declaration order.
+    pub fn to_bytes(&self) -> [u8; PACKED_UPDATE_LEN] {
+        let mut bytes = [0u8; PACKED_UPDATE_LEN];
+        bytes[0..4].copy_from_slice(&self.symbol_id.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.has_event_time.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.bid_price.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.ask_price.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.event_time_ms.to_le_bytes());
+        bytes
+    }
+
+    /// Inverse of `to_bytes`: decodes each field from its little-endian bytes. The fields are
+    /// copied out, so this is a plain decode rather than a zero-copy view of `bytes`.
+    pub fn from_bytes(bytes: &[u8; PACKED_UPDATE_LEN]) -> Self {
+        let u32_at = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+        let u64_at = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+        Self {
+            symbol_id: u32_at(0),
+            has_event_time: u32_at(4),
+            bid_price: f64::from_bits(u64_at(8)),
+            ask_price: f64::from_bits(u64_at(16)),
+            event_time_ms: u64_at(24),
+        }
+    }
+
+    /// Rebuilds the update, resolving the symbol through `table`; `None` for an unknown id.
+    pub fn unpack(&self, table: &SymbolTable, recv_ts: Instant) -> Option<TopOfBookUpdate> {
+        Some(TopOfBookUpdate {
+            symbol: table.name(self.symbol_id)?.to_string(),
+            bid_price: self.bid_price,
+            ask_price: self.ask_price,
+            recv_ts,
+            event_time_ms: self.event_time_ms(),
+        })
+    }
+}
+
+/// Encodes `update`, interning its symbol in `table` if needed.
+pub fn encode(update: &TopOfBookUpdate, table: &mut SymbolTable) -> [u8; PACKED_UPDATE_LEN] {
+    PackedUpdate::pack(update, table.intern(&update.symbol)).to_bytes()
+}
+
+/// Decodes one record produced by `encode` with an identically built `table`, stamping it as
+/// received now.
+pub fn decode(bytes: &[u8], table: &SymbolTable) -> Result<TopOfBookUpdate> {
+    let Ok(record) = <&[u8; PACKED_UPDATE_LEN]>::try_from(bytes) else {
+        bail!("Packed update must be {PACKED_UPDATE_LEN} bytes, got {}", bytes.len());
+    };
+    let packed = PackedUpdate::from_bytes(record);
+    packed
+        .unpack(table, Instant::now())
+        .with_context(|| format!("Unknown symbol id {} in packed update", packed.symbol_id))
+}
+
+/// Decodes packed records from `rx` against `table` and forwards the updates to `tx`, the packed
+/// counterpart of `parse::parser_loop`. A record that fails to decode is logged and skipped.
+/// Returns when `rx` closes or `tx`'s receiver is dropped.
+pub async fn unpack_loop(
+    mut rx: Receiver<[u8; PACKED_UPDATE_LEN]>,
+    table: SymbolTable,
+    tx: Sender<TopOfBookUpdate>,
+) -> Result<()> {
+    while let Some(record) = rx.recv().await {
+        match decode(&record, &table) {
+            Ok(update) => {
+                if tx.send(update).await.is_err() {
+                    return Ok(());
+                }
+            }
+            Err(e) => eprintln!("Failed to decode packed update: {e:#}"),
+        }
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::mock_update;
+
+    #[test]
+    fn encode_then_decode_reproduces_the_update() {
+        let mut sender = SymbolTable::new();
+        let receiver = SymbolTable::from_symbols(["BTCUSDT", "ETHBTC"]);
+
+        let mut with_event_time = mock_update("ETHBTC", 0.1 + 0.2, 0.01915);
+        with_event_time.event_time_ms = Some(1_700_000_000_123);
+        let updates = [
+            mock_update("BTCUSDT", 95_460.12, 95_461.45),
+            with_event_time,
+            mock_update("BTCUSDT", f64::MIN_POSITIVE, f64::MAX),
+        ];
+        for update in &updates {
+            let bytes = encode(update, &mut sender);
+            assert_eq!(bytes.len(), 32);
+
+            let decoded = decode(&bytes, &receiver).unwrap();
+            assert_eq!(decoded.symbol, update.symbol);
+            assert_eq!(decoded.bid_price.to_bits(), update.bid_price.to_bits());
+            assert_eq!(decoded.ask_price.to_bits(), update.ask_price.to_bits());
+            assert_eq!(decoded.event_time_ms, update.event_time_ms);
+        }
+        assert_eq!(sender.len(), 2);
+        assert_eq!(sender.id("ETHBTC"), receiver.id("ETHBTC"));
+    }
+
+    #[test]
+    fn decode_rejects_short_records_and_unknown_symbols() {
+        let mut table = SymbolTable::new();
+        let bytes = encode(&mock_update("SOLUSDT", 150.0, 150.1), &mut table);
+
+        assert!(decode(&bytes[..31], &table).is_err());
+        let unknown = decode(&bytes, &SymbolTable::new()).unwrap_err();
+        assert!(unknown.to_string().contains("Unknown symbol id 0"));
+    }
+}
@@ -0,0 +1,370 @@
+// src/pipeline.rs
+
+//! One-call wiring of the ws → parse → arb pipeline.
+//!
+//! ```rust,ignore
+//! let mut pipeline = Pipeline::builder()
+//!     .with_universe("USDT", &["BTC", "ETH", "SOL"])
+//!     .with_mode(ArbMode::RayonScan)
+//!     .build()?;
+//! pipeline.start();
+//! tokio::signal::ctrl_c().await?;
+//! pipeline.shutdown().await?;
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use bytes::Bytes;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::arb::{
+    arb_loop_with_stats, create_arb_evaluator, ArbEvaluator, ArbLoopStats, ArbMode, OpportunitySink, PipelineConfig,
+    StdoutSink,
+};
+use crate::parse::quote_counter::QuoteCounter;
+use crate::parse::{parser_loop_with_quote_counter, TopOfBookUpdate};
+use crate::price_path::{
+    build_paths, find_path_symbols_capped, load_exchange_info_checked, select_top_targets, PricingPath, SymbolFilter,
+    TargetRanking, EXCHANGE_INFO_FIXTURE,
+};
+use crate::ws::{
+    extract_symbols_from_paths, start_ws_listener, ConnectOptions, ConnectionState, ReconnectHook, SubscriptionTracker,
+};
+
+
+/// The WebSocket feed a pipeline subscribes to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Endpoint {
+    /// Binance spot market streams.
+    #[default]
+    Binance,
+    /// The in-repo mock server on `ws://localhost:9001` (see `mock_feed::ws_server`).
+    LocalMock,
+}
+
+impl Endpoint {
+    /// The `local_domain` flag understood by the `ws` listeners.
+    fn local_domain(self) -> Option<bool> {
+        match self {
+            Self::Binance => None,
+            Self::LocalMock => Some(true),
+        }
+    }
+}
+
+
+/// Configures a `Pipeline`; see `Pipeline::builder`.
+pub struct PipelineBuilder {
+    home_asset: String,
+    targets: Vec<String>,
+    top_targets: Option<(usize, TargetRanking)>,
+    max_paths: Option<usize>,
+    price_paths: Option<Vec<PricingPath>>,
+    mode: ArbMode,
+    endpoint: Endpoint,
+    config: PipelineConfig,
+    symbol_filter: SymbolFilter,
+    evaluator: Option<Arc<dyn ArbEvaluator>>,
+    sink: Arc<dyn OpportunitySink>,
+}
+
+impl Default for PipelineBuilder {
+    fn default() -> Self {
+        Self {
+            home_asset: "USDT".to_string(),
+            targets: ["BTC", "ETH", "SOL"].map(String::from).to_vec(),
+            top_targets: None,
+            max_paths: None,
+            price_paths: None,
+            mode: ArbMode::RayonScan,
+            endpoint: Endpoint::default(),
+            config: PipelineConfig::default(),
+            symbol_filter: SymbolFilter::default(),
+            evaluator: None,
+            sink: Arc::new(StdoutSink),
+        }
+    }
+}
+
+impl PipelineBuilder {
+    /// Home asset and target whitelist used to discover paths from the exchangeInfo fixture.
+    pub fn with_universe(mut self, home_asset: &str, targets: &[&str]) -> Self {
+        self.home_asset = home_asset.to_string();
+        self.targets = targets.iter().map(|t| t.to_string()).collect();
+        self.top_targets = None;
+        self
+    }
+
+    /// Discovers paths from `home_asset` through its `count` most active markets per `ranking`
+    /// (see `select_top_targets`) instead of listed targets. A later `with_universe` call
+    /// overrides this with explicit targets again.
+    pub fn with_top_targets(mut self, home_asset: &str, count: usize, ranking: TargetRanking) -> Self {
+        self.home_asset = home_asset.to_string();
+        self.top_targets = Some((count, ranking));
+        self
+    }
+
+    /// Stops discovery after `max_paths` paths, skipping the rest of the O(n³) search.
+    pub fn with_max_paths(mut self, max_paths: usize) -> Self {
+        self.max_paths = Some(max_paths);
+        self
+    }
+
+    /// Uses `price_paths` as-is instead of discovering them from the universe.
+    pub fn with_price_paths(mut self, price_paths: Vec<PricingPath>) -> Self {
+        self.price_paths = Some(price_paths);
+        self
+    }
+
+    pub fn with_mode(mut self, mode: ArbMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Channel capacities, ws watchdog and exchangeInfo staleness threshold (see `load_pipeline_config`).
+    pub fn with_config(mut self, config: PipelineConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Drops paths trading blocked or unlisted symbols before evaluation and subscription
+    /// (see `load_symbol_filter`).
+    pub fn with_symbol_filter(mut self, filter: SymbolFilter) -> Self {
+        self.symbol_filter = filter;
+        self
+    }
+
+    /// Replaces the evaluator `create_arb_evaluator` would build for the configured mode.
+    pub fn with_evaluator(mut self, evaluator: Arc<dyn ArbEvaluator>) -> Self {
+        self.evaluator = Some(evaluator);
+        self
+    }
+
+    pub fn with_sink(mut self, sink: Arc<dyn OpportunitySink>) -> Self {
+        self.sink = sink;
+        self
+    }
+
+    /// The paths `build` would evaluate: the explicit paths or the universe's discovered ones,
+    /// after the symbol filter. Lets the universe be inspected without building a pipeline.
+    pub fn discover_paths(&self) -> Result<Vec<PricingPath>> {
+        let price_paths = match &self.price_paths {
+            Some(paths) => paths.clone(),
+            None => {
+                let exchange_info =
+                    load_exchange_info_checked(EXCHANGE_INFO_FIXTURE, self.config.exchange_info_max_age())?;
+                let top_targets = self
+                    .top_targets
+                    .as_ref()
+                    .map(|(count, ranking)| select_top_targets(&exchange_info, &self.home_asset, ranking, *count));
+                let targets: Vec<&str> = top_targets.as_ref().unwrap_or(&self.targets).iter().map(String::as_str).collect();
+                let triplets = find_path_symbols_capped(&exchange_info, &self.home_asset, &targets, self.max_paths);
+                build_paths(&self.home_asset, triplets)
+            }
+        };
+        Ok(self.symbol_filter.apply(price_paths))
+    }
+
+    /// Resolves the paths and evaluator; nothing is spawned until `Pipeline::start`.
+    pub fn build(self) -> Result<Pipeline> {
+        let price_paths = self.discover_paths()?;
+        let evaluator = match self.evaluator {
+            Some(evaluator) => evaluator,
+            None => create_arb_evaluator(self.mode, &self.home_asset, price_paths.clone())?,
+        };
+        Ok(Pipeline {
+            price_paths,
+            evaluator,
+            sink: self.sink,
+            endpoint: self.endpoint,
+            config: self.config,
+            tracker: Arc::new(SubscriptionTracker::new()),
+            connection: Arc::new(ConnectionState::new()),
+            arb_stats: Arc::new(ArbLoopStats::new()),
+            quote_counter: Arc::new(QuoteCounter::new()),
+            started_at: None,
+            tasks: None,
+        })
+    }
+}
+
+
+/// Run report returned and logged by `Pipeline::shutdown`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunSummary {
+    /// WebSocket frames received, across reconnects.
+    pub frames: u64,
+    /// Frames the parser rejected.
+    pub parse_errors: u64,
+    /// Updates handed to the evaluator.
+    pub updates_processed: u64,
+    /// Opportunities handed to the sink.
+    pub opportunities_found: u64,
+    /// Highest net return among them, `None` if none were found.
+    pub best_return: Option<f64>,
+    /// Time from `start` to the summary.
+    #[serde(rename = "uptime_secs", serialize_with = "serialize_secs")]
+    pub uptime: Duration,
+}
+
+fn serialize_secs<S: serde::Serializer>(duration: &Duration, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+
+/// Handle to a running (or ready to run) ws → parse → arb pipeline.
+///
+/// Owns the two bounded channels and the three stage tasks.
+pub struct Pipeline {
+    price_paths: Vec<PricingPath>,
+    evaluator: Arc<dyn ArbEvaluator>,
+    sink: Arc<dyn OpportunitySink>,
+    endpoint: Endpoint,
+    config: PipelineConfig,
+    tracker: Arc<SubscriptionTracker>,
+    connection: Arc<ConnectionState>,
+    arb_stats: Arc<ArbLoopStats>,
+    quote_counter: Arc<QuoteCounter>,
+    started_at: Option<Instant>,
+    tasks: Option<PipelineTasks>,
+}
+
+struct PipelineTasks {
+    ws: JoinHandle<Result<()>>,
+    parser: JoinHandle<Result<()>>,
+    arb: JoinHandle<Result<()>>,
+}
+
+impl Pipeline {
+    pub fn builder() -> PipelineBuilder {
+        PipelineBuilder::default()
+    }
+
+    /// Creates the channels and spawns the listener, parser and arb loop. No-op if already started.
+    pub fn start(&mut self) {
+        if self.tasks.is_some() {
+            return;
+        }
+        let (ws_tx, ws_rx) = mpsc::channel::<Bytes>(self.config.ws_channel_capacity.max(1));
+        let (parser_tx, parser_rx) = mpsc::channel::<TopOfBookUpdate>(self.config.parser_channel_capacity.max(1));
+
+        let arb = tokio::spawn(arb_loop_with_stats(
+            parser_rx,
+            Arc::clone(&self.evaluator),
+            Arc::clone(&self.sink),
+            Arc::clone(&self.arb_stats),
+        ));
+        let parser = tokio::spawn(parser_loop_with_quote_counter(
+            ws_rx,
+            parser_tx,
+            self.config.parser,
+            Arc::clone(&self.quote_counter),
+        ));
+        // Quotes from before a reconnect may be stale across the gap, so the reconnected
+        // connection's symbols re-warm; other shards kept streaming and keep theirs
+        let evaluator = Arc::clone(&self.evaluator);
+        let on_reconnect: ReconnectHook = Arc::new(move |symbols: &[String]| evaluator.invalidate_symbols(symbols));
+        let options = ConnectOptions::from_local_domain(self.endpoint.local_domain())
+            .with_shards(self.config.ws_shards)
+            .with_idle_timeout(self.config.ws_idle_timeout())
+            .with_reconnect_hook(on_reconnect)
+            .with_tracker(Arc::clone(&self.tracker))
+            .with_connection_state(Arc::clone(&self.connection));
+        let ws = tokio::spawn(start_ws_listener(self.price_paths.clone(), ws_tx, options));
+        tracing::info!(paths = self.price_paths.len(), endpoint = ?self.endpoint, "Pipeline started");
+        self.started_at = Some(Instant::now());
+        self.tasks = Some(PipelineTasks { ws, parser, arb });
+    }
+
+    /// Stops the listener and lets the parser and arb loop drain and exit, then logs and returns
+    /// the run's `RunSummary`.
+    ///
+    /// Aborting the listener drops the ws channel's sender, so the parser finishes the queued
+    /// frames and exits, which in turn closes the arb channel. Returns the first stage error.
+    pub async fn shutdown(mut self) -> Result<RunSummary> {
+        let Some(tasks) = self.tasks.take() else {
+            return Ok(self.summary());
+        };
+        tasks.ws.abort();
+        if let Ok(Err(e)) = tasks.ws.await {
+            bail!("ws listener failed: {e}");
+        }
+        tasks.parser.await??;
+        tasks.arb.await??;
+        let dead = self.dead_symbols();
+        if !dead.is_empty() {
+            tracing::warn!(count = dead.len(), symbols = ?dead, "Subscribed symbols never delivered a quote");
+        }
+        let summary = self.summary();
+        tracing::info!(
+            frames = summary.frames,
+            parse_errors = summary.parse_errors,
+            updates_processed = summary.updates_processed,
+            opportunities_found = summary.opportunities_found,
+            best_return = ?summary.best_return,
+            uptime_secs = summary.uptime.as_secs_f64(),
+            "Pipeline shut down"
+        );
+        Ok(summary)
+    }
+
+    /// Counters accumulated since `start`; all zero if the pipeline never started.
+    pub fn summary(&self) -> RunSummary {
+        RunSummary {
+            frames: self.connection.frames_received(),
+            parse_errors: self.quote_counter.parse_errors(),
+            updates_processed: self.arb_stats.updates_processed(),
+            opportunities_found: self.arb_stats.opportunities_found(),
+            best_return: self.arb_stats.best_return(),
+            uptime: self.started_at.map_or(Duration::ZERO, |started| started.elapsed()),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.tasks.as_ref().is_some_and(|t| !(t.ws.is_finished() || t.parser.is_finished() || t.arb.is_finished()))
+    }
+
+    pub fn price_paths(&self) -> &[PricingPath] {
+        &self.price_paths
+    }
+
+    pub fn evaluator(&self) -> &Arc<dyn ArbEvaluator> {
+        &self.evaluator
+    }
+
+    /// Subscription acknowledgements and first-data tracking for the listener.
+    pub fn tracker(&self) -> &Arc<SubscriptionTracker> {
+        &self.tracker
+    }
+
+    /// Frame counters, last-frame time and status of the listener's connection, e.g. for a
+    /// health check.
+    pub fn connection(&self) -> &Arc<ConnectionState> {
+        &self.connection
+    }
+
+    /// Updates processed by the arb loop and whether it is still running.
+    pub fn arb_stats(&self) -> &Arc<ArbLoopStats> {
+        &self.arb_stats
+    }
+
+    /// Quotes parsed per symbol since `start`.
+    pub fn quote_counts(&self) -> HashMap<String, u64> {
+        self.quote_counter.quote_counts()
+    }
+
+    /// Subscribed symbols that haven't delivered a quote since `start`, sorted; logged on shutdown.
+    pub fn dead_symbols(&self) -> Vec<String> {
+        self.quote_counter.dead_symbols(&extract_symbols_from_paths(&self.price_paths))
+    }
+}
@@ -1,16 +1,25 @@
 // src/price_path.rs
 
-use std::{collections::HashSet, fmt,fs};
+use std::{collections::{HashMap, HashSet}, fmt, fs, path::Path, sync::{Arc, OnceLock}, time::{Duration, SystemTime}};
 
-use anyhow::Result;
-use serde::Deserialize;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::parse::TopOfBookUpdate;
+
+
+/// Bundled Binance exchangeInfo snapshot, relative to the crate root.
+pub const EXCHANGE_INFO_FIXTURE: &str = "fixtures/exchangeInfoSpot.json";
+
+/// Age past which an exchangeInfo file is reported as stale when loaded.
+pub const DEFAULT_EXCHANGE_INFO_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
 
 /// Loads exchange metadata and constructs all valid triangular pricing paths.
 ///
 /// # Arguments
 /// - `home_asset`: The asset to start and end each path with (e.g. "USDT").
-/// - `targets`: A whitelist of intermediate assets to consider (e.g. ["BTC", "ETH"]).
+/// - `targets`: A whitelist of assets the first leg may buy (e.g. ["BTC", "ETH"]). The second
+///   intermediate may be any asset with a market back into the home asset.
 ///
 /// # Returns
 /// A list of fully directional `PricingPath` objects, each containing 3 legs.
@@ -20,7 +29,17 @@ pub fn find_and_build_price_paths<'a>(
     home_asset: &'a str,
     targets: &[&'a str],
 ) -> Result<Vec<PricingPath>> {
-    let exchange_info = load_exchange_info_fixture()?;
+    find_and_build_price_paths_from(EXCHANGE_INFO_FIXTURE, home_asset, targets)
+}
+
+/// Same as `find_and_build_price_paths`, reading exchangeInfo from `fixture` instead of the
+/// bundled `EXCHANGE_INFO_FIXTURE`.
+pub fn find_and_build_price_paths_from<'a, P: AsRef<Path>>(
+    fixture: P,
+    home_asset: &'a str,
+    targets: &[&'a str],
+) -> Result<Vec<PricingPath>> {
+    let exchange_info = load_exchange_info_fixture_from(fixture)?;
     let triplets = find_path_symbols(&exchange_info, home_asset, targets);
     Ok(build_paths(home_asset, triplets))
 }
@@ -30,63 +49,263 @@ pub fn find_and_build_price_paths<'a>(
 #[derive(Debug, Deserialize)]
 pub struct ExchangeInfo {
     pub symbols: Vec<SymbolInfo>,
+    /// Symbol name → position in `symbols`, built on the first `symbol()` lookup.
+    #[serde(skip)]
+    symbol_index: OnceLock<HashMap<String, usize>>,
+}
+
+impl ExchangeInfo {
+    pub fn new(symbols: Vec<SymbolInfo>) -> Self {
+        Self { symbols, symbol_index: OnceLock::new() }
+    }
+
+    /// Looks up a symbol by name in O(1) after the first call.
+    ///
+    /// `symbols` stays the source of truth: if it was modified after the index was built,
+    /// a mismatched index entry falls back to a linear scan.
+    pub fn symbol(&self, name: &str) -> Option<&SymbolInfo> {
+        let index = self.symbol_index.get_or_init(|| {
+            self.symbols
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (s.symbol.clone(), i))
+                .collect()
+        });
+        match index.get(name).and_then(|&i| self.symbols.get(i)) {
+            Some(info) if info.symbol == name => Some(info),
+            _ => self.symbols.iter().find(|s| s.symbol == name),
+        }
+    }
 }
 
 
 /// Describes a tradable symbol from Binance, including its base and quote assets.
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct SymbolInfo {
+    /// Canonical symbol name (see `normalize_symbol`); scanners key their price stores by it.
+    #[serde(deserialize_with = "deserialize_symbol")]
     pub symbol: String,
     #[serde(rename = "baseAsset")]
     pub base_asset: String,
     #[serde(rename = "quoteAsset")]
     pub quote_asset: String,
     pub status: String,
+    /// Minimum price increment from the symbol's `PRICE_FILTER`, if one is listed and enabled.
+    #[serde(rename = "filters", default, deserialize_with = "deserialize_tick_size")]
+    pub tick_size: Option<f64>,
+}
+
+
+/// Canonical form of a symbol name: ASCII uppercase with `-`, `_` and `/` separators removed,
+/// so `"btcusdt"`, `"BTC-USDT"` and `"BTC/USDT"` all become Binance's `"BTCUSDT"`.
+///
+/// Parsed quotes, exchangeInfo symbols and subscriptions all pass through this, so a case or
+/// separator difference between sources can't leave a path's legs unmatched.
+pub fn normalize_symbol(symbol: &str) -> String {
+    symbol
+        .chars()
+        .filter(|c| !matches!(c, '-' | '_' | '/'))
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn deserialize_symbol<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<String, D::Error> {
+    String::deserialize(deserializer).map(|symbol| normalize_symbol(&symbol))
+}
+
+fn deserialize_symbols<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Vec<String>, D::Error> {
+    Vec::<String>::deserialize(deserializer).map(|symbols| symbols.iter().map(|s| normalize_symbol(s)).collect())
+}
+
+
+/// Extracts `tickSize` from the `PRICE_FILTER` entry of a symbol's `filters` array.
+///
+/// Binance reports `"0.00000000"` when the filter is disabled, which maps to `None`.
+fn deserialize_tick_size<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<f64>, D::Error> {
+    #[derive(Deserialize)]
+    struct RawFilter {
+        #[serde(rename = "filterType")]
+        filter_type: String,
+        #[serde(rename = "tickSize")]
+        tick_size: Option<String>,
+    }
+
+    let filters: Vec<RawFilter> = Vec::deserialize(deserializer)?;
+    Ok(filters
+        .into_iter()
+        .find(|f| f.filter_type == "PRICE_FILTER")
+        .and_then(|f| f.tick_size)
+        .and_then(|t| t.parse::<f64>().ok())
+        .filter(|t| *t > 0.0))
 }
 
 
 /// Indicates the direction to evaluate the price for a trade leg:
 /// - `Ask` means buy the base asset using the quote.
 /// - `Bid` means sell the base asset to get the quote.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum Side {
     Bid,
     Ask
 }
 
+impl Side {
+    /// Returns the opposite side of the book, i.e. the side used when trading the same
+    /// symbol in the reverse direction.
+    pub fn flipped(self) -> Self {
+        match self {
+            Self::Bid => Self::Ask,
+            Self::Ask => Self::Bid,
+        }
+    }
+
+    /// The price this side trades at: the ask when buying the base, the bid when selling it.
+    pub fn price(self, bid: f64, ask: f64) -> f64 {
+        match self {
+            Self::Ask => ask,
+            Self::Bid => bid,
+        }
+    }
+
+    /// Plain trade label for this side: `BUY` for `Ask`, `SELL` for `Bid`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ask => "BUY",
+            Self::Bid => "SELL",
+        }
+    }
+
+    /// Converts `amount` of a leg's input asset into its output asset at `price`.
+    ///
+    /// Buying spends quote units (`amount / price` base received); selling spends base units
+    /// (`amount * price` quote received).
+    pub fn convert(self, amount: f64, price: f64) -> f64 {
+        match self {
+            Self::Ask => amount / price,
+            Self::Bid => amount * price,
+        }
+    }
+}
+
+
+/// Converts `acc` through one leg at `quote`'s side-relevant price, before fees.
+///
+/// The single source of truth for leg economics: every scanner prices its legs through this
+/// (or `Side::price` + `Side::convert` when it stores raw prices).
+pub fn apply_leg(acc: f64, side: Side, quote: &TopOfBookUpdate) -> f64 {
+    side.convert(acc, side.price(quote.bid_price, quote.ask_price))
+}
+
+/// Whether every leg of `path` can be priced from `quotes` (in leg order): its side-relevant
+/// price is finite and positive.
+///
+/// A zero ask divides to infinity and a NaN poisons every comparison after it, so scanners skip
+/// a path failing this even if the quote slipped past their `QuoteFilter`.
+pub fn legs_priced(path: &PricingPath, quotes: [&TopOfBookUpdate; 3]) -> bool {
+    [&path.leg1, &path.leg2, &path.leg3].into_iter().zip(quotes).all(|(leg, quote)| {
+        let price = leg.side.price(quote.bid_price, quote.ask_price);
+        price.is_finite() && price > 0.0
+    })
+}
+
+/// Side-relevant price of each leg of `path` in `quotes` (in leg order): the ask for
+/// `Side::Ask` legs, the bid for `Side::Bid` legs.
+pub fn leg_prices(path: &PricingPath, quotes: [&TopOfBookUpdate; 3]) -> [f64; 3] {
+    let legs = [&path.leg1, &path.leg2, &path.leg3];
+    [0, 1, 2].map(|i| legs[i].side.price(quotes[i].bid_price, quotes[i].ask_price))
+}
+
+/// Renders the plain `label()`. With the `colored` feature, `BUY`/`SELL` are wrapped in
+/// green/red ANSI codes unless the alternate form (`{:#}`) is used.
 impl fmt::Display for Side {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (text, color) = match self {
-            Self::Ask => ("BUY", "\x1b[32m"), // Green
-            Self::Bid => ("SELL", "\x1b[31m"), // Red
+        if !cfg!(feature = "colored") || f.alternate() {
+            return f.write_str(self.label());
+        }
+        let color = match self {
+            Self::Ask => "\x1b[32m", // Green
+            Self::Bid => "\x1b[31m", // Red
         };
-        write!(f, "{}{}{}", color, text, "\x1b[0m")
+        write!(f, "{}{}\x1b[0m", color, self.label())
     }
 }
 
 
 /// A single leg of a pricing path: includes the trading pair and side of book
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PathLeg {
-    pub symbol: SymbolInfo,
+    /// Shared with every other leg trading the symbol when built through a `SymbolRegistry`.
+    /// Serialized as the bare symbol name; exchange metadata is left out of exports.
+    #[serde(serialize_with = "serialize_symbol_name")]
+    pub symbol: Arc<SymbolInfo>,
     pub side: Side,
 }
 
+fn serialize_symbol_name<S: Serializer>(symbol: &Arc<SymbolInfo>, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&symbol.symbol)
+}
+
+
+/// Interns `SymbolInfo`s so every leg trading a symbol points at one shared copy, rather than
+/// each path carrying its own four strings per leg.
+///
+/// Symbols are keyed by name; an entry that disagrees with the registered one (malformed
+/// exchange data listing a name twice) gets its own allocation instead of being merged.
+#[derive(Debug, Default)]
+pub struct SymbolRegistry {
+    symbols: HashMap<String, Arc<SymbolInfo>>,
+}
+
+impl SymbolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The shared copy of `info`, registered on first sight.
+    pub fn intern(&mut self, info: &SymbolInfo) -> Arc<SymbolInfo> {
+        match self.symbols.get(&info.symbol) {
+            Some(shared) if **shared == *info => Arc::clone(shared),
+            Some(_) => Arc::new(info.clone()),
+            None => {
+                let shared = Arc::new(info.clone());
+                self.symbols.insert(info.symbol.clone(), Arc::clone(&shared));
+                shared
+            }
+        }
+    }
+
+    /// Distinct symbols registered.
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
+
 
 /// A complete 3-leg pricing path forming a triangle that starts and ends in the home currency.
 /// Each leg specifies the market symbol and trade direction.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PricingPath {
     pub leg1: PathLeg,
     pub leg2: PathLeg,
     pub leg3: PathLeg,
 }
 
-impl<'a> fmt::Display for PricingPath {
+/// `BUY BTCUSDT → BUY ETHBTC → SELL ETHUSDT`, using the plain side labels; colored sides
+/// (`colored` feature) are used only for the default form, never for `{:#}`.
+impl fmt::Display for PricingPath {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fn describe_leg(leg: &PathLeg) -> String {
-            format!("{} {}", leg.side, leg.symbol.symbol)
-        }
+        let plain = f.alternate();
+        let describe_leg = |leg: &PathLeg| {
+            if plain {
+                format!("{} {}", leg.side.label(), leg.symbol.symbol)
+            } else {
+                format!("{} {}", leg.side, leg.symbol.symbol)
+            }
+        };
         write!(
             f,
             "{} → {} → {}",
@@ -98,6 +317,31 @@ impl<'a> fmt::Display for PricingPath {
 }
 
 impl PricingPath {
+    /// Builds the triangle `home → a → b → home` from `exchange_info`, resolving each pair's
+    /// symbol and deriving its side exactly as discovery does, so hand-specified paths can't get
+    /// a side wrong.
+    ///
+    /// Fails if either orientation of a pair isn't listed as `TRADING`.
+    pub fn from_assets(home: &str, [a, b]: [&str; 2], exchange_info: &ExchangeInfo) -> Result<PricingPath> {
+        let pair = |from: &str, to: &str| {
+            exchange_info
+                .symbols
+                .iter()
+                .find(|s| {
+                    s.status == "TRADING"
+                        && ((s.base_asset == from && s.quote_asset == to) || (s.base_asset == to && s.quote_asset == from))
+                })
+                .with_context(|| format!("No TRADING symbol for {from}/{to}"))
+        };
+        let leg = |from: &str, to: &str| -> Result<PathLeg> {
+            let symbol = pair(from, to)?;
+            Ok(PathLeg { symbol: Arc::new(symbol.clone()), side: side_for_trade(from, symbol) })
+        };
+        let path = PricingPath { leg1: leg(home, a)?, leg2: leg(a, b)?, leg3: leg(b, home)? };
+        path.validate()?;
+        Ok(path)
+    }
+
     /// Returns all unique symbol names (e.g. "BTCUSDT") used in this path.
     pub fn symbols(&self) -> Vec<String> {
         let mut set = HashSet::new();
@@ -106,6 +350,101 @@ impl PricingPath {
         set.insert(self.leg3.symbol.symbol.clone());
         set.into_iter().collect()
     }
+
+    /// Checks that the three legs trade three distinct symbols.
+    ///
+    /// Scanners index paths by `symbols()` and price each leg from the latest quote for its
+    /// symbol, so a path that trades the same symbol on two legs would be indexed once and
+    /// priced from a single quote for both legs. Such degenerate paths are rejected.
+    pub fn validate(&self) -> Result<()> {
+        let legs = [&self.leg1, &self.leg2, &self.leg3];
+        for i in 0..legs.len() {
+            for j in (i + 1)..legs.len() {
+                if legs[i].symbol.symbol == legs[j].symbol.symbol {
+                    bail!(
+                        "Pricing path reuses symbol {} on leg{} and leg{}",
+                        legs[i].symbol.symbol,
+                        i + 1,
+                        j + 1
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the same triangle traversed in the opposite direction.
+    ///
+    /// Legs are visited in reverse order and each side is flipped, so
+    /// `BUY BTCUSDT → BUY ETHBTC → SELL ETHUSDT` becomes
+    /// `BUY ETHUSDT → SELL ETHBTC → SELL BTCUSDT`.
+    pub fn reversed(&self) -> PricingPath {
+        let flip = |leg: &PathLeg| PathLeg { symbol: leg.symbol.clone(), side: leg.side.flipped() };
+        PricingPath {
+            leg1: flip(&self.leg3),
+            leg2: flip(&self.leg2),
+            leg3: flip(&self.leg1),
+        }
+    }
+
+    /// Identifies a path by its ordered (symbol, side) legs.
+    pub(crate) fn key(&self) -> [(String, Side); 3] {
+        [&self.leg1, &self.leg2, &self.leg3].map(|leg| (leg.symbol.symbol.clone(), leg.side))
+    }
+}
+
+
+/// Extends `paths` with the reversal of every path whose reverse direction is not already present.
+///
+/// Used when evaluating both directions of each triangle so hand-constructed path lists that only
+/// list one direction still surface opportunities that exist in the other.
+pub fn with_reversed_paths(paths: Vec<PricingPath>) -> Vec<PricingPath> {
+    let mut seen: HashSet<[(String, Side); 3]> = paths.iter().map(PricingPath::key).collect();
+    let reversals: Vec<PricingPath> = paths
+        .iter()
+        .map(PricingPath::reversed)
+        .filter(|reversed| seen.insert(reversed.key()))
+        .collect();
+
+    let mut result = paths;
+    result.extend(reversals);
+    result
+}
+
+
+/// Operator-maintained symbol lists (`[symbols]` in `config/arb.toml`) applied to discovered paths.
+///
+/// Filtering works on whole paths: a path survives only if every symbol it trades is allowed, so
+/// blocking one symbol never leaves a path waiting on a quote that will never be subscribed.
+/// Entries are normalized (see `normalize_symbol`) as the config is read, like exchangeInfo's
+/// symbols, so `btc-usdt` matches `BTCUSDT`.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct SymbolFilter {
+    /// Symbols never traded or subscribed, even if they complete a triangle.
+    #[serde(default, deserialize_with = "deserialize_symbols")]
+    pub blocklist: Vec<String>,
+    /// When non-empty, only these symbols may be traded or subscribed.
+    #[serde(default, deserialize_with = "deserialize_symbols")]
+    pub allowlist: Vec<String>,
+}
+
+impl SymbolFilter {
+    /// Whether `symbol`, in any case or separator style, may be traded.
+    pub fn allows(&self, symbol: &str) -> bool {
+        let symbol = normalize_symbol(symbol);
+        !self.blocklist.contains(&symbol) && (self.allowlist.is_empty() || self.allowlist.contains(&symbol))
+    }
+
+    /// Drops every path that trades a blocked (or, with an allowlist, unlisted) symbol.
+    pub fn apply(&self, paths: Vec<PricingPath>) -> Vec<PricingPath> {
+        if self.blocklist.is_empty() && self.allowlist.is_empty() {
+            return paths;
+        }
+        paths
+            .into_iter()
+            .filter(|path| [&path.leg1, &path.leg2, &path.leg3].iter().all(|leg| self.allows(&leg.symbol.symbol)))
+            .collect()
+    }
 }
 
 
@@ -113,12 +452,74 @@ impl PricingPath {
 ///
 /// Used for offline development or testing.
 pub fn load_exchange_info_fixture() -> Result<ExchangeInfo> {
-    let path = "fixtures/exchangeInfoSpot.json";
-    let raw = fs::read_to_string(path)?;
-    let parsed: ExchangeInfo = serde_json::from_str(&raw)?;
+    load_exchange_info_fixture_from(EXCHANGE_INFO_FIXTURE)
+}
+
+/// Loads exchangeInfo JSON from `path`, e.g. an alternate dataset or a test fixture.
+///
+/// Warns when the file is older than `DEFAULT_EXCHANGE_INFO_MAX_AGE` (see `stale_fixture_age`).
+pub fn load_exchange_info_fixture_from<P: AsRef<Path>>(path: P) -> Result<ExchangeInfo> {
+    load_exchange_info_checked(path, Some(DEFAULT_EXCHANGE_INFO_MAX_AGE))
+}
+
+/// Same as `load_exchange_info_fixture_from` with an explicit staleness threshold; `None`
+/// skips the check.
+pub fn load_exchange_info_checked<P: AsRef<Path>>(path: P, max_age: Option<Duration>) -> Result<ExchangeInfo> {
+    let path = path.as_ref();
+    if let Some(max_age) = max_age {
+        stale_fixture_age(path, max_age);
+    }
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read exchangeInfo fixture '{}'", path.display()))?;
+    let parsed: ExchangeInfo = serde_json::from_str(&raw)
+        .with_context(|| format!("Failed to parse exchangeInfo fixture '{}'", path.display()))?;
     Ok(parsed)
 }
 
+/// Returns the age of the file at `path` when its modification time is more than `max_age`
+/// ago, warning that it should be refreshed; `None` when it is fresh or has no readable mtime.
+///
+/// An old snapshot still loads, but it subscribes to delisted symbols and misses new listings.
+pub fn stale_fixture_age(path: &Path, max_age: Duration) -> Option<Duration> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let age = SystemTime::now().duration_since(modified).ok()?;
+    if age <= max_age {
+        return None;
+    }
+    tracing::warn!(
+        path = %path.display(),
+        age_days = age.as_secs() / (24 * 60 * 60),
+        max_age_days = max_age.as_secs() / (24 * 60 * 60),
+        "exchangeInfo snapshot is stale; refresh it from https://api.binance.com/api/v3/exchangeInfo",
+    );
+    Some(age)
+}
+
+/// Loads several exchangeInfo files into one, e.g. to compose test datasets or extend a universe.
+///
+/// Symbols keep the order they first appear in. A symbol listed more than once is kept once,
+/// preferring a `TRADING` entry over any other status, otherwise the first one seen.
+pub fn load_exchange_info_merged(paths: &[&Path]) -> Result<ExchangeInfo> {
+    let mut symbols: Vec<SymbolInfo> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for path in paths {
+        for info in load_exchange_info_fixture_from(path)?.symbols {
+            match positions.get(&info.symbol) {
+                Some(&i) => {
+                    if symbols[i].status != "TRADING" && info.status == "TRADING" {
+                        symbols[i] = info;
+                    }
+                }
+                None => {
+                    positions.insert(info.symbol.clone(), symbols.len());
+                    symbols.push(info);
+                }
+            }
+        }
+    }
+    Ok(ExchangeInfo::new(symbols))
+}
+
 
 /// Finds all valid symbol triplets forming a triangular trading loop starting and ending in `home`.
 ///
@@ -135,37 +536,53 @@ pub fn find_path_symbols<'a>(
     exchange_info: &'a ExchangeInfo,
     home: &str,
     targets: &[&str],
+) -> Vec<(&'a SymbolInfo, &'a SymbolInfo, &'a SymbolInfo)> {
+    find_path_symbols_capped(exchange_info, home, targets, None)
+}
+
+/// Triplets found between progress logs while discovering paths.
+const DISCOVERY_PROGRESS_INTERVAL: usize = 100_000;
+
+/// `find_path_symbols`, stopping as soon as `max_paths` triplets have been found.
+///
+/// The search is O(n³) in the symbol count, so on the full exchange with broad targets an
+/// uncapped run can take a long time; with a cap it returns the first `max_paths` triplets in
+/// discovery order without walking the rest. Progress is logged at debug level.
+pub fn find_path_symbols_capped<'a>(
+    exchange_info: &'a ExchangeInfo,
+    home: &str,
+    targets: &[&str],
+    max_paths: Option<usize>,
 ) -> Vec<(&'a SymbolInfo, &'a SymbolInfo, &'a SymbolInfo)> {
     let symbols: Vec<&SymbolInfo> = exchange_info
         .symbols
         .iter()
         .filter(|s| s.status == "TRADING")
         .collect();
+    let is_target = |asset: &str| targets.contains(&asset);
+    let max_paths = max_paths.unwrap_or(usize::MAX);
 
     let mut result = Vec::new();
+    if max_paths == 0 {
+        return result;
+    }
 
     for &leg1 in &symbols {
-        if leg1.quote_asset != home { continue; }
-        if !targets.contains(&leg1.base_asset.as_str()) { continue; }
-
-        let mid1 = &leg1.base_asset;
+        if !is_first_leg(leg1, home, &is_target) { continue; }
 
         for &leg2 in &symbols {
-            if leg2 == leg1 { continue; }
-
-            let connects_to_mid1 = leg2.base_asset == *mid1 || leg2.quote_asset == *mid1;
-            if !connects_to_mid1 { continue; }
-
-            if !(targets.contains(&leg2.base_asset.as_str()) && targets.contains(&leg2.quote_asset.as_str())) {
-                continue;
-            }
+            if !is_second_leg(leg1, leg2, home) { continue; }
 
             for &leg3 in &symbols {
-                if leg3 == leg1 || leg3 == leg2 { continue; }
-                if leg3.quote_asset != home { continue; }
-
-                if leg3.base_asset == leg2.base_asset || leg3.base_asset == leg2.quote_asset {
+                if is_third_leg(leg1, leg2, leg3, home) {
                     result.push((leg1, leg2, leg3));
+                    if result.len() % DISCOVERY_PROGRESS_INTERVAL == 0 {
+                        tracing::debug!(found = result.len(), leg1 = %leg1.symbol, "Discovering paths");
+                    }
+                    if result.len() == max_paths {
+                        tracing::info!(max_paths, "Path discovery stopped at the max_paths cap");
+                        return result;
+                    }
                 }
             }
         }
@@ -174,6 +591,186 @@ pub fn find_path_symbols<'a>(
 }
 
 
+/// Leg 1 buys a target asset with the home asset.
+fn is_first_leg(leg1: &SymbolInfo, home: &str, is_target: &impl Fn(&str) -> bool) -> bool {
+    leg1.quote_asset == home && is_target(&leg1.base_asset)
+}
+
+
+/// Leg 2 is a distinct symbol trading leg 1's target asset for some non-home asset.
+///
+/// The second intermediate doesn't have to be a target (e.g. USDT → BTC → EUR → USDT with
+/// only BTC targeted); leg 3 decides whether it can be converted back to the home asset.
+fn is_second_leg(leg1: &SymbolInfo, leg2: &SymbolInfo, home: &str) -> bool {
+    leg2 != leg1 && second_mid(leg1, leg2).is_some_and(|mid2| mid2 != home)
+}
+
+
+/// Leg 3 is a distinct home-quoted symbol selling the asset leg 2 bought.
+fn is_third_leg(leg1: &SymbolInfo, leg2: &SymbolInfo, leg3: &SymbolInfo, home: &str) -> bool {
+    leg3 != leg1
+        && leg3 != leg2
+        && leg3.quote_asset == home
+        && second_mid(leg1, leg2) == Some(leg3.base_asset.as_str())
+}
+
+
+/// The asset leg 2 exchanges leg 1's target asset for, if leg 2 trades that asset at all.
+fn second_mid<'a>(leg1: &SymbolInfo, leg2: &'a SymbolInfo) -> Option<&'a str> {
+    let mid1 = &leg1.base_asset;
+    if leg2.base_asset == *mid1 {
+        Some(&leg2.quote_asset)
+    } else if leg2.quote_asset == *mid1 {
+        Some(&leg2.base_asset)
+    } else {
+        None
+    }
+}
+
+
+/// How `select_top_targets` ranks candidate target assets, most active first.
+#[derive(Debug, Clone)]
+pub enum TargetRanking {
+    /// A static list of base assets, e.g. maintained by hand from a market-cap table.
+    Assets(Vec<String>),
+    /// 24h traded volume per symbol (see `load_volume_24h`); an asset ranks by the volume of
+    /// its market against the home asset.
+    Volume24h(HashMap<String, f64>),
+}
+
+/// The `count` most active assets per `ranking` that trade against `home` in `exchange_info`,
+/// most active first, for use as discovery targets ("scan the top 50 coins").
+///
+/// Assets without a TRADING `{asset}{home}` market can't start a path and are skipped; with
+/// `Volume24h`, markets missing from the volume map rank last. Ties break by asset name.
+pub fn select_top_targets(exchange_info: &ExchangeInfo, home: &str, ranking: &TargetRanking, count: usize) -> Vec<String> {
+    let home_markets = exchange_info
+        .symbols
+        .iter()
+        .filter(|s| s.status == "TRADING" && s.quote_asset == home && s.base_asset != home);
+    match ranking {
+        TargetRanking::Assets(assets) => {
+            let tradable: HashSet<&str> = home_markets.map(|s| s.base_asset.as_str()).collect();
+            let mut seen = HashSet::new();
+            assets
+                .iter()
+                .filter(|asset| tradable.contains(asset.as_str()) && seen.insert(asset.as_str()))
+                .take(count)
+                .cloned()
+                .collect()
+        }
+        TargetRanking::Volume24h(volumes) => {
+            let mut ranked: Vec<(&str, f64)> =
+                home_markets.map(|s| (s.base_asset.as_str(), volumes.get(&s.symbol).copied().unwrap_or(0.0))).collect();
+            ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            ranked.into_iter().take(count).map(|(asset, _)| asset.to_string()).collect()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TickerVolume {
+    symbol: String,
+    #[serde(rename = "quoteVolume")]
+    quote_volume: String,
+}
+
+/// Loads per-symbol 24h quote volume from a saved Binance `/api/v3/ticker/24hr` response,
+/// for `TargetRanking::Volume24h`.
+pub fn load_volume_24h<P: AsRef<Path>>(path: P) -> Result<HashMap<String, f64>> {
+    let path = path.as_ref();
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read 24h ticker file '{}'", path.display()))?;
+    let tickers: Vec<TickerVolume> = serde_json::from_str(&raw)
+        .with_context(|| format!("Failed to parse 24h ticker file '{}'", path.display()))?;
+    tickers
+        .into_iter()
+        .map(|t| {
+            let volume = t.quote_volume.parse().with_context(|| format!("Invalid quoteVolume for {}", t.symbol))?;
+            Ok((t.symbol, volume))
+        })
+        .collect()
+}
+
+
+/// Loads the exchangeInfo fixture and lazily yields every valid pricing path.
+///
+/// Same paths, in the same order, as `find_and_build_price_paths`, but nothing beyond the
+/// current path is materialized — callers that only sample or count can `take(n)` cheaply.
+pub fn iter_price_paths(home_asset: &str, targets: &[&str]) -> Result<PricePathIter> {
+    let exchange_info = load_exchange_info_fixture()?;
+    Ok(PricePathIter::new(exchange_info, home_asset, targets))
+}
+
+
+/// Lazy equivalent of `find_path_symbols` followed by `build_paths`, owning its exchange data.
+///
+/// Walks the same three nested symbol loops, suspended between yielded paths. Yielded paths
+/// share their symbols with each other.
+pub struct PricePathIter {
+    symbols: Vec<Arc<SymbolInfo>>,
+    home: String,
+    targets: HashSet<String>,
+    i: usize,
+    j: usize,
+    k: usize,
+}
+
+impl PricePathIter {
+    pub fn new(exchange_info: ExchangeInfo, home: &str, targets: &[&str]) -> Self {
+        Self {
+            symbols: exchange_info
+                .symbols
+                .into_iter()
+                .filter(|s| s.status == "TRADING")
+                .map(Arc::new)
+                .collect(),
+            home: home.to_string(),
+            targets: targets.iter().map(|t| t.to_string()).collect(),
+            i: 0,
+            j: 0,
+            k: 0,
+        }
+    }
+}
+
+impl Iterator for PricePathIter {
+    type Item = PricingPath;
+
+    fn next(&mut self) -> Option<PricingPath> {
+        let n = self.symbols.len();
+        let targets = &self.targets;
+        let is_target = |asset: &str| targets.contains(asset);
+
+        while self.i < n {
+            let leg1 = &self.symbols[self.i];
+            if is_first_leg(leg1, &self.home, &is_target) {
+                while self.j < n {
+                    let leg2 = &self.symbols[self.j];
+                    if is_second_leg(leg1, leg2, &self.home) {
+                        while self.k < n {
+                            let leg3 = &self.symbols[self.k];
+                            self.k += 1;
+                            if is_third_leg(leg1, leg2, leg3, &self.home)
+                                && let Some(path) = build_path(&self.home, leg1, leg2, leg3)
+                            {
+                                return Some(path);
+                            }
+                        }
+                    }
+                    self.j += 1;
+                    self.k = 0;
+                }
+            }
+            self.i += 1;
+            self.j = 0;
+            self.k = 0;
+        }
+        None
+    }
+}
+
+
 /// Converts symbol triplets into fully directional `PricingPath` structs with side-of-book info.
 ///
 /// # Arguments
@@ -181,37 +778,54 @@ pub fn find_path_symbols<'a>(
 /// - `triplets`: The raw symbols making up each triangular candidate.
 ///
 /// # Returns
-/// A vector of `PricingPath` with correct direction and book side assignment.
+/// A vector of `PricingPath` with correct direction and book side assignment, whose legs share
+/// one `Arc<SymbolInfo>` per symbol (see `SymbolRegistry`).
+/// Triplets that reuse a symbol on two legs (see `PricingPath::validate`) are skipped.
 pub fn build_paths<'a>(
     home: &str,
     triplets: Vec<(&'a SymbolInfo, &'a SymbolInfo, &'a SymbolInfo)>
 ) -> Vec<PricingPath> {
+    let mut registry = SymbolRegistry::new();
     let mut result = Vec::new();
     println!("Constructing pricing paths");
     for (s1, s2, s3) in triplets {
-        // leg1: home → mid1
-        let to1 = if s1.base_asset == home { &s1.quote_asset } else { &s1.base_asset };
-        let side1 = side_for_trade(home, s1);
-
-        // leg2: mid1 → mid2
-        let to2 = if s2.base_asset == *to1 { &s2.quote_asset } else { &s2.base_asset };
-        let side2 = side_for_trade(to1, s2);
-
-        // leg3: mid2 → home
-        let side3 = side_for_trade(to2, s3);
-        let path = PricingPath {
-            leg1: PathLeg { symbol: s1.clone(), side: side1 },
-            leg2: PathLeg { symbol: s2.clone(), side: side2 },
-            leg3: PathLeg { symbol: s3.clone(), side: side3 },
-        };
-        // println!("Constructed: {}", path);
-        result.push(path);
+        let [s1, s2, s3] = [s1, s2, s3].map(|symbol| registry.intern(symbol));
+        result.extend(build_path(home, &s1, &s2, &s3));
     }
 
     result
 }
 
 
+/// Assigns sides to a single symbol triplet, returning `None` for degenerate paths.
+///
+/// Sides follow `side_for_trade`, so any orientation is handled, but since discovery only
+/// pairs home-quoted first and third legs, discovered paths are always `Ask, _, Bid`.
+fn build_path(home: &str, s1: &Arc<SymbolInfo>, s2: &Arc<SymbolInfo>, s3: &Arc<SymbolInfo>) -> Option<PricingPath> {
+    // leg1: home → mid1
+    let to1 = if s1.base_asset == home { &s1.quote_asset } else { &s1.base_asset };
+    let side1 = side_for_trade(home, s1);
+
+    // leg2: mid1 → mid2
+    let to2 = if s2.base_asset == *to1 { &s2.quote_asset } else { &s2.base_asset };
+    let side2 = side_for_trade(to1, s2);
+
+    // leg3: mid2 → home
+    let side3 = side_for_trade(to2, s3);
+    let path = PricingPath {
+        leg1: PathLeg { symbol: Arc::clone(s1), side: side1 },
+        leg2: PathLeg { symbol: Arc::clone(s2), side: side2 },
+        leg3: PathLeg { symbol: Arc::clone(s3), side: side3 },
+    };
+    if let Err(e) = path.validate() {
+        tracing::warn!("Skipping degenerate path: {e}");
+        return None;
+    }
+    // println!("Constructed: {}", path);
+    Some(path)
+}
+
+
 /// Determines the correct side of the order book to use given an input asset and symbol.
 ///
 /// # Arguments
@@ -234,66 +848,24 @@ fn side_for_trade(input_asset: &str, symbol: &SymbolInfo) -> Side {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testutil::{make_symbol, mock_path, MockBuilder};
+
     static TARGETS: &[&str] = &["BTC", "ETH", "SOL"];
     static HOME: &str = "USDT";
     fn mock_exchange_info() -> ExchangeInfo {
-        ExchangeInfo {
-            symbols: vec![
-                // ───── BTC/ETH Triangle ─────
-                SymbolInfo {
-                    symbol: "BTCUSDT".into(),
-                    base_asset: "BTC".into(),
-                    quote_asset: "USDT".into(),
-                    status: "TRADING".into(),
-                },
-                SymbolInfo {
-                    symbol: "ETHBTC".into(),
-                    base_asset: "ETH".into(),
-                    quote_asset: "BTC".into(),
-                    status: "TRADING".into(),
-                },
-                SymbolInfo {
-                    symbol: "ETHUSDT".into(),
-                    base_asset: "ETH".into(),
-                    quote_asset: "USDT".into(),
-                    status: "TRADING".into(),
-                },
-    
-                // ───── SOL/BTC Triangle ─────
-                SymbolInfo {
-                    symbol: "SOLBTC".into(),
-                    base_asset: "SOL".into(),
-                    quote_asset: "BTC".into(),
-                    status: "TRADING".into(),
-                },
-                SymbolInfo {
-                    symbol: "SOLUSDT".into(),
-                    base_asset: "SOL".into(),
-                    quote_asset: "USDT".into(),
-                    status: "TRADING".into(),
-                },
-    
-                // ───── Controls ─────
-                SymbolInfo {
-                    symbol: "LTCUSDT".into(),
-                    base_asset: "LTC".into(),
-                    quote_asset: "USDT".into(),
-                    status: "TRADING".into(),
-                },
-                SymbolInfo {
-                    symbol: "BADPAIR".into(),
-                    base_asset: "BTC".into(),
-                    quote_asset: "ETH".into(),
-                    status: "BREAKING".into(), // should be ignored
-                }
-            ],
-        }
+        MockBuilder::new()
+            .triangle(HOME, "BTC", "ETH")
+            .triangle(HOME, "BTC", "SOL")
+            // Controls: no triangle, and not trading
+            .pair("LTCUSDT", "LTC", "USDT")
+            .pair_with_status("BADPAIR", "BTC", "ETH", "BREAKING")
+            .exchange_info()
     }
     
     #[test]
     fn test_find_path_symbols_triangle_with_btc_eth_sol() {
         let exchange_info = mock_exchange_info();
-        let paths = find_path_symbols(&exchange_info, &HOME, &TARGETS);
+        let paths = find_path_symbols(&exchange_info, HOME, TARGETS);
         assert_eq!(paths.len(), 4, "Expected 4 valid triangle paths");
 
         let syms: Vec<_> = paths.iter().map(|(a, b, c)| {
@@ -310,45 +882,99 @@ mod tests {
     }
 
     #[test]
-    fn no_triangle_when_cross_missing() {
-        let exchange_info = ExchangeInfo {
-            symbols: vec![
-                SymbolInfo {
-                    symbol: "BTCUSDT".into(),
-                    base_asset: "BTC".into(),
-                    quote_asset: "USDT".into(),
-                    status: "TRADING".into(),
-                },
-                SymbolInfo {
-                    symbol: "BTCUSDC".into(),
-                    base_asset: "BTC".into(),
-                    quote_asset: "USDC".into(),
-                    status: "TRADING".into(),
-                },
-                SymbolInfo {
-                    symbol: "ETHUSDT".into(),
-                    base_asset: "ETH".into(),
-                    quote_asset: "USDT".into(),
-                    status: "TRADING".into(),
-                },
-                // Control: not part of triangle
-                SymbolInfo {
-                    symbol: "ETHUSDC".into(),
-                    base_asset: "ETH".into(),
-                    quote_asset: "USDC".into(),
-                    status: "TRADING".into(),
-                },
-            ],
+    fn capped_discovery_stops_at_max_paths() {
+        let exchange_info = mock_exchange_info();
+        let all = find_path_symbols(&exchange_info, HOME, TARGETS);
+        let symbols = |triplets: &[(&SymbolInfo, &SymbolInfo, &SymbolInfo)]| {
+            triplets.iter().map(|(a, b, c)| [&a.symbol, &b.symbol, &c.symbol].map(String::clone)).collect::<Vec<_>>()
         };
+
+        let capped = find_path_symbols_capped(&exchange_info, HOME, TARGETS, Some(3));
+        assert_eq!(symbols(&capped), symbols(&all[..3]), "the first three in discovery order");
+        assert!(find_path_symbols_capped(&exchange_info, HOME, TARGETS, Some(0)).is_empty());
+        assert_eq!(find_path_symbols_capped(&exchange_info, HOME, TARGETS, Some(100)).len(), all.len());
+    }
+
+    #[test]
+    fn from_assets_matches_the_discovered_path() {
+        let exchange_info = mock_exchange_info();
+        let discovered = build_paths(HOME, find_path_symbols(&exchange_info, HOME, TARGETS));
+
+        for [a, b] in [["BTC", "ETH"], ["ETH", "BTC"]] {
+            let built = PricingPath::from_assets(HOME, [a, b], &exchange_info).unwrap();
+            let expected = discovered
+                .iter()
+                .find(|p| p.leg1.symbol.base_asset == a && p.leg3.symbol.base_asset == b)
+                .unwrap();
+            assert_eq!(built.key(), expected.key(), "USDT → {a} → {b} → USDT");
+            assert_eq!(built.leg2.symbol, expected.leg2.symbol);
+        }
+
+        let missing = PricingPath::from_assets(HOME, ["ETH", "SOL"], &exchange_info).unwrap_err();
+        assert!(missing.to_string().contains("ETH/SOL"), "{missing}");
+        assert!(PricingPath::from_assets(HOME, ["BTC", "LTC"], &exchange_info).is_err());
+    }
+
+    #[test]
+    fn top_targets_follow_the_ranking_and_drive_discovery() {
+        let exchange_info = mock_exchange_info();
+
+        // DOGE has no USDT market and is skipped; the home asset itself is never a target
+        let ranked = TargetRanking::Assets(["DOGE", "USDT", "SOL", "BTC", "ETH"].map(String::from).to_vec());
+        let top = select_top_targets(&exchange_info, HOME, &ranked, 2);
+        assert_eq!(top, ["SOL", "BTC"]);
+
+        let targets: Vec<&str> = top.iter().map(String::as_str).collect();
+        let first_legs: HashSet<&str> = find_path_symbols(&exchange_info, HOME, &targets)
+            .into_iter()
+            .map(|(leg1, _, _)| leg1.base_asset.as_str())
+            .collect();
+        assert_eq!(first_legs, HashSet::from(["SOL", "BTC"]));
+
+        let volumes = HashMap::from([("ETHUSDT".to_string(), 3e9), ("LTCUSDT".to_string(), 4e9), ("BTCUSDT".to_string(), 5e9)]);
+        let top = select_top_targets(&exchange_info, HOME, &TargetRanking::Volume24h(volumes), 3);
+        assert_eq!(top, ["BTC", "LTC", "ETH"]);
+    }
+
+    #[test]
+    fn no_triangle_when_cross_missing() {
+        let exchange_info = MockBuilder::new()
+            .pair("BTCUSDT", "BTC", "USDT")
+            .pair("BTCUSDC", "BTC", "USDC")
+            .pair("ETHUSDT", "ETH", "USDT")
+            // Control: not part of triangle
+            .pair("ETHUSDC", "ETH", "USDC")
+            .exchange_info();
         let result = find_path_symbols(&exchange_info, "USDT", &["BTC", "ETH"]);
         assert_eq!(result.len(), 0, "Should not find a triangle without ETHBTC");
     }
 
+    #[test]
+    fn cross_quote_triangle_with_non_target_second_asset() {
+        let exchange_info = MockBuilder::new()
+            .pair("BTCUSDT", "BTC", "USDT")
+            .pair("BTCEUR", "BTC", "EUR")
+            .pair("EURUSDT", "EUR", "USDT")
+            .exchange_info();
+
+        // EUR is not a target, but USDT → BTC → EUR → USDT is still a valid triangle
+        let triplets = find_path_symbols(&exchange_info, "USDT", &["BTC"]);
+        let syms: Vec<_> = triplets.iter()
+            .map(|(a, b, c)| (a.symbol.as_str(), b.symbol.as_str(), c.symbol.as_str()))
+            .collect();
+        assert_eq!(syms, vec![("BTCUSDT", "BTCEUR", "EURUSDT")]);
+
+        let paths = build_paths("USDT", triplets);
+        assert_eq!(paths[0].leg1.side, Side::Ask);
+        assert_eq!(paths[0].leg2.side, Side::Bid);
+        assert_eq!(paths[0].leg3.side, Side::Bid);
+    }
+
     #[test]
     fn all_paths_have_three_distinct_assets() {
         let exchange_info = mock_exchange_info();
-        let triplets = find_path_symbols(&exchange_info, &HOME, &TARGETS);
-        let paths = build_paths(&HOME, triplets);
+        let triplets = find_path_symbols(&exchange_info, HOME, TARGETS);
+        let paths = build_paths(HOME, triplets);
 
         for (i, path) in paths.iter().enumerate() {
             let mut assets = std::collections::HashSet::new();
@@ -372,8 +998,8 @@ mod tests {
     #[test]
     fn all_paths_start_and_end_with_home() {
         let exchange_info = mock_exchange_info();
-        let triplets = find_path_symbols(&exchange_info, &HOME, &TARGETS);
-        let paths = build_paths(&HOME, triplets);
+        let triplets = find_path_symbols(&exchange_info, HOME, TARGETS);
+        let paths = build_paths(HOME, triplets);
 
         for (i, path) in paths.iter().enumerate() {
             let start_assets = [&path.leg1.symbol.base_asset, &path.leg1.symbol.quote_asset];
@@ -393,8 +1019,8 @@ mod tests {
     #[test]
     fn no_duplicate_symbols_in_path() {
         let exchange_info = mock_exchange_info();
-        let triplets = find_path_symbols(&exchange_info, &HOME, &TARGETS);
-        let paths = build_paths(&HOME, triplets);
+        let triplets = find_path_symbols(&exchange_info, HOME, TARGETS);
+        let paths = build_paths(HOME, triplets);
 
         for (i, path) in paths.iter().enumerate() {
             let symbols = [
@@ -411,11 +1037,134 @@ mod tests {
         }
     }
     
+    #[test]
+    fn reversed_path_flips_order_and_sides() {
+        let exchange_info = mock_exchange_info();
+        let triplets = find_path_symbols(&exchange_info, HOME, TARGETS);
+        let paths = build_paths(HOME, triplets);
+        let path = &paths[0];
+        let reversed = path.reversed();
+
+        assert_eq!(reversed.leg1.symbol, path.leg3.symbol);
+        assert_eq!(reversed.leg2.symbol, path.leg2.symbol);
+        assert_eq!(reversed.leg3.symbol, path.leg1.symbol);
+        assert_eq!(reversed.leg1.side, path.leg3.side.flipped());
+        assert_eq!(reversed.leg2.side, path.leg2.side.flipped());
+        assert_eq!(reversed.leg3.side, path.leg1.side.flipped());
+    }
+
+    #[test]
+    fn with_reversed_paths_skips_directions_already_present() {
+        let exchange_info = mock_exchange_info();
+        let triplets = find_path_symbols(&exchange_info, HOME, TARGETS);
+        let paths = build_paths(HOME, triplets);
+
+        // Discovery already yields both directions of every triangle
+        assert_eq!(with_reversed_paths(paths.clone()).len(), paths.len());
+
+        let one_direction = vec![paths[0].clone()];
+        assert_eq!(with_reversed_paths(one_direction).len(), 2);
+    }
+
+    #[test]
+    fn paths_share_one_allocation_per_symbol() {
+        let exchange_info = mock_exchange_info();
+        let built = build_paths(HOME, find_path_symbols(&exchange_info, HOME, TARGETS));
+        let lazy: Vec<PricingPath> = PricePathIter::new(mock_exchange_info(), HOME, TARGETS).collect();
+
+        for paths in [&built, &lazy] {
+            let btcusdt: Vec<&Arc<SymbolInfo>> = paths
+                .iter()
+                .flat_map(|p| [&p.leg1.symbol, &p.leg2.symbol, &p.leg3.symbol])
+                .filter(|s| s.symbol == "BTCUSDT")
+                .collect();
+            assert!(btcusdt.len() >= 2, "both triangles trade BTCUSDT");
+            assert!(btcusdt.iter().all(|s| Arc::ptr_eq(s, btcusdt[0])));
+        }
+
+        let mut registry = SymbolRegistry::new();
+        let btcusdt = make_symbol("BTCUSDT", "BTC", "USDT");
+        assert!(Arc::ptr_eq(&registry.intern(&btcusdt), &registry.intern(&btcusdt)));
+        let conflicting = registry.intern(&make_symbol("BTCUSDT", "ETH", "USDT"));
+        assert_eq!(conflicting.base_asset, "ETH", "a conflicting listing is not merged");
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn degenerate_path_reusing_symbol_is_rejected() {
+        // Malformed exchange data: a second "BTCUSDT" entry listed as ETH/USDT
+        let btcusdt = make_symbol("BTCUSDT", "BTC", "USDT");
+        let ethbtc = make_symbol("ETHBTC", "ETH", "BTC");
+        let btcusdt_dup = make_symbol("BTCUSDT", "ETH", "USDT");
+
+        let path = PricingPath {
+            leg1: PathLeg { symbol: Arc::new(btcusdt.clone()), side: Side::Ask },
+            leg2: PathLeg { symbol: Arc::new(ethbtc.clone()), side: Side::Ask },
+            leg3: PathLeg { symbol: Arc::new(btcusdt_dup.clone()), side: Side::Bid },
+        };
+        let err = path.validate().expect_err("path reusing BTCUSDT should be rejected");
+        assert_eq!(err.to_string(), "Pricing path reuses symbol BTCUSDT on leg1 and leg3");
+
+        let paths = build_paths(HOME, vec![(&btcusdt, &ethbtc, &btcusdt_dup)]);
+        assert!(paths.is_empty(), "build_paths should skip degenerate triplets");
+    }
+
+    #[test]
+    fn symbol_lookup_by_name() {
+        let exchange_info = mock_exchange_info();
+
+        let info = exchange_info.symbol("ETHBTC").expect("ETHBTC is listed");
+        assert_eq!(info.base_asset, "ETH");
+        assert_eq!(info.quote_asset, "BTC");
+        assert!(exchange_info.symbol("DOGEUSDT").is_none());
+    }
+
+    #[test]
+    fn iterator_yields_same_paths_as_eager_build() {
+        let exchange_info = mock_exchange_info();
+        let eager: Vec<String> = build_paths(HOME, find_path_symbols(&exchange_info, HOME, TARGETS))
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        let lazy: Vec<String> = PricePathIter::new(mock_exchange_info(), HOME, TARGETS)
+            .map(|p| p.to_string())
+            .collect();
+
+        assert_eq!(lazy.len(), 4);
+        assert_eq!(lazy, eager, "iterator should yield the same paths in the same order");
+
+        let first_two: Vec<String> = PricePathIter::new(mock_exchange_info(), HOME, TARGETS)
+            .take(2)
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(first_two, eager[..2]);
+    }
+
+    #[test]
+    fn tick_size_is_parsed_from_price_filter() {
+        let raw = r#"{"symbols":[
+            {"symbol":"BTCUSDT","status":"TRADING","baseAsset":"BTC","quoteAsset":"USDT","filters":[
+                {"filterType":"PRICE_FILTER","minPrice":"0.01000000","maxPrice":"1000000.00000000","tickSize":"0.01000000"},
+                {"filterType":"LOT_SIZE","minQty":"0.00001000","maxQty":"9000.00000000","stepSize":"0.00001000"}
+            ]},
+            {"symbol":"ETHBTC","status":"TRADING","baseAsset":"ETH","quoteAsset":"BTC","filters":[
+                {"filterType":"PRICE_FILTER","minPrice":"0","maxPrice":"0","tickSize":"0.00000000"}
+            ]},
+            {"symbol":"ETHUSDT","status":"TRADING","baseAsset":"ETH","quoteAsset":"USDT"}
+        ]}"#;
+        let info: ExchangeInfo = serde_json::from_str(raw).expect("exchangeInfo should parse");
+
+        assert_eq!(info.symbols[0].tick_size, Some(0.01));
+        assert_eq!(info.symbols[1].tick_size, None, "disabled filter");
+        assert_eq!(info.symbols[2].tick_size, None, "missing filters");
+    }
+
     #[test]
     fn all_legs_have_valid_side_assignment() {
         let exchange_info = mock_exchange_info();
-        let triplets = find_path_symbols(&exchange_info, &HOME, &TARGETS);
-        let paths = build_paths(&HOME, triplets);
+        let triplets = find_path_symbols(&exchange_info, HOME, TARGETS);
+        let paths = build_paths(HOME, triplets);
         for path in paths.iter() {
             for leg in [&path.leg1, &path.leg2, &path.leg3] {
                 match leg.side {
@@ -424,4 +1173,209 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn apply_leg_buys_at_ask_and_sells_at_bid() {
+        let quote = TopOfBookUpdate {
+            symbol: "ETHUSDT".into(),
+            bid_price: 3000.0,
+            ask_price: 3001.0,
+            recv_ts: std::time::Instant::now(),
+            event_time_ms: None,
+        };
+
+        // Buying ETH spends USDT at the ask
+        assert_eq!(apply_leg(3001.0, Side::Ask, &quote), 1.0);
+        assert_eq!(apply_leg(1000.0, Side::Ask, &quote), 1000.0 / 3001.0);
+        // Selling ETH receives USDT at the bid
+        assert_eq!(apply_leg(2.0, Side::Bid, &quote), 6000.0);
+
+        // A round trip loses exactly the spread
+        let round_trip = apply_leg(apply_leg(1000.0, Side::Ask, &quote), Side::Bid, &quote);
+        assert_eq!(round_trip, 1000.0 / 3001.0 * 3000.0);
+        assert!(round_trip < 1000.0);
+    }
+
+    #[test]
+    fn legs_priced_rejects_zero_and_non_finite_leg_prices() {
+        let path = mock_path();
+        let quote = |symbol: &str, bid: f64, ask: f64| TopOfBookUpdate {
+            symbol: symbol.into(),
+            bid_price: bid,
+            ask_price: ask,
+            recv_ts: std::time::Instant::now(),
+            event_time_ms: None,
+        };
+        let btc = quote("BTCUSDT", 50000.0, 50010.0);
+        let eth_btc = quote("ETHBTC", 0.07, 0.0701);
+        let eth = quote("ETHUSDT", 3000.0, 3001.0);
+        assert!(legs_priced(&path, [&btc, &eth_btc, &eth]));
+
+        // Only the side the leg trades at matters: BTCUSDT is bought at the ask
+        let zero_bid = quote("BTCUSDT", 0.0, 50010.0);
+        assert!(legs_priced(&path, [&zero_bid, &eth_btc, &eth]));
+        let zero_ask = quote("BTCUSDT", 50000.0, 0.0);
+        assert!(!legs_priced(&path, [&zero_ask, &eth_btc, &eth]));
+        let nan_bid = quote("ETHUSDT", f64::NAN, 3001.0);
+        assert!(!legs_priced(&path, [&btc, &eth_btc, &nan_bid]));
+        let infinite_ask = quote("ETHBTC", 0.07, f64::INFINITY);
+        assert!(!legs_priced(&path, [&btc, &infinite_ask, &eth]));
+    }
+
+    #[test]
+    fn default_display_is_free_of_escape_codes() {
+        assert_eq!(Side::Ask.label(), "BUY");
+        assert_eq!(Side::Bid.label(), "SELL");
+
+        let exchange_info = mock_exchange_info();
+        let paths = build_paths(HOME, find_path_symbols(&exchange_info, HOME, TARGETS));
+        let plain = format!("{:#}", paths[0]);
+        assert!(plain.starts_with("BUY "), "discovered paths open with an Ask leg: {plain}");
+        assert!(!plain.contains('\x1b'));
+        if !cfg!(feature = "colored") {
+            assert_eq!(paths[0].to_string(), plain);
+            assert_eq!(Side::Bid.to_string(), "SELL");
+        }
+    }
+
+    #[test]
+    fn builds_paths_from_a_custom_fixture_file() {
+        let fixture = std::env::temp_dir().join(format!("triarb-exchange-info-{}.json", std::process::id()));
+        let symbol = |name: &str, base: &str, quote: &str| {
+            serde_json::json!({ "symbol": name, "baseAsset": base, "quoteAsset": quote, "status": "TRADING" })
+        };
+        let info = serde_json::json!({ "symbols": [
+            symbol("AAAHOME", "AAA", "HOME"),
+            symbol("BBBAAA", "BBB", "AAA"),
+            symbol("BBBHOME", "BBB", "HOME"),
+            symbol("CCCHOME", "CCC", "HOME"),
+        ]});
+        fs::write(&fixture, info.to_string()).unwrap();
+
+        let paths = find_and_build_price_paths_from(&fixture, "HOME", &["AAA"]);
+        let loaded = crate::devtools::load_exchange_info_from(&fixture);
+        fs::remove_file(&fixture).unwrap();
+
+        let paths = paths.unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(
+            paths[0].key().map(|(symbol, side)| format!("{} {symbol}", side.label())),
+            ["BUY AAAHOME", "BUY BBBAAA", "SELL BBBHOME"]
+        );
+        assert_eq!(loaded.unwrap().symbols.len(), 4);
+
+        let missing = find_and_build_price_paths_from(fixture, "HOME", &["AAA"]).unwrap_err();
+        assert!(missing.to_string().contains("Failed to read exchangeInfo fixture"));
+    }
+
+    #[test]
+    fn old_fixture_is_reported_stale_but_still_loads() {
+        let fixture = std::env::temp_dir().join(format!("triarb-stale-{}.json", std::process::id()));
+        fs::write(&fixture, r#"{ "symbols": [] }"#).unwrap();
+        let max_age = Duration::from_secs(7 * 24 * 60 * 60);
+        let fresh = stale_fixture_age(&fixture, max_age);
+
+        let sixty_days_ago = SystemTime::now() - Duration::from_secs(60 * 24 * 60 * 60);
+        fs::File::options().write(true).open(&fixture).unwrap().set_modified(sixty_days_ago).unwrap();
+        let stale = stale_fixture_age(&fixture, max_age);
+        let loaded = load_exchange_info_checked(&fixture, Some(max_age));
+        fs::remove_file(&fixture).unwrap();
+
+        assert_eq!(fresh, None);
+        assert!(stale.unwrap() >= Duration::from_secs(60 * 24 * 60 * 60));
+        assert!(loaded.unwrap().symbols.is_empty());
+        assert_eq!(stale_fixture_age(&fixture, max_age), None, "a missing file has no age");
+    }
+
+    #[test]
+    fn merged_fixtures_keep_one_entry_per_symbol_preferring_trading() {
+        let dir = std::env::temp_dir();
+        let first = dir.join(format!("triarb-merge-a-{}.json", std::process::id()));
+        let second = dir.join(format!("triarb-merge-b-{}.json", std::process::id()));
+        let symbol = |name: &str, base: &str, quote: &str, status: &str| {
+            serde_json::json!({ "symbol": name, "baseAsset": base, "quoteAsset": quote, "status": status })
+        };
+        let a = serde_json::json!({ "symbols": [
+            symbol("AAAHOME", "AAA", "HOME", "TRADING"),
+            symbol("BBBAAA", "BBB", "AAA", "BREAK"),
+        ]});
+        let b = serde_json::json!({ "symbols": [
+            symbol("BBBAAA", "BBB", "AAA", "TRADING"),
+            symbol("AAAHOME", "AAA", "HOME", "BREAK"),
+            symbol("BBBHOME", "BBB", "HOME", "TRADING"),
+        ]});
+        fs::write(&first, a.to_string()).unwrap();
+        fs::write(&second, b.to_string()).unwrap();
+
+        let merged = load_exchange_info_merged(&[&first, &second]);
+        fs::remove_file(&first).unwrap();
+        fs::remove_file(&second).unwrap();
+
+        let merged = merged.unwrap();
+        let listed: Vec<_> = merged.symbols.iter().map(|s| (s.symbol.as_str(), s.status.as_str())).collect();
+        assert_eq!(listed, [("AAAHOME", "TRADING"), ("BBBAAA", "TRADING"), ("BBBHOME", "TRADING")]);
+        assert_eq!(merged.symbol("BBBAAA").unwrap().status, "TRADING");
+        assert_eq!(build_paths("HOME", find_path_symbols(&merged, "HOME", &["AAA"])).len(), 1);
+
+        let missing = load_exchange_info_merged(&[&first]).unwrap_err();
+        assert!(missing.to_string().contains("Failed to read exchangeInfo fixture"));
+    }
+
+    #[test]
+    fn blocked_symbol_drops_every_path_that_trades_it() {
+        let exchange_info = mock_exchange_info();
+        let paths = build_paths(HOME, find_path_symbols(&exchange_info, HOME, TARGETS));
+        assert!(paths.iter().any(|p| p.symbols().contains(&"ETHBTC".to_string())));
+        assert!(paths.iter().any(|p| !p.symbols().contains(&"ETHBTC".to_string())));
+
+        let filter = SymbolFilter { blocklist: vec!["ETHBTC".into()], allowlist: vec![] };
+        let kept = filter.apply(paths.clone());
+        assert!(!kept.is_empty());
+        assert!(kept.iter().all(|p| !p.symbols().contains(&"ETHBTC".to_string())));
+        assert_eq!(
+            kept.len(),
+            paths.iter().filter(|p| !p.symbols().contains(&"ETHBTC".to_string())).count()
+        );
+
+        let subscribed = crate::ws::extract_symbols_from_paths(&kept);
+        assert!(!subscribed.contains(&"ETHBTC".to_string()));
+    }
+
+    #[test]
+    fn filter_entries_are_normalized_when_read() {
+        let filter: SymbolFilter = toml::from_str(r#"
+            blocklist = ["eth-btc"]
+            allowlist = ["btcusdt", "BTC_USDT", "ethusdt"]
+        "#).unwrap();
+        assert_eq!(filter.blocklist, ["ETHBTC"]);
+        assert!(!filter.allows("ETHBTC"));
+        assert!(filter.allows("BTCUSDT"));
+        assert!(filter.allows("btc/usdt"));
+        assert!(!filter.allows("SOLUSDT"));
+    }
+
+    #[test]
+    fn allowlist_keeps_only_fully_listed_paths() {
+        let exchange_info = mock_exchange_info();
+        let paths = build_paths(HOME, find_path_symbols(&exchange_info, HOME, TARGETS));
+        let allowed = ["BTCUSDT", "ETHBTC", "ETHUSDT"];
+        let filter = SymbolFilter { blocklist: vec![], allowlist: allowed.map(String::from).to_vec() };
+
+        let kept = filter.apply(paths);
+        assert!(!kept.is_empty());
+        let subscribed = crate::ws::extract_symbols_from_paths(&kept);
+        assert!(subscribed.iter().all(|s| allowed.contains(&s.as_str())), "{subscribed:?}");
+    }
+
+    #[test]
+    fn normalize_symbol_folds_case_and_separators() {
+        for raw in ["BTCUSDT", "btcusdt", "BtcUsdt", "BTC-USDT", "btc_usdt", "BTC/USDT"] {
+            assert_eq!(normalize_symbol(raw), "BTCUSDT", "{raw}");
+        }
+
+        let raw = r#"{"symbols":[{"symbol":"ethbtc","baseAsset":"ETH","quoteAsset":"BTC","status":"TRADING"}]}"#;
+        let info: ExchangeInfo = serde_json::from_str(raw).unwrap();
+        assert!(info.symbol("ETHBTC").is_some());
+    }
+
 }
@@ -0,0 +1,268 @@
+// src/testutil.rs
+
+//! Fixtures and assertions shared by the unit tests (compiled only under `cfg(test)`).
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::arb::config::OnUpdateReturn;
+use crate::arb::{ArbMode, ArbOpportunity, Clock, OpportunitySink, SystemClock};
+use crate::parse::TopOfBookUpdate;
+use crate::price_path::{build_paths, find_path_symbols, ExchangeInfo, PathLeg, PricingPath, Side, SymbolInfo};
+
+
+/// Asserts that two returns (or prices) agree to within `eps`.
+#[track_caller]
+pub(crate) fn assert_return_eq(actual: f64, expected: f64, eps: f64) {
+    assert!(
+        (actual - expected).abs() < eps,
+        "expected {expected}, got {actual} (|diff| = {:e}, eps = {eps:e})",
+        (actual - expected).abs()
+    );
+}
+
+/// A `TRADING` symbol without a tick size.
+pub(crate) fn make_symbol(symbol: &str, base: &str, quote: &str) -> SymbolInfo {
+    SymbolInfo {
+        symbol: symbol.to_string(),
+        base_asset: base.to_string(),
+        quote_asset: quote.to_string(),
+        status: "TRADING".into(),
+        tick_size: None,
+    }
+}
+
+/// Builds a path from `(symbol, base, quote, side)` per leg.
+pub(crate) fn make_path(legs: [(&str, &str, &str, Side); 3]) -> PricingPath {
+    let [leg1, leg2, leg3] = legs.map(|(symbol, base, quote, side)| PathLeg {
+        symbol: Arc::new(make_symbol(symbol, base, quote)),
+        side,
+    });
+    PricingPath { leg1, leg2, leg3 }
+}
+
+/// Concise exchangeInfo fixtures: list pairs (or whole triangles), then take the
+/// `ExchangeInfo`, hand-sided paths over the listed symbols, or every discovered path.
+///
+/// ```rust,ignore
+/// let mock = MockBuilder::new().triangle("USDT", "BTC", "ETH").pair("LTCUSDT", "LTC", "USDT");
+/// let paths = mock.paths("USDT", &["BTC", "ETH"]); // the triangle in both directions
+/// ```
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MockBuilder {
+    symbols: Vec<SymbolInfo>,
+}
+
+impl MockBuilder {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lists a `TRADING` `symbol` with `base` quoted in `quote`.
+    pub(crate) fn pair(self, symbol: &str, base: &str, quote: &str) -> Self {
+        self.listing(make_symbol(symbol, base, quote))
+    }
+
+    /// Lists `symbol` under a non-trading `status` (e.g. `BREAK`), which discovery skips.
+    pub(crate) fn pair_with_status(self, symbol: &str, base: &str, quote: &str, status: &str) -> Self {
+        self.listing(SymbolInfo { status: status.into(), ..make_symbol(symbol, base, quote) })
+    }
+
+    /// Lists a `TRADING` pair whose `PRICE_FILTER` has `tick_size`.
+    pub(crate) fn pair_with_tick_size(self, symbol: &str, base: &str, quote: &str, tick_size: f64) -> Self {
+        self.listing(SymbolInfo { tick_size: Some(tick_size), ..make_symbol(symbol, base, quote) })
+    }
+
+    /// Lists the pairs of the `home → a → b → home` triangle, named base-then-quote:
+    /// `{a}{home}`, `{b}{a}` and `{b}{home}`. Pairs already listed are kept as they are.
+    pub(crate) fn triangle(mut self, home: &str, a: &str, b: &str) -> Self {
+        for (base, quote) in [(a, home), (b, a), (b, home)] {
+            let symbol = format!("{base}{quote}");
+            if !self.symbols.iter().any(|s| s.symbol == symbol) {
+                self = self.pair(&symbol, base, quote);
+            }
+        }
+        self
+    }
+
+    fn listing(mut self, symbol: SymbolInfo) -> Self {
+        self.symbols.push(symbol);
+        self
+    }
+
+    /// The first listing of `name`; panics if it isn't listed.
+    pub(crate) fn symbol(&self, name: &str) -> SymbolInfo {
+        self.symbols
+            .iter()
+            .find(|s| s.symbol == name)
+            .unwrap_or_else(|| panic!("{name} is not listed"))
+            .clone()
+    }
+
+    /// A path over listed symbols, one `(symbol, side)` per leg.
+    pub(crate) fn path(&self, legs: [(&str, Side); 3]) -> PricingPath {
+        let [leg1, leg2, leg3] = legs.map(|(symbol, side)| PathLeg { symbol: Arc::new(self.symbol(symbol)), side });
+        PricingPath { leg1, leg2, leg3 }
+    }
+
+    /// Every listed symbol, in listing order.
+    pub(crate) fn exchange_info(&self) -> ExchangeInfo {
+        ExchangeInfo::new(self.symbols.clone())
+    }
+
+    /// Every path discovery finds from `home` through `targets`, each triangle in both directions.
+    pub(crate) fn paths(&self, home: &str, targets: &[&str]) -> Vec<PricingPath> {
+        build_paths(home, find_path_symbols(&self.exchange_info(), home, targets))
+    }
+}
+
+/// USDT → BTC → ETH → USDT: buy BTCUSDT, buy ETHBTC, sell ETHUSDT.
+pub(crate) fn mock_path() -> PricingPath {
+    make_path([
+        ("BTCUSDT", "BTC", "USDT", Side::Ask),
+        ("ETHBTC", "ETH", "BTC", Side::Ask),
+        ("ETHUSDT", "ETH", "USDT", Side::Bid),
+    ])
+}
+
+/// `mock_path` reversed, USDT → ETH → BTC → USDT: buy ETHUSDT, sell ETHBTC, sell BTCUSDT.
+/// On the usual quotes (BTCUSDT 50000/50010, ETHBTC 0.07/0.0701, ETHUSDT 3000/3001) it returns
+/// 1 / 3001 * 0.07 * 50000 ≈ 1.1663 before fees.
+pub(crate) fn mock_reverse_path() -> PricingPath {
+    make_path([
+        ("ETHUSDT", "ETH", "USDT", Side::Ask),
+        ("ETHBTC", "ETH", "BTC", Side::Bid),
+        ("BTCUSDT", "BTC", "USDT", Side::Bid),
+    ])
+}
+
+/// Every scanner `ScannerBuilder::build` can produce, for tests that must hold in each of them.
+pub(crate) const MODES: [(ArbMode, OnUpdateReturn); 8] = [
+    (ArbMode::Naive, OnUpdateReturn::First),
+    (ArbMode::EdgeMap, OnUpdateReturn::First),
+    (ArbMode::Interned, OnUpdateReturn::First),
+    (ArbMode::RayonScan, OnUpdateReturn::First),
+    (ArbMode::RayonScan, OnUpdateReturn::Best),
+    (ArbMode::RayonScan, OnUpdateReturn::Ordered),
+    (ArbMode::SimdBatch, OnUpdateReturn::First),
+    (ArbMode::Adaptive, OnUpdateReturn::First),
+];
+
+pub(crate) fn mock_update(symbol: &str, bid: f64, ask: f64) -> TopOfBookUpdate {
+    TopOfBookUpdate {
+        symbol: symbol.to_string(),
+        bid_price: bid,
+        ask_price: ask,
+        recv_ts: Instant::now(),
+        event_time_ms: None,
+    }
+}
+
+/// A clock that only moves when `advance` is called.
+#[derive(Debug)]
+pub(crate) struct MockClock {
+    start: Instant,
+    start_epoch_millis: u64,
+    offset: Mutex<Duration>,
+}
+
+impl MockClock {
+    pub(crate) fn new() -> Self {
+        Self { start: Instant::now(), start_epoch_millis: SystemClock.epoch_millis(), offset: Mutex::new(Duration::ZERO) }
+    }
+
+    pub(crate) fn advance(&self, by: Duration) {
+        *self.offset.lock().unwrap() += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + *self.offset.lock().unwrap()
+    }
+
+    fn epoch_millis(&self) -> u64 {
+        self.start_epoch_millis + self.offset.lock().unwrap().as_millis() as u64
+    }
+}
+
+/// Captures every emitted opportunity for inspection.
+#[derive(Default)]
+pub(crate) struct RecordingSink {
+    pub(crate) emitted: Mutex<Vec<ArbOpportunity>>,
+}
+
+impl OpportunitySink for RecordingSink {
+    fn emit(&self, opportunity: &ArbOpportunity) {
+        self.emitted.lock().unwrap().push(opportunity.clone());
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assert_return_eq_accepts_values_within_eps() {
+        assert_return_eq(1.0 + 1e-10, 1.0, 1e-9);
+        assert_return_eq(0.1 + 0.2, 0.3, 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 1, got 1.001")]
+    fn assert_return_eq_rejects_values_outside_eps() {
+        assert_return_eq(1.001, 1.0, 1e-6);
+    }
+
+    #[test]
+    fn mock_builder_lists_triangles_and_pairs() {
+        let mock = MockBuilder::new()
+            .triangle("USDT", "BTC", "ETH")
+            .triangle("USDT", "BTC", "SOL")
+            .pair_with_status("BADPAIR", "BTC", "ETH", "BREAK")
+            .pair_with_tick_size("LTCUSDT", "LTC", "USDT", 0.01);
+        let info = mock.exchange_info();
+
+        let listed: Vec<_> = info
+            .symbols
+            .iter()
+            .map(|s| (s.symbol.as_str(), s.base_asset.as_str(), s.quote_asset.as_str(), s.status.as_str(), s.tick_size))
+            .collect();
+        assert_eq!(listed, vec![
+            ("BTCUSDT", "BTC", "USDT", "TRADING", None),
+            ("ETHBTC", "ETH", "BTC", "TRADING", None),
+            ("ETHUSDT", "ETH", "USDT", "TRADING", None),
+            // BTCUSDT is shared with the first triangle and listed once
+            ("SOLBTC", "SOL", "BTC", "TRADING", None),
+            ("SOLUSDT", "SOL", "USDT", "TRADING", None),
+            ("BADPAIR", "BTC", "ETH", "BREAK", None),
+            ("LTCUSDT", "LTC", "USDT", "TRADING", Some(0.01)),
+        ]);
+        assert_eq!(info.symbol("ETHBTC"), Some(&mock.symbol("ETHBTC")));
+    }
+
+    #[test]
+    fn mock_builder_discovers_both_directions() {
+        let mock = MockBuilder::new().triangle("USDT", "BTC", "ETH");
+        let paths: Vec<String> = mock.paths("USDT", &["BTC", "ETH"]).iter().map(|p| format!("{p:#}")).collect();
+        assert_eq!(paths, vec![
+            "BUY BTCUSDT → BUY ETHBTC → SELL ETHUSDT",
+            "BUY ETHUSDT → SELL ETHBTC → SELL BTCUSDT",
+        ]);
+        assert_eq!(
+            mock.path([("BTCUSDT", Side::Ask), ("ETHBTC", Side::Ask), ("ETHUSDT", Side::Bid)]).key(),
+            mock_path().key(),
+        );
+    }
+
+    #[test]
+    fn mock_path_is_a_valid_triangle() {
+        let path = mock_path();
+        assert!(path.validate().is_ok());
+        let mut symbols = path.symbols();
+        symbols.sort();
+        assert_eq!(symbols, vec!["BTCUSDT", "ETHBTC", "ETHUSDT"]);
+        assert_eq!(path.leg3.side, Side::Bid);
+    }
+}
@@ -1,9 +1,18 @@
 // src/ws.rs
 
-use std::{collections::HashSet, future::Future, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 use anyhow::Result;
 use bytes::Bytes;
-use fastwebsockets::{FragmentCollector, Frame, OpCode, Payload};
+use fastwebsockets::{FragmentCollectorRead, Frame, OpCode, Payload, WebSocket, WebSocketWrite};
 use http_body_util::Empty;
 use hyper::{
     header::{CONNECTION, UPGRADE},
@@ -11,78 +20,757 @@ use hyper::{
     Request,
 };
 use hyper_util::rt::TokioIo;
-use tokio::{net::TcpStream, sync::mpsc::Sender};
+use tokio::{
+    io::AsyncWrite,
+    net::TcpStream,
+    sync::mpsc::{self, Sender},
+    task::{JoinHandle, JoinSet},
+};
 use tokio_rustls::{
     rustls::{ClientConfig, OwnedTrustAnchor},
     TlsConnector,
 };
-use crate::price_path::PricingPath;
+use crate::feed::{FeedEvent, RawFeedEvent};
+use crate::price_path::{normalize_symbol, PricingPath};
+
+/// Maximum streams per SUBSCRIBE frame. Binance caps a connection at 1024 streams,
+/// so a full connection needs at most 4 frames — within the 5 messages/sec limit.
+const SUBSCRIBE_BATCH_SIZE: usize = 256;
+
+/// How long after subscribing a symbol may go without an ack or data before it is reported.
+const SUBSCRIPTION_CONFIRM_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the connection may go without any frame before it is considered stale.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest request path `SubscribeStrategy::UrlPath` puts on the wire. Binance documents no
+/// limit, but proxies commonly cap the request line, so longer paths fall back to SUBSCRIBE frames.
+pub const MAX_STREAM_PATH_LEN: usize = 2000;
+
+/// Gap between the SUBSCRIBE request ids of consecutive shards, so that acks from every
+/// connection can be told apart in the shared `SubscriptionTracker`.
+const SHARD_ID_STRIDE: u64 = 1 << 32;
+
+/// Capacity of a connection's outbound frame queue.
+const WRITE_QUEUE_CAPACITY: usize = 64;
+
+type WsStream = WebSocket<TokioIo<Upgraded>>;
+
+type WsRead = FragmentCollectorRead<tokio::io::ReadHalf<TokioIo<Upgraded>>>;
+
+/// Queue feeding a connection's writer task; see `spawn_frame_writer`.
+type FrameSender = mpsc::Sender<Frame<'static>>;
+
+/// Called each time a connection reconnects, before it forwards any frame, with the symbols
+/// that connection carries (all of them unless the listener is sharded).
+pub type ReconnectHook = Arc<dyn Fn(&[String]) + Send + Sync>;
+
+/// Pause before replacing a connection the idle watchdog dropped when no reconnect delay is
+/// configured, so a feed that keeps going quiet isn't redialled in a tight loop.
+pub const DEFAULT_IDLE_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Address of the local mock server (see `mock_feed::ws_server::run`).
+pub const DEFAULT_LOCAL_ADDR: &str = "localhost:9001";
+
+
+/// Where and how the listener connects, and the shared state it reports into.
+///
+/// Defaults to Binance with the `DEFAULT_IDLE_TIMEOUT` watchdog, no reconnect after the server
+/// goes away, a single connection, and a tracker and connection state of its own; each
+/// `with_*` call replaces one of those.
+#[derive(Clone)]
+pub struct ConnectOptions {
+    local_addr: Option<String>,
+    reconnect_delay: Option<Duration>,
+    shards: usize,
+    idle_timeout: Option<Duration>,
+    on_reconnect: Option<ReconnectHook>,
+    tracker: Arc<SubscriptionTracker>,
+    connection: Arc<ConnectionState>,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        Self {
+            local_addr: None,
+            reconnect_delay: None,
+            shards: 1,
+            idle_timeout: Some(DEFAULT_IDLE_TIMEOUT),
+            on_reconnect: None,
+            tracker: Arc::new(SubscriptionTracker::new()),
+            connection: Arc::new(ConnectionState::new()),
+        }
+    }
+}
+
+impl fmt::Debug for ConnectOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectOptions")
+            .field("local_addr", &self.local_addr)
+            .field("reconnect_delay", &self.reconnect_delay)
+            .field("shards", &self.shards)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("on_reconnect", &self.on_reconnect.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConnectOptions {
+    /// The options implied by the `local_domain` flag: the default mock address if set.
+    pub fn from_local_domain(local_domain: Option<bool>) -> Self {
+        Self { local_addr: local_domain.map(|_| DEFAULT_LOCAL_ADDR.to_string()), ..Self::default() }
+    }
+
+    /// Connect to a mock server at `addr` over plain TCP instead of Binance.
+    pub fn with_local_addr(mut self, addr: impl Into<String>) -> Self {
+        self.local_addr = Some(addr.into());
+        self
+    }
+
+    /// Retry a connection the server closes or drops, or a connect attempt that fails, after
+    /// `delay` and resubscribe, instead of ending the listener.
+    pub fn with_reconnect_delay(mut self, delay: Duration) -> Self {
+        self.reconnect_delay = Some(delay);
+        self
+    }
+
+    /// Split the symbols across this many connections, each read by its own task into the same
+    /// channel, to stay under per-connection stream limits and parallelize reads. 0 and 1 both
+    /// mean a single connection; there are never more connections than symbols.
+    pub fn with_shards(mut self, shards: usize) -> Self {
+        self.shards = shards;
+        self
+    }
+
+    /// Replace a connection that delivers no frame within `idle_timeout`, after the reconnect
+    /// delay (or `DEFAULT_IDLE_RECONNECT_DELAY` without one); `None` disables the watchdog and
+    /// waits on the socket forever.
+    pub fn with_idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Call `hook` with a connection's symbols whenever it is replaced, e.g. to invalidate an
+    /// evaluator's prices for them across the gap.
+    pub fn with_reconnect_hook(mut self, hook: ReconnectHook) -> Self {
+        self.on_reconnect = Some(hook);
+        self
+    }
+
+    /// Record subscription acks and first data per symbol in `tracker`, which also carries the
+    /// `SubscribeStrategy`.
+    pub fn with_tracker(mut self, tracker: Arc<SubscriptionTracker>) -> Self {
+        self.tracker = tracker;
+        self
+    }
+
+    /// Keep frame counters, the last-frame time and the status in `connection` for callers to poll.
+    pub fn with_connection_state(mut self, connection: Arc<ConnectionState>) -> Self {
+        self.connection = connection;
+        self
+    }
+
+    pub fn tracker(&self) -> &Arc<SubscriptionTracker> {
+        &self.tracker
+    }
+
+    pub fn connection(&self) -> &Arc<ConnectionState> {
+        &self.connection
+    }
+}
+
+
+/// How the listener asks the server for each symbol's `@bookTicker` stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SubscribeStrategy {
+    /// Connect to `/ws` and send SUBSCRIBE frames, each acknowledged by the server.
+    #[default]
+    Message,
+    /// Name every stream in the connection URL (`/ws/btcusdt@bookTicker/...`) and send no
+    /// SUBSCRIBE frame, which some proxies handle better. There are no acks, so symbols confirm
+    /// by delivering data. Falls back to `Message` when the path exceeds `MAX_STREAM_PATH_LEN`.
+    UrlPath,
+}
+
+
+/// Tracks which SUBSCRIBE batches were acknowledged and which symbols have delivered data.
+///
+/// Data frames are only inspected while the confirmation window is open; once it closes, any
+/// symbol with neither an ack nor data is logged as a warning. SUBSCRIBE responses are
+/// recognised by shape and kept from the parser for the life of the listener, since every
+/// reconnect resubscribes. The tracker also carries the `SubscribeStrategy` the listener
+/// subscribes with.
+#[derive(Debug)]
+pub struct SubscriptionTracker {
+    window_open: AtomicBool,
+    confirm_window: Duration,
+    strategy: SubscribeStrategy,
+    state: Mutex<SubscriptionState>,
+}
+
+#[derive(Debug, Default)]
+struct SubscriptionState {
+    batches: HashMap<u64, Vec<String>>,
+    acked: HashSet<u64>,
+    seen: HashSet<String>,
+}
+
+impl Default for SubscriptionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubscriptionTracker {
+    pub fn new() -> Self {
+        Self {
+            window_open: AtomicBool::new(true),
+            confirm_window: SUBSCRIPTION_CONFIRM_TIMEOUT,
+            strategy: SubscribeStrategy::default(),
+            state: Mutex::new(SubscriptionState::default()),
+        }
+    }
+
+    /// Subscribes with `strategy` instead of SUBSCRIBE frames.
+    pub fn with_strategy(mut self, strategy: SubscribeStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn strategy(&self) -> SubscribeStrategy {
+        self.strategy
+    }
+
+    /// How long after the listener starts symbols have to confirm (default 10s).
+    pub fn with_confirm_window(mut self, confirm_window: Duration) -> Self {
+        self.confirm_window = confirm_window;
+        self
+    }
+
+    pub fn confirm_window(&self) -> Duration {
+        self.confirm_window
+    }
+
+    /// Symbols whose SUBSCRIBE batch was acknowledged by the server.
+    pub fn acknowledged_symbols(&self) -> HashSet<String> {
+        let state = self.state.lock().unwrap();
+        state.acked
+            .iter()
+            .filter_map(|id| state.batches.get(id))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Symbols that have delivered at least one data frame during the confirmation window.
+    pub fn symbols_with_data(&self) -> HashSet<String> {
+        self.state.lock().unwrap().seen.clone()
+    }
+
+    /// Subscribed symbols with neither an acknowledged batch nor any data, sorted.
+    pub fn unconfirmed_symbols(&self) -> Vec<String> {
+        let acked = self.acknowledged_symbols();
+        let state = self.state.lock().unwrap();
+        let mut missing: Vec<String> = state.batches
+            .values()
+            .flatten()
+            .filter(|s| !acked.contains(*s) && !state.seen.contains(*s))
+            .cloned()
+            .collect();
+        missing.sort();
+        missing
+    }
+
+    fn is_window_open(&self) -> bool {
+        self.window_open.load(Ordering::Relaxed)
+    }
+
+    fn register_batch(&self, id: u64, symbols: Vec<String>) {
+        self.state.lock().unwrap().batches.insert(id, symbols);
+    }
+
+    fn record_ack(&self, id: u64) {
+        self.state.lock().unwrap().acked.insert(id);
+    }
+
+    fn record_data(&self, symbol: &str) {
+        let symbol = normalize_symbol(symbol);
+        let mut state = self.state.lock().unwrap();
+        if !state.seen.contains(&symbol) {
+            state.seen.insert(symbol);
+        }
+    }
+
+    /// Closes the confirmation window and warns about symbols that never confirmed.
+    fn close_window(&self) {
+        self.window_open.store(false, Ordering::Relaxed);
+        let missing = self.unconfirmed_symbols();
+        if missing.is_empty() {
+            tracing::info!("✅ All subscriptions confirmed");
+        } else {
+            tracing::warn!(
+                count = missing.len(),
+                ?missing,
+                "Symbols produced neither a subscription ack nor data within {:?}",
+                self.confirm_window
+            );
+        }
+    }
+
+    /// Inspects an incoming frame, recording acks and, during the confirmation window, data.
+    ///
+    /// Returns `true` if the frame was a SUBSCRIBE response (which must not be forwarded
+    /// to the parser), `false` for data frames.
+    fn observe_frame(&self, data: &[u8]) -> bool {
+        if let Some((id, error)) = parse_subscribe_response(data) {
+            match error {
+                None => self.record_ack(id),
+                Some(error) => tracing::warn!(id, %error, "SUBSCRIBE request rejected"),
+            }
+            return true;
+        }
+        if self.is_window_open()
+            && let Some(symbol) = extract_symbol(data)
+        {
+            self.record_data(symbol);
+        }
+        false
+    }
+}
+
+
+/// Where the listener is in its connection lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    /// Opening a connection and subscribing, initially or after the idle watchdog fired.
+    Connecting,
+    /// Subscribed and reading frames.
+    Connected,
+    /// The listener (or, in `shard_statuses`, that connection) has exited: the server closed
+    /// the connection, or connecting failed.
+    Closed,
+}
+
+impl ConnectionStatus {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Connecting,
+            1 => Self::Connected,
+            _ => Self::Closed,
+        }
+    }
+}
+
+
+/// Liveness of the listener's connection, shared with callers that poll it (e.g. a health
+/// endpoint): frames and payload bytes read, when the last frame arrived, and the status.
+///
+/// Every frame the server sends counts, including subscription acks and control frames, so
+/// the counters show the socket is alive even when no quote is forwarded. A sharded listener
+/// keeps a status per connection, and `status` reports them together.
+#[derive(Debug)]
+pub struct ConnectionState {
+    frames: AtomicU64,
+    bytes: AtomicU64,
+    /// Reference point for `last_frame_nanos`, which can't hold an `Instant` directly.
+    created: Instant,
+    /// Nanoseconds from `created` to the last frame, plus one; zero until the first frame.
+    last_frame_nanos: AtomicU64,
+    /// Aggregate of `shard_statuses`, kept alongside so `status` never takes the lock.
+    status: AtomicU8,
+    shard_statuses: Mutex<Vec<ConnectionStatus>>,
+}
+
+impl Default for ConnectionState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectionState {
+    pub fn new() -> Self {
+        Self {
+            frames: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+            created: Instant::now(),
+            last_frame_nanos: AtomicU64::new(0),
+            status: AtomicU8::new(ConnectionStatus::Connecting as u8),
+            shard_statuses: Mutex::new(vec![ConnectionStatus::Connecting]),
+        }
+    }
+
+    /// Frames read from the server, across reconnects.
+    pub fn frames_received(&self) -> u64 {
+        self.frames.load(Ordering::Relaxed)
+    }
+
+    /// Payload bytes read from the server, across reconnects.
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// When the last frame arrived, or `None` before the first.
+    pub fn last_frame_at(&self) -> Option<Instant> {
+        match self.last_frame_nanos.load(Ordering::Relaxed) {
+            0 => None,
+            nanos => Some(self.created + Duration::from_nanos(nanos - 1)),
+        }
+    }
+
+    /// `Connected` only while every connection is, `Closed` once all of them are, and
+    /// `Connecting` otherwise.
+    pub fn status(&self) -> ConnectionStatus {
+        ConnectionStatus::from_u8(self.status.load(Ordering::Relaxed))
+    }
+
+    /// The status of each connection, by shard.
+    pub fn shard_statuses(&self) -> Vec<ConnectionStatus> {
+        self.shard_statuses.lock().unwrap().clone()
+    }
+
+    /// Starts tracking `shards` connections, all `Connecting`.
+    fn reset_shards(&self, shards: usize) {
+        let mut statuses = self.shard_statuses.lock().unwrap();
+        *statuses = vec![ConnectionStatus::Connecting; shards.max(1)];
+        self.status.store(ConnectionStatus::Connecting as u8, Ordering::Relaxed);
+    }
+
+    fn set_shard_status(&self, shard: usize, status: ConnectionStatus) {
+        let mut statuses = self.shard_statuses.lock().unwrap();
+        statuses[shard] = status;
+        let aggregate = if statuses.iter().all(|s| *s == ConnectionStatus::Connected) {
+            ConnectionStatus::Connected
+        } else if statuses.iter().all(|s| *s == ConnectionStatus::Closed) {
+            ConnectionStatus::Closed
+        } else {
+            ConnectionStatus::Connecting
+        };
+        self.status.store(aggregate as u8, Ordering::Relaxed);
+    }
+
+    fn close_all(&self) {
+        let mut statuses = self.shard_statuses.lock().unwrap();
+        statuses.fill(ConnectionStatus::Closed);
+        self.status.store(ConnectionStatus::Closed as u8, Ordering::Relaxed);
+    }
+
+    fn record_frame(&self, payload_len: usize) {
+        self.frames.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(payload_len as u64, Ordering::Relaxed);
+        let nanos = self.created.elapsed().as_nanos() as u64 + 1;
+        self.last_frame_nanos.store(nanos, Ordering::Relaxed);
+    }
+}
+
+
+/// Parses `{"result":null,"id":N}` (ack) or `{"error":{...},"id":N}` (rejection).
+fn parse_subscribe_response(data: &[u8]) -> Option<(u64, Option<serde_json::Value>)> {
+    // bookTicker payloads never carry an "id" key; skip full JSON parsing for them
+    let text = std::str::from_utf8(data).ok()?;
+    if !text.contains("\"id\"") {
+        return None;
+    }
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    let id = value.get("id")?.as_u64()?;
+    if let Some(error) = value.get("error") {
+        return Some((id, Some(error.clone())));
+    }
+    value.get("result").map(|_| (id, None))
+}
+
+
+/// Extracts the `"s"` symbol field from a raw bookTicker payload without full parsing.
+fn extract_symbol(data: &[u8]) -> Option<&str> {
+    let text = std::str::from_utf8(data).ok()?;
+    let key = "\"s\":\"";
+    let start = text.find(key)? + key.len();
+    let end = text[start..].find('"')? + start;
+    Some(&text[start..end])
+}
+
 
 /// Starts a WebSocket connection and streams raw frames into the `tx` channel.
 ///
-/// - Connects to either Binance (`wss://data-stream.binance.com`) or a local mock feed (`ws://localhost:9001`)
+/// - Connects to Binance (`wss://data-stream.binance.com`) or, with a local address in
+///   `options`, a mock feed over plain TCP
 /// - Subscribes to `@bookTicker` channels for all symbols derived from the pricing paths
 /// - Forwards raw WebSocket frames into the async channel for downstream parsing
 ///
-/// # Parameters
-/// - `price_paths`: The arbitrage pricing paths to extract symbols from
-/// - `tx`: The receiving end of the stream pipeline
-/// - `use_mock`: If `true`, connect to local mock server instead of Binance
-pub async fn start_ws_listener(
-    price_paths: Vec<PricingPath>,
-    tx: Sender<Bytes>,
-    local_domain: Option<bool>,
-) -> Result<()> {
+/// With a reconnect delay set in `options`, reconnects and resubscribes after the server goes
+/// away rather than returning. With more than one shard the symbols are split across that many
+/// connections (see `shard_symbols`), each listening on its own task. Returns once every
+/// connection has ended, or as soon as one fails, aborting the others and returning its error.
+pub async fn start_ws_listener(price_paths: Vec<PricingPath>, tx: Sender<Bytes>, options: ConnectOptions) -> Result<()> {
+    run_listeners(price_paths, FrameTx::Frames(tx), options).await
+}
+
+/// Same as `start_ws_listener`, but sends `RawFeedEvent`s: every data frame, interleaved in
+/// order with the `Gap`, `Disconnected` and `Reconnected` events of each connection, tagged with
+/// its shard.
+/// `feed::feed_event_loop` parses the frames into the matching `FeedEvent` stream.
+pub async fn start_ws_feed(price_paths: Vec<PricingPath>, tx: Sender<RawFeedEvent>, options: ConnectOptions) -> Result<()> {
+    run_listeners(price_paths, FrameTx::Feed(tx), options).await
+}
 
-    let mut ws = if  local_domain.is_some() {
-        tracing::info!("🔌 Connecting to local mock WebSocket feed at ws://localhost:9001...");
-        connect_local().await?
+/// Where a connection's data frames go: raw into a frame channel, or wrapped alongside its
+/// lifecycle events into a feed channel.
+#[derive(Clone)]
+enum FrameTx {
+    Frames(Sender<Bytes>),
+    Feed(Sender<RawFeedEvent>),
+}
+
+impl FrameTx {
+    async fn send_frame(&self, data: Bytes) -> Result<()> {
+        match self {
+            Self::Frames(tx) => tx.send(data).await?,
+            Self::Feed(tx) => tx.send(RawFeedEvent::Frame(data)).await?,
+        }
+        Ok(())
+    }
+
+    /// Reports a lifecycle event on a feed channel; a frame channel has nowhere to put it.
+    async fn send_event(&self, event: FeedEvent) {
+        if let Self::Feed(tx) = self {
+            // A consumer that went away is noticed by the next `send_frame`
+            let _ = tx.send(RawFeedEvent::Event(event)).await;
+        }
+    }
+}
+
+/// Shards the symbols per `options.shards` and runs `listen` for each shard (see
+/// `start_ws_listener`).
+async fn run_listeners(price_paths: Vec<PricingPath>, tx: FrameTx, options: ConnectOptions) -> Result<()> {
+    let connection = Arc::clone(&options.connection);
+    let mut shards = shard_symbols(extract_symbols_from_paths(&price_paths), options.shards);
+    connection.reset_shards(shards.len());
+
+    tokio::spawn({
+        let tracker = Arc::clone(&options.tracker);
+        async move {
+            tokio::time::sleep(tracker.confirm_window()).await;
+            tracker.close_window();
+        }
+    });
+
+    let result = if shards.len() <= 1 {
+        let symbols = shards.pop().unwrap_or_default();
+        listen(symbols, 0, tx, &options).await
     } else {
-        let domain = "data-stream.binance.com";
-        tracing::info!("🌐 Connecting to Binance at wss://{domain}:9443...");
-        connect_exchange(domain).await?
+        tracing::info!(shards = shards.len(), "Splitting subscriptions across connections");
+        let options = Arc::new(options);
+        let mut listeners = JoinSet::new();
+        for (shard, symbols) in shards.into_iter().enumerate() {
+            let (tx, options) = (tx.clone(), Arc::clone(&options));
+            listeners.spawn(async move { listen(symbols, shard, tx, &options).await });
+        }
+        let mut result = Ok(());
+        while let Some(joined) = listeners.join_next().await {
+            if let Err(e) = joined.map_err(anyhow::Error::from).and_then(|r| r) {
+                // One shard down leaves its symbols unpriced, so the listener fails as a whole
+                listeners.abort_all();
+                result = Err(e);
+                break;
+            }
+        }
+        result
     };
+    connection.close_all();
+    result
+}
 
-    let symbols = extract_symbols_from_paths(&price_paths);
-    subscribe_symbols(&mut ws, symbols).await?;
-    
+/// The connect, subscribe and forward loop behind `start_ws_listener`, for the `symbols` of
+/// connection `shard`. Its SUBSCRIBE request ids count up from `shard * SHARD_ID_STRIDE + 1`.
+async fn listen(symbols: Vec<String>, shard: usize, tx: FrameTx, options: &ConnectOptions) -> Result<()> {
+    let ConnectOptions { tracker, connection, idle_timeout, on_reconnect, .. } = options;
+    let idle_timeout = *idle_timeout;
+    let first_id = shard as u64 * SHARD_ID_STRIDE;
+    let url_path = match tracker.strategy() {
+        SubscribeStrategy::Message => None,
+        SubscribeStrategy::UrlPath => {
+            let url_path = stream_url_path(&symbols);
+            if url_path.is_none() {
+                tracing::warn!(
+                    symbols = symbols.len(),
+                    max_len = MAX_STREAM_PATH_LEN,
+                    "Stream URL too long; subscribing with SUBSCRIBE frames instead"
+                );
+            }
+            url_path
+        }
+    };
+    if url_path.is_some() {
+        // Never acked (SUBSCRIBE ids start after `first_id`), so these symbols confirm by data alone
+        tracker.register_batch(first_id, symbols.clone());
+    }
+    let request_path = url_path.as_deref().unwrap_or("/ws");
+
+    // When the last connection ended, for the downtime reported once the next one is up
+    let mut disconnected_at: Option<Instant> = None;
     loop {
-        let frame = match ws.read_frame().await {
+        connection.set_shard_status(shard, ConnectionStatus::Connecting);
+        let connected = if let Some(addr) = &options.local_addr {
+            tracing::info!("🔌 Connecting to local mock WebSocket feed at ws://{addr}...");
+            connect_local(addr, request_path).await
+        } else {
+            let domain = "data-stream.binance.com";
+            tracing::info!("🌐 Connecting to Binance at wss://{domain}:9443...");
+            connect_exchange(domain, request_path).await
+        };
+        let ws = match (connected, options.reconnect_delay) {
+            (Ok(ws), _) => ws,
+            (Err(e), Some(delay)) => {
+                tracing::warn!(error = %e, ?delay, "Connect failed; retrying");
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+            (Err(e), None) => return Err(e),
+        };
+
+        let (read, write) = ws.split(tokio::io::split);
+        let mut ws = FragmentCollectorRead::new(read);
+        let (writer, writer_task) = spawn_frame_writer(write);
+
+        if url_path.is_none() {
+            subscribe_symbols(&writer, symbols.clone(), first_id, tracker).await?;
+        }
+        connection.set_shard_status(shard, ConnectionStatus::Connected);
+        if let Some(disconnected_at) = disconnected_at.take() {
+            tx.send_event(FeedEvent::Reconnected { shard, downtime: disconnected_at.elapsed() }).await;
+        }
+
+        let end = forward_frames(&mut ws, &writer, &tx, tracker, connection, idle_timeout).await?;
+        if let (ConnectionEnd::Idle, Some(idle_timeout)) = (&end, idle_timeout) {
+            tx.send_event(FeedEvent::Gap { shard, silent_for: idle_timeout }).await;
+        }
+        tx.send_event(FeedEvent::Disconnected { shard }).await;
+        disconnected_at = Some(Instant::now());
+        if let ConnectionEnd::Idle = end {
+            tracing::warn!(?idle_timeout, "No frames received; reconnecting");
+            // The server stopped talking, so a failed close handshake is expected
+            let _ = writer.send(Frame::close_raw(vec![].into())).await;
+        }
+        // Let the writer flush what is queued before the socket is dropped
+        drop(writer);
+        let _ = writer_task.await;
+
+        match (end, options.reconnect_delay) {
+            (ConnectionEnd::Idle, delay) => {
+                tokio::time::sleep(delay.unwrap_or(DEFAULT_IDLE_RECONNECT_DELAY)).await;
+            }
+            (ConnectionEnd::Closed, Some(delay)) => {
+                tracing::warn!(?delay, "Server closed the connection; reconnecting");
+                tokio::time::sleep(delay).await;
+            }
+            (ConnectionEnd::Closed, None) => break,
+        }
+        if let Some(hook) = &on_reconnect {
+            hook(&symbols);
+        }
+    }
+    connection.set_shard_status(shard, ConnectionStatus::Closed);
+    Ok::<_, anyhow::Error>(())
+}
+
+/// Why `forward_frames` stopped reading from a connection.
+enum ConnectionEnd {
+    /// No frame arrived within the idle timeout; the caller should reconnect.
+    Idle,
+    /// The server closed the connection or the socket failed.
+    Closed,
+}
+
+/// Reads frames from `ws` and forwards data payloads into `tx` until the connection ends.
+///
+/// Frames the protocol obliges us to answer (pongs, the close echo) are queued on `writer`
+/// rather than written here, so this loop only ever reads from the socket.
+async fn forward_frames(
+    ws: &mut WsRead,
+    writer: &FrameSender,
+    tx: &FrameTx,
+    tracker: &SubscriptionTracker,
+    connection: &ConnectionState,
+    idle_timeout: Option<Duration>,
+) -> Result<ConnectionEnd> {
+    let mut send_obligated = |frame| enqueue(writer, frame);
+    loop {
+        // Dropping a half-read frame is fine here: an idle connection is discarded anyway
+        let read = tokio::select! {
+            read = ws.read_frame(&mut send_obligated) => read,
+            _ = idle(idle_timeout) => return Ok(ConnectionEnd::Idle),
+        };
+        let frame = match read {
             Ok(frame) => frame,
             Err(e) => {
                 eprintln!("Websocket error: {e}");
-                ws.write_frame(Frame::close_raw(vec![].into())).await?;
-                break;
+                let _ = writer.send(Frame::close_raw(vec![].into())).await;
+                return Ok(ConnectionEnd::Closed);
             }
         };
+        connection.record_frame(frame.payload.len());
 
         match frame.opcode {
             OpCode::Text | OpCode::Binary => {
-                match frame.payload {
-                    Payload::Bytes(data) => {
-                        tx.send(data.into()).await?;
-                    }
-                    Payload::Borrowed(data) => {
-                        tx.send(Bytes::copy_from_slice(data)).await?;
-                    }
-                    Payload::BorrowedMut(data) => {
-                        tx.send(Bytes::copy_from_slice(&*data)).await?;
-                    }
-                    Payload::Owned(data) => {
-                        tx.send(data.into()).await?;
-                    }
+                if tracker.observe_frame(&frame.payload) {
+                    continue;
                 }
+                let data = match frame.payload {
+                    Payload::Bytes(data) => data.into(),
+                    Payload::Borrowed(data) => Bytes::copy_from_slice(data),
+                    Payload::BorrowedMut(data) => Bytes::copy_from_slice(&*data),
+                    Payload::Owned(data) => data.into(),
+                };
+                tx.send_frame(data).await?;
             }
             OpCode::Close => {
                 println!("WebSocket Close frame received");
-                break;
+                return Ok(ConnectionEnd::Closed);
             }
             _ => {
                 // Ignore test
             }
         }
     }
-    Ok::<_, anyhow::Error>(())
+}
+
+/// Spawns the only task allowed to write to `write`, sending queued frames in order until
+/// every sender is dropped or a write fails.
+///
+/// Subscriptions come from the connect loop while pongs and close echoes come from inside
+/// `read_frame`; funnelling both through one queue keeps a frame from being written while
+/// another is half on the wire.
+fn spawn_frame_writer<S>(mut write: WebSocketWrite<S>) -> (FrameSender, JoinHandle<()>)
+where
+    S: AsyncWrite + Unpin + Send + 'static,
+{
+    let (tx, mut rx) = mpsc::channel::<Frame<'static>>(WRITE_QUEUE_CAPACITY);
+    let handle = tokio::spawn(async move {
+        while let Some(frame) = rx.recv().await {
+            if let Err(e) = write.write_frame(frame).await {
+                tracing::warn!(error = %e, "WebSocket write failed; dropping queued frames");
+                return;
+            }
+        }
+    });
+    (tx, handle)
+}
+
+/// Queues a frame handed out by `read_frame`, copying its payload out of the read buffer.
+fn enqueue(
+    writer: &FrameSender,
+    frame: Frame<'_>,
+) -> impl Future<Output = Result<(), &'static str>> + use<> {
+    let frame = Frame::new(frame.fin, frame.opcode, None, Payload::Owned(frame.payload.to_vec()));
+    let writer = writer.clone();
+    async move { writer.send(frame).await.map_err(|_| "write queue closed") }
+}
+
+/// Completes after `timeout`, or never when the watchdog is disabled.
+async fn idle(timeout: Option<Duration>) {
+    match timeout {
+        Some(timeout) => tokio::time::sleep(timeout).await,
+        None => std::future::pending().await,
+    }
 }
 
 /// Basic executor required by hyper handshake for spawning background tasks.
@@ -121,11 +809,11 @@ fn tls_connector() -> Result<TlsConnector> {
 }
 
 
-/// Connects to Binance using TLS and returns a WebSocket frame reader.
+/// Connects to Binance using TLS and returns the upgraded WebSocket.
 ///
 /// This establishes a secure `wss://` connection to Binance and completes
-/// the WebSocket upgrade handshake.
-async fn connect_exchange(domain: &str) -> Result<FragmentCollector<TokioIo<Upgraded>>> {
+/// the WebSocket upgrade handshake on `path` (`/ws`, or a raw-stream path).
+async fn connect_exchange(domain: &str, path: &str) -> Result<WsStream> {
     let mut addr = String::from(domain);
     addr.push_str(":9443");
 
@@ -140,7 +828,7 @@ async fn connect_exchange(domain: &str) -> Result<FragmentCollector<TokioIo<Upgr
 
     let req = Request::builder()
         .method("GET")
-        .uri(format!("wss://{}/ws", &addr))
+        .uri(format!("wss://{addr}{path}"))
         .header("Host", &addr)
         .header(UPGRADE, "websocket")
         .header(CONNECTION, "upgrade")
@@ -154,22 +842,21 @@ async fn connect_exchange(domain: &str) -> Result<FragmentCollector<TokioIo<Upgr
     let (ws, _) = 
         fastwebsockets::handshake::client(&SpawnExecutor, req, tls_stream).await?;
     
-    Ok(FragmentCollector::new(ws))
+    Ok(ws)
 }
 
 
-/// Connects to a local mock WebSocket server over plain TCP.
+/// Connects to a local mock WebSocket server at `addr` over plain TCP.
 ///
 /// This simulates a Binance-like feed without TLS and performs a standard
-/// WebSocket handshake with the local test server.
-async fn connect_local() -> Result<FragmentCollector<TokioIo<Upgraded>>> {
-    let addr = "localhost:9001";
+/// WebSocket handshake with the local test server on `path`.
+async fn connect_local(addr: &str, path: &str) -> Result<WsStream> {
     let stream = TcpStream::connect(addr).await?;
     tracing::info!("🧪 Local TCP connection established to {addr}");
     let req = Request::builder()
         .method("GET")
-        .uri(format!("http://{addr}"))
-        .header("Host", "localhost:9001")
+        .uri(format!("http://{addr}{path}"))
+        .header("Host", addr)
         .header(UPGRADE, "websocket")
         .header(CONNECTION, "upgrade")
         .header(
@@ -181,28 +868,55 @@ async fn connect_local() -> Result<FragmentCollector<TokioIo<Upgraded>>> {
 
     let (ws, _) =
         fastwebsockets::handshake::client(&SpawnExecutor, req, stream).await?;
-    Ok(FragmentCollector::new(ws))
+    Ok(ws)
 }
 
 /// Subscribes to Binance's `@bookTicker` stream for the given symbols.
+///
+/// Symbols are sent in batches of `SUBSCRIBE_BATCH_SIZE`, each with its own request id (from
+/// `first_id + 1`) registered in `tracker` so the server's ack can be matched back to its
+/// symbols. Frames are queued on the connection's writer task.
 async fn subscribe_symbols(
-    ws: &mut FragmentCollector<TokioIo<Upgraded>>,
+    writer: &FrameSender,
     symbols: Vec<String>,
+    first_id: u64,
+    tracker: &SubscriptionTracker,
 ) -> Result<()> {
-    let params: Vec<String> = symbols.iter()
-        .map(|s| format!("{}@bookTicker", s.to_lowercase()))
-        .collect();
+    for (id, batch) in (first_id + 1..).zip(symbols.chunks(SUBSCRIBE_BATCH_SIZE)) {
+        let params: Vec<String> = batch.iter().map(|s| stream_name(s)).collect();
 
-    let subscribe_message = serde_json::json!({
-        "method": "SUBSCRIBE",
-        "params": params,
-    });
+        let subscribe_message = serde_json::json!({
+            "method": "SUBSCRIBE",
+            "params": params,
+            "id": id,
+        });
 
-    let subscribe_payload = serde_json::to_string(&subscribe_message)?;
-    ws.write_frame(Frame::text(subscribe_payload.into_bytes().into())).await?;
+        tracker.register_batch(id, batch.to_vec());
+        let subscribe_payload = serde_json::to_string(&subscribe_message)?;
+        writer.send(Frame::text(subscribe_payload.into_bytes().into())).await
+            .map_err(|_| anyhow::anyhow!("WebSocket write queue closed"))?;
+    }
     Ok(())
 }
 
+/// The `@bookTicker` stream name Binance expects for `symbol`, e.g. `btcusdt@bookTicker`.
+fn stream_name(symbol: &str) -> String {
+    format!("{}@bookTicker", normalize_symbol(symbol).to_lowercase())
+}
+
+/// The raw-stream request path naming every symbol's `@bookTicker` stream, e.g.
+/// `/ws/btcusdt@bookTicker/ethusdt@bookTicker`, for `SubscribeStrategy::UrlPath`.
+///
+/// `None` when the path would exceed `MAX_STREAM_PATH_LEN`.
+pub fn stream_url_path(symbols: &[String]) -> Option<String> {
+    let mut path = String::from("/ws");
+    for symbol in symbols {
+        path.push('/');
+        path.push_str(&stream_name(symbol));
+    }
+    (path.len() <= MAX_STREAM_PATH_LEN).then_some(path)
+}
+
 /// Extracts a de-duplicated list of symbols from the pricing paths.
 ///
 /// Useful for determining which WebSocket channels to subscribe to.
@@ -215,3 +929,132 @@ pub fn extract_symbols_from_paths(price_paths: &[PricingPath]) -> Vec<String> {
     }
     symbols.into_iter().collect()
 }
+
+/// Splits `symbols` into at most `shards` contiguous groups, differing in size by at most one,
+/// after sorting them so the same universe always shards the same way. Never returns an empty
+/// group.
+pub fn shard_symbols(mut symbols: Vec<String>, shards: usize) -> Vec<Vec<String>> {
+    symbols.sort();
+    let shards = shards.clamp(1, symbols.len().max(1));
+    let (per_shard, remainder) = (symbols.len() / shards, symbols.len() % shards);
+    let mut rest = symbols.into_iter();
+    (0..shards)
+        .map(|shard| rest.by_ref().take(per_shard + usize::from(shard < remainder)).collect::<Vec<_>>())
+        .filter(|group| !group.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastwebsockets::{FragmentCollector, Role};
+
+    #[test]
+    fn stream_url_path_joins_every_stream_and_gives_up_when_too_long() {
+        let symbols: Vec<String> = ["BTCUSDT", "ethbtc", "ETH/USDT"].map(String::from).to_vec();
+        let path = stream_url_path(&symbols).unwrap();
+        assert_eq!(path, "/ws/btcusdt@bookTicker/ethbtc@bookTicker/ethusdt@bookTicker");
+        assert_eq!(
+            format!("wss://data-stream.binance.com:9443{path}"),
+            "wss://data-stream.binance.com:9443/ws/btcusdt@bookTicker/ethbtc@bookTicker/ethusdt@bookTicker"
+        );
+        assert_eq!(stream_url_path(&[]).as_deref(), Some("/ws"));
+
+        // "/ws" plus "/xxxxxxusdt@bookTicker" (22 bytes) per symbol
+        let many: Vec<String> = (0..200).map(|i| format!("S{i:05}USDT")).collect();
+        let fitting = (MAX_STREAM_PATH_LEN - 3) / 22;
+        assert!(stream_url_path(&many[..fitting]).is_some_and(|p| p.len() <= MAX_STREAM_PATH_LEN));
+        assert_eq!(stream_url_path(&many[..fitting + 1]), None);
+    }
+
+    #[test]
+    fn shard_symbols_splits_evenly_and_never_leaves_a_shard_empty() {
+        let symbols: Vec<String> = (0..7).map(|i| format!("S{i}USDT")).collect();
+        let shards = shard_symbols(symbols.iter().rev().cloned().collect(), 3);
+        assert_eq!(shards.iter().map(Vec::len).collect::<Vec<_>>(), [3, 2, 2]);
+        assert_eq!(shards.concat(), symbols, "sorted, each symbol in exactly one shard");
+
+        assert_eq!(shard_symbols(symbols[..2].to_vec(), 3).len(), 2);
+        assert_eq!(shard_symbols(symbols.clone(), 0), [symbols]);
+        assert!(shard_symbols(Vec::new(), 3).is_empty());
+    }
+
+    #[test]
+    fn connection_status_is_connected_only_while_every_shard_is() {
+        let connection = ConnectionState::new();
+        connection.reset_shards(3);
+        connection.set_shard_status(0, ConnectionStatus::Connected);
+        connection.set_shard_status(1, ConnectionStatus::Connected);
+        assert_eq!(connection.status(), ConnectionStatus::Connecting);
+
+        connection.set_shard_status(2, ConnectionStatus::Connected);
+        assert_eq!(connection.status(), ConnectionStatus::Connected);
+
+        // One shard's watchdog firing takes the whole feed out of Connected
+        connection.set_shard_status(1, ConnectionStatus::Connecting);
+        assert_eq!(connection.status(), ConnectionStatus::Connecting);
+        connection.set_shard_status(1, ConnectionStatus::Closed);
+        assert_eq!(connection.status(), ConnectionStatus::Connecting);
+        assert_eq!(
+            connection.shard_statuses(),
+            [ConnectionStatus::Connected, ConnectionStatus::Closed, ConnectionStatus::Connected]
+        );
+
+        connection.close_all();
+        assert_eq!(connection.status(), ConnectionStatus::Closed);
+    }
+
+    #[tokio::test]
+    async fn queued_frames_are_written_whole_and_in_order() {
+        // A tiny pipe forces every frame to be written in several pieces
+        let (client, server) = tokio::io::duplex(16);
+        let (_, write) = WebSocket::after_handshake(client, Role::Client).split(tokio::io::split);
+        let (writer, writer_task) = spawn_frame_writer(write);
+        let mut server = FragmentCollector::new(WebSocket::after_handshake(server, Role::Server));
+
+        const FRAMES: u64 = 20;
+        let subscribing = tokio::spawn({
+            let writer = writer.clone();
+            async move {
+                for id in 1..=FRAMES {
+                    let subscribe = serde_json::json!({
+                        "method": "SUBSCRIBE",
+                        "params": ["btcusdt@bookTicker", "ethbtc@bookTicker", "ethusdt@bookTicker"],
+                        "id": id,
+                    });
+                    writer.send(Frame::text(subscribe.to_string().into_bytes().into())).await.unwrap();
+                }
+            }
+        });
+        let ponging = tokio::spawn({
+            let writer = writer.clone();
+            async move {
+                for n in 1..=FRAMES {
+                    writer.send(Frame::pong(format!("pong-{n}").into_bytes().into())).await.unwrap();
+                }
+            }
+        });
+        drop(writer);
+
+        let (mut subscribe_ids, mut pongs) = (Vec::new(), Vec::new());
+        for _ in 0..2 * FRAMES {
+            let frame = server.read_frame().await.unwrap();
+            match frame.opcode {
+                OpCode::Text => {
+                    let msg: serde_json::Value = serde_json::from_slice(&frame.payload).unwrap();
+                    assert_eq!(msg["method"], "SUBSCRIBE");
+                    assert_eq!(msg["params"].as_array().unwrap().len(), 3);
+                    subscribe_ids.push(msg["id"].as_u64().unwrap());
+                }
+                OpCode::Pong => pongs.push(String::from_utf8(frame.payload.to_vec()).unwrap()),
+                other => panic!("unexpected {other:?} frame"),
+            }
+        }
+        subscribing.await.unwrap();
+        ponging.await.unwrap();
+        writer_task.await.unwrap();
+
+        assert_eq!(subscribe_ids, (1..=FRAMES).collect::<Vec<_>>());
+        assert_eq!(pongs, (1..=FRAMES).map(|n| format!("pong-{n}")).collect::<Vec<_>>());
+    }
+}
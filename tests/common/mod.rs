@@ -0,0 +1,70 @@
+// tests/common/mod.rs
+
+//! Fixtures shared by the integration tests, as `src/testutil.rs` is for the unit tests. Each
+//! test binary compiles its own copy and uses only part of it.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tri_arb::arb::{ArbEvaluator, ArbOpportunity, HaltHandle, ImpliedQuote};
+use tri_arb::parse::TopOfBookUpdate;
+use tri_arb::price_path::{PathLeg, PricingPath, Side, SymbolInfo};
+
+
+pub fn make_symbol(symbol: &str, base: &str, quote: &str) -> Arc<SymbolInfo> {
+    Arc::new(SymbolInfo {
+        symbol: symbol.to_string(),
+        base_asset: base.to_string(),
+        quote_asset: quote.to_string(),
+        status: "TRADING".into(),
+        tick_size: None,
+    })
+}
+
+/// USDT → `a` → `b` → USDT, e.g. `triangle("BTC", "ETH")` trades BTCUSDT, ETHBTC and ETHUSDT.
+pub fn triangle(a: &str, b: &str) -> PricingPath {
+    PricingPath {
+        leg1: PathLeg { symbol: make_symbol(&format!("{a}USDT"), a, "USDT"), side: Side::Ask },
+        leg2: PathLeg { symbol: make_symbol(&format!("{b}{a}"), b, a), side: Side::Ask },
+        leg3: PathLeg { symbol: make_symbol(&format!("{b}USDT"), b, "USDT"), side: Side::Bid },
+    }
+}
+
+
+/// Counts updates reaching the arb stage without evaluating anything.
+#[derive(Default)]
+pub struct CountingEvaluator {
+    pub updates: AtomicUsize,
+}
+
+impl ArbEvaluator for CountingEvaluator {
+    fn process_update(&self, _update: &TopOfBookUpdate) -> Option<ArbOpportunity> {
+        self.updates.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    fn price_snapshot(&self) -> HashMap<String, TopOfBookUpdate> {
+        HashMap::new()
+    }
+
+    fn halt_handle(&self) -> HaltHandle {
+        HaltHandle::default()
+    }
+
+    fn evaluate_path(&self, _path: &PricingPath) -> Option<f64> {
+        None
+    }
+
+    fn implied_quote(&self, _path: &PricingPath) -> Option<ImpliedQuote> {
+        None
+    }
+
+    fn invalidate_all(&self) {}
+
+    fn top_paths(&self, _n: usize) -> Vec<(Arc<PricingPath>, f64)> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,126 @@
+// tests/feed_events.rs
+
+// cargo test --test feed_events -- --nocapture
+
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+use tri_arb::feed::{feed_event_loop, FeedEvent};
+use tri_arb::mock_feed::hot_cache::start_hot_cache_updater;
+use tri_arb::mock_feed::ws_server::MockServer;
+use tri_arb::parse::quote_counter::QuoteCounter;
+use tri_arb::parse::ParserKind;
+use tri_arb::ws::{start_ws_feed, ConnectOptions};
+use common::triangle;
+
+
+/// Reads events into `events` until one matches `until`.
+async fn record_until(rx: &mut mpsc::Receiver<FeedEvent>, events: &mut Vec<FeedEvent>, until: fn(&FeedEvent) -> bool) {
+    loop {
+        let event = rx.recv().await.expect("feed stopped");
+        let done = until(&event);
+        events.push(event);
+        if done {
+            return;
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_feed_events_follow_a_server_restart() {
+    let price_paths = vec![triangle("BTC", "ETH")];
+
+    let cache = start_hot_cache_updater(price_paths[0].symbols(), 20, Some(7));
+    let server = MockServer::start(Arc::clone(&cache), "127.0.0.1:0").await.unwrap();
+    let addr = server.local_addr();
+
+    let (raw_tx, raw_rx) = mpsc::channel(1024);
+    let (event_tx, mut event_rx) = mpsc::channel(1024);
+    let options = ConnectOptions::default()
+        .with_local_addr(addr.to_string())
+        .with_reconnect_delay(Duration::from_millis(50))
+        .with_idle_timeout(None);
+    tokio::spawn(start_ws_feed(price_paths, raw_tx, options));
+    tokio::spawn(feed_event_loop(raw_rx, event_tx, ParserKind::Manual, Arc::new(QuoteCounter::new())));
+
+    let mut events = Vec::new();
+    let is_quote = |event: &FeedEvent| matches!(event, FeedEvent::Quote(_));
+    timeout(Duration::from_secs(5), record_until(&mut event_rx, &mut events, is_quote))
+        .await
+        .expect("quotes should stream before the restart");
+
+    server.stop().await;
+    timeout(Duration::from_secs(5), record_until(&mut event_rx, &mut events, |e| matches!(e, FeedEvent::Disconnected { .. })))
+        .await
+        .expect("the feed should report the server going away");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let _server = MockServer::start(cache, &addr.to_string()).await.unwrap();
+    timeout(Duration::from_secs(5), async {
+        record_until(&mut event_rx, &mut events, |e| matches!(e, FeedEvent::Reconnected { .. })).await;
+        record_until(&mut event_rx, &mut events, is_quote).await;
+    })
+    .await
+    .expect("the feed should report the reconnect and resume quoting");
+
+    let position = |wanted: fn(&FeedEvent) -> bool| events.iter().position(wanted).unwrap();
+    let disconnected = position(|e| matches!(e, FeedEvent::Disconnected { .. }));
+    let reconnected = position(|e| matches!(e, FeedEvent::Reconnected { .. }));
+    assert!(position(is_quote) < disconnected);
+    assert!(disconnected < reconnected);
+    assert!(is_quote(events.last().unwrap()), "quotes resume after the reconnect");
+    // Nothing arrives while the server is down
+    assert!(events[disconnected + 1..reconnected].iter().all(|e| !is_quote(e)));
+    match &events[reconnected] {
+        FeedEvent::Reconnected { shard, downtime } => {
+            assert_eq!(*shard, 0, "an unsharded listener is shard 0");
+            assert!(*downtime >= Duration::from_millis(200));
+        }
+        other => panic!("expected Reconnected, got {other:?}"),
+    }
+    assert!(!events.iter().any(|e| matches!(e, FeedEvent::ParseError { .. } | FeedEvent::Gap { .. })));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sharded_feed_tags_lifecycle_events_with_their_shard() {
+    let price_paths = vec![triangle("BTC", "ETH")];
+
+    let cache = start_hot_cache_updater(price_paths[0].symbols(), 20, Some(7));
+    let server = MockServer::start(Arc::clone(&cache), "127.0.0.1:0").await.unwrap();
+    let addr = server.local_addr();
+
+    let (raw_tx, raw_rx) = mpsc::channel(1024);
+    let (event_tx, mut event_rx) = mpsc::channel(1024);
+    let options = ConnectOptions::default()
+        .with_local_addr(addr.to_string())
+        .with_shards(2)
+        .with_reconnect_delay(Duration::from_millis(50))
+        .with_idle_timeout(None);
+    tokio::spawn(start_ws_feed(price_paths, raw_tx, options));
+    tokio::spawn(feed_event_loop(raw_rx, event_tx, ParserKind::Manual, Arc::new(QuoteCounter::new())));
+
+    let mut events = Vec::new();
+    timeout(Duration::from_secs(5), record_until(&mut event_rx, &mut events, |e| matches!(e, FeedEvent::Quote(_))))
+        .await
+        .expect("quotes should stream before the restart");
+
+    server.stop().await;
+    let mut disconnected = Vec::new();
+    timeout(Duration::from_secs(5), async {
+        while disconnected.len() < 2 {
+            if let FeedEvent::Disconnected { shard } = event_rx.recv().await.expect("feed stopped") {
+                disconnected.push(shard);
+            }
+        }
+    })
+    .await
+    .expect("both shards should report the server going away");
+    disconnected.sort();
+    assert_eq!(disconnected, [0, 1], "each shard reports its own disconnect once");
+}
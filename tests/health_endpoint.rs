@@ -0,0 +1,88 @@
+// tests/health_endpoint.rs
+
+// cargo test --features health --test health_endpoint -- --nocapture
+
+#![cfg(feature = "health")]
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+
+use tri_arb::health::{serve_health, HealthCheck};
+use tri_arb::mock_feed::hot_cache::start_hot_cache_updater;
+use tri_arb::mock_feed::ws_server;
+use tri_arb::pipeline::{Endpoint, Pipeline};
+use common::triangle;
+
+
+/// Status code and body of a plain `GET /health`.
+async fn get_health(addr: std::net::SocketAddr) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let status = response.split(' ').nth(1).unwrap().parse().unwrap();
+    let body = response.split("\r\n\r\n").nth(1).unwrap_or_default().to_string();
+    (status, body)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_health_is_ok_while_streaming_and_unavailable_once_idle() {
+    let price_paths = vec![triangle("BTC", "ETH")];
+
+    // Each connection streams a few rounds of ticks, then stays open but silent
+    let cache = start_hot_cache_updater(price_paths[0].symbols(), 20, Some(3));
+    tokio::spawn(ws_server::run_silent_after(cache, Some(10)));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let mut pipeline = Pipeline::builder()
+        .with_price_paths(price_paths)
+        .with_endpoint(Endpoint::LocalMock)
+        .build()
+        .unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_health(listener, HealthCheck::for_pipeline(&pipeline, Duration::from_millis(300))));
+
+    let (status, body) = get_health(addr).await;
+    assert_eq!(status, 503, "not started yet: {body}");
+
+    pipeline.start();
+    let healthy = timeout(Duration::from_secs(5), async {
+        loop {
+            if get_health(addr).await.0 == 200 {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    assert!(healthy.is_ok(), "/health never returned 200 while the feed streamed");
+
+    let (status, body) = timeout(Duration::from_secs(5), async {
+        loop {
+            let (status, body) = get_health(addr).await;
+            if status != 200 {
+                return (status, body);
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("/health stayed 200 after the feed went idle");
+    assert_eq!(status, 503);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["status"], "unavailable");
+    assert!(json["reason"].as_str().unwrap().starts_with("last frame"), "{json}");
+
+    pipeline.shutdown().await.unwrap();
+}
@@ -0,0 +1,53 @@
+// tests/mock_server_connection_limit.rs
+
+// cargo test --test mock_server_connection_limit -- --nocapture
+
+
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use tokio::time::timeout;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+
+use tri_arb::mock_feed::hot_cache::start_hot_cache_updater;
+use tri_arb::mock_feed::ws_server;
+
+
+const URL: &str = "ws://127.0.0.1:9001";
+
+/// Waits for the next frame and returns its close code, if it was a Close frame.
+async fn close_code<S>(ws: &mut S) -> Option<CloseCode>
+where
+    S: StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    match timeout(Duration::from_millis(500), ws.next()).await {
+        Ok(Some(Ok(Message::Close(frame)))) => frame.map(|f| f.code),
+        _ => None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_excess_connections_are_rejected_until_a_slot_frees() {
+    let cache = start_hot_cache_updater(vec!["BTCUSDT".to_string()], 20, Some(1));
+    tokio::spawn(ws_server::run_with_max_connections(cache, 2));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let (mut first, _) = connect_async(URL).await.expect("first client should connect");
+    let (mut second, _) = connect_async(URL).await.expect("second client should connect");
+
+    // The third handshake completes, but the server closes it straight away
+    let (mut third, _) = connect_async(URL).await.expect("handshake should still complete");
+    assert_eq!(close_code(&mut third).await, Some(CloseCode::Again));
+
+    // Admitted clients stay open (nothing subscribed, so nothing is sent)
+    assert_eq!(close_code(&mut second).await, None);
+
+    // Disconnecting one admitted client frees a slot for the next
+    first.close(None).await.unwrap();
+    drop(first);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let (mut fourth, _) = connect_async(URL).await.expect("fourth client should connect");
+    assert_eq!(close_code(&mut fourth).await, None, "freed slot should admit a new client");
+}
@@ -0,0 +1,49 @@
+// tests/mock_server_sweep.rs
+
+// cargo test --test mock_server_sweep -- --nocapture
+
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::time::{timeout, Instant};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+use tri_arb::mock_feed::hot_cache::start_hot_cache_updater;
+use tri_arb::mock_feed::ws_server;
+
+
+const URL: &str = "ws://127.0.0.1:9001";
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fast_sweeps_send_each_update_once() {
+    let symbols = vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()];
+    // The cache changes every 50ms but is swept every 5ms
+    let cache = start_hot_cache_updater(symbols, 50, Some(1));
+    tokio::spawn(ws_server::run_with_sweep_interval(cache, Duration::from_millis(5)));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let (mut ws, _) = connect_async(URL).await.expect("client should connect");
+    let subscribe = r#"{"method":"SUBSCRIBE","params":["btcusdt@bookTicker","ethusdt@bookTicker"],"id":1}"#;
+    ws.send(Message::Text(subscribe.into())).await.unwrap();
+
+    let mut update_ids: HashMap<String, Vec<u64>> = HashMap::new();
+    let deadline = Instant::now() + Duration::from_millis(600);
+    while let Ok(Some(Ok(msg))) = timeout(deadline.saturating_duration_since(Instant::now()), ws.next()).await {
+        let Message::Text(text) = msg else { continue };
+        let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+        // Skip the SUBSCRIBE ack
+        let (Some(symbol), Some(u)) = (value["s"].as_str(), value["u"].as_u64()) else { continue };
+        update_ids.entry(symbol.to_string()).or_default().push(u);
+    }
+
+    for symbol in ["BTCUSDT", "ETHUSDT"] {
+        let ids = &update_ids[symbol];
+        assert!(ids.len() >= 5, "{symbol} got only {} updates", ids.len());
+        // 120 sweeps in the window, but only ~12 cache updates per symbol
+        assert!(ids.len() <= 20, "{symbol} got {} updates, more than the cache produced", ids.len());
+        assert!(ids.windows(2).all(|w| w[0] < w[1]), "{symbol} repeated an update: {ids:?}");
+    }
+}
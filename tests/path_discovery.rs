@@ -0,0 +1,80 @@
+// tests/path_discovery.rs
+
+// cargo test --test path_discovery -- --nocapture
+
+
+use std::collections::HashSet;
+
+use tri_arb::arb::ArbConfig;
+use tri_arb::pipeline::Pipeline;
+use tri_arb::price_path::{SymbolFilter, TargetRanking};
+use tri_arb::ws::extract_symbols_from_paths;
+
+
+/// The universe `main` runs, against the bundled exchangeInfo fixture.
+const HOME_ASSET: &str = "USDT";
+const TARGETS: [&str; 3] = ["BTC", "ETH", "SOL"];
+
+#[test]
+fn discovery_finds_the_fixture_universe() {
+    let paths = Pipeline::builder()
+        .with_universe(HOME_ASSET, &TARGETS)
+        .discover_paths()
+        .unwrap();
+
+    assert_eq!(paths.len(), 15);
+    assert_eq!(extract_symbols_from_paths(&paths).len(), 21);
+    assert!(paths.iter().all(|path| path.leg1.symbol.quote_asset == HOME_ASSET));
+}
+
+#[test]
+fn discovery_applies_the_symbol_filter() {
+    let filter = SymbolFilter { blocklist: vec!["SOLBTC".into()], allowlist: Vec::new() };
+    let paths = Pipeline::builder()
+        .with_universe(HOME_ASSET, &TARGETS)
+        .with_symbol_filter(filter)
+        .discover_paths()
+        .unwrap();
+
+    // Both directions of the SOL/BTC triangle trade SOLBTC
+    assert_eq!(paths.len(), 13);
+    assert!(!extract_symbols_from_paths(&paths).iter().any(|s| s == "SOLBTC"));
+}
+
+#[test]
+fn discovery_applies_a_mixed_case_symbol_filter_from_config() {
+    let config: ArbConfig = toml::from_str(r#"
+        [symbols]
+        blocklist = ["sol-btc"]
+        allowlist = []
+    "#).unwrap();
+    let paths = Pipeline::builder()
+        .with_universe(HOME_ASSET, &TARGETS)
+        .with_symbol_filter(config.symbols.unwrap())
+        .discover_paths()
+        .unwrap();
+
+    // Matches exchangeInfo's SOLBTC despite the case and separator
+    assert_eq!(paths.len(), 13);
+    assert!(!extract_symbols_from_paths(&paths).iter().any(|s| s == "SOLBTC"));
+}
+
+#[test]
+fn discovery_uses_exactly_the_top_ranked_targets() {
+    let ranking = TargetRanking::Assets(["SOL", "ETH", "BTC"].map(String::from).to_vec());
+    let paths = Pipeline::builder()
+        .with_top_targets(HOME_ASSET, 2, ranking)
+        .discover_paths()
+        .unwrap();
+
+    let first_legs: HashSet<&str> = paths.iter().map(|path| path.leg1.symbol.base_asset.as_str()).collect();
+    assert_eq!(first_legs, HashSet::from(["SOL", "ETH"]));
+
+    // Explicit targets given afterwards override the ranking
+    let explicit = Pipeline::builder()
+        .with_top_targets(HOME_ASSET, 2, TargetRanking::Assets(vec!["SOL".into()]))
+        .with_universe(HOME_ASSET, &TARGETS)
+        .discover_paths()
+        .unwrap();
+    assert_eq!(explicit.len(), 15);
+}
@@ -0,0 +1,58 @@
+// tests/pipeline_lifecycle.rs
+
+// cargo test --test pipeline_lifecycle -- --nocapture
+
+
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use tokio::time::timeout;
+
+use tri_arb::mock_feed::hot_cache::start_hot_cache_updater;
+use tri_arb::mock_feed::ws_server;
+use tri_arb::pipeline::{Endpoint, Pipeline};
+use common::{triangle, CountingEvaluator};
+
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_pipeline_runs_against_mock_feed_and_shuts_down() {
+    let price_paths = vec![triangle("BTC", "ETH")];
+
+    let cache = start_hot_cache_updater(price_paths[0].symbols(), 5, Some(7));
+    tokio::spawn(ws_server::run(cache));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let evaluator = Arc::new(CountingEvaluator::default());
+    let mut pipeline = Pipeline::builder()
+        .with_price_paths(price_paths)
+        .with_endpoint(Endpoint::LocalMock)
+        .with_evaluator(evaluator.clone())
+        .build()
+        .unwrap();
+    assert!(!pipeline.is_running());
+
+    pipeline.start();
+    timeout(Duration::from_secs(5), async {
+        while evaluator.updates.load(Ordering::Relaxed) < 30 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("updates should flow from the mock feed to the arb stage");
+    assert!(pipeline.is_running());
+    assert!(pipeline.quote_counts().values().sum::<u64>() >= 30);
+    assert!(pipeline.dead_symbols().is_empty(), "the mock feed quotes every path symbol");
+
+    timeout(Duration::from_secs(5), pipeline.shutdown())
+        .await
+        .expect("shutdown should not hang")
+        .expect("every stage should exit cleanly");
+
+    // With the pipeline gone, nothing reaches the evaluator any more
+    let after_shutdown = evaluator.updates.load(Ordering::Relaxed);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(evaluator.updates.load(Ordering::Relaxed), after_shutdown);
+}
@@ -0,0 +1,76 @@
+// tests/pipeline_no_arb.rs
+
+// cargo test --test pipeline_no_arb -- --nocapture
+
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+use tri_arb::arb::{ArbEvaluator, ArbMode, ChannelSink, ScannerBuilder};
+use tri_arb::mock_feed::hot_cache::start_hot_cache_updater_with_generator;
+use tri_arb::mock_feed::{ws_server, ConsistentGenerator};
+use tri_arb::pipeline::{Endpoint, Pipeline};
+use tri_arb::ws::extract_symbols_from_paths;
+
+
+/// The universe `main` runs, against the bundled exchangeInfo fixture.
+const HOME_ASSET: &str = "USDT";
+const TARGETS: [&str; 3] = ["BTC", "ETH", "SOL"];
+
+/// Updates to see after every path is priced before the run counts as a full window.
+const WINDOW_UPDATES: u64 = 500;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn consistent_prices_produce_no_opportunities_end_to_end() {
+    let paths = Pipeline::builder().with_universe(HOME_ASSET, &TARGETS).discover_paths().unwrap();
+
+    // 5 bps each side: every triangle crosses three spreads and returns about 0.997
+    let prices = ConsistentGenerator::from_paths(&paths, 0.0005, Some(11));
+    let cache = start_hot_cache_updater_with_generator(extract_symbols_from_paths(&paths), 2, Box::new(prices));
+    tokio::spawn(ws_server::run(cache));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let evaluator = ScannerBuilder::new(paths.clone()).with_home_asset(HOME_ASSET).build(ArbMode::EdgeMap);
+    let (tx, mut opportunities) = mpsc::channel(64);
+    let mut pipeline = Pipeline::builder()
+        .with_price_paths(paths.clone())
+        .with_endpoint(Endpoint::LocalMock)
+        .with_evaluator(evaluator.clone())
+        .with_sink(Arc::new(ChannelSink::new(tx)))
+        .build()
+        .unwrap();
+    pipeline.start();
+
+    // Wait until every leg of every path has been quoted over the wire, then keep running
+    let stats = Arc::clone(pipeline.arb_stats());
+    timeout(Duration::from_secs(10), async {
+        while !paths.iter().all(|path| evaluator.evaluate_path(path).is_some()) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let priced_at = stats.updates_processed();
+        while stats.updates_processed() < priced_at + WINDOW_UPDATES {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("every path should be priced and the window filled from the mock feed");
+
+    for path in &paths {
+        let path_return = evaluator.evaluate_path(path).unwrap();
+        assert!(path_return < 1.0, "{path} returns {path_return} on arbitrage-free prices");
+    }
+
+    timeout(Duration::from_secs(5), pipeline.shutdown())
+        .await
+        .expect("shutdown should not hang")
+        .expect("every stage should exit cleanly");
+
+    let mut false_positives = Vec::new();
+    while let Ok(opportunity) = opportunities.try_recv() {
+        false_positives.push(opportunity.to_string());
+    }
+    assert!(false_positives.is_empty(), "phantom opportunities:\n{}", false_positives.join("\n"));
+}
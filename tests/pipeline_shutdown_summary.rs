@@ -0,0 +1,63 @@
+// tests/pipeline_shutdown_summary.rs
+
+// cargo test --test pipeline_shutdown_summary -- --nocapture
+
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+use tri_arb::arb::{ArbMode, ChannelSink, ScannerBuilder};
+use tri_arb::mock_feed::hot_cache::start_hot_cache_updater;
+use tri_arb::mock_feed::ws_server;
+use tri_arb::pipeline::{Endpoint, Pipeline};
+use tri_arb::ws::extract_symbols_from_paths;
+
+
+#[tokio::test(flavor = "multi_thread")]
+async fn shutdown_reports_the_runs_counters() {
+    let paths = Pipeline::builder().with_universe("USDT", &["BTC", "ETH"]).discover_paths().unwrap();
+
+    // Independent uniform prices make some direction of most triangles wildly profitable
+    let cache = start_hot_cache_updater(extract_symbols_from_paths(&paths), 5, Some(3));
+    tokio::spawn(ws_server::run(cache));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let (tx, _opportunities) = mpsc::channel(4096);
+    let mut pipeline = Pipeline::builder()
+        .with_price_paths(paths.clone())
+        .with_endpoint(Endpoint::LocalMock)
+        .with_evaluator(ScannerBuilder::new(paths).build(ArbMode::EdgeMap))
+        .with_sink(Arc::new(ChannelSink::new(tx)))
+        .build()
+        .unwrap();
+    assert_eq!(pipeline.summary().updates_processed, 0);
+    pipeline.start();
+
+    let stats = Arc::clone(pipeline.arb_stats());
+    timeout(Duration::from_secs(5), async {
+        while stats.updates_processed() < 50 || stats.opportunities_found() == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("updates and opportunities should flow from the mock feed");
+
+    let summary = timeout(Duration::from_secs(5), pipeline.shutdown())
+        .await
+        .expect("shutdown should not hang")
+        .expect("every stage should exit cleanly");
+
+    assert!(summary.updates_processed >= 50, "{summary:?}");
+    assert!(summary.frames >= summary.updates_processed, "one update per single-stream frame: {summary:?}");
+    assert!(summary.opportunities_found > 0, "{summary:?}");
+    assert!(summary.best_return.is_some_and(|r| r > 1.0), "{summary:?}");
+    assert!(summary.uptime > Duration::ZERO);
+
+    let json = serde_json::to_value(&summary).unwrap();
+    for key in ["frames", "parse_errors", "updates_processed", "opportunities_found", "best_return", "uptime_secs"] {
+        assert!(json.get(key).is_some(), "summary JSON lacks {key}: {json}");
+    }
+}
@@ -0,0 +1,110 @@
+// tests/subscription_tracking.rs
+
+// cargo test --test subscription_tracking -- --nocapture
+
+
+mod common;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+use tri_arb::mock_feed::hot_cache::start_hot_cache_updater;
+use tri_arb::mock_feed::ws_server::{self, MockServer};
+use tri_arb::ws::{start_ws_listener, ConnectOptions, ConnectionState, ConnectionStatus, SubscriptionTracker};
+use common::triangle;
+
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_acknowledged_symbols_are_tracked() {
+    let price_paths = vec![triangle("BTC", "ETH")];
+    let expected: HashSet<String> = price_paths[0].symbols().into_iter().collect();
+
+    let cache = start_hot_cache_updater(expected.iter().cloned().collect(), 20, Some(1));
+    tokio::spawn(ws_server::run(cache));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let tracker = Arc::new(SubscriptionTracker::new());
+    let (tx, mut rx) = mpsc::channel::<Bytes>(100);
+    let options = ConnectOptions::from_local_domain(Some(true)).with_tracker(Arc::clone(&tracker));
+    tokio::spawn(start_ws_listener(price_paths, tx, options));
+
+    let confirmed = timeout(Duration::from_secs(5), async {
+        loop {
+            if tracker.acknowledged_symbols() == expected && tracker.symbols_with_data() == expected {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .is_ok();
+
+    assert!(confirmed, "acked: {:?}, data: {:?}", tracker.acknowledged_symbols(), tracker.symbols_with_data());
+    assert!(tracker.unconfirmed_symbols().is_empty());
+
+    // The ack itself is consumed by the listener; only bookTicker payloads are forwarded
+    while let Ok(bytes) = rx.try_recv() {
+        let msg = String::from_utf8_lossy(&bytes);
+        assert!(!msg.contains("\"result\""), "ack leaked to the parser channel: {msg}");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_acks_after_the_window_closes_are_not_forwarded() {
+    let price_paths = vec![triangle("BTC", "ETH")];
+    let expected: HashSet<String> = price_paths[0].symbols().into_iter().collect();
+
+    let cache = start_hot_cache_updater(expected.iter().cloned().collect(), 20, Some(1));
+    let server = MockServer::start(Arc::clone(&cache), "127.0.0.1:0").await.unwrap();
+    let addr = server.local_addr();
+
+    let tracker = Arc::new(SubscriptionTracker::new().with_confirm_window(Duration::from_millis(100)));
+    let connection = Arc::new(ConnectionState::new());
+    let (tx, mut rx) = mpsc::channel::<Bytes>(1024);
+    let options = ConnectOptions::default()
+        .with_local_addr(addr.to_string())
+        .with_reconnect_delay(Duration::from_millis(50))
+        .with_idle_timeout(None)
+        .with_tracker(Arc::clone(&tracker))
+        .with_connection_state(Arc::clone(&connection));
+    let listener = tokio::spawn(start_ws_listener(price_paths, tx, options));
+
+    // Let the confirmation window close, then restart the server so the listener resubscribes
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    server.stop().await;
+    timeout(Duration::from_secs(5), async {
+        while connection.status() == ConnectionStatus::Connected {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the listener should notice the server went away");
+    while rx.try_recv().is_ok() {}
+    let _server = MockServer::start(cache, &addr.to_string()).await.unwrap();
+
+    let mut resumed = HashSet::new();
+    timeout(Duration::from_secs(5), async {
+        while resumed != expected {
+            let bytes = rx.recv().await.expect("listener stopped");
+            let msg = String::from_utf8_lossy(&bytes);
+            assert!(!msg.contains("\"result\""), "ack leaked to the parser channel: {msg}");
+            if let Ok(quote) = serde_json::from_slice::<serde_json::Value>(&bytes)
+                && let Some(symbol) = quote["s"].as_str()
+            {
+                resumed.insert(symbol.to_string());
+            }
+        }
+    })
+    .await
+    .expect("every symbol should resume after the restart");
+    while let Ok(bytes) = rx.try_recv() {
+        let msg = String::from_utf8_lossy(&bytes);
+        assert!(!msg.contains("\"result\""), "ack leaked to the parser channel: {msg}");
+    }
+    listener.abort();
+}
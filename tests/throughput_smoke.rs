@@ -0,0 +1,38 @@
+// tests/throughput_smoke.rs
+
+// cargo test --test throughput_smoke -- --nocapture
+
+
+use std::process::Command;
+
+
+#[test]
+fn test_throughput_binary_reports_nonzero_rate() {
+    let output = Command::new(env!("CARGO_BIN_EXE_throughput"))
+        .args(["--mode", "edge", "--paths", "50", "--secs", "0.2"])
+        .output()
+        .expect("failed to run throughput binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "throughput failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let line = stdout
+        .lines()
+        .find(|l| l.starts_with("EdgeMap"))
+        .unwrap_or_else(|| panic!("no EdgeMap line in output:\n{stdout}"));
+    let rate: f64 = line
+        .split("updates/s")
+        .nth(1)
+        .and_then(|rest| rest.split('|').next())
+        .and_then(|n| n.trim().parse().ok())
+        .unwrap_or_else(|| panic!("unparseable line: {line}"));
+    assert!(rate > 0.0, "expected non-zero throughput: {line}");
+}
+
+#[test]
+fn test_throughput_binary_rejects_unknown_mode() {
+    let output = Command::new(env!("CARGO_BIN_EXE_throughput"))
+        .args(["--mode", "quantum"])
+        .output()
+        .expect("failed to run throughput binary");
+    assert!(!output.status.success());
+}
@@ -0,0 +1,60 @@
+// tests/ws_connection_state.rs
+
+// cargo test --test ws_connection_state -- --nocapture
+
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+use tri_arb::mock_feed::hot_cache::start_hot_cache_updater;
+use tri_arb::mock_feed::ws_server;
+use tri_arb::ws::{start_ws_listener, ConnectOptions, ConnectionState, ConnectionStatus};
+use common::triangle;
+
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_frame_counters_and_last_frame_advance_with_data() {
+    let price_paths = vec![triangle("BTC", "ETH")];
+
+    let cache = start_hot_cache_updater(price_paths[0].symbols(), 20, Some(1));
+    tokio::spawn(ws_server::run(cache));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let connection = Arc::new(ConnectionState::new());
+    assert_eq!(connection.frames_received(), 0);
+    assert_eq!(connection.last_frame_at(), None);
+
+    let (tx, mut rx) = mpsc::channel::<Bytes>(1000);
+    let options = ConnectOptions::from_local_domain(Some(true))
+        .with_connection_state(Arc::clone(&connection))
+        .with_idle_timeout(None);
+    tokio::spawn(start_ws_listener(price_paths, tx, options));
+
+    // Wait for data to flow, then take a snapshot
+    timeout(Duration::from_secs(5), rx.recv()).await.expect("no frame forwarded").unwrap();
+    let frames = connection.frames_received();
+    let bytes = connection.bytes_received();
+    let last_frame = connection.last_frame_at().expect("a frame was forwarded");
+    assert!(frames > 0);
+    assert!(bytes > 0);
+    assert_eq!(connection.status(), ConnectionStatus::Connected);
+
+    // Keep draining so the listener never blocks on the channel
+    let advanced = timeout(Duration::from_secs(5), async {
+        while connection.frames_received() <= frames {
+            let _ = rx.recv().await;
+        }
+    })
+    .await
+    .is_ok();
+
+    assert!(advanced, "frame counter stuck at {frames}");
+    assert!(connection.bytes_received() > bytes);
+    assert!(connection.last_frame_at().unwrap() > last_frame);
+}
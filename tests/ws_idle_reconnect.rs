@@ -0,0 +1,69 @@
+// tests/ws_idle_reconnect.rs
+
+// cargo test --test ws_idle_reconnect -- --nocapture
+
+
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+use tri_arb::mock_feed::hot_cache::start_hot_cache_updater;
+use tri_arb::mock_feed::ws_server;
+use tri_arb::ws::{start_ws_listener, ConnectOptions, SubscriptionTracker};
+use common::triangle;
+
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_listener_reconnects_after_idle_timeout() {
+    let price_paths = vec![triangle("BTC", "ETH")];
+    let symbols = price_paths[0].symbols();
+
+    // Each connection delivers a single round of ticks (one frame per symbol), then stalls
+    let cache = start_hot_cache_updater(symbols.clone(), 20, Some(1));
+    tokio::spawn(ws_server::run_silent_after(cache, Some(1)));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let (tx, mut rx) = mpsc::channel::<Bytes>(100);
+    let reconnects = Arc::new(AtomicUsize::new(0));
+    let hook_count = Arc::clone(&reconnects);
+    // The confirmation window closes before the first idle reconnect resubscribes
+    let tracker = SubscriptionTracker::new().with_confirm_window(Duration::from_millis(100));
+    let options = ConnectOptions::from_local_domain(Some(true))
+        .with_idle_timeout(Some(Duration::from_millis(300)))
+        .with_reconnect_delay(Duration::from_millis(200))
+        .with_tracker(Arc::new(tracker))
+        .with_reconnect_hook(Arc::new(move |_: &[String]| {
+            hook_count.fetch_add(1, Ordering::Relaxed);
+        }));
+    let started = Instant::now();
+    tokio::spawn(start_ws_listener(price_paths, tx, options));
+
+    // Three connections' worth of frames can only arrive if the watchdog reconnected twice
+    let expected_frames = symbols.len() * 3;
+    let mut received = 0;
+    let reconnected = timeout(Duration::from_secs(5), async {
+        while received < expected_frames {
+            let frame = rx.recv().await.expect("listener stopped");
+            let msg = String::from_utf8_lossy(&frame);
+            // Each reconnect's SUBSCRIBE ack is kept from the parser, even after the window
+            assert!(!msg.contains("\"result\""), "ack leaked to the parser channel: {msg}");
+            received += 1;
+        }
+    })
+    .await
+    .is_ok();
+
+    assert!(
+        reconnected,
+        "received {received} frames; expected {expected_frames} across reconnects"
+    );
+    assert!(reconnects.load(Ordering::Relaxed) >= 2, "reconnect hook should fire on each reconnect");
+    // Both idle reconnects waited out the watchdog and then the reconnect delay
+    assert!(started.elapsed() >= Duration::from_millis(2 * (300 + 200)), "reconnected after {:?}", started.elapsed());
+}
@@ -0,0 +1,85 @@
+// tests/ws_server_restart.rs
+
+// cargo test --test ws_server_restart -- --nocapture
+
+
+mod common;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use bytes::Bytes;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+use tri_arb::mock_feed::hot_cache::start_hot_cache_updater;
+use tri_arb::mock_feed::ws_server::MockServer;
+use tri_arb::ws::{start_ws_listener, ConnectOptions, ConnectionState, ConnectionStatus};
+use common::triangle;
+
+
+/// Reads frames until every symbol in `expected` has delivered a quote.
+async fn receive_all(rx: &mut mpsc::Receiver<Bytes>, expected: &HashSet<String>) -> HashSet<String> {
+    let mut seen = HashSet::new();
+    while &seen != expected {
+        let frame = rx.recv().await.expect("listener stopped");
+        if let Ok(quote) = serde_json::from_slice::<serde_json::Value>(&frame)
+            && let Some(symbol) = quote["s"].as_str()
+        {
+            seen.insert(symbol.to_string());
+        }
+    }
+    seen
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_listener_resubscribes_after_server_restart() {
+    let price_paths = vec![triangle("BTC", "ETH")];
+    let symbols: HashSet<String> = price_paths[0].symbols().into_iter().collect();
+
+    // An ephemeral port keeps this test clear of the fixed-port mock server other tests use
+    let cache = start_hot_cache_updater(symbols.iter().cloned().collect(), 20, Some(5));
+    let server = MockServer::start(Arc::clone(&cache), "127.0.0.1:0").await.unwrap();
+    let addr = server.local_addr();
+
+    let (tx, mut rx) = mpsc::channel::<Bytes>(1024);
+    let connection = Arc::new(ConnectionState::new());
+    let reconnects = Arc::new(AtomicUsize::new(0));
+    let hook_count = Arc::clone(&reconnects);
+    let options = ConnectOptions::default()
+        .with_local_addr(addr.to_string())
+        .with_reconnect_delay(Duration::from_millis(50))
+        .with_idle_timeout(None)
+        .with_connection_state(Arc::clone(&connection))
+        .with_reconnect_hook(Arc::new(move |_: &[String]| {
+            hook_count.fetch_add(1, Ordering::Relaxed);
+        }));
+    let listener = tokio::spawn(start_ws_listener(price_paths, tx, options));
+
+    timeout(Duration::from_secs(5), receive_all(&mut rx, &symbols))
+        .await
+        .expect("every symbol should stream before the restart");
+
+    server.stop().await;
+    timeout(Duration::from_secs(5), async {
+        while connection.status() == ConnectionStatus::Connected {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the listener should notice the server went away");
+
+    // Retries against the dead port fail until the server is back
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!listener.is_finished(), "the listener should keep retrying while the server is down");
+    while rx.try_recv().is_ok() {}
+
+    let _server = MockServer::start(cache, &addr.to_string()).await.unwrap();
+    let resumed = timeout(Duration::from_secs(5), receive_all(&mut rx, &symbols)).await;
+
+    assert_eq!(resumed.ok().as_ref(), Some(&symbols), "every subscribed symbol should resume after the restart");
+    assert_eq!(connection.status(), ConnectionStatus::Connected);
+    assert!(reconnects.load(Ordering::Relaxed) >= 1, "reconnect hook should fire on the reconnect");
+}
@@ -0,0 +1,151 @@
+// tests/ws_sharding.rs
+
+// cargo test --test ws_sharding -- --nocapture
+
+
+mod common;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::Bytes;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::timeout;
+
+use tri_arb::mock_feed::hot_cache::{start_hot_cache_updater, HotCache, RandomTickGenerator};
+use tri_arb::mock_feed::ws_server::MockServer;
+use tri_arb::price_path::PricingPath;
+use tri_arb::ws::{shard_symbols, start_ws_listener, ConnectOptions, ConnectionState, ConnectionStatus, SubscriptionTracker};
+use common::triangle;
+
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_three_shards_deliver_every_symbol_exactly_once() {
+    let price_paths = vec![triangle("BTC", "ETH"), triangle("BNB", "SOL"), triangle("XRP", "ADA"), triangle("DOT", "LTC")];
+    let symbols: Vec<String> = price_paths.iter().flat_map(PricingPath::symbols).collect();
+    assert_eq!(symbols.len(), 12);
+
+    // One fixed tick per symbol: the server sends a tick once per connection subscribed to it,
+    // so a symbol subscribed on two shards would arrive twice
+    let mut generator = RandomTickGenerator::new(Some(1165));
+    let ticks: HashMap<String, String> = symbols.iter().map(|s| (s.clone(), generator.next_tick(s))).collect();
+    let cache: HotCache = Arc::new(RwLock::new(ticks));
+    let server = MockServer::start(cache, "127.0.0.1:0").await.unwrap();
+
+    let (tx, mut rx) = mpsc::channel::<Bytes>(1024);
+    let tracker = Arc::new(SubscriptionTracker::new());
+    let options = ConnectOptions::default()
+        .with_local_addr(server.local_addr().to_string())
+        .with_shards(3)
+        .with_idle_timeout(None)
+        .with_tracker(Arc::clone(&tracker));
+    tokio::spawn(start_ws_listener(price_paths, tx, options));
+
+    let mut received: HashMap<String, usize> = HashMap::new();
+    let all_arrived = timeout(Duration::from_secs(5), async {
+        while received.len() < symbols.len() {
+            let frame = rx.recv().await.expect("listener stopped");
+            let quote: serde_json::Value = serde_json::from_slice(&frame).unwrap();
+            *received.entry(quote["s"].as_str().unwrap().to_string()).or_default() += 1;
+        }
+    })
+    .await;
+    assert!(all_arrived.is_ok(), "received {received:?}");
+
+    // Give any duplicate from an overlapping shard time to show up
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    while let Ok(frame) = rx.try_recv() {
+        let quote: serde_json::Value = serde_json::from_slice(&frame).unwrap();
+        *received.entry(quote["s"].as_str().unwrap().to_string()).or_default() += 1;
+    }
+
+    assert_eq!(received.len(), symbols.len());
+    assert!(received.values().all(|&n| n == 1), "each symbol exactly once: {received:?}");
+    // Acks from all three connections land in the shared tracker without colliding
+    assert_eq!(tracker.acknowledged_symbols().len(), symbols.len());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reconnecting_shards_report_only_their_own_symbols() {
+    let price_paths = vec![triangle("BTC", "ETH"), triangle("BNB", "SOL")];
+    let symbols: Vec<String> = price_paths.iter().flat_map(PricingPath::symbols).collect();
+    let cache = start_hot_cache_updater(symbols.clone(), 20, Some(1165));
+    let server = MockServer::start(Arc::clone(&cache), "127.0.0.1:0").await.unwrap();
+    let addr = server.local_addr();
+
+    let (tx, mut rx) = mpsc::channel::<Bytes>(1024);
+    let connection = Arc::new(ConnectionState::new());
+    let reconnected: Arc<Mutex<Vec<Vec<String>>>> = Arc::default();
+    let hook_calls = Arc::clone(&reconnected);
+    let options = ConnectOptions::default()
+        .with_local_addr(addr.to_string())
+        .with_shards(2)
+        .with_reconnect_delay(Duration::from_millis(50))
+        .with_idle_timeout(None)
+        .with_connection_state(Arc::clone(&connection))
+        .with_reconnect_hook(Arc::new(move |symbols: &[String]| {
+            hook_calls.lock().unwrap().push(symbols.to_vec());
+        }));
+    let listener = tokio::spawn(start_ws_listener(price_paths, tx, options));
+
+    let until = |wanted: ConnectionStatus| {
+        let connection = Arc::clone(&connection);
+        async move {
+            while connection.status() != wanted {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+    };
+    timeout(Duration::from_secs(5), until(ConnectionStatus::Connected)).await.expect("both shards connect");
+    assert_eq!(connection.shard_statuses(), [ConnectionStatus::Connected; 2]);
+
+    server.stop().await;
+    timeout(Duration::from_secs(5), async {
+        while connection.shard_statuses().contains(&ConnectionStatus::Connected) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("both shards notice the server going away");
+    assert_eq!(connection.status(), ConnectionStatus::Connecting);
+
+    let _server = MockServer::start(cache, &addr.to_string()).await.unwrap();
+    timeout(Duration::from_secs(5), until(ConnectionStatus::Connected)).await.expect("both shards reconnect");
+    while rx.try_recv().is_ok() {}
+
+    // Each shard invalidates exactly its own symbols, never the whole universe
+    let shards: HashSet<Vec<String>> = shard_symbols(symbols, 2).into_iter().collect();
+    let calls = reconnected.lock().unwrap().clone();
+    assert!(!calls.is_empty());
+    assert!(calls.iter().all(|call| shards.contains(call)), "{calls:?} vs {shards:?}");
+    assert_eq!(calls.iter().collect::<HashSet<_>>().len(), 2, "both shards reconnected: {calls:?}");
+    listener.abort();
+}
+
+#[tokio::test]
+async fn test_first_failing_shard_stops_the_listener() {
+    // The first connection hangs mid-handshake forever; every later one is dropped, which
+    // fails its shard's connect (an error without a reconnect delay)
+    let server = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = server.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (_hanging, _) = server.accept().await.unwrap();
+        loop {
+            drop(server.accept().await);
+        }
+    });
+
+    let (tx, _rx) = mpsc::channel::<Bytes>(16);
+    let connection = Arc::new(ConnectionState::new());
+    let options = ConnectOptions::default()
+        .with_local_addr(addr.to_string())
+        .with_shards(3)
+        .with_connection_state(Arc::clone(&connection));
+    let result = timeout(Duration::from_secs(5), start_ws_listener(vec![triangle("BTC", "ETH")], tx, options))
+        .await
+        .expect("a failed shard stops the listener without waiting on the hung one");
+    assert!(result.is_err());
+    assert_eq!(connection.status(), ConnectionStatus::Closed);
+    assert_eq!(connection.shard_statuses(), [ConnectionStatus::Closed; 3]);
+}
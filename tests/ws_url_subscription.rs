@@ -0,0 +1,48 @@
+// tests/ws_url_subscription.rs
+
+// cargo test --test ws_url_subscription -- --nocapture
+
+
+mod common;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+use tri_arb::mock_feed::hot_cache::start_hot_cache_updater;
+use tri_arb::mock_feed::ws_server;
+use tri_arb::ws::{start_ws_listener, ConnectOptions, SubscribeStrategy, SubscriptionTracker};
+use common::triangle;
+
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_url_path_subscription_streams_without_subscribe_frames() {
+    let price_paths = vec![triangle("BTC", "ETH")];
+    let expected: HashSet<String> = price_paths[0].symbols().into_iter().collect();
+
+    let cache = start_hot_cache_updater(expected.iter().cloned().collect(), 20, Some(1));
+    tokio::spawn(ws_server::run(cache));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let tracker = Arc::new(SubscriptionTracker::new().with_strategy(SubscribeStrategy::UrlPath));
+    let (tx, mut rx) = mpsc::channel::<Bytes>(100);
+    let options = ConnectOptions::from_local_domain(Some(true)).with_tracker(Arc::clone(&tracker));
+    tokio::spawn(start_ws_listener(price_paths, tx, options));
+
+    // The mock server only knows the symbols from the connection URL
+    let confirmed = timeout(Duration::from_secs(5), async {
+        while tracker.symbols_with_data() != expected {
+            let _ = rx.recv().await;
+        }
+    })
+    .await
+    .is_ok();
+
+    assert!(confirmed, "data: {:?}", tracker.symbols_with_data());
+    assert!(tracker.acknowledged_symbols().is_empty(), "no SUBSCRIBE frame should have been sent");
+    assert!(tracker.unconfirmed_symbols().is_empty());
+}